@@ -0,0 +1,85 @@
+use std::process;
+use trpg_json_core::export::auth::{AuthError, GoogleSheetsAuth, LoginOptions, OAuthConfig};
+
+/// 認証マネージャーを作成（失敗時は終了）
+fn auth_or_exit() -> GoogleSheetsAuth {
+    match GoogleSheetsAuth::new() {
+        Ok(auth) => auth,
+        Err(e) => {
+            eprintln!("エラー: {}", e);
+            process::exit(1);
+        }
+    }
+}
+
+/// ログインコマンドのハンドラ
+pub fn handle_login(port: Option<u16>, no_browser: bool) {
+    let auth = auth_or_exit();
+    let options = LoginOptions { port, no_browser };
+
+    match auth.authenticate_with(&options) {
+        Ok(credentials) => {
+            println!("成功: Google にログインしました（認証情報: {}）", auth.credentials_path().display());
+            if credentials.refresh_token.is_none() {
+                eprintln!("警告: リフレッシュトークンが発行されませんでした。トークンの期限が切れたら再度ログインしてください");
+            }
+        }
+        Err(e) => {
+            eprintln!("エラー: ログインに失敗しました: {}", e);
+            process::exit(1);
+        }
+    }
+}
+
+/// ログアウトコマンドのハンドラ
+pub fn handle_logout() {
+    let auth = auth_or_exit();
+    if !auth.credentials_path().exists() {
+        println!("ログインしていません");
+        return;
+    }
+
+    match auth.clear_credentials() {
+        Ok(()) => println!("成功: 認証情報を削除しました（{}）", auth.credentials_path().display()),
+        Err(e) => {
+            eprintln!("エラー: 認証情報を削除できませんでした: {}", e);
+            process::exit(1);
+        }
+    }
+}
+
+/// 認証状態の表示コマンドのハンドラ
+pub fn handle_status() {
+    let auth = auth_or_exit();
+
+    println!("認証情報: {}", auth.credentials_path().display());
+    match auth.read_credentials() {
+        Ok(credentials) => {
+            let remaining = credentials.expires_in();
+            let refresh = if credentials.refresh_token.is_some() { "あり" } else { "なし" };
+            if credentials.is_expired() {
+                let note = if credentials.refresh_token.is_some() { "次回のエクスポート時に自動で更新します" } else { "gm auth login で再ログインしてください" };
+                println!("  状態: 期限切れ（{}）", note);
+            } else {
+                println!("  状態: 有効（残り {} 分）", remaining / 60);
+            }
+            println!("  リフレッシュトークン: {}", refresh);
+        }
+        Err(AuthError::MissingCredentials) => println!("  状態: 未ログイン（gm auth login でログインします）"),
+        Err(e) => println!("  状態: 読み込めません（{}）", e),
+    }
+
+    let source = if std::env::var("GOOGLE_CLIENT_ID").is_ok() {
+        "環境変数".to_string()
+    } else {
+        auth.config_path().display().to_string()
+    };
+    println!("OAuth 設定: {}", source);
+    match OAuthConfig::load(auth.config_path()) {
+        Ok(config) => {
+            println!("  リダイレクト URI: {}", config.redirect_uri);
+            println!("  トークンエンドポイント: {}", config.token_uri);
+        }
+        Err(_) => println!("  未設定（GOOGLE_CLIENT_ID / GOOGLE_CLIENT_SECRET または oauth_config.json が必要です）"),
+    }
+}
@@ -0,0 +1,188 @@
+use std::path::{Path, PathBuf};
+use std::process;
+use trpg_json_core::combat::CombatSession;
+use trpg_json_core::{io, query, Monster};
+use crate::utils;
+
+/// 戦闘トラッカーの操作
+pub enum CombatAction<'a> {
+    Start { names: &'a [String], from: Option<&'a str> },
+    Add { names: &'a [String], from: Option<&'a str> },
+    Show,
+    Damage { target: &'a str, amount: i32, physical: bool },
+    Heal { target: &'a str, amount: i32 },
+    SpendMp { target: &'a str, amount: i32 },
+    StatusAdd { target: &'a str, status: &'a str },
+    StatusRemove { target: &'a str, status: &'a str },
+    Next,
+    End,
+}
+
+/// セッションファイルのデフォルトパス（OS別のデータディレクトリ配下の trpg-json/combat.json）
+pub fn default_session_path() -> PathBuf {
+    dirs::data_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("trpg-json")
+        .join("combat.json")
+}
+
+/// 戦闘コマンドのハンドラ
+pub fn handle_combat(data_paths: &[String], session_path: &Path, action: CombatAction) {
+    match action {
+        CombatAction::Start { names, from } => {
+            if session_path.exists()
+                && !utils::confirm_action(&format!(
+                    "進行中の戦闘があります（{}）。破棄して新しく開始しますか？",
+                    session_path.display()
+                ))
+            {
+                eprintln!("キャンセルされました");
+                process::exit(1);
+            }
+            let monsters = collect_monsters_or_exit(data_paths, names, from);
+            let session = CombatSession::start(&monsters);
+            save_or_exit(&session, session_path);
+            print!("{}", session.format_table());
+        }
+        CombatAction::Add { names, from } => {
+            let mut session = load_or_exit(session_path);
+            let monsters = collect_monsters_or_exit(data_paths, names, from);
+            let added = session.add(&monsters);
+            save_or_exit(&session, session_path);
+            println!("追加: {}", added.join(", "));
+            print!("{}", session.format_table());
+        }
+        CombatAction::Show => {
+            let session = load_or_exit(session_path);
+            print!("{}", session.format_table());
+        }
+        CombatAction::Damage { target, amount, physical } => {
+            let mut session = load_or_exit(session_path);
+            let dealt = exit_on_error(session.damage(target, amount, physical));
+            report_hp(&session, target, &format!("{} ダメージ", dealt));
+            save_or_exit(&session, session_path);
+        }
+        CombatAction::Heal { target, amount } => {
+            let mut session = load_or_exit(session_path);
+            let healed = exit_on_error(session.heal(target, amount));
+            report_hp(&session, target, &format!("{} 回復", healed));
+            save_or_exit(&session, session_path);
+        }
+        CombatAction::SpendMp { target, amount } => {
+            let mut session = load_or_exit(session_path);
+            let remaining = exit_on_error(session.spend_mp(target, amount));
+            let part = exit_on_error(session.find_part(target));
+            println!("{}: MP {} 消費（MP {}/{}）", part.label, amount, remaining, part.stats.mp);
+            save_or_exit(&session, session_path);
+        }
+        CombatAction::StatusAdd { target, status } => {
+            let mut session = load_or_exit(session_path);
+            exit_on_error(session.add_status(target, status));
+            let part = exit_on_error(session.find_part(target));
+            println!("{}: 状態「{}」を追加（{}）", part.label, status, part.statuses.join(", "));
+            save_or_exit(&session, session_path);
+        }
+        CombatAction::StatusRemove { target, status } => {
+            let mut session = load_or_exit(session_path);
+            exit_on_error(session.remove_status(target, status));
+            let part = exit_on_error(session.find_part(target));
+            println!("{}: 状態「{}」を解除", part.label, status);
+            save_or_exit(&session, session_path);
+        }
+        CombatAction::Next => {
+            let mut session = load_or_exit(session_path);
+            exit_on_error(session.next_turn());
+            save_or_exit(&session, session_path);
+            print!("{}", session.format_table());
+        }
+        CombatAction::End => {
+            if !session_path.exists() {
+                eprintln!("エラー: 進行中の戦闘がありません");
+                process::exit(1);
+            }
+            if !utils::confirm_action("戦闘を終了してセッションを削除しますか？") {
+                eprintln!("キャンセルされました");
+                process::exit(1);
+            }
+            if let Err(e) = std::fs::remove_file(session_path) {
+                eprintln!("エラー: セッションファイルを削除できません: {}", e);
+                process::exit(1);
+            }
+            println!("成功: 戦闘を終了しました");
+        }
+    }
+}
+
+/// 名前指定（"ゴブリン*3" で3体）と JSON ファイルから参加する魔物を集める
+fn collect_monsters_or_exit(data_paths: &[String], names: &[String], from: Option<&str>) -> Vec<Monster> {
+    let mut monsters = Vec::new();
+
+    if let Some(path) = from {
+        match io::load_json_array(path) {
+            Ok(loaded) => monsters.extend(loaded),
+            Err(e) => {
+                eprintln!("エラー: {}: {}", path, e);
+                process::exit(1);
+            }
+        }
+    }
+
+    if !names.is_empty() {
+        let all = utils::load_monsters_or_exit(data_paths);
+        for spec in names {
+            let (name, count) = parse_spec(spec);
+            match query::find_by_exact_name(&all, name) {
+                Some(monster) => monsters.extend(std::iter::repeat_n(monster.clone(), count)),
+                None => {
+                    eprintln!("エラー: \"{}\" という名前のモンスターが見つかりません", name);
+                    process::exit(1);
+                }
+            }
+        }
+    }
+
+    if monsters.is_empty() {
+        eprintln!("エラー: 参加させるモンスターを名前または --from で指定してください");
+        process::exit(1);
+    }
+    monsters
+}
+
+/// "名前*数" / "名前×数" を分解（数が無い場合は1）
+fn parse_spec(spec: &str) -> (&str, usize) {
+    if let Some((name, count)) = spec.rsplit_once(['*', '×'])
+        && let Ok(count) = count.trim().parse::<usize>()
+        && count > 0
+    {
+        return (name.trim(), count);
+    }
+    (spec, 1)
+}
+
+fn report_hp(session: &CombatSession, target: &str, action: &str) {
+    let part = exit_on_error(session.find_part(target));
+    let down = if part.is_down() { " 戦闘不能" } else { "" };
+    println!("{}: {}（HP {}/{}）{}", part.label, action, part.hp, part.stats.hp, down);
+}
+
+fn load_or_exit(path: &Path) -> CombatSession {
+    if !path.exists() {
+        eprintln!("エラー: 進行中の戦闘がありません（gm combat start で開始してください）");
+        process::exit(1);
+    }
+    exit_on_error(CombatSession::load(path))
+}
+
+fn save_or_exit(session: &CombatSession, path: &Path) {
+    exit_on_error(session.save(path));
+}
+
+fn exit_on_error<T, E: std::fmt::Display>(result: Result<T, E>) -> T {
+    match result {
+        Ok(value) => value,
+        Err(e) => {
+            eprintln!("エラー: {}", e);
+            process::exit(1);
+        }
+    }
+}
@@ -0,0 +1,122 @@
+use std::process;
+use trpg_json_core::encounter::{self, Difficulty, Encounter, EncounterParams};
+use trpg_json_core::export::ExportOptions;
+use trpg_json_core::query;
+use crate::commands::monster;
+use crate::utils;
+
+/// エンカウンター作成コマンドのオプション
+pub struct BuildOptions<'a> {
+    pub party_level: i32,
+    pub party_size: u32,
+    pub category: Option<&'a str>,
+    pub difficulty: &'a str,
+    pub min_level: Option<i32>,
+    pub max_level: Option<i32>,
+    pub max_monsters: Option<usize>,
+    pub candidates: usize,
+    pub filter_expr: Option<&'a str>,
+    pub pick: Option<usize>,
+    pub export_format: Option<&'a str>,
+    pub output_dest: Option<&'a str>,
+}
+
+/// エンカウンター作成コマンドのハンドラ
+pub fn handle_build(data_paths: &[String], options: &BuildOptions, export_options: &ExportOptions) {
+    let difficulty = match options.difficulty.parse::<Difficulty>() {
+        Ok(d) => d,
+        Err(e) => {
+            eprintln!("エラー: {}", e);
+            process::exit(1);
+        }
+    };
+    if options.export_format.is_some() && options.output_dest.is_none() {
+        eprintln!("エラー: --export を使用する場合は --output で出力先を指定してください");
+        process::exit(1);
+    }
+
+    // フィルタ式を先に解析（構文エラーはデータ読み込み前に報告）
+    let filter = options.filter_expr.map(utils::parse_filter_or_exit);
+
+    let monsters = utils::load_monsters_or_exit(data_paths);
+    let mut pool = query::find_multi(&monsters, None, None, options.category);
+    if let Some(f) = &filter {
+        pool.retain(|m| f.matches(*m));
+    }
+
+    let mut params = EncounterParams::new(options.party_level, options.party_size);
+    params.difficulty = difficulty;
+    params.min_level = options.min_level;
+    params.max_level = options.max_level;
+    params.candidates = options.candidates;
+    if let Some(max) = options.max_monsters {
+        params.max_monsters = max;
+    }
+
+    let candidates = match encounter::build(&pool, &params) {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("エラー: {}", e);
+            process::exit(1);
+        }
+    };
+
+    // 候補を選択した場合は JSON 出力またはエクスポート
+    if options.pick.is_some() || options.export_format.is_some() {
+        let pick = options.pick.unwrap_or(1);
+        let Some(chosen) = pick.checked_sub(1).and_then(|i| candidates.get(i)) else {
+            eprintln!("エラー: 候補の番号は 1〜{} で指定してください", candidates.len());
+            process::exit(1);
+        };
+        let chosen_monsters = chosen.to_monsters();
+
+        match (options.export_format, options.output_dest) {
+            (Some(fmt), Some(output)) => monster::export_results(&chosen_monsters, fmt, output, export_options),
+            _ => utils::save_json_stdout_or_exit(&chosen_monsters),
+        }
+        return;
+    }
+
+    let (min_level, max_level) = params.level_range();
+    println!(
+        "予算: {:.1}（パーティ Lv{} × {}人、難易度 {}）／ 魔物レベル {}〜{}",
+        params.budget(),
+        params.party_level,
+        params.party_size,
+        params.difficulty,
+        min_level,
+        max_level
+    );
+    for (i, candidate) in candidates.iter().enumerate() {
+        println!();
+        print_candidate(i + 1, candidate);
+    }
+}
+
+/// 候補を整形して出力
+fn print_candidate(number: usize, candidate: &Encounter) {
+    println!(
+        "候補 {}: 脅威度 {:.1}（予算比 {:+.0}%）",
+        number,
+        candidate.threat,
+        candidate.deviation() * 100.0
+    );
+    for member in &candidate.members {
+        println!(
+            "  {} Lv{} ×{}  部位{} HP{}  脅威度 {:.1}",
+            member.monster.name,
+            member.monster.level,
+            member.count,
+            encounter::part_count(member.monster),
+            encounter::hp_total(member.monster),
+            member.threat * member.count as f64
+        );
+    }
+    println!(
+        "  合計: {}体 / 部位 {} / HP {} / Lv合計 {}",
+        candidate.monster_count(),
+        candidate.part_total(),
+        candidate.hp_total(),
+        candidate.level_total()
+    );
+}
@@ -0,0 +1,103 @@
+use std::path::{Path, PathBuf};
+use std::process;
+use trpg_json_core::backup::{BackupEntry, BackupPolicy};
+use crate::utils;
+
+/// 履歴表示コマンドのハンドラ
+pub fn handle_history(data_paths: &[String], file: Option<&str>, backup: &BackupPolicy) {
+    let targets = match file {
+        Some(which) => vec![utils::resolve_data_file_or_exit(data_paths, Some(which))],
+        None => data_paths.iter().map(PathBuf::from).collect(),
+    };
+
+    for (i, path) in targets.iter().enumerate() {
+        if i > 0 {
+            println!();
+        }
+        println!("{}", path.display());
+
+        let entries = list_backups_or_exit(backup, path);
+        if entries.is_empty() {
+            println!("  （履歴はありません）");
+            continue;
+        }
+        for (number, entry) in entries.iter().enumerate() {
+            let note = if entry.restored.is_some() { "  （gm undo の直前）" } else { "" };
+            println!("  {:>2}. {}  {}{}", number + 1, entry.display_time(), entry.path.display(), note);
+        }
+    }
+}
+
+/// 元に戻すコマンドのハンドラ
+///
+/// `to` 未指定時は現在の状態の1つ前に戻す（続けて実行すると、gm undo 自身のバックアップを
+/// 飛ばしてさらに前へ進む）。指定時は gm history の番号のバックアップに戻す。
+pub fn handle_undo(data_paths: &[String], file: Option<&str>, to: Option<usize>, backup: &BackupPolicy) {
+    // 対象ファイルを決定（未指定時は最後にバックアップされたファイル）
+    let path = match file {
+        Some(which) => utils::resolve_data_file_or_exit(data_paths, Some(which)),
+        None => match backup.latest(data_paths) {
+            Ok(Some((path, _))) => path.to_path_buf(),
+            Ok(None) => {
+                eprintln!("エラー: 元に戻せる履歴がありません");
+                process::exit(1);
+            }
+            Err(e) => {
+                eprintln!("エラー: {}", e);
+                process::exit(1);
+            }
+        },
+    };
+
+    let entries = list_backups_or_exit(backup, &path);
+    if entries.is_empty() {
+        eprintln!("エラー: {} には元に戻せる履歴がありません", path.display());
+        process::exit(1);
+    }
+    let entry = match to {
+        Some(to) if to == 0 || to > entries.len() => {
+            eprintln!("エラー: 履歴の番号は 1〜{} で指定してください", entries.len());
+            process::exit(1);
+        }
+        Some(to) => entries[to - 1].clone(),
+        None => match backup.undo_target(&path, 1) {
+            Ok(Some(entry)) => entry,
+            Ok(None) => {
+                eprintln!("エラー: {} にはこれより前の履歴がありません（gm history の番号を --to で指定できます）", path.display());
+                process::exit(1);
+            }
+            Err(e) => {
+                eprintln!("エラー: 履歴を読み込めません: {}", e);
+                process::exit(1);
+            }
+        },
+    };
+
+    // 確認ダイアログを表示
+    if !utils::confirm_action(&format!(
+        "{} を {} の状態に戻しますか？（現在の内容は履歴に保存されます）",
+        path.display(),
+        entry.display_time()
+    )) {
+        eprintln!("キャンセルされました");
+        process::exit(1);
+    }
+
+    if let Err(e) = backup.restore(&path, &entry) {
+        eprintln!("エラー: 復元できません: {}", e);
+        process::exit(1);
+    }
+
+    println!("成功: {} を {} の状態に戻しました", path.display(), entry.display_time());
+}
+
+/// バックアップ一覧を取得する（失敗時はエラーを表示して終了）
+fn list_backups_or_exit(backup: &BackupPolicy, path: &Path) -> Vec<BackupEntry> {
+    match backup.list(path) {
+        Ok(entries) => entries,
+        Err(e) => {
+            eprintln!("エラー: 履歴を読み込めません: {}", e);
+            process::exit(1);
+        }
+    }
+}
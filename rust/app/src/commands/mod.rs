@@ -1,2 +1,7 @@
+pub mod auth;
+pub mod combat;
+pub mod encounter;
+pub mod history;
 pub mod monster;
+pub mod roll;
 pub mod spell;
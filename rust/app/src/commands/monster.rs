@@ -1,5 +1,7 @@
+use std::path::Path;
 use std::process;
-use trpg_json_core::{export, io, query, stats, Monster};
+use trpg_json_core::import::{delimited::DelimitedImporter, sheets::SheetsImporter, udonarium::UdonariumImporter};
+use trpg_json_core::{backup::BackupPolicy, export, query, stats, validate, Monster};
 use crate::utils;
 
 /// 検索コマンドのハンドラ
@@ -12,8 +14,9 @@ pub fn handle_find(data_paths: &[String], name: &str, level: Option<i32>, catego
     // 結果を処理
     match results.len() {
         0 => {
-            let error_msg = utils::format_monster_filter_conditions(Some(name), level, category);
+            let error_msg = utils::format_monster_filter_conditions(Some(name), level, category, None);
             eprintln!("エラー: {}", error_msg);
+            utils::print_monster_suggestions(&monsters, name);
             process::exit(1);
         }
         1 => {
@@ -24,8 +27,8 @@ pub fn handle_find(data_paths: &[String], name: &str, level: Option<i32>, catego
             // 複数件の場合は件数を出力
             println!("{} 件のモンスターが見つかりました", n);
             
-            // 完全一致するモンスターがあればそのデータを出力
-            if let Some(exact_match) = results.iter().find(|m| m.name == name) {
+            // 完全一致（表記の違いを除く）するモンスターがあればそのデータを出力
+            if let Some(exact_match) = results.iter().find(|m| query::fuzzy::same_name(&m.name, name)) {
                 let exact_monster = (*exact_match).clone();
                 utils::save_json_stdout_or_exit(&[exact_monster]);
             }
@@ -43,8 +46,9 @@ pub fn handle_list(data_paths: &[String], pattern: &str) {
     // 結果を処理
     match results.len() {
         0 => {
-            let error_msg = utils::format_monster_filter_conditions(Some(pattern), None, None);
+            let error_msg = utils::format_monster_filter_conditions(Some(pattern), None, None, None);
             eprintln!("エラー: {}", error_msg);
+            utils::print_monster_suggestions(&monsters, pattern);
             process::exit(1);
         }
         1 => {
@@ -61,8 +65,8 @@ pub fn handle_list(data_paths: &[String], pattern: &str) {
             }
             println!("\n計 {} 件のモンスターが見つかりました", n);
             
-            // 完全一致するモンスターがあればそのデータを出力
-            if let Some(exact_match) = results.iter().find(|m| m.name == pattern) {
+            // 完全一致（表記の違いを除く）するモンスターがあればそのデータを出力
+            if let Some(exact_match) = results.iter().find(|m| query::fuzzy::same_name(&m.name, pattern)) {
                 let exact_monster = (*exact_match).clone();
                 utils::save_json_stdout_or_exit(&[exact_monster]);
             }
@@ -71,23 +75,32 @@ pub fn handle_list(data_paths: &[String], pattern: &str) {
 }
 
 /// 選択・エクスポートコマンドのハンドラ
+#[allow(clippy::too_many_arguments)]
 pub fn handle_select(
     data_paths: &[String],
     name: Option<&str>,
     level: Option<i32>,
     category: Option<&str>,
+    filter_expr: Option<&str>,
     export_format: Option<&str>,
     output_dest: Option<&str>,
+    export_options: &export::ExportOptions,
 ) {
+    // フィルタ式を先に解析（構文エラーはデータ読み込み前に報告）
+    let filter = filter_expr.map(utils::parse_filter_or_exit);
+
     let monsters = utils::load_monsters_or_exit(data_paths);
 
     // 複合検索を実行
-    let results = query::find_multi(&monsters, name, level, category);
+    let mut results = query::find_multi(&monsters, name, level, category);
+    if let Some(f) = &filter {
+        results.retain(|m| f.matches(*m));
+    }
 
     // 結果を処理
     match results.len() {
         0 => {
-            let error_msg = utils::format_monster_filter_conditions(name, level, category);
+            let error_msg = utils::format_monster_filter_conditions(name, level, category, filter_expr);
             eprintln!("エラー: {}", error_msg);
             process::exit(1);
         }
@@ -97,7 +110,7 @@ pub fn handle_select(
             // エクスポート機能が指定されている場合
             if let Some(fmt) = export_format {
                 if let Some(output) = output_dest {
-                    export_results(&json_results, fmt, output);
+                    export_results(&json_results, fmt, output, export_options);
                 } else {
                     eprintln!("エラー: --export を使用する場合は --output で出力先を指定してください");
                     process::exit(1);
@@ -111,19 +124,10 @@ pub fn handle_select(
 }
 
 /// エクスポート処理を実行
-fn export_results(monsters: &[Monster], format: &str, output: &str) {
-    // エクスポート形式をパース
-    let export_format = match format.parse::<export::ExportFormat>() {
-        Ok(fmt) => fmt,
-        Err(e) => {
-            eprintln!("エラー: {}", e);
-            process::exit(1);
-        }
-    };
-
-    // エクスポーターを生成
-    let exporter = match export::ExporterFactory::create_exporter(export_format) {
-        Ok(exp) => exp,
+pub fn export_results(monsters: &[Monster], format: &str, output: &str, options: &export::ExportOptions) {
+    // エクスポーターを生成（組み込みの形式名または設定ファイルのテンプレート名）
+    let (export_format, exporter) = match export::ExporterFactory::create_named_exporter(format, options) {
+        Ok(pair) => pair,
         Err(e) => {
             eprintln!("エラー: {}", e);
             process::exit(1);
@@ -148,8 +152,93 @@ fn export_results(monsters: &[Monster], format: &str, output: &str) {
     }
 }
 
+/// ココフォリア出力コマンドのハンドラ
+pub fn handle_ccfolia(
+    data_paths: &[String],
+    name: &str,
+    part: Option<usize>,
+    copy: bool,
+    export_options: &export::ExportOptions,
+) {
+    let palette = match export_options.palette() {
+        Ok(palette) => palette,
+        Err(e) => {
+            eprintln!("エラー: {}", e);
+            process::exit(1);
+        }
+    };
+    let monsters = utils::load_monsters_or_exit(data_paths);
+
+    let results = query::find_by_name(&monsters, name);
+    let monster = match results.iter().find(|m| query::fuzzy::same_name(&m.name, name)) {
+        Some(exact) => *exact,
+        None => match results.as_slice() {
+            [] => {
+                let error_msg = utils::format_monster_filter_conditions(Some(name), None, None, None);
+                eprintln!("エラー: {}", error_msg);
+                utils::print_monster_suggestions(&monsters, name);
+                process::exit(1);
+            }
+            [only] => *only,
+            _ => {
+                eprintln!("エラー: {} 件のモンスターが該当します。名前を正確に指定してください:", results.len());
+                for m in &results {
+                    eprintln!("  {}", m.name);
+                }
+                process::exit(1);
+            }
+        },
+    };
+
+    let pieces = export::ccfolia::CcfoliaGenerator::generate(monster, &palette);
+    let selected: Vec<_> = match part {
+        Some(n) if n == 0 || n > pieces.len() => {
+            eprintln!("エラー: 部位番号は 1〜{} で指定してください", pieces.len());
+            process::exit(1);
+        }
+        Some(n) => vec![&pieces[n - 1]],
+        None => pieces.iter().collect(),
+    };
+
+    let mut jsons = Vec::new();
+    for (_, piece) in &selected {
+        match export::ccfolia::CcfoliaGenerator::to_clipboard_json(piece) {
+            Ok(json) => jsons.push(json),
+            Err(e) => {
+                eprintln!("エラー: JSON 生成に失敗しました: {}", e);
+                process::exit(1);
+            }
+        }
+    }
+
+    if !copy {
+        for json in &jsons {
+            println!("{}", json);
+        }
+        return;
+    }
+
+    // ココフォリアは1回の貼り付けで1駒しか作れないため、先頭の部位のみコピーする
+    let Some(first) = jsons.first() else {
+        eprintln!("エラー: '{}' には部位がありません", monster.name);
+        process::exit(1);
+    };
+    match utils::copy_to_clipboard(first) {
+        Ok(_) => {
+            eprintln!("✓ {} をクリップボードにコピーしました（ココフォリアの盤面に貼り付けてください）", selected[0].1.data.name);
+            if jsons.len() > 1 {
+                eprintln!("  残り {} 部位は --part で指定してコピーしてください", jsons.len() - 1);
+            }
+        }
+        Err(e) => {
+            eprintln!("エラー: クリップボードへのコピーに失敗しました: {}", e);
+            process::exit(1);
+        }
+    }
+}
+
 /// 追加コマンドのハンドラ
-pub fn handle_add(data_paths: &[String], file: &str) {
+pub fn handle_add(data_paths: &[String], file: &str, target_file: Option<&str>, backup: &BackupPolicy) {
     // JSON ファイルから新規モンスターを読み込む
     let new_monster_json = match std::fs::read_to_string(file) {
         Ok(content) => content,
@@ -167,40 +256,129 @@ pub fn handle_add(data_paths: &[String], file: &str) {
         }
     };
 
-    // 現在のデータを読み込む
-    let mut monsters = utils::load_monsters_or_exit(data_paths);
+    // 現在のデータを読み込み（読み込み元ファイルを記録）、追加または上書きする
+    let mut monsters = utils::load_monster_set_or_exit(data_paths, backup);
+    let saved_to = utils::add_record_or_exit(&mut monsters, new_monster.clone(), |m| &m.name, "モンスター", target_file);
 
-    // 重複チェック
-    if let Some(_duplicate) = query::find_by_exact_name(&monsters, &new_monster.name) {
-        // 確認ダイアログを表示
-        if !utils::confirm_action(&format!("\"{}\" という名前のモンスターは既に存在します。上書きしますか？", new_monster.name)) {
-            eprintln!("キャンセルされました");
+    // 変更のあったファイルのみ保存
+    utils::saved_files_or_exit(monsters.save());
+
+    println!("成功: \"{}\" を {} に追加しました", new_monster.name, saved_to.display());
+}
+
+/// インポートコマンドのハンドラ
+pub fn handle_import(file: &str, format: Option<&str>, output_dir: Option<&str>) {
+    let format = utils::import_format_or_exit(file, format, &["udonarium", "csv", "tsv"]);
+
+    let path = Path::new(file);
+    let outcome = match format.as_str() {
+        "udonarium" => UdonariumImporter::import_zip(file),
+        "csv" => DelimitedImporter::csv().import_monsters_file(path),
+        _ => DelimitedImporter::tsv().import_monsters_file(path),
+    };
+    let outcome = match outcome {
+        Ok(o) => o,
+        Err(e) => {
+            eprintln!("エラー: {} を読み込めません: {}", file, e);
+            process::exit(1);
+        }
+    };
+
+    utils::emit_import_or_exit(&outcome, output_dir, |m: &Monster| m.name.as_str(), "モンスター", "gm monster add");
+}
+
+/// シートからの取り込みコマンドのハンドラ
+pub fn handle_pull(
+    data_paths: &[String],
+    spreadsheet_id: &str,
+    target_file: Option<&str>,
+    yes: bool,
+    dry_run: bool,
+    options: &export::ExportOptions,
+    backup: &BackupPolicy,
+) {
+    let layout = match options.sheet_layout() {
+        Ok(layout) => layout,
+        Err(e) => {
+            eprintln!("エラー: {}", e);
             process::exit(1);
         }
+    };
 
-        // 既存のモンスターを削除
-        monsters.retain(|m| m.name != new_monster.name);
+    // 現在のデータを読み込む（読み込み元ファイルを記録）
+    let mut monsters = utils::load_monster_set_or_exit(data_paths, backup);
+    let target = utils::resolve_target_file_or_exit(&monsters, target_file);
+
+    let importer = SheetsImporter::new(layout).with_api_base_url(options.sheets_api_base_url.clone());
+    let outcome = match importer.import_spreadsheet(spreadsheet_id, monsters.records()) {
+        Ok(o) => o,
+        Err(e) => {
+            eprintln!("エラー: シートを読み込めません: {}", e);
+            process::exit(1);
+        }
+    };
+    for warning in &outcome.warnings {
+        eprintln!("警告: {}", warning);
     }
 
-    // モンスターを追加
-    monsters.push(new_monster.clone());
+    let changed: Vec<_> = outcome.records.iter().filter(|c| c.has_changes()).collect();
+    if changed.is_empty() {
+        println!("変更はありません（{} 体）", outcome.records.len());
+        return;
+    }
+
+    // 差分を表示
+    for change in &changed {
+        if change.is_new() {
+            println!("+ {}（新規、{} 部位）", change.monster.name, change.monster.part.len());
+        } else {
+            println!("~ {}", change.monster.name);
+            for field in &change.changes {
+                println!("    {}", field);
+            }
+        }
+    }
+    println!("{} 体のうち {} 体に変更があります", outcome.records.len(), changed.len());
 
-    // ファイルに保存（最初のファイルに保存）
-    if let Err(e) = io::save_json_array_file(&data_paths[0], &monsters) {
-        eprintln!("エラー: ファイルに保存できません: {}", e);
+    if dry_run {
+        return;
+    }
+
+    let mut accepted = 0;
+    for change in changed {
+        let action = if change.is_new() { "追加" } else { "更新" };
+        if !yes && !utils::confirm_action(&format!("\"{}\" を{}しますか？", change.monster.name, action)) {
+            continue;
+        }
+        // 既存のモンスターは所有ファイル内の同じ位置で置き換え、新規は追加先の末尾に追加する
+        if let Err(e) = change.apply(&mut monsters, &target) {
+            eprintln!("エラー: {}", e);
+            process::exit(1);
+        }
+        accepted += 1;
+    }
+
+    if accepted == 0 {
+        eprintln!("キャンセルされました");
         process::exit(1);
     }
 
-    println!("成功: \"{}\" を追加しました", new_monster.name);
+    // 変更のあったファイルのみ保存
+    let saved = utils::saved_files_or_exit(monsters.save());
+
+    for path in &saved {
+        println!("成功: {} を更新しました", path.display());
+    }
+    println!("成功: {} 体のモンスターをシートから取り込みました", accepted);
 }
 
 /// 削除コマンドのハンドラ
-pub fn handle_delete(data_paths: &[String], name: &str) {
-    // 現在のデータを読み込む
-    let mut monsters = utils::load_monsters_or_exit(data_paths);
+pub fn handle_delete(data_paths: &[String], name: &str, backup: &BackupPolicy) {
+    // 現在のデータを読み込む（読み込み元ファイルを記録）
+    let mut monsters = utils::load_monster_set_or_exit(data_paths, backup);
 
     // 完全一致で検索
-    if query::find_by_exact_name(&monsters, name).is_none() {
+    if query::find_by_exact_name(monsters.records(), name).is_none() {
         eprintln!("エラー: \"{}\" という名前のモンスターが見つかりません", name);
         process::exit(1);
     }
@@ -211,16 +389,15 @@ pub fn handle_delete(data_paths: &[String], name: &str) {
         process::exit(1);
     }
 
-    // モンスターを削除
-    monsters.retain(|m| m.name != name);
+    // モンスターを削除（所有ファイルのみ変更される）
+    monsters.remove_where(|m| m.name == name);
 
-    // ファイルに保存（最初のファイルに保存）
-    if let Err(e) = io::save_json_array_file(&data_paths[0], &monsters) {
-        eprintln!("エラー: ファイルに保存できません: {}", e);
-        process::exit(1);
-    }
+    // 変更のあったファイルのみ保存
+    let saved = utils::saved_files_or_exit(monsters.save());
 
-    println!("成功: \"{}\" を削除しました", name);
+    for path in &saved {
+        println!("成功: \"{}\" を {} から削除しました", name, path.display());
+    }
 }
 
 /// 統計コマンドのハンドラ
@@ -233,3 +410,8 @@ pub fn handle_stats(data_paths: &[String]) {
     // 整形して出力
     print!("{}", stats.format());
 }
+
+/// モンスター検証コマンドのハンドラ
+pub fn handle_validate(data_paths: &[String]) {
+    utils::validate_files_or_exit(validate::DataKind::Monster, data_paths);
+}
@@ -0,0 +1,62 @@
+use std::collections::HashMap;
+use std::process;
+use trpg_json_core::dice::{self, DiceRng};
+
+/// ダイスロールコマンドのハンドラ
+pub fn handle_roll(command: &str, var_args: &[String], vars_file: Option<&str>, seed: Option<u64>, times: usize) {
+    let mut variables = match vars_file {
+        Some(path) => load_variables_or_exit(path),
+        None => HashMap::new(),
+    };
+    for arg in var_args {
+        let Some((name, value)) = arg.split_once('=') else {
+            eprintln!("エラー: 変数は NAME=VALUE の形式で指定してください: {}", arg);
+            process::exit(1);
+        };
+        match value.trim().parse::<i32>() {
+            Ok(v) => {
+                variables.insert(name.trim().to_string(), v);
+            }
+            Err(_) => {
+                eprintln!("エラー: 変数 {} の値が整数ではありません: {}", name.trim(), value);
+                process::exit(1);
+            }
+        }
+    }
+
+    let mut rng = match seed {
+        Some(seed) => DiceRng::seeded(seed),
+        None => DiceRng::from_entropy(),
+    };
+
+    for _ in 0..times.max(1) {
+        match dice::roll_line(command, &variables, &mut rng) {
+            Ok(result) => println!("{}", result),
+            Err(e) => {
+                eprintln!("エラー: {}", e);
+                process::exit(1);
+            }
+        }
+    }
+}
+
+/// 変数定義の JSON オブジェクトを読み込む（整数以外の値は無視）
+fn load_variables_or_exit(path: &str) -> HashMap<String, i32> {
+    let content = match std::fs::read_to_string(path) {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("エラー: {} を読み込めません: {}", path, e);
+            process::exit(1);
+        }
+    };
+    match serde_json::from_str::<serde_json::Map<String, serde_json::Value>>(&content) {
+        Ok(map) => map
+            .into_iter()
+            .filter_map(|(k, v)| v.as_i64().and_then(|n| i32::try_from(n).ok()).map(|n| (k, n)))
+            .collect(),
+        Err(e) => {
+            eprintln!("エラー: {} は変数定義の JSON オブジェクトではありません: {}", path, e);
+            process::exit(1);
+        }
+    }
+}
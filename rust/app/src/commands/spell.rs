@@ -1,8 +1,13 @@
-use std::process;
-use trpg_json_core::{export, query, stats};
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::process::{self, Command};
+use trpg_json_core::import::delimited::DelimitedImporter;
+use trpg_json_core::{backup::BackupPolicy, export, query, stats, validate, Spell};
 use crate::utils;
 
 /// スペル検索コマンドのハンドラ
+#[allow(clippy::too_many_arguments)]
 pub fn handle_find(
     data_paths: &[String],
     name: &str,
@@ -11,6 +16,10 @@ pub fn handle_find(
     school: Option<&str>,
     school_variant: Option<&str>,
     god: Option<&str>,
+    filter_expr: Option<&str>,
+    export_format: Option<&str>,
+    output_dest: Option<&str>,
+    export_options: &export::ExportOptions,
 ) {
     // level と rank の同時指定チェック
     if level.is_some() && rank.is_some() {
@@ -18,28 +27,39 @@ pub fn handle_find(
         process::exit(1);
     }
 
+    let export = export_target_or_exit(export_format, output_dest);
+    let filter = filter_expr.map(utils::parse_filter_or_exit);
+
     let spells = utils::load_spells_or_exit(data_paths);
 
     // 検索を実行
-    let results = query::spell_find_multi(&spells, Some(name), school, level, rank, school_variant, god);
+    let mut results = query::spell_find_multi(&spells, Some(name), school, level, rank, school_variant, god);
+    if let Some(f) = &filter {
+        results.retain(|s| f.matches(*s));
+    }
 
     // 結果を処理
-    match results.len() {
-        0 => {
-            let error_msg = utils::format_spell_filter_conditions(Some(name), school, level, rank, school_variant, god);
+    match (results.len(), export) {
+        (0, _) => {
+            let error_msg = utils::format_spell_filter_conditions(Some(name), school, level, rank, school_variant, god, filter_expr);
             eprintln!("エラー: {}", error_msg);
+            utils::print_spell_suggestions(&spells, name);
             process::exit(1);
         }
-        1 => {
+        // エクスポート指定時はマッチした全件を書き出す
+        (_, Some((fmt, output))) => {
+            export_results(&results.iter().map(|&s| s.clone()).collect::<Vec<_>>(), fmt, output, export_options);
+        }
+        (1, None) => {
             // 1件の場合は JSON で出力
             utils::save_json_stdout_or_exit(&results.iter().map(|&s| s.clone()).collect::<Vec<_>>());
         }
-        n => {
+        (n, None) => {
             // 複数件の場合は件数を出力
             println!("{} 件のスペルが見つかりました", n);
             
-            // 完全一致するスペルがあればそのデータを出力
-            if let Some(exact_match) = results.iter().find(|s| s.name == name) {
+            // 完全一致（表記の違いを除く）するスペルがあればそのデータを出力
+            if let Some(exact_match) = results.iter().find(|s| query::fuzzy::same_name(&s.name, name)) {
                 let exact_spell = (*exact_match).clone();
                 utils::save_json_stdout_or_exit(&[exact_spell]);
             }
@@ -57,8 +77,9 @@ pub fn handle_list(data_paths: &[String], pattern: &str) {
     // 結果を処理
     match results.len() {
         0 => {
-            let error_msg = utils::format_spell_filter_conditions(Some(pattern), None, None, None, None, None);
+            let error_msg = utils::format_spell_filter_conditions(Some(pattern), None, None, None, None, None, None);
             eprintln!("エラー: {}", error_msg);
+            utils::print_spell_suggestions(&spells, pattern);
             process::exit(1);
         }
         1 => {
@@ -72,8 +93,8 @@ pub fn handle_list(data_paths: &[String], pattern: &str) {
                  println!("  - {} ({})", spell.name, spell.school);
              }
             
-            // 完全一致するスペルがあればそのデータを出力
-            if let Some(exact_match) = results.iter().find(|s| s.name == pattern) {
+            // 完全一致（表記の違いを除く）するスペルがあればそのデータを出力
+            if let Some(exact_match) = results.iter().find(|s| query::fuzzy::same_name(&s.name, pattern)) {
                 let exact_spell = (*exact_match).clone();
                 utils::save_json_stdout_or_exit(&[exact_spell]);
             }
@@ -81,6 +102,210 @@ pub fn handle_list(data_paths: &[String], pattern: &str) {
     }
 }
 
+/// スペル追加コマンドのハンドラ
+pub fn handle_add(data_paths: &[String], file: &str, target_file: Option<&str>, backup: &BackupPolicy) {
+    // JSON ファイルから新規スペルを読み込む（スキーマ検証を含む）
+    let content = match fs::read_to_string(file) {
+        Ok(content) => content,
+        Err(e) => {
+            eprintln!("エラー: ファイルを読み込めません: {}", e);
+            process::exit(1);
+        }
+    };
+    let new_spell = parse_spell_or_exit(&content, file);
+
+    // 現在のデータを読み込み（読み込み元ファイルを記録）、追加または上書きする
+    let mut spells = utils::load_spell_set_or_exit(data_paths, backup);
+    let saved_to = utils::add_record_or_exit(&mut spells, new_spell.clone(), |s| &s.name, "スペル", target_file);
+
+    // 変更のあったファイルのみ保存
+    utils::saved_files_or_exit(spells.save());
+
+    println!("成功: \"{}\" を {} に追加しました", new_spell.name, saved_to.display());
+}
+
+/// スペルインポートコマンドのハンドラ
+pub fn handle_import(file: &str, format: Option<&str>, output_dir: Option<&str>) {
+    let format = utils::import_format_or_exit(file, format, &["csv", "tsv"]);
+
+    let importer = if format == "tsv" { DelimitedImporter::tsv() } else { DelimitedImporter::csv() };
+    let outcome = match importer.import_spells_file(Path::new(file)) {
+        Ok(o) => o,
+        Err(e) => {
+            eprintln!("エラー: {} を読み込めません: {}", file, e);
+            process::exit(1);
+        }
+    };
+
+    utils::emit_import_or_exit(&outcome, output_dir, |s: &Spell| s.name.as_str(), "スペル", "gm spell add");
+}
+
+/// スペル削除コマンドのハンドラ
+pub fn handle_delete(data_paths: &[String], name: &str, backup: &BackupPolicy) {
+    // 現在のデータを読み込む（読み込み元ファイルを記録）
+    let mut spells = utils::load_spell_set_or_exit(data_paths, backup);
+
+    // 完全一致で検索
+    if query::spell_find_by_exact_name(spells.records(), name).is_none() {
+        eprintln!("エラー: \"{}\" という名前のスペルが見つかりません", name);
+        process::exit(1);
+    }
+
+    // 確認ダイアログを表示
+    if !utils::confirm_action(&format!("\"{}\" を削除しますか？", name)) {
+        eprintln!("キャンセルされました");
+        process::exit(1);
+    }
+
+    // スペルを削除（所有ファイルのみ変更される）
+    spells.remove_where(|s| s.name == name);
+
+    // 変更のあったファイルのみ保存
+    let saved = utils::saved_files_or_exit(spells.save());
+
+    for path in &saved {
+        println!("成功: \"{}\" を {} から削除しました", name, path.display());
+    }
+}
+
+/// スペル編集コマンドのハンドラ
+///
+/// `from` が指定された場合はその JSON ファイルの内容で置き換え、
+/// 未指定の場合は現在の JSON をエディタで開いて編集結果で置き換える
+pub fn handle_edit(data_paths: &[String], name: &str, from: Option<&str>, backup: &BackupPolicy) {
+    // 現在のデータを読み込む（読み込み元ファイルを記録）
+    let mut spells = utils::load_spell_set_or_exit(data_paths, backup);
+
+    let current = match query::spell_find_by_exact_name(spells.records(), name) {
+        Some(spell) => spell,
+        None => {
+            eprintln!("エラー: \"{}\" という名前のスペルが見つかりません", name);
+            process::exit(1);
+        }
+    };
+    let current_json = match serde_json::to_string_pretty(current) {
+        Ok(json) => json,
+        Err(e) => {
+            eprintln!("エラー: JSON 変換に失敗しました: {}", e);
+            process::exit(1);
+        }
+    };
+
+    // 編集後の内容を取得
+    let (content, origin) = match from {
+        Some(path) => match fs::read_to_string(path) {
+            Ok(content) => (content, path.to_string()),
+            Err(e) => {
+                eprintln!("エラー: ファイルを読み込めません: {}", e);
+                process::exit(1);
+            }
+        },
+        None => (edit_in_editor_or_exit(&current_json), name.to_string()),
+    };
+
+    if content.trim() == current_json.trim() {
+        println!("変更はありません");
+        return;
+    }
+
+    let edited = parse_spell_or_exit(&content, &origin);
+
+    // 名前を変更する場合は別スペルとの重複をチェック
+    if edited.name != name && query::spell_find_by_exact_name(spells.records(), &edited.name).is_some() {
+        eprintln!("エラー: \"{}\" という名前のスペルは既に存在します", edited.name);
+        process::exit(1);
+    }
+
+    // 確認ダイアログを表示
+    if !utils::confirm_action(&format!("\"{}\" を更新しますか？", name)) {
+        eprintln!("キャンセルされました");
+        process::exit(1);
+    }
+
+    // 所有ファイル内の同じ位置で置き換える
+    let position = spells.position(|s| s.name == name).unwrap();
+    let edited_name = edited.name.clone();
+    spells.replace(position, edited);
+
+    utils::saved_files_or_exit(spells.save());
+
+    if edited_name == name {
+        println!("成功: \"{}\" を更新しました", name);
+    } else {
+        println!("成功: \"{}\" を \"{}\" として更新しました", name, edited_name);
+    }
+}
+
+/// スペル JSON を解析する（スキーマ検証に失敗した場合は違反を表示して終了）
+fn parse_spell_or_exit(content: &str, origin: &str) -> Spell {
+    let value: serde_json::Value = match serde_json::from_str(content) {
+        Ok(v) => v,
+        Err(e) => {
+            eprintln!("エラー: JSON を解析できません: {}", e);
+            process::exit(1);
+        }
+    };
+
+    let diagnostics = validate::validate_record(validate::DataKind::Spell, &value);
+    if !diagnostics.is_empty() {
+        for mut diagnostic in diagnostics.iter().cloned() {
+            diagnostic.file = Some(origin.to_string());
+            eprintln!("{}", diagnostic);
+        }
+        eprintln!("エラー: スキーマ検証に失敗しました（{} 件の違反）", diagnostics.len());
+        process::exit(1);
+    }
+
+    match serde_json::from_value(value) {
+        Ok(spell) => spell,
+        Err(e) => {
+            eprintln!("エラー: JSON を解析できません: {}", e);
+            process::exit(1);
+        }
+    }
+}
+
+/// 一時ファイルを $VISUAL / $EDITOR（未設定時は vi）で開き、編集結果を返す
+fn edit_in_editor_or_exit(initial: &str) -> String {
+    let editor = env::var("VISUAL")
+        .or_else(|_| env::var("EDITOR"))
+        .unwrap_or_else(|_| "vi".to_string());
+    let path = env::temp_dir().join(format!("gm-spell-edit-{}.json", process::id()));
+
+    if let Err(e) = fs::write(&path, initial) {
+        eprintln!("エラー: 一時ファイルを作成できません: {}", e);
+        process::exit(1);
+    }
+
+    // "code --wait" のような引数付きの指定にも対応する
+    let mut words = editor.split_whitespace();
+    let program = words.next().unwrap_or("vi");
+    let status = Command::new(program).args(words).arg(&path).status();
+
+    let result = match status {
+        Ok(status) if status.success() => fs::read_to_string(&path),
+        Ok(status) => {
+            let _ = fs::remove_file(&path);
+            eprintln!("エラー: エディタが異常終了しました: {}", status);
+            process::exit(1);
+        }
+        Err(e) => {
+            let _ = fs::remove_file(&path);
+            eprintln!("エラー: エディタを起動できません ({}): {}", program, e);
+            process::exit(1);
+        }
+    };
+    let _ = fs::remove_file(&path);
+
+    match result {
+        Ok(content) => content,
+        Err(e) => {
+            eprintln!("エラー: 一時ファイルを読み込めません: {}", e);
+            process::exit(1);
+        }
+    }
+}
+
 /// スペル統計コマンドのハンドラ
 pub fn handle_stats(data_paths: &[String]) {
     let spells = utils::load_spells_or_exit(data_paths);
@@ -93,6 +318,7 @@ pub fn handle_stats(data_paths: &[String]) {
 }
 
 /// スペルパレットコマンドのハンドラ
+#[allow(clippy::too_many_arguments)]
 pub fn handle_palette(
     data_paths: &[String],
     name: Option<&str>,
@@ -101,7 +327,11 @@ pub fn handle_palette(
     school: Option<&str>,
     school_variant: Option<&str>,
     god: Option<&str>,
+    filter_expr: Option<&str>,
     copy: bool,
+    export_format: Option<&str>,
+    output_dest: Option<&str>,
+    export_options: &export::ExportOptions,
 ) {
     // level と rank の同時指定チェック
     if level.is_some() && rank.is_some() {
@@ -110,28 +340,37 @@ pub fn handle_palette(
     }
 
     // 最低1つのフィルタが必須
-    if name.is_none() && level.is_none() && rank.is_none() && school.is_none() && school_variant.is_none() && god.is_none() {
-        eprintln!("エラー: 最低1つのフィルタ（-n, -l, -r, -s, -v, -g）を指定してください");
+    if name.is_none() && level.is_none() && rank.is_none() && school.is_none() && school_variant.is_none() && god.is_none() && filter_expr.is_none() {
+        eprintln!("エラー: 最低1つのフィルタ（-n, -l, -r, -s, -v, -g, -w）を指定してください");
         process::exit(1);
     }
 
+    let export = export_target_or_exit(export_format, output_dest);
+    let filter = filter_expr.map(utils::parse_filter_or_exit);
+
     let spells = utils::load_spells_or_exit(data_paths);
 
     // マルチフィルタで検索
-    let results = query::spell_find_multi(&spells, name, school, level, rank, school_variant, god);
+    let mut results = query::spell_find_multi(&spells, name, school, level, rank, school_variant, god);
+    if let Some(f) = &filter {
+        results.retain(|s| f.matches(*s));
+    }
 
-    match results.len() {
-        0 => {
-            let error_msg = utils::format_spell_filter_conditions(name, school, level, rank, school_variant, god);
+    match (results.len(), export) {
+        (0, _) => {
+            let error_msg = utils::format_spell_filter_conditions(name, school, level, rank, school_variant, god, filter_expr);
             eprintln!("エラー: {}", error_msg);
             process::exit(1);
         }
-        _ => {
+        (_, Some((fmt, output))) => {
+            export_results(&results.iter().map(|&s| s.clone()).collect::<Vec<_>>(), fmt, output, export_options);
+        }
+        (_, None) => {
             // 全マッチしたスペルのパレットを複数行で出力
             let mut first_palette: Option<String> = None;
 
             for spell in &results {
-                match export::palette::generate_spell_palette(&spell) {
+                match export::palette::generate_spell_palette(spell) {
                     Ok(palette) => {
                         println!("{}", palette);
                         
@@ -147,18 +386,62 @@ pub fn handle_palette(
             }
 
             // --copy フラグが指定されている場合、先頭のパレットをクリップボードにコピー
-            if copy {
-                if let Some(palette) = first_palette {
-                    match utils::copy_to_clipboard(&palette) {
-                        Ok(_) => {
-                            eprintln!("✓ チャットパレット（先頭行）をクリップボードにコピーしました");
-                        }
-                        Err(e) => {
-                            eprintln!("警告: クリップボードへのコピーに失敗しました: {}", e);
-                        }
+            if copy
+                && let Some(palette) = first_palette
+            {
+                match utils::copy_to_clipboard(&palette) {
+                    Ok(_) => {
+                        eprintln!("✓ チャットパレット（先頭行）をクリップボードにコピーしました");
+                    }
+                    Err(e) => {
+                        eprintln!("警告: クリップボードへのコピーに失敗しました: {}", e);
                     }
                 }
             }
         }
     }
 }
+
+/// --export と --output の組を検証する（--export のみの指定はエラー）
+fn export_target_or_exit<'a>(export_format: Option<&'a str>, output_dest: Option<&'a str>) -> Option<(&'a str, &'a str)> {
+    match (export_format, output_dest) {
+        (Some(fmt), Some(output)) => Some((fmt, output)),
+        (Some(_), None) => {
+            eprintln!("エラー: --export を使用する場合は --output で出力先を指定してください");
+            process::exit(1);
+        }
+        _ => None,
+    }
+}
+
+/// スペルのエクスポート処理を実行
+pub fn export_results(spells: &[Spell], format: &str, output: &str, options: &export::ExportOptions) {
+    // エクスポーターを生成（組み込みの形式名または設定ファイルのテンプレート名）
+    let (export_format, exporter) = match export::ExporterFactory::create_named_spell_exporter(format, options) {
+        Ok(pair) => pair,
+        Err(e) => {
+            eprintln!("エラー: {}", e);
+            process::exit(1);
+        }
+    };
+
+    let config = export::ExportConfig {
+        destination: output.to_string(),
+        format: export_format,
+    };
+
+    match exporter.export_spells(spells, &config) {
+        Ok(()) => {
+            println!("成功: {} 件のスペルを {} にエクスポートしました", spells.len(), output);
+        }
+        Err(e) => {
+            eprintln!("エラー: エクスポートに失敗しました: {}", e);
+            process::exit(1);
+        }
+    }
+}
+
+/// スペル検証コマンドのハンドラ
+pub fn handle_validate(data_paths: &[String]) {
+    utils::validate_files_or_exit(validate::DataKind::Spell, data_paths);
+}
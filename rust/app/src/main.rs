@@ -1,4 +1,5 @@
 use clap::{Parser, Subcommand};
+use std::path::PathBuf;
 use std::process;
 use trpg_json_core::config;
 
@@ -32,6 +33,297 @@ enum Commands {
         #[command(subcommand)]
         command: SpellCommands,
     },
+
+    /// エンカウンター関連コマンド
+    Encounter {
+        #[command(subcommand)]
+        command: EncounterCommands,
+    },
+
+    /// 戦闘トラッカー（HP/MP/状態/ラウンドの管理）
+    Combat {
+        /// セッションファイルのパス（デフォルト: OS別のデータディレクトリ配下の trpg-json/combat.json）
+        #[arg(long, global = true)]
+        session: Option<String>,
+
+        #[command(subcommand)]
+        command: CombatCommands,
+    },
+
+    /// ダイスを振る（BCDice 形式、チャットパレットの行をそのまま指定可能）
+    /// 
+    /// 使用例:
+    ///   gm roll 2d6+5                                  # 加算ロール
+    ///   gm roll "k20+10@9"                             # 威力表ロール（威力20、クリティカル値9）
+    ///   gm roll "2d+{神聖魔法}+{行使修正}  ゴッド・ジャッジメント" -v 神聖魔法=7 -v 行使修正=1
+    ///   gm roll "2d+{神聖魔法}" --vars character.json  # 変数を JSON オブジェクトから読み込む
+    ///   gm roll 2d6 --seed 42                          # シードを指定して出目を再現
+    Roll {
+        /// ダイス式（空白以降はコメントとして扱う）
+        command: String,
+
+        /// プレースホルダの値（NAME=VALUE、複数指定可）
+        #[arg(short = 'v', long = "var", value_name = "NAME=VALUE")]
+        vars: Vec<String>,
+
+        /// プレースホルダの値を定義した JSON ファイル（{"神聖魔法": 7, ...}）
+        #[arg(long = "vars", value_name = "FILE")]
+        vars_file: Option<String>,
+
+        /// 乱数のシード（同じシードで同じ出目を再現）
+        #[arg(long)]
+        seed: Option<u64>,
+
+        /// 振る回数
+        #[arg(short = 'n', long, default_value_t = 1)]
+        times: usize,
+    },
+
+    /// Google Sheets エクスポート用の Google アカウント認証
+    Auth {
+        #[command(subcommand)]
+        command: AuthCommands,
+    },
+
+    /// データファイルの変更履歴（バックアップ）を表示する
+    /// 
+    /// 使用例:
+    ///   gm history                 # すべてのデータファイルの履歴を表示
+    ///   gm history monsters.json   # 指定したデータファイルの履歴を表示
+    History {
+        /// データファイル（設定済みのパスまたはファイル名、省略時はすべて）
+        file: Option<String>,
+    },
+
+    /// データファイルを以前の状態に戻す
+    /// 
+    /// 使用例:
+    ///   gm undo                        # 最後に変更されたデータファイルを直前の状態に戻す（続けて実行するとさらに前へ）
+    ///   gm undo monsters.json          # 指定したデータファイルを直前の状態に戻す
+    ///   gm undo monsters.json --to 3   # gm history の 3 番目の状態に戻す
+    Undo {
+        /// データファイル（設定済みのパスまたはファイル名、省略時は最後に変更されたファイル）
+        file: Option<String>,
+
+        /// 戻す履歴の番号（gm history の表示番号、省略時は現在の状態の1つ前）
+        #[arg(long, value_name = "N")]
+        to: Option<usize>,
+    },
+}
+
+#[derive(Subcommand)]
+enum AuthCommands {
+    /// Google にログインして認証情報を保存する（PKCE を使った OAuth 2.0）
+    /// 
+    /// 使用例:
+    ///   gm auth login                  # ブラウザを開き、localhost:8080 でコールバックを受け取る
+    ///   gm auth login --port 8765      # コールバックを受け取るポートを変更
+    ///   gm auth login --no-browser     # SSH 接続時など: 表示された URL を別の端末で開き、リダイレクト先の URL を貼り付ける
+    Login {
+        /// コールバックを受け取るポート（リダイレクト URI のポートを上書き）
+        #[arg(long)]
+        port: Option<u16>,
+
+        /// ブラウザとコールバックサーバーを使わず、リダイレクト先の URL を貼り付けて認証する
+        #[arg(long)]
+        no_browser: bool,
+    },
+
+    /// 保存した認証情報を削除する
+    Logout,
+
+    /// 認証情報の有効期限と OAuth 設定を表示する
+    Status,
+}
+
+#[derive(Subcommand)]
+enum EncounterCommands {
+    /// パーティに合わせたエンカウンター候補を作成する
+    /// 
+    /// 魔物の脅威度（レベル × 部位数 × HP 補正）の合計が
+    /// 予算（パーティレベル × 人数 × 難易度補正）に近い組み合わせを提示する
+    /// 
+    /// 使用例:
+    ///   gm encounter build --party-level 5 --party-size 4                 # 候補を表示
+    ///   gm encounter build --party-level 5 --party-size 4 -c 蛮族 -d hard  # カテゴリと難易度を指定
+    ///   gm encounter build --party-level 5 --party-size 4 --pick 2        # 候補2の魔物を JSON で出力
+    ///   gm encounter build --party-level 5 --party-size 4 --pick 2 --export udonarium --output enc.zip
+    ///   gm encounter build --party-level 5 --party-size 4 --pick 2 --export udonarium-room --output room.zip  # 駒を並べたルームデータ
+    Build {
+        /// パーティの平均レベル
+        #[arg(long)]
+        party_level: i32,
+
+        /// パーティの人数
+        #[arg(long)]
+        party_size: u32,
+
+        /// カテゴリで絞り込む（オプション）
+        #[arg(short = 'c', long)]
+        category: Option<String>,
+
+        /// 難易度（easy, normal, hard, deadly）
+        #[arg(short = 'd', long, default_value = "normal")]
+        difficulty: String,
+
+        /// 魔物の最低レベル（デフォルト: パーティレベル - 3）
+        #[arg(long)]
+        min_level: Option<i32>,
+
+        /// 魔物の最高レベル（デフォルト: パーティレベル + 2）
+        #[arg(long)]
+        max_level: Option<i32>,
+
+        /// 1つの候補に含める魔物の最大数（デフォルト: パーティ人数 + 2）
+        #[arg(long)]
+        max_monsters: Option<usize>,
+
+        /// 表示する候補の数
+        #[arg(short = 'n', long, default_value_t = 5)]
+        candidates: usize,
+
+        /// フィルタ式で魔物を絞り込む（オプション）
+        #[arg(short = 'w', long = "where", value_name = "EXPR")]
+        filter: Option<String>,
+
+        /// 選択する候補の番号（指定時は魔物を JSON で出力）
+        #[arg(long, value_name = "N")]
+        pick: Option<usize>,
+
+        /// エクスポート形式（json, sheets, udonarium, udonarium-room, csv, tsv, markdown, html, ccfolia、または設定ファイルのテンプレート名。--pick 未指定時は候補1）
+        #[arg(long)]
+        export: Option<String>,
+
+        /// エクスポート出力先
+        #[arg(long)]
+        output: Option<String>,
+
+        /// チャットパレットのプリセット（udonarium / udonarium-room / ccfolia 用。standard, classic、または設定ファイルで定義した名前）
+        #[arg(long, value_name = "PRESET")]
+        palette: Option<String>,
+
+        /// Google Sheets のレイアウトファイル（sheets 用。未指定時は設定ファイルの [export.sheets] または組み込みのレイアウト）
+        #[arg(long, value_name = "FILE")]
+        sheet_layout: Option<PathBuf>,
+
+        /// 書き込み先のシート名（sheets 用。レイアウトの sheet_name を上書き）
+        #[arg(long, value_name = "NAME")]
+        sheet_name: Option<String>,
+
+        /// 最初のモンスターを書き込む行（sheets 用。レイアウトの start_row を上書き）
+        #[arg(long, value_name = "ROW")]
+        start_row: Option<usize>,
+
+        /// 開始行以降の既存の行を消去してから書き込む（sheets 用）
+        #[arg(long)]
+        overwrite: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum CombatCommands {
+    /// 戦闘を開始する（魔物を部位ごとにインスタンス化）
+    /// 
+    /// 使用例:
+    ///   gm combat start ゴブリン*3 トレント     # ゴブリンA〜C とトレントで開始
+    ///   gm combat start --from encounter.json  # JSON 配列（gm encounter build --pick の出力など）から開始
+    Start {
+        /// モンスター名（完全一致、"名前*数" で複数体）
+        names: Vec<String>,
+
+        /// モンスターの JSON 配列ファイル
+        #[arg(long, value_name = "FILE")]
+        from: Option<String>,
+    },
+
+    /// 進行中の戦闘に魔物を追加する
+    /// 
+    /// 使用例:
+    ///   gm combat add ゴブリン*2
+    Add {
+        /// モンスター名（完全一致、"名前*数" で複数体）
+        names: Vec<String>,
+
+        /// モンスターの JSON 配列ファイル
+        #[arg(long, value_name = "FILE")]
+        from: Option<String>,
+    },
+
+    /// 状態表を表示する
+    Show,
+
+    /// ダメージを与える
+    /// 
+    /// 使用例:
+    ///   gm combat damage ゴブリンA 8             # 8 点のダメージ
+    ///   gm combat damage "トレント(根1)" 12 -p   # 防護点を差し引く物理ダメージ
+    ///   gm combat damage 3 5                     # 状態表の 3 番にダメージ
+    Damage {
+        /// 対象（部位ラベル、部位が1つの魔物のラベル、または状態表の番号）
+        target: String,
+
+        /// ダメージ量
+        amount: i32,
+
+        /// 防護点を差し引く
+        #[arg(short = 'p', long)]
+        physical: bool,
+    },
+
+    /// HP を回復する
+    Heal {
+        /// 対象（部位ラベル、部位が1つの魔物のラベル、または状態表の番号）
+        target: String,
+
+        /// 回復量
+        amount: i32,
+    },
+
+    /// MP を消費する
+    SpendMp {
+        /// 対象（部位ラベル、部位が1つの魔物のラベル、または状態表の番号）
+        target: String,
+
+        /// 消費量
+        amount: i32,
+    },
+
+    /// 状態異常・効果を管理する
+    /// 
+    /// 使用例:
+    ///   gm combat status add ゴブリンA 転倒
+    ///   gm combat status remove ゴブリンA 転倒
+    Status {
+        #[command(subcommand)]
+        command: CombatStatusCommands,
+    },
+
+    /// 手番を次に進める（一巡したらラウンドを進める）
+    Next,
+
+    /// 戦闘を終了してセッションを削除する
+    End,
+}
+
+#[derive(Subcommand)]
+enum CombatStatusCommands {
+    /// 状態を追加する
+    Add {
+        /// 対象（部位ラベル、部位が1つの魔物のラベル、または状態表の番号）
+        target: String,
+
+        /// 状態名
+        status: String,
+    },
+
+    /// 状態を解除する
+    Remove {
+        /// 対象（部位ラベル、部位が1つの魔物のラベル、または状態表の番号）
+        target: String,
+
+        /// 状態名
+        status: String,
+    },
 }
 
 #[derive(Subcommand)]
@@ -42,8 +334,9 @@ enum MonsterCommands {
     ///   gm monster find テスト           # 名前に「テスト」を含むモンスターを検索
     ///   gm monster find テスト -l 6      # 名前に「テスト」を含み、レベル6のモンスターを検索
     ///   gm monster find テスト -c 蛮族  # 名前に「テスト」を含み、カテゴリ「蛮族」のモンスターを検索
+    ///   gm monster find ごぶりん         # ひらがな・半角カナ、長音・中黒の違いは無視（見つからなければ近い名前を表示）
     Find {
-        /// 検索する名前（部分マッチ）
+        /// 検索する名前（部分マッチ、ひらがな・カタカナ、全角・半角、長音・中黒の違いを無視）
         name: String,
         
         /// レベルで絞り込む（オプション）
@@ -60,7 +353,7 @@ enum MonsterCommands {
     /// 使用例:
     ///   gm monster list テスト          # 名前に「テスト」を含むモンスターの一覧
     List {
-        /// 検索パターン（部分マッチ）
+        /// 検索パターン（部分マッチ、表記の違いは find と同じく無視）
         pattern: String,
     },
     
@@ -70,9 +363,14 @@ enum MonsterCommands {
     ///   gm monster select -l 6          # レベル6のモンスターをすべて取得
     ///   gm monster select -c 蛮族       # カテゴリ「蛮族」のモンスターをすべて取得
     ///   gm monster select -l 6 -c 蛮族  # レベル6かつカテゴリ「蛮族」のモンスターを取得
+    ///   gm monster select -w 'Lv in 5..8 and part.部位特殊能力 ~ 飛行' # フィルタ式で絞り込む
     ///   gm monster select -l 6 --export json --output results.json # 結果をJSONファイルにエクスポート
     ///   gm monster select -l 6 --export sheets --output "Spreadsheet ID" # Google Sheetsにエクスポート
     ///   gm monster select -l 6 --export udonarium --output monsters.zip # Udonarium形式にエクスポート
+    ///   gm monster select -c 蛮族 --export html --output 蛮族.html # 目次付きのステータスブロック（HTML）を出力
+    ///   gm monster select -n ゴブリン --export udonarium --palette classic --output goblin.zip # 判定のみのチャットパレットで出力
+    ///   gm monster select -l 6 --export sheets --output "Spreadsheet ID" --sheet-layout layout.toml --sheet-name 敵 # 独自のレイアウトで書き込む
+    ///   gm monster select -l 6 --export sheets --output "Spreadsheet ID" --overwrite # 既存の行を消去してから書き込む
     Select {
         /// 名前で検索（部分マッチ、オプション）
         #[arg(short = 'n', long)]
@@ -86,22 +384,51 @@ enum MonsterCommands {
         #[arg(short = 'c', long)]
         category: Option<String>,
         
-        /// エクスポート形式（json, sheets, udonarium）
+        /// フィルタ式で絞り込む（例: "Lv >= 5 and 弱点値 <= 15 and part.HP >= 50"）
+        #[arg(short = 'w', long = "where", value_name = "EXPR")]
+        filter: Option<String>,
+        
+        /// エクスポート形式（json, sheets, udonarium, udonarium-room, csv, tsv, markdown, html, ccfolia、または設定ファイルのテンプレート名）
         #[arg(long)]
         export: Option<String>,
         
         /// エクスポート出力先（JSONの場合: ファイルパス、Sheetsの場合: スプレッドシートID、Udonariumの場合: ZIPファイルパス）
         #[arg(long)]
         output: Option<String>,
+
+        /// チャットパレットのプリセット（udonarium / udonarium-room / ccfolia 用。standard, classic、または設定ファイルで定義した名前）
+        #[arg(long, value_name = "PRESET")]
+        palette: Option<String>,
+
+        /// Google Sheets のレイアウトファイル（sheets 用。未指定時は設定ファイルの [export.sheets] または組み込みのレイアウト）
+        #[arg(long, value_name = "FILE")]
+        sheet_layout: Option<PathBuf>,
+
+        /// 書き込み先のシート名（sheets 用。レイアウトの sheet_name を上書き）
+        #[arg(long, value_name = "NAME")]
+        sheet_name: Option<String>,
+
+        /// 最初のモンスターを書き込む行（sheets 用。レイアウトの start_row を上書き）
+        #[arg(long, value_name = "ROW")]
+        start_row: Option<usize>,
+
+        /// 開始行以降の既存の行を消去してから書き込む（sheets 用）
+        #[arg(long)]
+        overwrite: bool,
     },
-    
+
     /// モンスターを追加する
     /// 
     /// 使用例:
     ///   gm monster add monster.json      # monster.json からモンスターを追加
+    ///   gm monster add monster.json --file extra.json  # 追加先のデータファイルを指定
     Add {
         /// JSON ファイルパス（単一モンスター JSON）
         file: String,
+
+        /// 追加先のデータファイル（設定済みのパスまたはファイル名、未指定時は最初のファイル）
+        #[arg(long = "file", value_name = "DATA_FILE")]
+        target_file: Option<String>,
     },
     
     /// モンスターを削除する
@@ -113,11 +440,104 @@ enum MonsterCommands {
         name: String,
     },
     
+    /// ココフォリアに貼り付けるキャラクター JSON を出力する
+    /// 
+    /// 使用例:
+    ///   gm monster ccfolia ゴブリン               # 部位ごとの JSON を1行ずつ出力
+    ///   gm monster ccfolia トレント --part 2 --copy  # 2部位目をクリップボードにコピー
+    ///   gm monster ccfolia ゴブリン --palette classic  # 部位特殊能力・魔法の行を含めない
+    ///   gm monster select -c 蛮族 --export ccfolia --output ccfolia/  # 部位ごとの JSON ファイルを書き出す
+    Ccfolia {
+        /// モンスター名（完全一致を優先、なければ部分マッチで1件に絞れること）
+        name: String,
+
+        /// 出力する部位の番号（1始まり、省略時はすべての部位）
+        #[arg(short = 'p', long, value_name = "N")]
+        part: Option<usize>,
+
+        /// クリップボードにコピー（--part 未指定時は1部位目）
+        #[arg(long, short = 'y')]
+        copy: bool,
+
+        /// チャットパレットのプリセット（standard, classic、または設定ファイルで定義した名前）
+        #[arg(long, value_name = "PRESET")]
+        palette: Option<String>,
+    },
+
+    /// 他ツールのデータからモンスター JSON を復元する
+    /// 
+    /// 使用例:
+    ///   gm monster import export.zip                  # Udonarium の ZIP を読み込んで JSON を出力
+    ///   gm monster import export.zip -o imported/     # モンスターごとに JSON ファイルを書き出す
+    ///   gm monster import monsters.csv                # CSV / TSV（--export csv / tsv の形式）を読み込む
+    ///   gm monster add imported/トレント.json          # 書き出したファイルをデータに追加
+    Import {
+        /// 読み込むファイル
+        file: String,
+
+        /// 入力形式（udonarium, csv, tsv、省略時は拡張子から判定）
+        #[arg(long)]
+        format: Option<String>,
+
+        /// モンスターごとの JSON ファイルを書き出すディレクトリ（省略時は標準出力）
+        #[arg(short = 'o', long = "output-dir", value_name = "DIR")]
+        output_dir: Option<String>,
+    },
+
+    /// Google Sheets で編集したモンスターをデータに取り込む
+    /// 
+    /// --export sheets と同じレイアウトでシートを読み込み、ローカルのデータとの差分を表示して、
+    /// モンスターごとに確認してから保存する。
+    /// 
+    /// 使用例:
+    ///   gm monster pull "Spreadsheet ID"                 # 差分を表示してモンスターごとに確認
+    ///   gm monster pull "Spreadsheet ID" --dry-run       # 差分の表示のみ
+    ///   gm monster pull "Spreadsheet ID" --yes --file extra.json  # すべて取り込む（新しいモンスターは extra.json に追加）
+    ///   gm monster pull "Spreadsheet ID" --sheet-layout layout.toml --sheet-name 敵  # 独自のレイアウトで読み込む
+    Pull {
+        /// スプレッドシートID
+        spreadsheet_id: String,
+
+        /// Google Sheets のレイアウトファイル（未指定時は設定ファイルの [export.sheets] または組み込みのレイアウト）
+        #[arg(long, value_name = "FILE")]
+        sheet_layout: Option<PathBuf>,
+
+        /// 読み込むシート名（レイアウトの sheet_name を上書き）
+        #[arg(long, value_name = "NAME")]
+        sheet_name: Option<String>,
+
+        /// 最初のモンスターの行（レイアウトの start_row を上書き）
+        #[arg(long, value_name = "ROW")]
+        start_row: Option<usize>,
+
+        /// 新しいモンスターの追加先のデータファイル（設定済みのパスまたはファイル名、未指定時は最初のファイル）
+        #[arg(long = "file", value_name = "DATA_FILE")]
+        target_file: Option<String>,
+
+        /// 確認せずにすべての変更を取り込む
+        #[arg(long, short = 'y')]
+        yes: bool,
+
+        /// 差分を表示するだけで保存しない
+        #[arg(long)]
+        dry_run: bool,
+    },
+
     /// データセット統計情報を表示する
     /// 
     /// 使用例:
     ///   gm monster stats  # モンスターデータの統計を表示
     Stats,
+    
+    /// モンスターデータをスキーマで検証する
+    /// 
+    /// 使用例:
+    ///   gm monster validate                # 設定ファイルのすべてのデータファイルを検証
+    ///   gm monster validate monsters.json  # 指定したファイルを検証
+    Validate {
+        /// 検証するファイル（省略時は設定ファイルのデータファイル）
+        files: Vec<String>,
+    },
 }
 
 #[derive(Subcommand)]
@@ -131,8 +551,12 @@ enum SpellCommands {
     ///   gm spell find 妖精 -r 3          # 名前に「妖精」を含み、ランク3のスペルを検索
     ///   gm spell find 神聖 -v 特殊       # 名前に「神聖」を含み、schoolVariant「特殊」のスペルを検索
     ///   gm spell find 神聖 -v 特殊 -g 神名  # schoolVariant「特殊」かつgod「神名」のスペルを検索
+    ///   gm spell find "" -w 'MP <= 5 and 対象.kind == エリア'  # フィルタ式で絞り込む
+    ///   gm spell find ボルト --export json --output spells.json     # マッチした全件をJSONファイルにエクスポート
+    ///   gm spell find "" -s 神聖 --export markdown --output 神聖.md  # スペルカードを Markdown で出力
+    ///   gm spell find "" -s 神聖 --export sheets --output "Spreadsheet ID" --sheet-name 神聖 --start-row 3  # シートと開始行を指定
     Find {
-        /// 検索する名前（部分マッチ）
+        /// 検索する名前（部分マッチ、ひらがな・カタカナ、全角・半角、長音・中黒の違いを無視）
         name: String,
         
         /// レベルで絞り込む（オプション、-rと同時指定不可）
@@ -154,14 +578,43 @@ enum SpellCommands {
         /// godで絞り込む（オプション）
         #[arg(short = 'g', long)]
         god: Option<String>,
+        
+        /// フィルタ式で絞り込む（例: "MP <= 5 and 抵抗 == 消滅"）
+        #[arg(short = 'w', long = "where", value_name = "EXPR")]
+        filter: Option<String>,
+
+        /// エクスポート形式（json, sheets, udonarium, csv, tsv, markdown, html、または設定ファイルのテンプレート名）
+        #[arg(long)]
+        export: Option<String>,
+
+        /// エクスポート出力先（JSONの場合: ファイルパス、Sheetsの場合: スプレッドシートID、Udonariumの場合: ZIPファイルパス）
+        #[arg(long)]
+        output: Option<String>,
+
+        /// チャットパレットのプリセット（udonarium 用。プリセットのダイスボットを使う。standard, classic、または設定ファイルで定義した名前）
+        #[arg(long, value_name = "PRESET")]
+        palette: Option<String>,
+
+        /// 書き込み先のシート名（sheets 用。既定: spell）
+        #[arg(long, value_name = "NAME")]
+        sheet_name: Option<String>,
+
+        /// 最初のスペルを書き込む行（sheets 用。既定: 2、上の行は見出し行）
+        #[arg(long, value_name = "ROW")]
+        start_row: Option<usize>,
+
+        /// 開始行以降の既存の行を消去してから書き込む（sheets 用）
+        #[arg(long)]
+        overwrite: bool,
     },
     
     /// スペル名一覧を取得する
     /// 
     /// 使用例:
     ///   gm spell list ファイア          # 名前に「ファイア」を含むスペルの一覧
+    ///   gm spell list えねるぎーぼると  # 「エネルギー・ボルト」に一致（見つからなければ近い名前を表示）
     List {
-        /// 検索パターン（部分マッチ）
+        /// 検索パターン（部分マッチ、表記の違いは find と同じく無視）
         pattern: String,
     },
     
@@ -176,6 +629,9 @@ enum SpellCommands {
     ///   gm spell palette -g "神名"                 # godでフィルタ
     ///   gm spell palette -n "ファイア" -s "MagicCat_1"  # 複数フィルタ
     ///   gm spell palette -n "ファイア" --copy     # 先頭行をクリップボードにコピー
+    ///   gm spell palette -w '補助 and 射程 == 接触' # フィルタ式で絞り込む
+    ///   gm spell palette -s 真語 --export udonarium --output 真語.zip  # Udonarium のチャットパレット駒として出力
+    ///   gm spell palette -s 真語 --export udonarium --output 真語.zip --palette sw20  # プリセットのダイスボットで出力
     Palette {
         /// スペル名（部分マッチ、オプション）
         #[arg(short = 'n')]
@@ -201,9 +657,92 @@ enum SpellCommands {
         #[arg(short = 'g', long)]
         god: Option<String>,
         
+        /// フィルタ式で絞り込む（オプション）
+        #[arg(short = 'w', long = "where", value_name = "EXPR")]
+        filter: Option<String>,
+        
         /// クリップボードにコピー（オプション、先頭行のみ）
         #[arg(long, short = 'y')]
         copy: bool,
+
+        /// エクスポート形式（json, sheets, udonarium, csv, tsv, markdown, html、または設定ファイルのテンプレート名）
+        #[arg(long)]
+        export: Option<String>,
+
+        /// エクスポート出力先（JSONの場合: ファイルパス、Sheetsの場合: スプレッドシートID、Udonariumの場合: ZIPファイルパス）
+        #[arg(long)]
+        output: Option<String>,
+
+        /// チャットパレットのプリセット（udonarium 用。プリセットのダイスボットを使う。standard, classic、または設定ファイルで定義した名前）
+        #[arg(long, value_name = "PRESET")]
+        palette: Option<String>,
+
+        /// 書き込み先のシート名（sheets 用。既定: spell）
+        #[arg(long, value_name = "NAME")]
+        sheet_name: Option<String>,
+
+        /// 最初のスペルを書き込む行（sheets 用。既定: 2、上の行は見出し行）
+        #[arg(long, value_name = "ROW")]
+        start_row: Option<usize>,
+
+        /// 開始行以降の既存の行を消去してから書き込む（sheets 用）
+        #[arg(long)]
+        overwrite: bool,
+    },
+    
+    /// スペルを追加する
+    /// 
+    /// 使用例:
+    ///   gm spell add spell.json                    # spell.json からスペルを追加
+    ///   gm spell add spell.json --file extra.json  # 追加先のデータファイルを指定
+    Add {
+        /// JSON ファイルパス（単一スペル JSON）
+        file: String,
+
+        /// 追加先のデータファイル（設定済みのパスまたはファイル名、未指定時は最初のファイル）
+        #[arg(long = "file", value_name = "DATA_FILE")]
+        target_file: Option<String>,
+    },
+    
+    /// CSV / TSV からスペル JSON を復元する
+    /// 
+    /// 使用例:
+    ///   gm spell import spells.csv                 # JSON を標準出力に出力
+    ///   gm spell import spells.tsv -o imported/    # スペルごとに JSON ファイルを書き出す
+    Import {
+        /// 読み込むファイル
+        file: String,
+
+        /// 入力形式（csv, tsv、省略時は拡張子から判定）
+        #[arg(long)]
+        format: Option<String>,
+
+        /// スペルごとの JSON ファイルを書き出すディレクトリ（省略時は標準出力）
+        #[arg(short = 'o', long = "output-dir", value_name = "DIR")]
+        output_dir: Option<String>,
+    },
+
+    /// スペルを削除する
+    /// 
+    /// 使用例:
+    ///   gm spell delete "スペル名"  # 完全一致するスペルを削除
+    Delete {
+        /// 対象スペルの正確な名前
+        name: String,
+    },
+    
+    /// スペルを編集する
+    /// 
+    /// 使用例:
+    ///   gm spell edit "スペル名"                   # $EDITOR で JSON を編集
+    ///   gm spell edit "スペル名" --from new.json   # new.json の内容で置き換える
+    Edit {
+        /// 対象スペルの正確な名前
+        name: String,
+
+        /// 置き換える内容の JSON ファイル（未指定時は $VISUAL / $EDITOR を起動）
+        #[arg(long, value_name = "FILE")]
+        from: Option<String>,
     },
     
     /// データセット統計情報を表示する
@@ -211,11 +750,37 @@ enum SpellCommands {
     /// 使用例:
     ///   gm spell stats  # スペルデータの統計を表示
     Stats,
+    
+    /// スペルデータをスキーマで検証する
+    /// 
+    /// 使用例:
+    ///   gm spell validate              # 設定ファイルのすべてのデータファイルを検証
+    ///   gm spell validate spells.json  # 指定したファイルを検証
+    Validate {
+        /// 検証するファイル（省略時は設定ファイルのデータファイル）
+        files: Vec<String>,
+    },
 }
 
 fn main() {
     let cli = Cli::parse();
 
+    // ダイスロールはデータファイルを使わないため、設定を読み込まずに処理する
+    if let Some(Commands::Roll { command, vars, vars_file, seed, times }) = &cli.command {
+        commands::roll::handle_roll(command, vars, vars_file.as_deref(), *seed, *times);
+        return;
+    }
+
+    // 認証はデータファイルを使わないため、設定を読み込まずに処理する
+    if let Some(Commands::Auth { command }) = &cli.command {
+        match command {
+            AuthCommands::Login { port, no_browser } => commands::auth::handle_login(*port, *no_browser),
+            AuthCommands::Logout => commands::auth::handle_logout(),
+            AuthCommands::Status => commands::auth::handle_status(),
+        }
+        return;
+    }
+
     // 設定ファイルを読み込む
     let cfg = load_config(&cli.config);
     
@@ -224,6 +789,8 @@ fn main() {
     let home_dir = dirs::home_dir();
     let monster_paths = cfg.resolve_monsters_paths(home_dir.as_deref());
     let spell_paths = cfg.resolve_spells_paths(home_dir.as_deref());
+    let backup_policy = cfg.backup_policy(home_dir.as_deref());
+    let export_options = cfg.export_options(home_dir.as_deref());
     
     // モンスターパスが存在するかチェック
     for data_path in &monster_paths {
@@ -260,38 +827,174 @@ fn main() {
                 MonsterCommands::List { pattern } => {
                     commands::monster::handle_list(&monster_path_strs, pattern);
                 }
-                MonsterCommands::Select { name, level, category, export: export_format, output } => {
-                    commands::monster::handle_select(&monster_path_strs, name.as_deref(), *level, category.as_deref(), export_format.as_deref(), output.as_deref());
+                MonsterCommands::Select { name, level, category, filter, export: export_format, output, palette, sheet_layout, sheet_name, start_row, overwrite } => {
+                    let export_options = export_options
+                        .clone()
+                        .with_palette_preset(palette.as_deref())
+                        .with_sheet_layout(sheet_layout.clone(), sheet_name.clone(), *start_row)
+                        .with_sheets_overwrite(*overwrite)
+                        .with_spells(&utils::load_spells_or_warn(&spell_path_strs));
+                    commands::monster::handle_select(&monster_path_strs, name.as_deref(), *level, category.as_deref(), filter.as_deref(), export_format.as_deref(), output.as_deref(), &export_options);
                 }
-                MonsterCommands::Add { file } => {
-                    commands::monster::handle_add(&monster_path_strs, file);
+                MonsterCommands::Add { file, target_file } => {
+                    commands::monster::handle_add(&monster_path_strs, file, target_file.as_deref(), &backup_policy);
                 }
                 MonsterCommands::Delete { name } => {
-                    commands::monster::handle_delete(&monster_path_strs, name);
+                    commands::monster::handle_delete(&monster_path_strs, name, &backup_policy);
+                }
+                MonsterCommands::Ccfolia { name, part, copy, palette } => {
+                    let export_options = export_options
+                        .clone()
+                        .with_palette_preset(palette.as_deref())
+                        .with_spells(&utils::load_spells_or_warn(&spell_path_strs));
+                    commands::monster::handle_ccfolia(&monster_path_strs, name, *part, *copy, &export_options);
+                }
+                MonsterCommands::Import { file, format, output_dir } => {
+                    commands::monster::handle_import(file, format.as_deref(), output_dir.as_deref());
+                }
+                MonsterCommands::Pull { spreadsheet_id, sheet_layout, sheet_name, start_row, target_file, yes, dry_run } => {
+                    let export_options = export_options.clone().with_sheet_layout(sheet_layout.clone(), sheet_name.clone(), *start_row);
+                    commands::monster::handle_pull(&monster_path_strs, spreadsheet_id, target_file.as_deref(), *yes, *dry_run, &export_options, &backup_policy);
                 }
                 MonsterCommands::Stats => {
                     commands::monster::handle_stats(&monster_path_strs);
                 }
+                MonsterCommands::Validate { files } => {
+                    let targets = if files.is_empty() { &monster_path_strs } else { files };
+                    commands::monster::handle_validate(targets);
+                }
             }
         }
 
         Some(Commands::Spell { command }) => {
             match command {
-                SpellCommands::Find { name, level, rank, school, school_variant, god } => {
-                    commands::spell::handle_find(&spell_path_strs, name, *level, *rank, school.as_deref(), school_variant.as_deref(), god.as_deref());
+                SpellCommands::Find { name, level, rank, school, school_variant, god, filter, export: export_format, output, palette, sheet_name, start_row, overwrite } => {
+                    let export_options = export_options
+                        .clone()
+                        .with_palette_preset(palette.as_deref())
+                        .with_spell_sheet(sheet_name.clone(), *start_row)
+                        .with_sheets_overwrite(*overwrite);
+                    commands::spell::handle_find(&spell_path_strs, name, *level, *rank, school.as_deref(), school_variant.as_deref(), god.as_deref(), filter.as_deref(), export_format.as_deref(), output.as_deref(), &export_options);
                 }
                 SpellCommands::List { pattern } => {
                     commands::spell::handle_list(&spell_path_strs, pattern);
                 }
-                SpellCommands::Palette { name, level, rank, school, school_variant, god, copy } => {
-                    commands::spell::handle_palette(&spell_path_strs, name.as_deref(), *level, *rank, school.as_deref(), school_variant.as_deref(), god.as_deref(), *copy);
+                SpellCommands::Palette { name, level, rank, school, school_variant, god, filter, copy, export: export_format, output, palette, sheet_name, start_row, overwrite } => {
+                    let export_options = export_options
+                        .clone()
+                        .with_palette_preset(palette.as_deref())
+                        .with_spell_sheet(sheet_name.clone(), *start_row)
+                        .with_sheets_overwrite(*overwrite);
+                    commands::spell::handle_palette(&spell_path_strs, name.as_deref(), *level, *rank, school.as_deref(), school_variant.as_deref(), god.as_deref(), filter.as_deref(), *copy, export_format.as_deref(), output.as_deref(), &export_options);
+                }
+                SpellCommands::Add { file, target_file } => {
+                    commands::spell::handle_add(&spell_path_strs, file, target_file.as_deref(), &backup_policy);
+                }
+                SpellCommands::Import { file, format, output_dir } => {
+                    commands::spell::handle_import(file, format.as_deref(), output_dir.as_deref());
+                }
+                SpellCommands::Delete { name } => {
+                    commands::spell::handle_delete(&spell_path_strs, name, &backup_policy);
+                }
+                SpellCommands::Edit { name, from } => {
+                    commands::spell::handle_edit(&spell_path_strs, name, from.as_deref(), &backup_policy);
                 }
                 SpellCommands::Stats => {
                     commands::spell::handle_stats(&spell_path_strs);
                 }
+                SpellCommands::Validate { files } => {
+                    let targets = if files.is_empty() { &spell_path_strs } else { files };
+                    commands::spell::handle_validate(targets);
+                }
             }
         }
 
+        Some(Commands::Encounter { command }) => {
+            match command {
+                EncounterCommands::Build {
+                    party_level,
+                    party_size,
+                    category,
+                    difficulty,
+                    min_level,
+                    max_level,
+                    max_monsters,
+                    candidates,
+                    filter,
+                    pick,
+                    export: export_format,
+                    output,
+                    palette,
+                    sheet_layout,
+                    sheet_name,
+                    start_row,
+                    overwrite,
+                } => {
+                    let options = commands::encounter::BuildOptions {
+                        party_level: *party_level,
+                        party_size: *party_size,
+                        category: category.as_deref(),
+                        difficulty,
+                        min_level: *min_level,
+                        max_level: *max_level,
+                        max_monsters: *max_monsters,
+                        candidates: *candidates,
+                        filter_expr: filter.as_deref(),
+                        pick: *pick,
+                        export_format: export_format.as_deref(),
+                        output_dest: output.as_deref(),
+                    };
+                    let export_options = export_options
+                        .clone()
+                        .with_palette_preset(palette.as_deref())
+                        .with_sheet_layout(sheet_layout.clone(), sheet_name.clone(), *start_row)
+                        .with_sheets_overwrite(*overwrite)
+                        .with_spells(&utils::load_spells_or_warn(&spell_path_strs));
+                    commands::encounter::handle_build(&monster_path_strs, &options, &export_options);
+                }
+            }
+        }
+
+        Some(Commands::Combat { session, command }) => {
+            use commands::combat::CombatAction;
+
+            let session_path = session
+                .as_ref()
+                .map(std::path::PathBuf::from)
+                .unwrap_or_else(commands::combat::default_session_path);
+            let action = match command {
+                CombatCommands::Start { names, from } => CombatAction::Start { names, from: from.as_deref() },
+                CombatCommands::Add { names, from } => CombatAction::Add { names, from: from.as_deref() },
+                CombatCommands::Show => CombatAction::Show,
+                CombatCommands::Damage { target, amount, physical } => {
+                    CombatAction::Damage { target, amount: *amount, physical: *physical }
+                }
+                CombatCommands::Heal { target, amount } => CombatAction::Heal { target, amount: *amount },
+                CombatCommands::SpendMp { target, amount } => CombatAction::SpendMp { target, amount: *amount },
+                CombatCommands::Status { command } => match command {
+                    CombatStatusCommands::Add { target, status } => CombatAction::StatusAdd { target, status },
+                    CombatStatusCommands::Remove { target, status } => CombatAction::StatusRemove { target, status },
+                },
+                CombatCommands::Next => CombatAction::Next,
+                CombatCommands::End => CombatAction::End,
+            };
+            commands::combat::handle_combat(&monster_path_strs, &session_path, action);
+        }
+
+        Some(Commands::Roll { .. }) => unreachable!("gm roll は設定の読み込み前に処理済み"),
+
+        Some(Commands::Auth { .. }) => unreachable!("gm auth は設定の読み込み前に処理済み"),
+
+        Some(Commands::History { file }) => {
+            let data_paths: Vec<String> = monster_path_strs.iter().chain(&spell_path_strs).cloned().collect();
+            commands::history::handle_history(&data_paths, file.as_deref(), &backup_policy);
+        }
+
+        Some(Commands::Undo { file, to }) => {
+            let data_paths: Vec<String> = monster_path_strs.iter().chain(&spell_path_strs).cloned().collect();
+            commands::history::handle_undo(&data_paths, file.as_deref(), *to, &backup_policy);
+        }
+
         None => {
             eprintln!("gm: サブコマンドを指定してください (--help で確認できます)");
             process::exit(1);
@@ -1,6 +1,7 @@
 use std::io::{self as std_io, Write};
+use std::path::{Path, PathBuf};
 use std::process;
-use trpg_json_core::{io, Monster, Spell};
+use trpg_json_core::{backup::BackupPolicy, dataset::{self, DataSet}, import::ImportOutcome, io, query, query::Filter, validate, Monster, Spell};
 use serde::Serialize;
 
 /// モンスターデータを読み込む（失敗時はエラーを表示して終了）
@@ -29,6 +30,185 @@ pub fn load_spells_or_exit(data_paths: &[String]) -> Vec<Spell> {
     }
 }
 
+/// スペルデータを読み込む（チャットパレットの「呪文」の判定用）
+///
+/// 存在しないファイルは読み飛ばし、読み込めない場合は警告を表示して空の一覧を返す。
+pub fn load_spells_or_warn(data_paths: &[String]) -> Vec<Spell> {
+    let existing: Vec<&str> = data_paths
+        .iter()
+        .filter(|p| Path::new(p.as_str()).exists())
+        .map(|p| p.as_str())
+        .collect();
+    match io::load_multiple_spells_json_arrays(&existing) {
+        Ok(s) => s,
+        Err(e) => {
+            eprintln!("警告: スペルデータを読み込めないため、呪文の判定値を省略します: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+/// 読み込み元付きでモンスターデータを読み込む（保存時にバックアップを作成、失敗時はエラーを表示して終了）
+pub fn load_monster_set_or_exit(data_paths: &[String], backup: &BackupPolicy) -> DataSet<Monster> {
+    match DataSet::load_monsters(data_paths) {
+        Ok(set) => set.with_backup(backup.clone()),
+        Err(e) => {
+            eprintln!("エラー: {}", e);
+            process::exit(1);
+        }
+    }
+}
+
+/// 読み込み元付きでスペルデータを読み込む（保存時にバックアップを作成、失敗時はエラーを表示して終了）
+pub fn load_spell_set_or_exit(data_paths: &[String], backup: &BackupPolicy) -> DataSet<Spell> {
+    match DataSet::load_spells(data_paths) {
+        Ok(set) => set.with_backup(backup.clone()),
+        Err(e) => {
+            eprintln!("エラー: {}", e);
+            process::exit(1);
+        }
+    }
+}
+
+/// 追加先のデータファイルを決定する（未指定時は最初のファイル、該当なしはエラーで終了）
+pub fn resolve_target_file_or_exit<T>(set: &DataSet<T>, target: Option<&str>) -> PathBuf {
+    resolve_data_file_or_exit(set.files(), target)
+}
+
+/// データファイルの一覧から対象のファイルを決定する（データを読み込まずに使える）
+///
+/// 指定は完全なパス、ファイル名、またはパスの末尾。未指定時は最初のファイル、該当なしはエラーで終了。
+pub fn resolve_data_file_or_exit<P: AsRef<Path>>(files: &[P], target: Option<&str>) -> PathBuf {
+    let resolved = match target {
+        Some(which) => dataset::resolve_path(files, which),
+        None => files.first().map(AsRef::as_ref),
+    };
+    match resolved {
+        Some(path) => path.to_path_buf(),
+        None => {
+            eprintln!("エラー: データファイルが見つかりません: {}", target.unwrap_or(""));
+            eprintln!("設定されているデータファイル:");
+            for file in files {
+                eprintln!("  - {}", file.as_ref().display());
+            }
+            process::exit(1);
+        }
+    }
+}
+
+/// レコードを指定データファイルに追加する（失敗時はエラーを表示して終了）
+pub fn push_record_or_exit<T>(set: &mut DataSet<T>, record: T, target: &Path) {
+    if let Err(e) = set.push(record, target) {
+        eprintln!("エラー: {}", e);
+        process::exit(1);
+    }
+}
+
+/// レコードを追加する（同名のレコードがあれば確認の上で上書き、キャンセル時は終了）
+///
+/// 追加先が指定され、同名のレコードが別のファイルにある場合は既存を削除して指定ファイルへ移す。
+/// それ以外で同名のレコードがある場合は、所有ファイル内の同じ位置で置き換える。
+///
+/// # 引数
+/// * `set` - 追加先のデータセット
+/// * `record` - 追加するレコード
+/// * `name` - レコードの名前を取り出す関数
+/// * `kind` - 確認メッセージに使うデータの種類（"モンスター"、"スペル"）
+/// * `target_file` - 追加先のデータファイル（未指定時は同名のレコードの所有ファイル、新規なら最初のファイル）
+///
+/// # 戻り値
+/// レコードを追加したデータファイル
+pub fn add_record_or_exit<T, F>(set: &mut DataSet<T>, record: T, name: F, kind: &str, target_file: Option<&str>) -> PathBuf
+where
+    F: Fn(&T) -> &str,
+{
+    let target = target_file.map(|t| resolve_target_file_or_exit(set, Some(t)));
+    let record_name = name(&record).to_string();
+
+    // 重複チェック
+    let Some(existing) = set.find_source(|r| name(r) == record_name).cloned() else {
+        let target = target.unwrap_or_else(|| resolve_target_file_or_exit(set, None));
+        push_record_or_exit(set, record, &target);
+        return target;
+    };
+
+    // 確認ダイアログを表示
+    if !confirm_action(&format!(
+        "\"{}\" という名前の{}は既に存在します（{}）。上書きしますか？",
+        record_name,
+        kind,
+        existing.path.display()
+    )) {
+        eprintln!("キャンセルされました");
+        process::exit(1);
+    }
+
+    match target {
+        // 別ファイルが指定された場合は既存を削除して指定ファイルへ移す
+        Some(target) if target != existing.path => {
+            set.remove_where(|r| name(r) == record_name);
+            push_record_or_exit(set, record, &target);
+            target
+        }
+        // それ以外は所有ファイル内の同じ位置で置き換える
+        _ => {
+            if let Some(position) = set.position(|r| name(r) == record_name) {
+                set.replace(position, record);
+            }
+            existing.path
+        }
+    }
+}
+
+/// 変更されたデータファイルの保存結果を処理する（失敗時はエラーを表示して終了）
+pub fn saved_files_or_exit(result: Result<Vec<PathBuf>, io::IoError>) -> Vec<PathBuf> {
+    match result {
+        Ok(saved) => saved,
+        Err(e) => {
+            eprintln!("エラー: ファイルに保存できません: {}", e);
+            process::exit(1);
+        }
+    }
+}
+
+/// フィルタ式を解析する（失敗時はエラーを表示して終了）
+pub fn parse_filter_or_exit(expr: &str) -> Filter {
+    match Filter::parse(expr) {
+        Ok(f) => f,
+        Err(e) => {
+            eprintln!("エラー: {}", e);
+            eprintln!("  {}", expr);
+            process::exit(1);
+        }
+    }
+}
+
+/// データファイルをスキーマで検証して結果を表示する（違反があれば終了コード1で終了）
+pub fn validate_files_or_exit(kind: validate::DataKind, data_paths: &[String]) {
+    let mut violation_count = 0;
+
+    for path in data_paths {
+        match validate::validate_file(kind, path) {
+            Ok(diagnostics) => {
+                for diagnostic in &diagnostics {
+                    println!("{}", diagnostic);
+                }
+                violation_count += diagnostics.len();
+            }
+            Err(e) => {
+                println!("{}: {}", path, e);
+                violation_count += 1;
+            }
+        }
+    }
+
+    if violation_count > 0 {
+        eprintln!("エラー: {} 件の違反が見つかりました（{} ファイル）", violation_count, data_paths.len());
+        process::exit(1);
+    }
+    eprintln!("✓ スキーマ検証に成功しました（{} ファイル）", data_paths.len());
+}
+
 /// データをJSONとして標準出力に出力する（失敗時はエラーを表示して終了）
 pub fn save_json_stdout_or_exit<T: Serialize>(data: &[T]) {
     let json = match serde_json::to_string_pretty(data) {
@@ -41,6 +221,91 @@ pub fn save_json_stdout_or_exit<T: Serialize>(data: &[T]) {
     println!("{}", json);
 }
 
+/// インポートの入力形式を決める（未指定時は拡張子から推定）
+///
+/// `.zip` は udonarium、`.csv` は csv、`.tsv` `.tab` は tsv。`supported` に無い形式はエラーで終了する。
+pub fn import_format_or_exit(file: &str, format: Option<&str>, supported: &[&str]) -> String {
+    let lower = file.to_lowercase();
+    let format = match format {
+        Some(f) => f.to_lowercase(),
+        None if lower.ends_with(".zip") => "udonarium".to_string(),
+        None if lower.ends_with(".csv") => "csv".to_string(),
+        None if lower.ends_with(".tsv") || lower.ends_with(".tab") => "tsv".to_string(),
+        None => {
+            eprintln!("エラー: 入力形式を判定できません。--format で指定してください（{}）", supported.join(", "));
+            process::exit(1);
+        }
+    };
+
+    if !supported.contains(&format.as_str()) {
+        eprintln!("エラー: 未対応の入力形式です: {}（対応形式: {}）", format, supported.join(", "));
+        process::exit(1);
+    }
+    format
+}
+
+/// インポート結果を出力する（失敗時はエラーを表示して終了）
+///
+/// 復元できなかった項目は標準エラーに警告として出す。`output_dir` を指定した場合は
+/// レコードごとに `{名前}.json` を書き出し、指定が無い場合は1件なら単一オブジェクト、
+/// 複数なら配列を標準出力に出す（単一オブジェクトは add コマンドにそのまま渡せる）。
+pub fn emit_import_or_exit<T: Serialize>(
+    outcome: &ImportOutcome<T>,
+    output_dir: Option<&str>,
+    name_of: impl Fn(&T) -> &str,
+    kind: &str,
+    add_command: &str,
+) {
+    for warning in &outcome.warnings {
+        eprintln!("警告: {}", warning);
+    }
+
+    match output_dir {
+        Some(dir) => {
+            let dir = Path::new(dir);
+            if let Err(e) = std::fs::create_dir_all(dir) {
+                eprintln!("エラー: ディレクトリを作成できません: {}", e);
+                process::exit(1);
+            }
+            for record in &outcome.records {
+                let path = dir.join(format!("{}.json", sanitize_file_name(name_of(record))));
+                let written = serde_json::to_string_pretty(record)
+                    .map_err(|e| e.to_string())
+                    .and_then(|json| io::write_atomic(&path, json.as_bytes()).map_err(|e| e.to_string()));
+                if let Err(e) = written {
+                    eprintln!("エラー: {} に書き込めません: {}", path.display(), e);
+                    process::exit(1);
+                }
+                println!("{}", path.display());
+            }
+            eprintln!(
+                "成功: {} 件の{}を書き出しました（{} <ファイル> で追加できます）",
+                outcome.records.len(),
+                kind,
+                add_command
+            );
+        }
+        None if outcome.records.len() == 1 => match serde_json::to_string_pretty(&outcome.records[0]) {
+            Ok(json) => println!("{}", json),
+            Err(e) => {
+                eprintln!("エラー: JSON 出力に失敗しました: {}", e);
+                process::exit(1);
+            }
+        },
+        None => save_json_stdout_or_exit(&outcome.records),
+    }
+}
+
+/// ファイル名に使えない文字を置き換える
+fn sanitize_file_name(name: &str) -> String {
+    name.chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' | '\n' => '_',
+            c => c,
+        })
+        .collect()
+}
+
 /// 確認ダイアログを表示する
 pub fn confirm_action(message: &str) -> bool {
     eprint!("警告: {} (y/n) ", message);
@@ -114,11 +379,41 @@ pub fn copy_to_clipboard(text: &str) -> std::io::Result<()> {
     }
 }
 
+/// 「もしかして」で表示する候補の最大数
+const SUGGESTION_LIMIT: usize = 5;
+
+/// 名前に一致するモンスターが無い場合に、近い名前を「もしかして」として表示する
+///
+/// 名前には一致していて他の条件で絞り込まれた場合は何も表示しない。
+pub fn print_monster_suggestions(monsters: &[Monster], name: &str) {
+    if query::find_by_name(monsters, name).is_empty() {
+        print_suggestions(&query::suggest_monster_names(monsters, name, SUGGESTION_LIMIT));
+    }
+}
+
+/// 名前に一致するスペルが無い場合に、近い名前を「もしかして」として表示する
+pub fn print_spell_suggestions(spells: &[Spell], name: &str) {
+    if query::spell_find_by_name(spells, name).is_empty() {
+        print_suggestions(&query::spell_suggest_names(spells, name, SUGGESTION_LIMIT));
+    }
+}
+
+fn print_suggestions(names: &[&str]) {
+    if names.is_empty() {
+        return;
+    }
+    eprintln!("もしかして:");
+    for name in names {
+        eprintln!("  - {}", name);
+    }
+}
+
 /// モンスターのフィルター条件を整形して文字列で返す
 pub fn format_monster_filter_conditions(
     name: Option<&str>,
     level: Option<i32>,
     category: Option<&str>,
+    filter: Option<&str>,
 ) -> String {
     let mut conditions = Vec::new();
     
@@ -131,6 +426,9 @@ pub fn format_monster_filter_conditions(
     if let Some(c) = category {
         conditions.push(format!("  - category: \"{}\"", c));
     }
+    if let Some(f) = filter {
+        conditions.push(format!("  - where: {}", f));
+    }
     
     if conditions.is_empty() {
         String::new()
@@ -147,6 +445,7 @@ pub fn format_spell_filter_conditions(
     rank: Option<i32>,
     school_variant: Option<&str>,
     god: Option<&str>,
+    filter: Option<&str>,
 ) -> String {
     let mut conditions = Vec::new();
     
@@ -168,6 +467,9 @@ pub fn format_spell_filter_conditions(
     if let Some(g) = god {
         conditions.push(format!("  - god: \"{}\"", g));
     }
+    if let Some(f) = filter {
+        conditions.push(format!("  - where: {}", f));
+    }
     
     if conditions.is_empty() {
         String::new()
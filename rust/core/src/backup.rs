@@ -0,0 +1,560 @@
+//! データファイルのバックアップと復元
+//!
+//! データファイルを書き換える前に、現在の内容をタイムスタンプ付きの
+//! バックアップファイル（`monsters.json.20250101-120000-000.bak`）として保存し、
+//! 設定された世代数を超えた古いものから削除します。
+//! 復元（undo）する前にも現在の内容をバックアップするため、復元は取り消せます。
+//! このバックアップには復元したバックアップのタイムスタンプを付け
+//! （`monsters.json.20250101-120500-000.undo-20250101-120000-000.bak`）、
+//! 続けて元に戻すときはそこから1つ前の状態へ進みます。
+//! 共有のバックアップ先ディレクトリでは、同名のデータファイルを区別するため
+//! 名前にディレクトリのハッシュを含めます（`monsters.json.1a2b3c4d5e6f7a8b.20250101-120000-000.bak`）。
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::io::{self, IoError};
+
+/// デフォルトのバックアップ保持数
+pub const DEFAULT_BACKUP_COUNT: usize = 10;
+
+/// バックアップ先ディレクトリを指定しない場合のディレクトリ名（データファイルと同じ階層）
+pub const DEFAULT_BACKUP_DIR_NAME: &str = ".backup";
+
+const BACKUP_EXTENSION: &str = "bak";
+
+/// 復元の直前に作成したバックアップの名前で、復元したバックアップの作成日時の前に付ける
+const UNDO_MARKER: &str = "undo-";
+
+/// バックアップの方針
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BackupPolicy {
+    /// 保持するバックアップの世代数（0 の場合はバックアップしない）
+    pub count: usize,
+
+    /// バックアップ先ディレクトリ（None の場合はデータファイルと同じ階層の `.backup`）
+    pub dir: Option<PathBuf>,
+}
+
+impl Default for BackupPolicy {
+    fn default() -> Self {
+        BackupPolicy {
+            count: DEFAULT_BACKUP_COUNT,
+            dir: None,
+        }
+    }
+}
+
+/// バックアップ1件分
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BackupEntry {
+    /// バックアップファイルのパス
+    pub path: PathBuf,
+
+    /// 作成日時（UTC、`YYYYMMDD-HHMMSS-mmm` 形式）
+    pub timestamp: String,
+
+    /// 復元の直前に作成したバックアップなら、復元したバックアップの作成日時
+    pub restored: Option<String>,
+}
+
+impl BackupEntry {
+    /// 表示用の作成日時（例: "2025-01-01 12:00:00 UTC"）
+    pub fn display_time(&self) -> String {
+        let t = &self.timestamp;
+        if t.len() < 15 {
+            return t.clone();
+        }
+        format!(
+            "{}-{}-{} {}:{}:{} UTC",
+            &t[0..4],
+            &t[4..6],
+            &t[6..8],
+            &t[9..11],
+            &t[11..13],
+            &t[13..15]
+        )
+    }
+}
+
+impl BackupPolicy {
+    /// データファイルのバックアップ先ディレクトリ
+    pub fn dir_for(&self, data_path: &Path) -> PathBuf {
+        match &self.dir {
+            Some(dir) => dir.clone(),
+            None => data_path
+                .parent()
+                .unwrap_or_else(|| Path::new("."))
+                .join(DEFAULT_BACKUP_DIR_NAME),
+        }
+    }
+
+    /// データファイルの現在の内容をバックアップし、古いバックアップを削除
+    ///
+    /// # 引数
+    /// * `data_path` - バックアップするデータファイル
+    ///
+    /// # 戻り値
+    /// * `Ok(Some(BackupEntry))` - 作成したバックアップ
+    /// * `Ok(None)` - ファイルが存在しない、またはバックアップが無効な場合
+    /// * `Err(IoError)` - コピーまたは削除に失敗した場合
+    pub fn create(&self, data_path: &Path) -> Result<Option<BackupEntry>, IoError> {
+        self.backup(data_path, None)
+    }
+
+    /// `create` の本体（`restored` は復元するバックアップの作成日時）
+    fn backup(&self, data_path: &Path, restored: Option<&str>) -> Result<Option<BackupEntry>, IoError> {
+        if self.count == 0 || !data_path.exists() {
+            return Ok(None);
+        }
+
+        let dir = self.dir_for(data_path);
+        fs::create_dir_all(&dir)?;
+
+        // 同一ミリ秒や時計の巻き戻りでも、最新のバックアップより後ろに並ぶ名前にする
+        let mut timestamp = timestamp(SystemTime::now());
+        if let Some(newest) = self.list(data_path)?.first() {
+            let (newest_base, newest_seq) = sort_key(&newest.timestamp);
+            if newest_base >= timestamp.as_str() {
+                timestamp = format!("{}-{}", newest_base, newest_seq + 1);
+            }
+        }
+
+        let marker = restored.map(|r| format!(".{}{}", UNDO_MARKER, r)).unwrap_or_default();
+        let path = dir.join(format!("{}.{}{}.{}", self.prefix(data_path), timestamp, marker, BACKUP_EXTENSION));
+        fs::copy(data_path, &path)?;
+        self.prune(data_path)?;
+
+        Ok(Some(BackupEntry { path, timestamp, restored: restored.map(str::to_string) }))
+    }
+
+    /// データファイルのバックアップ一覧（新しい順）
+    ///
+    /// # エラー
+    /// - バックアップディレクトリが読み込めない場合は `IoError::FileRead`（存在しない場合は空）
+    pub fn list(&self, data_path: &Path) -> Result<Vec<BackupEntry>, IoError> {
+        let dir = self.dir_for(data_path);
+        if !dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let prefix = format!("{}.", self.prefix(data_path));
+        let suffix = format!(".{}", BACKUP_EXTENSION);
+        let mut entries = Vec::new();
+        for entry in fs::read_dir(&dir)? {
+            let entry = entry?;
+            let name = entry.file_name().to_string_lossy().to_string();
+            let Some(stem) = name
+                .strip_prefix(&prefix)
+                .and_then(|rest| rest.strip_suffix(&suffix))
+            else {
+                continue;
+            };
+            let (timestamp, restored) = match stem.split_once(&format!(".{}", UNDO_MARKER)) {
+                Some((timestamp, restored)) => (timestamp, Some(restored)),
+                None => (stem, None),
+            };
+            if !is_timestamp(timestamp) || !restored.is_none_or(is_timestamp) {
+                continue;
+            }
+            entries.push(BackupEntry {
+                path: entry.path(),
+                timestamp: timestamp.to_string(),
+                restored: restored.map(str::to_string),
+            });
+        }
+
+        entries.sort_by(|a, b| sort_key(&b.timestamp).cmp(&sort_key(&a.timestamp)));
+        Ok(entries)
+    }
+
+    /// バックアップからデータファイルを復元
+    ///
+    /// 復元する前に現在の内容を、復元するバックアップの作成日時を付けてバックアップします
+    /// （復元自体を元に戻せるように）。既存のバックアップは削除しません。
+    ///
+    /// # 引数
+    /// * `data_path` - 復元先のデータファイル
+    /// * `entry` - 復元するバックアップ（`list` で取得したもの）
+    ///
+    /// # エラー
+    /// - バックアップが一覧に存在しない場合は `IoError::InvalidFormat`
+    /// - 読み込み・書き込みに失敗した場合は `IoError::FileRead`
+    pub fn restore(&self, data_path: &Path, entry: &BackupEntry) -> Result<(), IoError> {
+        let entries = self.list(data_path)?;
+        let Some(position) = entries.iter().position(|e| e == entry) else {
+            return Err(IoError::InvalidFormat(format!(
+                "バックアップが見つかりません: {}",
+                entry.path.display()
+            )));
+        };
+
+        let contents = fs::read(&entries[position].path)?;
+        // 先に読み込んでおく（古いバックアップは現在の内容のバックアップで削除されることがある）
+        self.backup(data_path, Some(&entry.timestamp))?;
+        io::write_atomic(data_path, &contents)?;
+        Ok(())
+    }
+
+    /// 現在の状態から `steps` 段階前の状態のバックアップ
+    ///
+    /// 最新のバックアップが復元の直前に作成したものなら、現在の状態は復元したバックアップなので、
+    /// そこから数える。復元の直前に作成したバックアップ自体は数えない（続けて元に戻すと
+    /// 同じ2つの状態を行き来しないように）。
+    ///
+    /// # 戻り値
+    /// * `Ok(Some(entry))` - 戻す先のバックアップ
+    /// * `Ok(None)` - それより前の履歴が無い場合
+    pub fn undo_target(&self, data_path: &Path, steps: usize) -> Result<Option<BackupEntry>, IoError> {
+        let entries = self.list(data_path)?;
+        let start = match entries.first().and_then(|e| e.restored.as_deref()) {
+            // 復元したバックアップが保持数を超えて削除されていれば、それより前の履歴は無い
+            Some(restored) => match entries.iter().position(|e| e.timestamp == restored) {
+                Some(position) => position + 1,
+                None => return Ok(None),
+            },
+            None => 0,
+        };
+        Ok(entries
+            .into_iter()
+            .skip(start)
+            .filter(|e| e.restored.is_none())
+            .nth(steps.saturating_sub(1)))
+    }
+
+    /// 最後にバックアップされたデータファイルとその最新のバックアップ
+    ///
+    /// # 引数
+    /// * `data_paths` - 候補となるデータファイル
+    ///
+    /// # 戻り値
+    /// * `Ok(Some((path, entry)))` - 最新のバックアップを持つデータファイル
+    /// * `Ok(None)` - どのファイルにもバックアップが無い場合
+    pub fn latest<'a, P: AsRef<Path>>(
+        &self,
+        data_paths: &'a [P],
+    ) -> Result<Option<(&'a Path, BackupEntry)>, IoError> {
+        let mut latest: Option<(&'a Path, BackupEntry)> = None;
+        for path in data_paths {
+            let path = path.as_ref();
+            if let Some(newest) = self.list(path)?.into_iter().next() {
+                let is_newer = latest
+                    .as_ref()
+                    .is_none_or(|(_, current)| sort_key(&newest.timestamp) > sort_key(&current.timestamp));
+                if is_newer {
+                    latest = Some((path, newest));
+                }
+            }
+        }
+        Ok(latest)
+    }
+
+    /// 保持数を超えた古いバックアップを削除
+    fn prune(&self, data_path: &Path) -> Result<(), IoError> {
+        for old in self.list(data_path)?.iter().skip(self.count) {
+            fs::remove_file(&old.path)?;
+        }
+        Ok(())
+    }
+
+    /// バックアップファイル名のタイムスタンプより前の部分
+    ///
+    /// データファイルと同じ階層の `.backup` ではファイル名、共有のディレクトリでは
+    /// ファイル名とデータファイルのディレクトリのハッシュ。
+    fn prefix(&self, data_path: &Path) -> String {
+        match self.dir {
+            None => file_name(data_path),
+            Some(_) => format!("{}.{:016x}", file_name(data_path), dir_hash(data_path)),
+        }
+    }
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+/// データファイルのディレクトリの絶対パスのハッシュ（FNV-1a、実行環境によらず同じ値）
+fn dir_hash(data_path: &Path) -> u64 {
+    let parent = data_path.parent().unwrap_or_else(|| Path::new("."));
+    let parent = parent
+        .canonicalize()
+        .or_else(|_| std::path::absolute(parent))
+        .unwrap_or_else(|_| parent.to_path_buf());
+
+    parent.to_string_lossy().bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// `YYYYMMDD-HHMMSS-mmm` または同一ミリ秒の連番付き `YYYYMMDD-HHMMSS-mmm-N`
+fn is_timestamp(s: &str) -> bool {
+    let parts: Vec<&str> = s.split('-').collect();
+    let lengths_ok = matches!(parts.as_slice(), [d, t, ms] | [d, t, ms, _]
+        if d.len() == 8 && t.len() == 6 && ms.len() == 3);
+    lengths_ok && parts.iter().all(|p| !p.is_empty() && p.chars().all(|c| c.is_ascii_digit()))
+}
+
+/// 連番を数値として比較するためのソートキー
+fn sort_key(timestamp: &str) -> (&str, u32) {
+    match timestamp.get(..19) {
+        Some(base) if timestamp.len() > 20 => (base, timestamp[20..].parse().unwrap_or(0)),
+        _ => (timestamp, 0),
+    }
+}
+
+/// UTC のタイムスタンプ文字列（`YYYYMMDD-HHMMSS-mmm`）
+fn timestamp(time: SystemTime) -> String {
+    let elapsed = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let secs = elapsed.as_secs();
+    let (year, month, day) = civil_from_days((secs / 86_400) as i64);
+    let rem = secs % 86_400;
+    format!(
+        "{:04}{:02}{:02}-{:02}{:02}{:02}-{:03}",
+        year,
+        month,
+        day,
+        rem / 3600,
+        rem % 3600 / 60,
+        rem % 60,
+        elapsed.subsec_millis()
+    )
+}
+
+/// 1970-01-01 からの日数を (年, 月, 日) に変換
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use tempfile::TempDir;
+
+    fn data_file(dir: &TempDir, contents: &str) -> PathBuf {
+        let path = dir.path().join("monsters.json");
+        fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_timestamp_format() {
+        let time = UNIX_EPOCH + Duration::from_millis(1_735_732_800_123);
+        assert_eq!(timestamp(time), "20250101-120000-123");
+        assert_eq!(timestamp(UNIX_EPOCH), "19700101-000000-000");
+        assert!(is_timestamp("20250101-120000-123"));
+        assert!(is_timestamp("20250101-120000-123-2"));
+        assert!(!is_timestamp("old"));
+        assert!(!is_timestamp("20250101-120000"));
+    }
+
+    #[test]
+    fn test_civil_from_days_leap_year() {
+        // 2024-02-29
+        assert_eq!(civil_from_days(19_782), (2024, 2, 29));
+        assert_eq!(civil_from_days(19_783), (2024, 3, 1));
+    }
+
+    #[test]
+    fn test_create_and_list_newest_first() {
+        let dir = TempDir::new().unwrap();
+        let path = data_file(&dir, "v1");
+        let policy = BackupPolicy::default();
+
+        let first = policy.create(&path).unwrap().unwrap();
+        fs::write(&path, "v2").unwrap();
+        let second = policy.create(&path).unwrap().unwrap();
+
+        assert_eq!(first.path.parent().unwrap(), dir.path().join(DEFAULT_BACKUP_DIR_NAME));
+        let entries = policy.list(&path).unwrap();
+        assert_eq!(entries, vec![second.clone(), first.clone()]);
+        assert_eq!(fs::read_to_string(&second.path).unwrap(), "v2");
+    }
+
+    #[test]
+    fn test_create_rotates_old_backups() {
+        let dir = TempDir::new().unwrap();
+        let path = data_file(&dir, "v0");
+        let policy = BackupPolicy { count: 2, dir: None };
+
+        for i in 1..=4 {
+            policy.create(&path).unwrap();
+            fs::write(&path, format!("v{}", i)).unwrap();
+        }
+
+        let entries = policy.list(&path).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(fs::read_to_string(&entries[0].path).unwrap(), "v3");
+        assert_eq!(fs::read_to_string(&entries[1].path).unwrap(), "v2");
+    }
+
+    #[test]
+    fn test_create_skips_missing_file_and_disabled_policy() {
+        let dir = TempDir::new().unwrap();
+        let missing = dir.path().join("missing.json");
+        assert!(BackupPolicy::default().create(&missing).unwrap().is_none());
+
+        let path = data_file(&dir, "v1");
+        let disabled = BackupPolicy { count: 0, dir: None };
+        assert!(disabled.create(&path).unwrap().is_none());
+        assert!(disabled.list(&path).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_list_ignores_other_files() {
+        let dir = TempDir::new().unwrap();
+        let path = data_file(&dir, "v1");
+        let backup_dir = dir.path().join("backups");
+        let policy = BackupPolicy { count: 5, dir: Some(backup_dir.clone()) };
+        policy.create(&path).unwrap();
+
+        fs::write(backup_dir.join("spells.json.20250101-120000-000.bak"), "").unwrap();
+        fs::write(backup_dir.join("monsters.json.note.bak"), "").unwrap();
+        assert_eq!(policy.list(&path).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_restore_backs_up_current_contents() {
+        let dir = TempDir::new().unwrap();
+        let path = data_file(&dir, "v1");
+        let policy = BackupPolicy::default();
+
+        policy.create(&path).unwrap();
+        fs::write(&path, "v2").unwrap();
+        policy.create(&path).unwrap();
+        fs::write(&path, "v3").unwrap();
+
+        let entries = policy.list(&path).unwrap();
+        policy.restore(&path, &entries[1]).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "v1");
+
+        let after = policy.list(&path).unwrap();
+        assert_eq!(after.len(), 3);
+        assert_eq!(after[1..], entries[..]);
+        assert_eq!(fs::read_to_string(&after[0].path).unwrap(), "v3");
+        assert_eq!(after[0].restored.as_ref(), Some(&entries[1].timestamp));
+
+        // 復元を取り消す
+        policy.restore(&path, &after[0]).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "v3");
+    }
+
+    #[test]
+    fn test_undo_steps_past_its_own_backups() {
+        let dir = TempDir::new().unwrap();
+        let path = data_file(&dir, "v1");
+        let policy = BackupPolicy::default();
+        for version in ["v2", "v3", "v4"] {
+            policy.create(&path).unwrap();
+            fs::write(&path, version).unwrap();
+        }
+
+        // 続けて元に戻すと v3 → v2 → v1 と1つずつ前に進む
+        for expected in ["v3", "v2", "v1"] {
+            let entry = policy.undo_target(&path, 1).unwrap().unwrap();
+            policy.restore(&path, &entry).unwrap();
+            assert_eq!(fs::read_to_string(&path).unwrap(), expected);
+        }
+        assert!(policy.undo_target(&path, 1).unwrap().is_none());
+
+        // 編集した後は、その直前の状態から数え直す
+        policy.create(&path).unwrap();
+        fs::write(&path, "v5").unwrap();
+        let entry = policy.undo_target(&path, 1).unwrap().unwrap();
+        assert_eq!(fs::read_to_string(&entry.path).unwrap(), "v1");
+    }
+
+    #[test]
+    fn test_restore_oldest_backup_when_full() {
+        let dir = TempDir::new().unwrap();
+        let path = data_file(&dir, "v1");
+        let policy = BackupPolicy { count: 2, dir: None };
+
+        policy.create(&path).unwrap();
+        fs::write(&path, "v2").unwrap();
+        policy.create(&path).unwrap();
+        fs::write(&path, "v3").unwrap();
+
+        let entries = policy.list(&path).unwrap();
+        policy.restore(&path, &entries[1]).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "v1");
+        assert_eq!(policy.list(&path).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_shared_dir_keeps_same_named_files_apart() {
+        let dir = TempDir::new().unwrap();
+        let backup_dir = dir.path().join("backups");
+        let policy = BackupPolicy { count: 5, dir: Some(backup_dir.clone()) };
+        let a = dir.path().join("a").join("monsters.json");
+        let b = dir.path().join("b").join("monsters.json");
+        for (path, contents) in [(&a, "a1"), (&b, "b1")] {
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, contents).unwrap();
+            policy.create(path).unwrap();
+        }
+
+        let entries_a = policy.list(&a).unwrap();
+        let entries_b = policy.list(&b).unwrap();
+        assert_eq!(entries_a.len(), 1);
+        assert_eq!(entries_b.len(), 1);
+        assert_eq!(fs::read_to_string(&entries_a[0].path).unwrap(), "a1");
+        assert_eq!(fs::read_to_string(&entries_b[0].path).unwrap(), "b1");
+        assert!(entries_a[0].path.starts_with(&backup_dir));
+    }
+
+    #[test]
+    fn test_latest_across_files() {
+        let dir = TempDir::new().unwrap();
+        let monsters = data_file(&dir, "m1");
+        let spells = dir.path().join("spells.json");
+        fs::write(&spells, "s1").unwrap();
+        let policy = BackupPolicy::default();
+        let paths = [monsters.clone(), spells.clone()];
+
+        assert!(policy.latest(&paths).unwrap().is_none());
+
+        policy.create(&monsters).unwrap();
+        std::thread::sleep(Duration::from_millis(5));
+        let newest = policy.create(&spells).unwrap().unwrap();
+
+        let (path, entry) = policy.latest(&paths).unwrap().unwrap();
+        assert_eq!(path, spells.as_path());
+        assert_eq!(entry, newest);
+    }
+
+    #[test]
+    fn test_restore_unknown_backup_is_error() {
+        let dir = TempDir::new().unwrap();
+        let path = data_file(&dir, "v1");
+        let entry = BackupEntry {
+            path: dir.path().join("other.bak"),
+            timestamp: "20250101-120000-000".to_string(),
+            restored: None,
+        };
+        assert!(BackupPolicy::default().restore(&path, &entry).is_err());
+        assert_eq!(fs::read_to_string(&path).unwrap(), "v1");
+    }
+
+    #[test]
+    fn test_display_time() {
+        let entry = BackupEntry {
+            path: PathBuf::from("x"),
+            timestamp: "20250101-120000-123".to_string(),
+            restored: None,
+        };
+        assert_eq!(entry.display_time(), "2025-01-01 12:00:00 UTC");
+    }
+}
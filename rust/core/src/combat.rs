@@ -0,0 +1,648 @@
+//! 戦闘トラッカー
+//!
+//! 魔物を部位ごと（`部位数` の分だけ複製）にインスタンス化し、HP・MP・状態異常、
+//! ラウンドと手番（先制値の高い順）をセッションファイルに保存して管理します。
+//! 部位の能力値は Udonarium エクスポーターと同じ `TransformedPart` を使用します。
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use crate::encounter::copy_label;
+use crate::export::udonarium::data_transformer::{DataTransformer, TransformedPart};
+use crate::io::{self, IoError};
+use crate::Monster;
+
+/// 戦闘トラッカーのエラー型
+#[derive(thiserror::Error, Debug)]
+pub enum CombatError {
+    #[error("対象が見つかりません: {0}")]
+    TargetNotFound(String),
+
+    #[error("対象が特定できません: {target}（候補: {}）", candidates.join(", "))]
+    AmbiguousTarget { target: String, candidates: Vec<String> },
+
+    #[error("{0} には MP がありません")]
+    NoMp(String),
+
+    #[error("{label} の MP が足りません（残り {remaining}、消費 {amount}）")]
+    InsufficientMp { label: String, remaining: i32, amount: i32 },
+
+    #[error("{label} には状態「{status}」がありません")]
+    StatusNotFound { label: String, status: String },
+
+    #[error("戦闘に参加している魔物がいません")]
+    Empty,
+
+    #[error(transparent)]
+    Io(#[from] IoError),
+}
+
+/// 部位の状態
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PartState {
+    /// 表示ラベル（例: "ゴブリンA"、"トレント(根2)"）
+    pub label: String,
+
+    /// 部位の能力値（最大 HP / MP を含む）
+    pub stats: TransformedPart,
+
+    /// 現在の HP
+    pub hp: i32,
+
+    /// 現在の MP（MP を持たない部位は None）
+    pub mp: Option<i32>,
+
+    /// 状態異常・効果
+    #[serde(default)]
+    pub statuses: Vec<String>,
+}
+
+impl PartState {
+    /// 戦闘不能か（HP 0 以下）
+    pub fn is_down(&self) -> bool {
+        self.hp <= 0
+    }
+}
+
+/// 戦闘に参加している魔物1体
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Combatant {
+    /// 表示ラベル（同じ魔物が複数いる場合は A, B, ... を付与）
+    pub label: String,
+
+    /// 元の魔物名
+    pub monster: String,
+
+    /// レベル
+    pub level: i32,
+
+    /// 先制値
+    pub initiative: i32,
+
+    /// 部位（部位数の分だけ展開済み）
+    pub parts: Vec<PartState>,
+}
+
+/// 戦闘セッション
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CombatSession {
+    /// 現在のラウンド（1 から開始）
+    pub round: u32,
+
+    /// 手番の魔物のラベル
+    pub current: Option<String>,
+
+    /// 参加している魔物（追加順）
+    pub combatants: Vec<Combatant>,
+}
+
+impl CombatSession {
+    /// 魔物を参加させて新しいセッションを開始
+    pub fn start(monsters: &[Monster]) -> Self {
+        let mut session = CombatSession::default();
+        session.add(monsters);
+        session.round = 1;
+        session.current = session.initiative_order().first().map(|c| c.label.clone());
+        session
+    }
+
+    /// セッションファイルを読み込む
+    ///
+    /// # エラー
+    /// - ファイルが読み込めない場合は `IoError::FileRead`
+    /// - JSON が無効な場合は `IoError::JsonParse`
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, CombatError> {
+        let content = fs::read_to_string(path).map_err(IoError::from)?;
+        Ok(serde_json::from_str(&content).map_err(IoError::from)?)
+    }
+
+    /// セッションファイルに保存（アトミックに書き込み）
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), CombatError> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(IoError::from)?;
+        }
+        let json = serde_json::to_string_pretty(self).map_err(IoError::from)?;
+        io::write_atomic(path, json.as_bytes())?;
+        Ok(())
+    }
+
+    /// 魔物を参加させる
+    ///
+    /// 同じ魔物が複数になる場合はラベルに A, B, ... を付けます
+    /// （既に1体だけいる場合、その魔物のラベルは A に変わります）。
+    ///
+    /// # 戻り値
+    /// 追加した魔物のラベル
+    pub fn add(&mut self, monsters: &[Monster]) -> Vec<String> {
+        let mut totals: HashMap<&str, usize> = HashMap::new();
+        for monster in monsters {
+            *totals.entry(monster.name.as_str()).or_insert(0) += 1;
+        }
+
+        let mut added = Vec::new();
+        for monster in monsters {
+            let existing = self.combatants.iter().filter(|c| c.monster == monster.name).count();
+            let label = if existing + totals[monster.name.as_str()] > 1 {
+                if existing == 1 {
+                    self.relabel_single(&monster.name);
+                }
+                self.next_free_label(&monster.name, existing)
+            } else {
+                monster.name.clone()
+            };
+
+            self.combatants.push(instantiate(monster, &label));
+            added.push(label);
+        }
+
+        if self.current.is_none() {
+            self.current = self.initiative_order().first().map(|c| c.label.clone());
+        }
+        added
+    }
+
+    /// 先制値の高い順（同値は追加順）
+    pub fn initiative_order(&self) -> Vec<&Combatant> {
+        let mut order: Vec<&Combatant> = self.combatants.iter().collect();
+        order.sort_by_key(|c| std::cmp::Reverse(c.initiative));
+        order
+    }
+
+    /// 手番を次に進める（最後まで進んだらラウンドを進める）
+    ///
+    /// # 戻り値
+    /// 新しい手番の魔物
+    pub fn next_turn(&mut self) -> Result<&Combatant, CombatError> {
+        let order: Vec<String> = self.initiative_order().iter().map(|c| c.label.clone()).collect();
+        if order.is_empty() {
+            return Err(CombatError::Empty);
+        }
+
+        let position = self
+            .current
+            .as_ref()
+            .and_then(|current| order.iter().position(|l| l == current));
+        let next = match position {
+            Some(p) if p + 1 < order.len() => p + 1,
+            Some(_) => {
+                self.round += 1;
+                0
+            }
+            None => 0,
+        };
+        self.round = self.round.max(1);
+        self.current = Some(order[next].clone());
+        Ok(self.combatants.iter().find(|c| c.label == order[next]).unwrap())
+    }
+
+    /// ダメージを与える
+    ///
+    /// # 引数
+    /// * `target` - 部位のラベル、魔物のラベル（部位が1つの場合）、または一覧の番号
+    /// * `amount` - ダメージ量
+    /// * `physical` - true の場合は防護点を差し引く
+    ///
+    /// # 戻り値
+    /// 実際に与えたダメージ
+    pub fn damage(&mut self, target: &str, amount: i32, physical: bool) -> Result<i32, CombatError> {
+        let part = self.part_mut(target)?;
+        let dealt = if physical {
+            (amount - part.stats.armor).max(0)
+        } else {
+            amount.max(0)
+        };
+        part.hp -= dealt;
+        Ok(dealt)
+    }
+
+    /// HP を回復する（最大 HP まで）
+    ///
+    /// # 戻り値
+    /// 実際に回復した量
+    pub fn heal(&mut self, target: &str, amount: i32) -> Result<i32, CombatError> {
+        let part = self.part_mut(target)?;
+        let healed = (part.stats.hp - part.hp).clamp(0, amount.max(0));
+        part.hp += healed;
+        Ok(healed)
+    }
+
+    /// MP を消費する
+    ///
+    /// # 戻り値
+    /// 残りの MP
+    pub fn spend_mp(&mut self, target: &str, amount: i32) -> Result<i32, CombatError> {
+        let part = self.part_mut(target)?;
+        let Some(mp) = part.mp else {
+            return Err(CombatError::NoMp(part.label.clone()));
+        };
+        if mp < amount {
+            return Err(CombatError::InsufficientMp {
+                label: part.label.clone(),
+                remaining: mp,
+                amount,
+            });
+        }
+        part.mp = Some(mp - amount);
+        Ok(mp - amount)
+    }
+
+    /// 状態を追加する（既にある場合は何もしない）
+    pub fn add_status(&mut self, target: &str, status: &str) -> Result<(), CombatError> {
+        let part = self.part_mut(target)?;
+        if !part.statuses.iter().any(|s| s == status) {
+            part.statuses.push(status.to_string());
+        }
+        Ok(())
+    }
+
+    /// 状態を取り除く
+    pub fn remove_status(&mut self, target: &str, status: &str) -> Result<(), CombatError> {
+        let part = self.part_mut(target)?;
+        match part.statuses.iter().position(|s| s == status) {
+            Some(i) => {
+                part.statuses.remove(i);
+                Ok(())
+            }
+            None => Err(CombatError::StatusNotFound {
+                label: part.label.clone(),
+                status: status.to_string(),
+            }),
+        }
+    }
+
+    /// 部位の一覧（表示順: 先制値の高い順、番号は 1 から）
+    pub fn parts(&self) -> Vec<(&Combatant, &PartState)> {
+        self.initiative_order()
+            .into_iter()
+            .flat_map(|c| c.parts.iter().map(move |p| (c, p)))
+            .collect()
+    }
+
+    /// 部位を検索（番号、部位ラベル、または部位が1つの魔物のラベル）
+    pub fn find_part(&self, target: &str) -> Result<&PartState, CombatError> {
+        let (ci, pi) = self.locate(target)?;
+        Ok(&self.combatants[ci].parts[pi])
+    }
+
+    /// 状態表を整形
+    pub fn format_table(&self) -> String {
+        let mut out = String::new();
+        let current = self.current.as_deref().unwrap_or("-");
+        out.push_str(&format!("ラウンド {} ／ 手番: {}\n", self.round, current));
+
+        let header = [
+            pad("#", 4),
+            pad("名前", 20),
+            pad("HP", 9),
+            pad("MP", 9),
+            pad("防護", 5),
+            pad("命中", 5),
+            pad("回避", 5),
+            pad("打撃", 5),
+            pad("先制", 5),
+            "状態".to_string(),
+        ];
+        out.push_str(header.concat().trim_end());
+        out.push('\n');
+
+        for (i, (combatant, part)) in self.parts().into_iter().enumerate() {
+            let marker = if Some(combatant.label.as_str()) == self.current.as_deref() { ">" } else { " " };
+            let mp = match part.mp {
+                Some(mp) => format!("{}/{}", mp, part.stats.mp),
+                None => "-".to_string(),
+            };
+            let mut statuses = part.statuses.clone();
+            if part.is_down() {
+                statuses.insert(0, "戦闘不能".to_string());
+            }
+            let row = [
+                pad(&format!("{}{}", marker, i + 1), 4),
+                pad(&part.label, 20),
+                pad(&format!("{}/{}", part.hp, part.stats.hp), 9),
+                pad(&mp, 9),
+                pad(&part.stats.armor.to_string(), 5),
+                pad(&part.stats.hit_rate.to_string(), 5),
+                pad(&part.stats.dodge.to_string(), 5),
+                pad(&part.stats.damage.to_string(), 5),
+                pad(&combatant.initiative.to_string(), 5),
+                statuses.join(", "),
+            ];
+            out.push_str(row.concat().trim_end());
+            out.push('\n');
+        }
+        out
+    }
+
+    fn part_mut(&mut self, target: &str) -> Result<&mut PartState, CombatError> {
+        let (ci, pi) = self.locate(target)?;
+        Ok(&mut self.combatants[ci].parts[pi])
+    }
+
+    /// 対象を (魔物, 部位) の位置に解決
+    fn locate(&self, target: &str) -> Result<(usize, usize), CombatError> {
+        let target = target.trim();
+
+        // 状態表の番号
+        if let Ok(number) = target.parse::<usize>() {
+            let parts = self.parts();
+            let (combatant, part) = number
+                .checked_sub(1)
+                .and_then(|i| parts.get(i))
+                .ok_or_else(|| CombatError::TargetNotFound(target.to_string()))?;
+            return Ok(self.position_of(combatant, part));
+        }
+
+        // 部位ラベルの完全一致
+        for (ci, combatant) in self.combatants.iter().enumerate() {
+            if let Some(pi) = combatant.parts.iter().position(|p| p.label == target) {
+                return Ok((ci, pi));
+            }
+        }
+
+        // 魔物ラベル（部位が1つならその部位）
+        if let Some(ci) = self.combatants.iter().position(|c| c.label == target) {
+            let combatant = &self.combatants[ci];
+            if combatant.parts.len() == 1 {
+                return Ok((ci, 0));
+            }
+            return Err(CombatError::AmbiguousTarget {
+                target: target.to_string(),
+                candidates: combatant.parts.iter().map(|p| p.label.clone()).collect(),
+            });
+        }
+
+        Err(CombatError::TargetNotFound(target.to_string()))
+    }
+
+    fn position_of(&self, combatant: &Combatant, part: &PartState) -> (usize, usize) {
+        let ci = self
+            .combatants
+            .iter()
+            .position(|c| std::ptr::eq(c, combatant))
+            .unwrap();
+        let pi = combatant.parts.iter().position(|p| std::ptr::eq(p, part)).unwrap();
+        (ci, pi)
+    }
+
+    /// 1体だけいる魔物のラベルを A 付きに変更
+    fn relabel_single(&mut self, monster_name: &str) {
+        let relabeled = format!("{}{}", monster_name, copy_label(0));
+        let Some(combatant) = self
+            .combatants
+            .iter_mut()
+            .find(|c| c.monster == monster_name && c.label == monster_name)
+        else {
+            return;
+        };
+        for part in &mut combatant.parts {
+            part.label = part.label.replacen(monster_name, &relabeled, 1);
+        }
+        combatant.label = relabeled.clone();
+        if self.current.as_deref() == Some(monster_name) {
+            self.current = Some(relabeled);
+        }
+    }
+
+    /// 使われていない最初のコピーラベル
+    fn next_free_label(&self, monster_name: &str, start: usize) -> String {
+        (start..)
+            .map(|i| format!("{}{}", monster_name, copy_label(i)))
+            .find(|label| !self.combatants.iter().any(|c| &c.label == label))
+            .unwrap()
+    }
+}
+
+/// 魔物を部位ごとにインスタンス化
+fn instantiate(monster: &Monster, label: &str) -> Combatant {
+    let transformed = DataTransformer::transform(monster, vec![String::new(); monster.part.len()]);
+
+    // 部位名ごとの総数（部位数を展開した後）
+    let mut totals: HashMap<&str, i32> = HashMap::new();
+    for part in &monster.part {
+        *totals.entry(part.name.as_str()).or_insert(0) += part.part_count.max(1);
+    }
+
+    let single = monster.part.len() == 1 && monster.part[0].part_count <= 1;
+    let mut seen: HashMap<&str, i32> = HashMap::new();
+    let mut parts = Vec::new();
+    for (part, stats) in monster.part.iter().zip(transformed.parts) {
+        for _ in 0..part.part_count.max(1) {
+            let n = seen.entry(part.name.as_str()).or_insert(0);
+            *n += 1;
+
+            let name = if part.name.is_empty() { "部位" } else { part.name.as_str() };
+            let part_label = if single {
+                label.to_string()
+            } else if totals[part.name.as_str()] > 1 {
+                format!("{}({}{})", label, name, n)
+            } else {
+                format!("{}({})", label, name)
+            };
+
+            let mut stats = stats.clone();
+            stats.display_name = part_label.clone();
+            parts.push(PartState {
+                label: part_label,
+                hp: stats.hp,
+                mp: (part.mp >= 0).then_some(part.mp),
+                stats,
+                statuses: Vec::new(),
+            });
+        }
+    }
+
+    Combatant {
+        label: label.to_string(),
+        monster: monster.name.clone(),
+        level: monster.level,
+        initiative: monster.initiative,
+        parts,
+    }
+}
+
+/// 全角文字を幅 2 として右側を空白で埋める
+fn pad(s: &str, width: usize) -> String {
+    let w: usize = s.chars().map(|c| if c.is_ascii() { 1 } else { 2 }).sum();
+    format!("{}{}", s, " ".repeat(width.saturating_sub(w).max(1)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Part;
+
+    fn part(name: &str, hp: i32, mp: i32, count: i32, core: bool) -> Part {
+        Part {
+            hp: Some(hp),
+            mp,
+            name: name.to_string(),
+            core: Some(core),
+            hit_rate: Some(12),
+            dodge: Some(11),
+            damage: Some(4),
+            part_count: count,
+            special_abilities: String::new(),
+            armor: 3,
+        }
+    }
+
+    fn monster(name: &str, initiative: i32, parts: Vec<Part>) -> Monster {
+        Monster {
+            category: "蛮族".to_string(),
+            level: 3,
+            revision: 2.5,
+            data: String::new(),
+            illust: String::new(),
+            movein: 10,
+            movein_description: String::new(),
+            moveon: -1,
+            moveon_description: String::new(),
+            name: name.to_string(),
+            part: parts,
+            notes: String::new(),
+            initiative,
+            common_abilities: String::new(),
+            weakness: String::new(),
+            weakness_value: 10,
+            life_resistance: 5,
+            fame: 8,
+            mental_resistance: 5,
+            extra: HashMap::new(),
+        }
+    }
+
+    fn goblin() -> Monster {
+        monster("ゴブリン", 11, vec![part("", 16, -1, 1, true)])
+    }
+
+    fn treant() -> Monster {
+        monster("トレント", 9, vec![part("幹", 60, 20, 1, true), part("根", 30, -1, 2, false)])
+    }
+
+    #[test]
+    fn test_start_expands_part_count() {
+        let session = CombatSession::start(&[treant()]);
+        let labels: Vec<&str> = session.combatants[0].parts.iter().map(|p| p.label.as_str()).collect();
+        assert_eq!(labels, vec!["トレント(幹)", "トレント(根1)", "トレント(根2)"]);
+        assert_eq!(session.round, 1);
+        assert_eq!(session.current.as_deref(), Some("トレント"));
+        assert_eq!(session.combatants[0].parts[0].mp, Some(20));
+        assert_eq!(session.combatants[0].parts[1].mp, None);
+    }
+
+    #[test]
+    fn test_copies_get_distinct_labels() {
+        let mut session = CombatSession::start(&[goblin()]);
+        assert_eq!(session.combatants[0].label, "ゴブリン");
+
+        let added = session.add(&[goblin(), goblin()]);
+        assert_eq!(added, vec!["ゴブリンB", "ゴブリンC"]);
+        assert_eq!(session.combatants[0].label, "ゴブリンA");
+        assert_eq!(session.combatants[0].parts[0].label, "ゴブリンA");
+        assert_eq!(session.current.as_deref(), Some("ゴブリンA"));
+
+        let session = CombatSession::start(&[goblin(), goblin()]);
+        let labels: Vec<&str> = session.combatants.iter().map(|c| c.label.as_str()).collect();
+        assert_eq!(labels, vec!["ゴブリンA", "ゴブリンB"]);
+    }
+
+    #[test]
+    fn test_damage_and_heal() {
+        let mut session = CombatSession::start(&[goblin()]);
+        assert_eq!(session.damage("ゴブリン", 5, false).unwrap(), 5);
+        assert_eq!(session.damage("ゴブリン", 5, true).unwrap(), 2);
+        assert_eq!(session.find_part("ゴブリン").unwrap().hp, 9);
+
+        assert_eq!(session.heal("ゴブリン", 100).unwrap(), 7);
+        assert_eq!(session.find_part("ゴブリン").unwrap().hp, 16);
+
+        session.damage("1", 20, false).unwrap();
+        assert!(session.find_part("1").unwrap().is_down());
+    }
+
+    #[test]
+    fn test_spend_mp() {
+        let mut session = CombatSession::start(&[treant()]);
+        assert_eq!(session.spend_mp("トレント(幹)", 5).unwrap(), 15);
+        assert!(matches!(
+            session.spend_mp("トレント(幹)", 50),
+            Err(CombatError::InsufficientMp { remaining: 15, .. })
+        ));
+        assert!(matches!(session.spend_mp("トレント(根1)", 1), Err(CombatError::NoMp(_))));
+    }
+
+    #[test]
+    fn test_target_resolution() {
+        let session = CombatSession::start(&[goblin(), treant()]);
+        // 番号は先制値の高い順（ゴブリン → トレント）
+        assert_eq!(session.find_part("2").unwrap().label, "トレント(幹)");
+        assert!(matches!(session.find_part("トレント"), Err(CombatError::AmbiguousTarget { .. })));
+        assert!(matches!(session.find_part("オーク"), Err(CombatError::TargetNotFound(_))));
+        assert!(matches!(session.find_part("9"), Err(CombatError::TargetNotFound(_))));
+    }
+
+    #[test]
+    fn test_statuses() {
+        let mut session = CombatSession::start(&[goblin()]);
+        session.add_status("ゴブリン", "転倒").unwrap();
+        session.add_status("ゴブリン", "転倒").unwrap();
+        assert_eq!(session.find_part("ゴブリン").unwrap().statuses, vec!["転倒"]);
+
+        session.remove_status("ゴブリン", "転倒").unwrap();
+        assert!(session.find_part("ゴブリン").unwrap().statuses.is_empty());
+        assert!(matches!(
+            session.remove_status("ゴブリン", "転倒"),
+            Err(CombatError::StatusNotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn test_next_turn_follows_initiative_and_rounds() {
+        let mut session = CombatSession::start(&[treant(), goblin()]);
+        assert_eq!(session.current.as_deref(), Some("ゴブリン"));
+        assert_eq!(session.next_turn().unwrap().label, "トレント");
+        assert_eq!(session.round, 1);
+        assert_eq!(session.next_turn().unwrap().label, "ゴブリン");
+        assert_eq!(session.round, 2);
+
+        let mut empty = CombatSession::default();
+        assert!(matches!(empty.next_turn(), Err(CombatError::Empty)));
+    }
+
+    #[test]
+    fn test_save_and_load_roundtrip() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("nested/combat.json");
+
+        let mut session = CombatSession::start(&[goblin(), treant()]);
+        session.damage("ゴブリン", 4, false).unwrap();
+        session.add_status("トレント(根2)", "炎上").unwrap();
+        session.save(&path).unwrap();
+
+        let loaded = CombatSession::load(&path).unwrap();
+        assert_eq!(loaded.round, 1);
+        assert_eq!(loaded.find_part("ゴブリン").unwrap().hp, 12);
+        assert_eq!(loaded.find_part("トレント(根2)").unwrap().statuses, vec!["炎上"]);
+        assert_eq!(loaded.find_part("トレント(根2)").unwrap().stats.armor, 3);
+    }
+
+    #[test]
+    fn test_format_table() {
+        let mut session = CombatSession::start(&[goblin(), treant()]);
+        session.damage("ゴブリン", 20, false).unwrap();
+        session.add_status("トレント(幹)", "転倒").unwrap();
+        let table = session.format_table();
+
+        assert!(table.starts_with("ラウンド 1 ／ 手番: ゴブリン\n"));
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(lines.len(), 6);
+        assert!(lines[2].starts_with(">1"));
+        assert!(lines[2].contains("-4/16"));
+        assert!(lines[2].contains("戦闘不能"));
+        assert!(lines[3].contains("20/20"));
+        assert!(lines[3].ends_with("転倒"));
+    }
+}
@@ -1,12 +1,28 @@
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 
+use crate::backup::{BackupPolicy, DEFAULT_BACKUP_COUNT};
+use crate::export::udonarium::chat_palette::PaletteDefinition;
+use crate::export::udonarium::images::IllustrationSource;
+use crate::export::udonarium::room::{CardDeck, RoomLayout};
+use crate::export::sheets::SheetLayoutSource;
+use crate::export::ExportOptions;
+
 /// 設定ファイルの構造
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Config {
     pub data: DataConfig,
     pub system: Option<SystemConfig>,
+
+    /// バックアップ設定（オプション）
+    #[serde(default)]
+    pub backup: Option<BackupConfig>,
+
+    /// エクスポート設定（オプション）
+    #[serde(default)]
+    pub export: Option<ExportSection>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -42,6 +58,75 @@ pub struct SystemConfig {
     pub name: Option<String>,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct BackupConfig {
+    /// 保持する世代数（0 でバックアップ無効、未指定時は 10）
+    pub count: Option<usize>,
+
+    /// バックアップ先ディレクトリ（未指定時はデータファイルと同じ階層の .backup）
+    pub dir: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ExportSection {
+    /// CSV / TSV エクスポートの設定
+    pub csv: Option<CsvExportConfig>,
+    /// Udonarium エクスポートの設定
+    pub udonarium: Option<UdonariumExportConfig>,
+    /// Udonarium ルームデータの設定
+    pub udonarium_room: Option<UdonariumRoomConfig>,
+    /// テンプレート名 → テンプレートファイルのパス（--export にテンプレート名を指定して使う）
+    pub templates: Option<BTreeMap<String, String>>,
+    /// Udonarium / ココフォリアのチャットパレットの設定
+    pub palette: Option<PaletteConfig>,
+    /// Google Sheets エクスポートの設定
+    pub sheets: Option<SheetsExportConfig>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct CsvExportConfig {
+    /// 2部位目以降の行にもモンスターの列を出力するか（未指定時は true）
+    pub repeat_monster_columns: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct UdonariumExportConfig {
+    /// 駒の画像を探すディレクトリ（`{illust}.png` や `{モンスター名}.jpg` など）
+    pub image_dir: Option<String>,
+    /// illust コード → 画像ファイルのパス（image_dir より優先）
+    pub illust: Option<BTreeMap<String, String>>,
+    /// 画像が見つからないモンスターにプレースホルダー画像を生成するか（未指定時は false）
+    pub placeholder: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct UdonariumRoomConfig {
+    /// 1行に並べる駒の数（未指定時は 8）
+    pub columns: Option<usize>,
+    /// テーブルに置く山札（"trump"）
+    pub decks: Option<Vec<CardDeck>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct SheetsExportConfig {
+    /// レイアウトファイルのパス（未指定時は組み込みのレイアウト。--sheet-layout で上書きできる）
+    pub layout: Option<String>,
+    /// 書き込み先のシート名（レイアウトの sheet_name を上書き）
+    pub sheet_name: Option<String>,
+    /// 最初のモンスターを書き込む行（レイアウトの start_row を上書き）
+    pub start_row: Option<usize>,
+    /// Sheets API のベース URL（未指定時は Google の API。ローカルのモックサーバーでの確認用）
+    pub api_base_url: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct PaletteConfig {
+    /// 使用するプリセット名（未指定時は "standard"。--palette で上書きできる）
+    pub preset: Option<String>,
+    /// 独自のプリセット（組み込みと同じ名前の場合は置き換える）
+    pub presets: Option<BTreeMap<String, PaletteDefinition>>,
+}
+
 impl Config {
     /// 設定ファイルを読み込む
     ///
@@ -69,6 +154,8 @@ impl Config {
             system: Some(SystemConfig {
                 name: Some("sw25".to_string()),
             }),
+            backup: None,
+            export: None,
         }
     }
 
@@ -137,6 +224,79 @@ impl Config {
         }
     }
 
+    /// バックアップ方針を取得
+    ///
+    /// # 引数
+    /// * `base_path` - 基準パス（通常はホームディレクトリ）
+    ///   バックアップ先ディレクトリが相対パスの場合は base_path から解決
+    ///
+    /// # 戻り値
+    /// * `BackupPolicy` - 設定がない項目はデフォルト値
+    pub fn backup_policy(&self, base_path: Option<&Path>) -> BackupPolicy {
+        let backup = self.backup.clone().unwrap_or_default();
+        BackupPolicy {
+            count: backup.count.unwrap_or(DEFAULT_BACKUP_COUNT),
+            dir: backup.dir.map(|dir| self.resolve_single_path(&dir, base_path)),
+        }
+    }
+
+    /// エクスポーターの動作設定を取得（設定がない項目はデフォルト値）
+    ///
+    /// # 引数
+    /// * `base_path` - 基準パス（通常はホームディレクトリ）
+    ///   テンプレートファイルが相対パスの場合は base_path から解決
+    pub fn export_options(&self, base_path: Option<&Path>) -> ExportOptions {
+        let export = self.export.clone().unwrap_or_default();
+        let csv = export.csv.unwrap_or_default();
+        let room = export.udonarium_room.unwrap_or_default();
+        let udonarium = export.udonarium.unwrap_or_default();
+        let palette = export.palette.unwrap_or_default();
+        let sheets = export.sheets.unwrap_or_default();
+        let defaults = ExportOptions::default();
+        let mut palette_presets = defaults.palette_presets;
+        palette_presets.extend(palette.presets.unwrap_or_default());
+        ExportOptions {
+            templates: export
+                .templates
+                .unwrap_or_default()
+                .into_iter()
+                .map(|(name, path)| {
+                    let path = self.resolve_single_path(&path, base_path);
+                    (name, path)
+                })
+                .collect(),
+            csv_repeat_monster_columns: csv.repeat_monster_columns.unwrap_or(defaults.csv_repeat_monster_columns),
+            udonarium_room: RoomLayout {
+                columns: room.columns.unwrap_or(defaults.udonarium_room.columns),
+                decks: room.decks.unwrap_or(defaults.udonarium_room.decks),
+            },
+            udonarium_images: IllustrationSource {
+                image_dir: udonarium.image_dir.map(|dir| self.resolve_single_path(&dir, base_path)),
+                illust: udonarium
+                    .illust
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|(code, path)| {
+                        let path = self.resolve_single_path(&path, base_path);
+                        (code, path)
+                    })
+                    .collect(),
+                placeholder: udonarium.placeholder.unwrap_or(defaults.udonarium_images.placeholder),
+            },
+            palette_presets,
+            palette_preset: palette.preset.unwrap_or(defaults.palette_preset),
+            spell_schools: defaults.spell_schools,
+            sheets: SheetLayoutSource {
+                layout: sheets.layout.map(|path| self.resolve_single_path(&path, base_path)),
+                sheet_name: sheets.sheet_name,
+                start_row: sheets.start_row,
+            },
+            spell_sheet: defaults.spell_sheet,
+            sheets_overwrite: defaults.sheets_overwrite,
+            sheets_api_base_url: sheets.api_base_url,
+        }
+    }
+
     /// 単一パスを解決する（内部用ヘルパー）
     fn resolve_single_path(&self, path: &str, base_path: Option<&Path>) -> PathBuf {
         let data_path = Path::new(path);
@@ -223,6 +383,8 @@ name = "sw25"
                 spells: None,
             },
             system: None,
+            backup: None,
+            export: None,
         };
 
         let resolved = config.resolve_monsters_path(Some(Path::new("/repo")));
@@ -237,6 +399,8 @@ name = "sw25"
                 spells: None,
             },
             system: None,
+            backup: None,
+            export: None,
         };
 
         let resolved = config.resolve_monsters_path(Some(Path::new("/repo")));
@@ -254,6 +418,8 @@ name = "sw25"
                 spells: None,
             },
             system: None,
+            backup: None,
+            export: None,
         };
 
         let resolved = config.resolve_monsters_paths(Some(Path::new("/repo")));
@@ -325,6 +491,8 @@ name = "sw25"
                 ])),
             },
             system: None,
+            backup: None,
+            export: None,
         };
 
         let resolved = config.resolve_spells_paths(Some(Path::new("/repo")));
@@ -341,9 +509,104 @@ name = "sw25"
                 spells: None,
             },
             system: None,
+            backup: None,
+            export: None,
         };
 
         let resolved = config.resolve_spells_paths(Some(Path::new("/repo")));
         assert!(resolved.is_empty());
     }
+
+    #[test]
+    fn test_backup_policy() {
+        let mut file = NamedTempFile::new().expect("Failed to create temp file");
+        let config_content = r#"
+[data]
+monsters = "data/SW2.5/monsters.json"
+
+[backup]
+count = 3
+dir = "backups/trpg-json"
+"#;
+        writeln!(file, "{}", config_content).expect("Failed to write to temp file");
+
+        let config = Config::load(file.path()).expect("Failed to load config");
+        let policy = config.backup_policy(Some(Path::new("/home/user")));
+        assert_eq!(policy.count, 3);
+        assert_eq!(policy.dir, Some(PathBuf::from("/home/user/backups/trpg-json")));
+
+        let default_policy = Config::default_config().backup_policy(None);
+        assert_eq!(default_policy, BackupPolicy::default());
+    }
+
+    #[test]
+    fn test_export_options() {
+        let mut file = NamedTempFile::new().expect("Failed to create temp file");
+        let config_content = r#"
+[data]
+monsters = "data/SW2.5/monsters.json"
+
+[export.csv]
+repeat_monster_columns = false
+
+[export.udonarium]
+image_dir = "images"
+placeholder = true
+
+[export.udonarium.illust]
+BT114 = "/srv/art/goblin.png"
+
+[export.udonarium_room]
+columns = 4
+decks = ["trump"]
+
+[export.templates]
+memo = "templates/memo.txt"
+abs = "/etc/gm/abs.xml"
+
+[export.palette]
+preset = "short"
+
+[export.palette.presets.short]
+dicebot = "SwordWorld2.0"
+abilities = true
+
+[export.sheets]
+layout = "sheets/layout.toml"
+start_row = 5
+api_base_url = "http://127.0.0.1:8099/v4"
+"#;
+        writeln!(file, "{}", config_content).expect("Failed to write to temp file");
+
+        let config = Config::load(file.path()).expect("Failed to load config");
+        let options = config.export_options(Some(Path::new("/home/user")));
+        assert!(!options.csv_repeat_monster_columns);
+        assert_eq!(options.udonarium_room, RoomLayout { columns: 4, decks: vec![CardDeck::Trump] });
+        assert_eq!(
+            options.udonarium_images,
+            IllustrationSource {
+                image_dir: Some(PathBuf::from("/home/user/images")),
+                illust: BTreeMap::from([("BT114".to_string(), PathBuf::from("/srv/art/goblin.png"))]),
+                placeholder: true,
+            }
+        );
+        assert_eq!(options.templates["memo"], PathBuf::from("/home/user/templates/memo.txt"));
+        assert_eq!(options.templates["abs"], PathBuf::from("/etc/gm/abs.xml"));
+        let palette = options.palette().unwrap();
+        assert_eq!(palette.dicebot, "SwordWorld2.0");
+        assert!(palette.abilities && !palette.magic);
+        assert!(options.palette_presets.contains_key("classic"));
+        assert_eq!(
+            options.sheets,
+            SheetLayoutSource {
+                layout: Some(PathBuf::from("/home/user/sheets/layout.toml")),
+                sheet_name: None,
+                start_row: Some(5),
+            }
+        );
+        assert_eq!(options.sheets_api_base_url.as_deref(), Some("http://127.0.0.1:8099/v4"));
+        assert!(!options.sheets_overwrite);
+
+        assert_eq!(Config::default_config().export_options(None), ExportOptions::default());
+    }
 }
@@ -0,0 +1,478 @@
+//! 読み込み元ファイルを記録したデータセット
+//!
+//! 複数のデータファイルから読み込んだレコードが、どのファイルの何番目に
+//! あったかを保持します。追加・削除・置換は所有ファイルに対してのみ行い、
+//! 保存時には変更のあったファイルだけを書き戻します。
+
+use std::collections::BTreeSet;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+use crate::backup::BackupPolicy;
+use crate::io::{self, IoError};
+use crate::{Monster, Spell};
+
+/// レコードの読み込み元
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordSource {
+    /// ファイルパス
+    pub path: PathBuf,
+
+    /// ファイル内の配列インデックス
+    pub index: usize,
+}
+
+/// ファイル指定文字列をファイル一覧から解決（[`DataSet::resolve_file`] と同じ規則）
+///
+/// データを読み込まずにファイルを指定したい場合（履歴の表示・復元など）に使う。
+pub fn resolve_path<'a, P: AsRef<Path>>(files: &'a [P], which: &str) -> Option<&'a Path> {
+    let wanted = Path::new(which);
+    files
+        .iter()
+        .map(AsRef::as_ref)
+        .find(|f| *f == wanted)
+        .or_else(|| files.iter().map(AsRef::as_ref).find(|f| f.ends_with(wanted)))
+}
+
+/// 読み込み元付きのレコード
+#[derive(Debug, Clone)]
+pub struct SourcedRecord<T> {
+    pub record: T,
+    pub source: RecordSource,
+}
+
+/// 読み込み元を記録したデータセット
+///
+/// レコードは常にファイルの設定順・ファイル内の順序で並んでおり、
+/// 1つのファイルが所有するレコードは連続した範囲になります。
+#[derive(Debug, Clone)]
+pub struct DataSet<T> {
+    files: Vec<PathBuf>,
+    records: Vec<T>,
+    sources: Vec<RecordSource>,
+    dirty: BTreeSet<PathBuf>,
+    backup: Option<BackupPolicy>,
+}
+
+impl<T> DataSet<T> {
+    /// ファイルごとの読み込み関数を指定してデータセットを構築
+    ///
+    /// # 引数
+    /// * `paths` - 読み込むファイルのパスのリスト
+    /// * `loader` - 1ファイル分のレコードを読み込む関数
+    ///
+    /// # 戻り値
+    /// * `Ok(DataSet<T>)` - 読み込んだデータセット
+    /// * `Err(IoError)` - 読み込みに失敗した場合（最初のエラーで中止）
+    pub fn load_with<P, F>(paths: &[P], loader: F) -> Result<Self, IoError>
+    where
+        P: AsRef<Path>,
+        F: Fn(&Path) -> Result<Vec<T>, IoError>,
+    {
+        let mut files = Vec::new();
+        let mut records = Vec::new();
+        let mut sources = Vec::new();
+
+        for path in paths {
+            let path = path.as_ref().to_path_buf();
+            let loaded = loader(&path)?;
+            sources.extend((0..loaded.len()).map(|index| RecordSource { path: path.clone(), index }));
+            records.extend(loaded);
+            files.push(path);
+        }
+
+        Ok(DataSet {
+            files,
+            records,
+            sources,
+            dirty: BTreeSet::new(),
+            backup: None,
+        })
+    }
+
+    /// 保存前にバックアップを作成する
+    pub fn with_backup(mut self, policy: BackupPolicy) -> Self {
+        self.backup = Some(policy);
+        self
+    }
+
+    /// 読み込んだファイルのリスト（設定順）
+    pub fn files(&self) -> &[PathBuf] {
+        &self.files
+    }
+
+    /// すべてのレコード（`query` の検索関数にそのまま渡せる）
+    pub fn records(&self) -> &[T] {
+        &self.records
+    }
+
+    /// 指定位置のレコードの読み込み元
+    pub fn source(&self, position: usize) -> Option<&RecordSource> {
+        self.sources.get(position)
+    }
+
+    /// レコード数
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    /// レコードが空か
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// 条件に一致する最初のレコードの位置
+    pub fn position<F: Fn(&T) -> bool>(&self, pred: F) -> Option<usize> {
+        self.records.iter().position(pred)
+    }
+
+    /// 条件に一致する最初のレコードの読み込み元
+    pub fn find_source<F: Fn(&T) -> bool>(&self, pred: F) -> Option<&RecordSource> {
+        self.position(pred).map(|i| &self.sources[i])
+    }
+
+    /// ファイル指定文字列を読み込み済みファイルに解決
+    ///
+    /// 完全なパス、ファイル名、またはパスの末尾（`sample/monsters.json` など）で指定できる
+    pub fn resolve_file(&self, which: &str) -> Option<&Path> {
+        resolve_path(&self.files, which)
+    }
+
+    /// レコードを指定ファイルの末尾に追加
+    ///
+    /// # エラー
+    /// - 指定ファイルがデータセットに含まれない場合は `IoError::InvalidFormat`
+    pub fn push(&mut self, record: T, file: &Path) -> Result<&RecordSource, IoError> {
+        let path = self.owned_file(file)?;
+        let range = self.range_of(&path);
+        let position = range.end;
+        self.records.insert(position, record);
+        self.sources.insert(position, RecordSource { path: path.clone(), index: range.len() });
+        self.dirty.insert(path);
+        Ok(&self.sources[position])
+    }
+
+    /// 指定位置のレコードを置換（所有ファイル内の同じ位置）
+    ///
+    /// # 戻り値
+    /// 置換したレコードの読み込み元（位置が範囲外の場合は None）
+    pub fn replace(&mut self, position: usize, record: T) -> Option<&RecordSource> {
+        let slot = self.records.get_mut(position)?;
+        *slot = record;
+        let source = &self.sources[position];
+        self.dirty.insert(source.path.clone());
+        Some(source)
+    }
+
+    /// 条件に一致するレコードをすべて削除
+    ///
+    /// # 戻り値
+    /// 削除したレコード（読み込み元付き）
+    pub fn remove_where<F: Fn(&T) -> bool>(&mut self, pred: F) -> Vec<SourcedRecord<T>> {
+        let mut removed = Vec::new();
+        let mut records = Vec::with_capacity(self.records.len());
+        let mut sources = Vec::with_capacity(self.sources.len());
+        for (record, source) in self.records.drain(..).zip(self.sources.drain(..)) {
+            if pred(&record) {
+                self.dirty.insert(source.path.clone());
+                removed.push(SourcedRecord { record, source });
+            } else {
+                records.push(record);
+                sources.push(source);
+            }
+        }
+        self.records = records;
+        self.sources = sources;
+        self.renumber();
+        removed
+    }
+
+    /// 変更されたファイルのリスト
+    pub fn dirty_files(&self) -> Vec<&Path> {
+        self.dirty.iter().map(PathBuf::as_path).collect()
+    }
+
+    /// 指定ファイルが所有するレコード（ファイル内の順序）
+    pub fn records_in(&self, file: &Path) -> &[T] {
+        &self.records[self.range_of(file)]
+    }
+
+    /// 変更されたファイルだけを書き戻す
+    ///
+    /// バックアップ方針が設定されている場合は、書き込み前に現在の内容をバックアップします。
+    ///
+    /// # 引数
+    /// * `saver` - 1ファイル分のレコードを保存する関数
+    ///
+    /// # 戻り値
+    /// * `Ok(Vec<PathBuf>)` - 保存したファイルのリスト
+    /// * `Err(IoError)` - 保存に失敗した場合（保存できなかったファイルは変更済みのまま残る）
+    pub fn save_with<F>(&mut self, saver: F) -> Result<Vec<PathBuf>, IoError>
+    where
+        F: Fn(&Path, &[T]) -> Result<(), IoError>,
+    {
+        let mut saved = Vec::new();
+        while let Some(path) = self.dirty.first().cloned() {
+            if let Some(policy) = &self.backup {
+                policy.create(&path)?;
+            }
+            saver(&path, self.records_in(&path))?;
+            self.dirty.remove(&path);
+            saved.push(path);
+        }
+        Ok(saved)
+    }
+
+    /// データセットを所有権付きのレコード配列に変換
+    pub fn into_records(self) -> Vec<T> {
+        self.records
+    }
+
+    fn owned_file(&self, file: &Path) -> Result<PathBuf, IoError> {
+        self.files
+            .iter()
+            .find(|f| f.as_path() == file)
+            .cloned()
+            .ok_or_else(|| {
+                IoError::InvalidFormat(format!("データファイルとして設定されていません: {}", file.display()))
+            })
+    }
+
+    /// 指定ファイルが所有するレコードの範囲（空のファイルは挿入位置の空範囲）
+    fn range_of(&self, file: &Path) -> Range<usize> {
+        let order = self.files.iter().position(|f| f == file).unwrap_or(self.files.len());
+        let start = self
+            .sources
+            .iter()
+            .position(|s| self.files.iter().position(|f| *f == s.path).unwrap_or(0) >= order)
+            .unwrap_or(self.sources.len());
+        let len = self.sources[start..].iter().take_while(|s| s.path == file).count();
+        start..start + len
+    }
+
+    fn renumber(&mut self) {
+        let mut previous: Option<&PathBuf> = None;
+        let mut index = 0;
+        for source in self.sources.iter_mut() {
+            if previous != Some(&source.path) {
+                index = 0;
+            }
+            source.index = index;
+            index += 1;
+            previous = Some(&source.path);
+        }
+    }
+}
+
+impl DataSet<Monster> {
+    /// 複数の JSON ファイルからモンスターを読み込む
+    ///
+    /// # エラー
+    /// `io::load_json_array` と同じ
+    pub fn load_monsters<P: AsRef<Path>>(paths: &[P]) -> Result<Self, IoError> {
+        DataSet::load_with(paths, |p| io::load_json_array(p))
+    }
+
+    /// 変更されたファイルを `io::save_json_array_file` で書き戻す
+    pub fn save(&mut self) -> Result<Vec<PathBuf>, IoError> {
+        self.save_with(|path, monsters| io::save_json_array_file(path, monsters))
+    }
+}
+
+impl DataSet<Spell> {
+    /// 複数の JSON ファイルからスペルを読み込む
+    ///
+    /// # エラー
+    /// `io::load_spells_json_array` と同じ
+    pub fn load_spells<P: AsRef<Path>>(paths: &[P]) -> Result<Self, IoError> {
+        DataSet::load_with(paths, |p| io::load_spells_json_array(p))
+    }
+
+    /// 変更されたファイルを `io::save_spells_json_array_file` で書き戻す
+    pub fn save(&mut self) -> Result<Vec<PathBuf>, IoError> {
+        self.save_with(|path, spells| io::save_spells_json_array_file(path, spells))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn monster_json(name: &str) -> serde_json::Value {
+        serde_json::json!({
+            "Category": "蛮族",
+            "Lv": 3,
+            "Revision": 2.5,
+            "data": "",
+            "illust": "",
+            "movein": 10,
+            "movein_des": "",
+            "moveon": -1,
+            "moveon_des": "",
+            "name": name,
+            "part": [{
+                "HP": 20, "MP": -1, "name": "", "コア": true,
+                "命中力": 5, "回避力": 5, "打撃点": 3,
+                "部位数": 1, "部位特殊能力": "", "防護点": 2
+            }],
+            "備考": "",
+            "先制値": 10,
+            "共通特殊能力": "",
+            "弱点": "",
+            "弱点値": 10,
+            "生命抵抗力": 5,
+            "知名度": 8,
+            "精神抵抗力": 5
+        })
+    }
+
+    fn write_file(dir: &TempDir, file: &str, names: &[&str]) -> PathBuf {
+        let path = dir.path().join(file);
+        let data: Vec<_> = names.iter().map(|n| monster_json(n)).collect();
+        fs::write(&path, serde_json::to_string_pretty(&data).unwrap()).unwrap();
+        path
+    }
+
+    fn names_in(path: &Path) -> Vec<String> {
+        io::load_json_array(path).unwrap().into_iter().map(|m| m.name).collect()
+    }
+
+    #[test]
+    fn test_load_tracks_source_file_and_index() {
+        let dir = TempDir::new().unwrap();
+        let a = write_file(&dir, "a.json", &["A1", "A2"]);
+        let b = write_file(&dir, "b.json", &["B1"]);
+
+        let set = DataSet::load_monsters(&[&a, &b]).unwrap();
+        assert_eq!(set.len(), 3);
+        let b1 = set.find_source(|m| m.name == "B1").unwrap();
+        assert_eq!(b1, &RecordSource { path: b.clone(), index: 0 });
+        let a2 = set.find_source(|m| m.name == "A2").unwrap();
+        assert_eq!(a2, &RecordSource { path: a.clone(), index: 1 });
+    }
+
+    #[test]
+    fn test_remove_writes_only_owning_file() {
+        let dir = TempDir::new().unwrap();
+        let a = write_file(&dir, "a.json", &["A1", "A2"]);
+        let b = write_file(&dir, "b.json", &["B1", "B2"]);
+        let a_before = fs::read_to_string(&a).unwrap();
+
+        let mut set = DataSet::load_monsters(&[&a, &b]).unwrap();
+        let removed = set.remove_where(|m| m.name == "B1");
+        assert_eq!(removed.len(), 1);
+        assert_eq!(removed[0].source.path, b);
+
+        let saved = set.save().unwrap();
+        assert_eq!(saved, vec![b.clone()]);
+        assert_eq!(names_in(&b), vec!["B2"]);
+        assert_eq!(fs::read_to_string(&a).unwrap(), a_before);
+        assert_eq!(set.find_source(|m| m.name == "B2").unwrap().index, 0);
+    }
+
+    #[test]
+    fn test_push_to_chosen_file() {
+        let dir = TempDir::new().unwrap();
+        let a = write_file(&dir, "a.json", &["A1"]);
+        let b = write_file(&dir, "b.json", &["B1"]);
+
+        let mut set = DataSet::load_monsters(&[&a, &b]).unwrap();
+        let new_monster: Monster = serde_json::from_value(monster_json("NEW")).unwrap();
+        let source = set.push(new_monster, &b).unwrap().clone();
+        assert_eq!(source, RecordSource { path: b.clone(), index: 1 });
+
+        set.save().unwrap();
+        assert_eq!(names_in(&a), vec!["A1"]);
+        assert_eq!(names_in(&b), vec!["B1", "NEW"]);
+    }
+
+    #[test]
+    fn test_push_into_empty_file_keeps_file_order() {
+        let dir = TempDir::new().unwrap();
+        let a = write_file(&dir, "a.json", &[]);
+        let b = write_file(&dir, "b.json", &["B1"]);
+
+        let mut set = DataSet::load_monsters(&[&a, &b]).unwrap();
+        let new_monster: Monster = serde_json::from_value(monster_json("NEW")).unwrap();
+        set.push(new_monster, &a).unwrap();
+        let names: Vec<_> = set.records().iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(names, vec!["NEW", "B1"]);
+        assert_eq!(set.records_in(&a).len(), 1);
+        assert_eq!(set.source(1).unwrap().path, b);
+    }
+
+    #[test]
+    fn test_push_to_unknown_file_is_error() {
+        let dir = TempDir::new().unwrap();
+        let a = write_file(&dir, "a.json", &["A1"]);
+        let mut set = DataSet::load_monsters(&[&a]).unwrap();
+        let new_monster: Monster = serde_json::from_value(monster_json("NEW")).unwrap();
+        assert!(set.push(new_monster, Path::new("/tmp/other.json")).is_err());
+        assert!(set.dirty_files().is_empty());
+    }
+
+    #[test]
+    fn test_replace_keeps_position() {
+        let dir = TempDir::new().unwrap();
+        let a = write_file(&dir, "a.json", &["A1"]);
+        let b = write_file(&dir, "b.json", &["B1", "B2"]);
+
+        let mut set = DataSet::load_monsters(&[&a, &b]).unwrap();
+        let mut updated: Monster = serde_json::from_value(monster_json("B1")).unwrap();
+        updated.level = 9;
+        let position = set.position(|m| m.name == "B1").unwrap();
+        assert_eq!(set.replace(position, updated).unwrap().index, 0);
+        assert_eq!(set.dirty_files(), vec![b.as_path()]);
+
+        set.save().unwrap();
+        let monsters = io::load_json_array(&b).unwrap();
+        assert_eq!(monsters[0].level, 9);
+        assert_eq!(monsters[1].name, "B2");
+    }
+
+    #[test]
+    fn test_save_creates_backup_of_changed_file_only() {
+        let dir = TempDir::new().unwrap();
+        let a = write_file(&dir, "a.json", &["A1"]);
+        let b = write_file(&dir, "b.json", &["B1", "B2"]);
+        let b_before = fs::read_to_string(&b).unwrap();
+        let policy = BackupPolicy { count: 3, dir: Some(dir.path().join("backups")) };
+
+        let mut set = DataSet::load_monsters(&[&a, &b]).unwrap().with_backup(policy.clone());
+        set.remove_where(|m| m.name == "B2");
+        set.save().unwrap();
+
+        assert!(policy.list(&a).unwrap().is_empty());
+        let backups = policy.list(&b).unwrap();
+        assert_eq!(backups.len(), 1);
+        assert_eq!(fs::read_to_string(&backups[0].path).unwrap(), b_before);
+    }
+
+    #[test]
+    fn test_resolve_file() {
+        let dir = TempDir::new().unwrap();
+        let a = write_file(&dir, "a.json", &[]);
+        let b = write_file(&dir, "b.json", &[]);
+        let set = DataSet::load_monsters(&[&a, &b]).unwrap();
+
+        assert_eq!(set.resolve_file("b.json"), Some(b.as_path()));
+        assert_eq!(set.resolve_file(a.to_str().unwrap()), Some(a.as_path()));
+        assert_eq!(set.resolve_file("c.json"), None);
+    }
+
+    #[test]
+    fn test_load_spells() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("spells.json");
+        fs::write(&path, r#"[{"name": "S1", "school": "MagicCat_1"}]"#).unwrap();
+        let mut set = DataSet::load_spells(&[&path]).unwrap();
+        assert_eq!(set.source(0).unwrap().path, path);
+
+        set.push(Spell::new("S2", "MagicCat_1"), &path).unwrap();
+        assert_eq!(set.save().unwrap(), vec![path.clone()]);
+        let names: Vec<_> = io::load_spells_json_array(&path).unwrap().into_iter().map(|s| s.name).collect();
+        assert_eq!(names, vec!["S1", "S2"]);
+    }
+}
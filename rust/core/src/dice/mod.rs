@@ -0,0 +1,818 @@
+//! BCDice 形式のダイスロール
+//!
+//! 対応している書式:
+//! - 加算ロール: `2d6+5`、`2D+{神聖魔法}+{行使修正}`、`(1d6+2)*2`、`3d6>=10`
+//! - 計算のみ: `C(10+3*2)`
+//! - 威力表ロール（ソード・ワールド2.5）: `K20+10@10`、`k30[9]+5`、`K20$+1`（出目修正）、`K20$9`（出目固定）
+//!
+//! 先頭の空白までがダイス式で、それ以降はコメントとして扱う（チャットパレットの行をそのまま振れる）。
+
+use std::collections::HashMap;
+use std::fmt;
+use thiserror::Error;
+
+pub mod power_table;
+pub mod rng;
+
+pub use rng::DiceRng;
+
+/// 一度に振れるダイスの最大個数
+const MAX_DICE_COUNT: u32 = 200;
+/// ダイスの最大面数
+const MAX_DICE_SIDES: u32 = 1000;
+/// 威力表ロールの最大回転数（無限ループ防止）
+const MAX_CRITICAL_ROLLS: u32 = 100;
+
+/// ダイスロールのエラー型
+#[derive(Error, Debug, PartialEq)]
+pub enum DiceError {
+    #[error("ダイス式を解釈できません: {input}（{position}文字目: {message}）")]
+    Parse { input: String, position: usize, message: String },
+
+    #[error("変数 {{{0}}} の値が指定されていません")]
+    UnknownVariable(String),
+
+    #[error("威力は 0〜{max} で指定してください: {0}", max = power_table::MAX_POWER)]
+    InvalidPower(u32),
+
+    #[error("クリティカル値は 3 以上で指定してください: {0}")]
+    InvalidCritical(i32),
+
+    #[error("出目固定は 2〜12 で指定してください: {0}")]
+    InvalidFixedRoll(i32),
+
+    #[error("ダイスの個数・面数が大きすぎます: {0}D{1}")]
+    TooManyDice(u32, u32),
+
+    #[error("0 で割ることはできません")]
+    DivisionByZero,
+}
+
+/// 算術演算子
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+impl Op {
+    fn symbol(self) -> char {
+        match self {
+            Op::Add => '+',
+            Op::Sub => '-',
+            Op::Mul => '*',
+            Op::Div => '/',
+        }
+    }
+}
+
+/// 算術式
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    Number(i32),
+    Dice { count: u32, sides: u32 },
+    Neg(Box<Expr>),
+    Binary(Box<Expr>, Op, Box<Expr>),
+    Paren(Box<Expr>),
+    Calc(Box<Expr>),
+}
+
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Expr::Number(n) => write!(f, "{}", n),
+            Expr::Dice { count, sides } => write!(f, "{}D{}", count, sides),
+            Expr::Neg(e) => write!(f, "-{}", e),
+            Expr::Binary(l, op, r) => write!(f, "{}{}{}", l, op.symbol(), r),
+            Expr::Paren(e) => write!(f, "({})", e),
+            Expr::Calc(e) => write!(f, "C({})", e),
+        }
+    }
+}
+
+impl Expr {
+    /// 式を評価して（値, 出目を展開した表記）を返す
+    fn eval(&self, rng: &mut DiceRng) -> Result<(i32, String), DiceError> {
+        match self {
+            Expr::Number(n) => Ok((*n, n.to_string())),
+            Expr::Dice { count, sides } => {
+                let rolls: Vec<u32> = (0..*count).map(|_| rng.roll(*sides)).collect();
+                let sum: u32 = rolls.iter().sum();
+                let faces: Vec<String> = rolls.iter().map(u32::to_string).collect();
+                Ok((sum as i32, format!("{}[{}]", sum, faces.join(","))))
+            }
+            Expr::Neg(e) => {
+                let (value, text) = e.eval(rng)?;
+                Ok((-value, format!("-{}", text)))
+            }
+            Expr::Binary(l, op, r) => {
+                let (lv, lt) = l.eval(rng)?;
+                let (rv, rt) = r.eval(rng)?;
+                let value = match op {
+                    Op::Add => lv.saturating_add(rv),
+                    Op::Sub => lv.saturating_sub(rv),
+                    Op::Mul => lv.saturating_mul(rv),
+                    Op::Div => {
+                        if rv == 0 {
+                            return Err(DiceError::DivisionByZero);
+                        }
+                        lv.div_euclid(rv)
+                    }
+                };
+                Ok((value, format!("{}{}{}", lt, op.symbol(), rt)))
+            }
+            Expr::Paren(e) => {
+                let (value, text) = e.eval(rng)?;
+                Ok((value, format!("({})", text)))
+            }
+            Expr::Calc(e) => {
+                let (value, _) = e.eval(rng)?;
+                Ok((value, value.to_string()))
+            }
+        }
+    }
+
+    fn has_dice(&self) -> bool {
+        match self {
+            Expr::Number(_) => false,
+            Expr::Dice { .. } => true,
+            Expr::Neg(e) | Expr::Paren(e) | Expr::Calc(e) => e.has_dice(),
+            Expr::Binary(l, _, r) => l.has_dice() || r.has_dice(),
+        }
+    }
+}
+
+/// 成功判定の比較演算子
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Comparison {
+    GreaterOrEqual,
+    Greater,
+    LessOrEqual,
+    Less,
+    Equal,
+    NotEqual,
+}
+
+impl Comparison {
+    fn symbol(self) -> &'static str {
+        match self {
+            Comparison::GreaterOrEqual => ">=",
+            Comparison::Greater => ">",
+            Comparison::LessOrEqual => "<=",
+            Comparison::Less => "<",
+            Comparison::Equal => "=",
+            Comparison::NotEqual => "<>",
+        }
+    }
+
+    fn test(self, left: i32, right: i32) -> bool {
+        match self {
+            Comparison::GreaterOrEqual => left >= right,
+            Comparison::Greater => left > right,
+            Comparison::LessOrEqual => left <= right,
+            Comparison::Less => left < right,
+            Comparison::Equal => left == right,
+            Comparison::NotEqual => left != right,
+        }
+    }
+}
+
+/// 威力表ロールの指定
+#[derive(Debug, Clone, PartialEq)]
+pub struct PowerRoll {
+    /// 威力
+    pub power: u32,
+    /// クリティカル値（出目がこの値以上で振り足し。13 以上でクリティカルなし）
+    pub critical: i32,
+    /// 出目修正（最初の 2D の出目に加算）
+    pub dice_modifier: i32,
+    /// 出目固定（最初の 2D の出目をこの値にする）
+    pub fixed_roll: Option<i32>,
+    modifier: Option<Expr>,
+}
+
+/// 威力表ロールのデフォルトのクリティカル値
+pub const DEFAULT_CRITICAL: i32 = 10;
+
+/// 解析済みのダイスコマンド
+#[derive(Debug, Clone, PartialEq)]
+pub enum DiceCommand {
+    /// 加算ロール（任意で成功判定付き）
+    Sum { expr: Expr, target: Option<(Comparison, Expr)> },
+    /// 威力表ロール
+    Power(PowerRoll),
+}
+
+impl fmt::Display for DiceCommand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DiceCommand::Sum { expr, target } => {
+                write!(f, "{}", expr)?;
+                if let Some((cmp, rhs)) = target {
+                    write!(f, "{}{}", cmp.symbol(), rhs)?;
+                }
+                Ok(())
+            }
+            DiceCommand::Power(p) => {
+                write!(f, "K{}", p.power)?;
+                if let Some(m) = &p.modifier {
+                    let text = m.to_string();
+                    if text.starts_with('-') {
+                        write!(f, "{}", text)?;
+                    } else {
+                        write!(f, "+{}", text)?;
+                    }
+                }
+                write!(f, "@{}", p.critical)?;
+                if let Some(fixed) = p.fixed_roll {
+                    write!(f, "${}", fixed)?;
+                } else if p.dice_modifier != 0 {
+                    write!(f, "${:+}", p.dice_modifier)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// ロール結果
+#[derive(Debug, Clone, PartialEq)]
+pub struct RollResult {
+    /// 正規化したダイス式
+    pub command: String,
+    /// ダイス式に続くコメント
+    pub comment: Option<String>,
+    /// 途中経過（出目の展開など）
+    pub steps: Vec<String>,
+    /// 合計値
+    pub total: i32,
+    /// 成功判定の結果（判定なしの場合は None）
+    pub success: Option<bool>,
+    /// 自動失敗（威力表ロールで出目 2）
+    pub fumble: bool,
+    /// クリティカルによる回転数
+    pub critical_count: u32,
+}
+
+impl fmt::Display for RollResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({})", self.command)?;
+        for step in &self.steps {
+            write!(f, " ＞ {}", step)?;
+        }
+        if self.critical_count > 0 {
+            write!(f, " ＞ {}回転", self.critical_count)?;
+        }
+        write!(f, " ＞ {}", self.total)?;
+        if self.fumble {
+            write!(f, " ＞ 自動失敗")?;
+        }
+        match self.success {
+            Some(true) => write!(f, " ＞ 成功")?,
+            Some(false) => write!(f, " ＞ 失敗")?,
+            None => {}
+        }
+        if let Some(comment) = &self.comment {
+            write!(f, "  {}", comment)?;
+        }
+        Ok(())
+    }
+}
+
+impl DiceCommand {
+    /// ダイスを振る
+    ///
+    /// # エラー
+    /// 0 で割った場合
+    pub fn roll(&self, rng: &mut DiceRng) -> Result<RollResult, DiceError> {
+        match self {
+            DiceCommand::Sum { expr, target } => {
+                let (total, text) = expr.eval(rng)?;
+                let mut steps = Vec::new();
+                if text != total.to_string() {
+                    steps.push(text);
+                }
+                let success = match target {
+                    Some((cmp, rhs)) => {
+                        let (goal, _) = rhs.eval(rng)?;
+                        Some(cmp.test(total, goal))
+                    }
+                    None => None,
+                };
+                Ok(RollResult {
+                    command: self.to_string(),
+                    comment: None,
+                    steps,
+                    total,
+                    success,
+                    fumble: false,
+                    critical_count: 0,
+                })
+            }
+            DiceCommand::Power(spec) => spec.roll(rng, self.to_string()),
+        }
+    }
+}
+
+impl PowerRoll {
+    fn roll(&self, rng: &mut DiceRng, command: String) -> Result<RollResult, DiceError> {
+        let mut faces = Vec::new();
+        let mut sums = Vec::new();
+        let mut values = Vec::new();
+        let mut fumble = false;
+
+        for i in 0..MAX_CRITICAL_ROLLS {
+            let first = i == 0;
+            let (dice_text, natural) = match self.fixed_roll {
+                Some(fixed) if first => ("固定".to_string(), fixed),
+                _ => {
+                    let (a, b) = (rng.roll(6), rng.roll(6));
+                    (format!("{},{}", a, b), (a + b) as i32)
+                }
+            };
+            faces.push(dice_text);
+
+            if natural <= 2 {
+                // 最初の 2 は自動失敗、回転後の 2 は 0 点で打ち止め
+                sums.push(natural.to_string());
+                values.push("**".to_string());
+                fumble = first;
+                break;
+            }
+
+            let modified = if first { (natural + self.dice_modifier).clamp(3, 12) } else { natural };
+            sums.push(if first && self.dice_modifier != 0 {
+                format!("{}{:+}", natural, self.dice_modifier)
+            } else {
+                natural.to_string()
+            });
+            let value = power_table::lookup(self.power, modified).ok_or(DiceError::InvalidPower(self.power))?;
+            values.push(value.to_string());
+
+            if modified < self.critical {
+                break;
+            }
+        }
+
+        let table_total: i32 = values.iter().filter_map(|v| v.parse::<i32>().ok()).sum();
+        let (modifier_value, modifier_text) = match &self.modifier {
+            Some(m) => {
+                let (value, text) = m.eval(rng)?;
+                let text = if text.starts_with('-') { text } else { format!("+{}", text) };
+                (value, text)
+            }
+            None => (0, String::new()),
+        };
+
+        let total = if fumble { 0 } else { table_total.saturating_add(modifier_value) };
+        let critical_count = if fumble { 0 } else { values.len() as u32 - 1 };
+        Ok(RollResult {
+            command,
+            comment: None,
+            steps: vec![
+                format!("2D:[{}]={}", faces.join(" "), sums.join(",")),
+                format!("{}{}", values.join(","), modifier_text),
+            ],
+            total,
+            success: None,
+            fumble,
+            critical_count,
+        })
+    }
+}
+
+/// ダイス式を解析する
+///
+/// # 引数
+/// * `expression` - ダイス式（大文字小文字・全角半角は区別しない）
+///
+/// # 戻り値
+/// 解析済みのコマンド
+///
+/// # エラー
+/// 書式が不正な場合、威力・クリティカル値などが範囲外の場合
+pub fn parse(expression: &str) -> Result<DiceCommand, DiceError> {
+    let mut parser = Parser::new(expression);
+    let command = parser.parse_command()?;
+    Ok(command)
+}
+
+/// `{変数名}` 形式のプレースホルダを値で置き換える
+///
+/// 負の値は括弧で囲んで埋め込む（`2d+{修正}` → `2d+(-1)`）。
+///
+/// # 引数
+/// * `text` - プレースホルダを含む文字列（チャットパレットの行など）
+/// * `variables` - 変数名と値の対応
+///
+/// # エラー
+/// 値が指定されていない変数がある場合、`{` が閉じられていない場合
+pub fn resolve_placeholders(text: &str, variables: &HashMap<String, i32>) -> Result<String, DiceError> {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let Some(end) = after.find('}') else {
+            return Err(DiceError::Parse {
+                input: text.to_string(),
+                position: text[..text.len() - after.len()].chars().count(),
+                message: "'}' がありません".to_string(),
+            });
+        };
+        let name = after[..end].trim();
+        match variables.get(name) {
+            Some(value) if *value < 0 => out.push_str(&format!("({})", value)),
+            Some(value) => out.push_str(&value.to_string()),
+            None => return Err(DiceError::UnknownVariable(name.to_string())),
+        }
+        rest = &after[end + 1..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+/// チャットパレットの行を振る
+///
+/// プレースホルダを置き換えたあと、最初の空白までをダイス式、残りをコメントとして扱う。
+///
+/// # 引数
+/// * `line` - ダイス式（コメント付きでもよい）
+/// * `variables` - プレースホルダの値
+/// * `rng` - 乱数生成器
+///
+/// # エラー
+/// プレースホルダの値が足りない場合、ダイス式が不正な場合
+pub fn roll_line(line: &str, variables: &HashMap<String, i32>, rng: &mut DiceRng) -> Result<RollResult, DiceError> {
+    let resolved = resolve_placeholders(line.trim(), variables)?;
+    let (expression, comment) = match resolved.split_once(|c: char| c.is_whitespace()) {
+        Some((expr, comment)) => (expr, Some(comment.trim().to_string()).filter(|c| !c.is_empty())),
+        None => (resolved.as_str(), None),
+    };
+    let mut result = parse(expression)?.roll(rng)?;
+    result.comment = comment;
+    Ok(result)
+}
+
+/// ダイス式のパーサ
+struct Parser {
+    input: String,
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Parser {
+    fn new(input: &str) -> Self {
+        // 全角英数記号を半角に、英字を大文字にそろえる
+        let chars = input
+            .trim()
+            .chars()
+            .map(|c| match c {
+                '！'..='～' => char::from_u32(c as u32 - 0xFEE0).unwrap_or(c),
+                _ => c,
+            })
+            .map(|c| c.to_ascii_uppercase())
+            .collect();
+        Self { input: input.trim().to_string(), chars, pos: 0 }
+    }
+
+    fn error(&self, message: &str) -> DiceError {
+        DiceError::Parse { input: self.input.clone(), position: self.pos + 1, message: message.to_string() }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<char> {
+        self.chars.get(self.pos + offset).copied()
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(c) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), DiceError> {
+        if self.eat(c) { Ok(()) } else { Err(self.error(&format!("'{}' が必要です", c))) }
+    }
+
+    fn number(&mut self) -> Option<u32> {
+        let start = self.pos;
+        while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+            self.pos += 1;
+        }
+        if start == self.pos {
+            return None;
+        }
+        self.chars[start..self.pos].iter().collect::<String>().parse().ok()
+    }
+
+    fn expect_number(&mut self) -> Result<u32, DiceError> {
+        self.number().ok_or_else(|| self.error("数値が必要です"))
+    }
+
+    fn parse_command(&mut self) -> Result<DiceCommand, DiceError> {
+        if self.chars.is_empty() {
+            return Err(self.error("ダイス式が空です"));
+        }
+        let command = if self.peek() == Some('K') && self.peek_at(1).is_some_and(|c| c.is_ascii_digit()) {
+            self.pos += 1;
+            DiceCommand::Power(self.parse_power()?)
+        } else {
+            let expr = self.parse_expr()?;
+            let target = match self.parse_comparison() {
+                Some(cmp) => Some((cmp, self.parse_expr()?)),
+                None => None,
+            };
+            if !expr.has_dice() && !matches!(expr, Expr::Calc(_)) && target.is_none() {
+                return Err(self.error("ダイスが含まれていません（計算のみの場合は C(...) を使用してください）"));
+            }
+            DiceCommand::Sum { expr, target }
+        };
+        if self.pos < self.chars.len() {
+            return Err(self.error("解釈できない文字があります"));
+        }
+        Ok(command)
+    }
+
+    fn parse_power(&mut self) -> Result<PowerRoll, DiceError> {
+        let power = self.expect_number()?;
+        if power > power_table::MAX_POWER {
+            return Err(DiceError::InvalidPower(power));
+        }
+        let mut spec = PowerRoll { power, critical: DEFAULT_CRITICAL, dice_modifier: 0, fixed_roll: None, modifier: None };
+
+        while let Some(c) = self.peek() {
+            match c {
+                '@' => {
+                    self.pos += 1;
+                    spec.critical = self.expect_number()? as i32;
+                }
+                '[' => {
+                    self.pos += 1;
+                    spec.critical = self.expect_number()? as i32;
+                    self.expect(']')?;
+                }
+                '$' => {
+                    self.pos += 1;
+                    match self.peek() {
+                        Some('+') | Some('-') => {
+                            let negative = self.eat('-') || !self.eat('+');
+                            let n = self.expect_number()? as i32;
+                            spec.dice_modifier = if negative { -n } else { n };
+                        }
+                        _ => {
+                            let n = self.expect_number()? as i32;
+                            if !(2..=12).contains(&n) {
+                                return Err(DiceError::InvalidFixedRoll(n));
+                            }
+                            spec.fixed_roll = Some(n);
+                        }
+                    }
+                }
+                '+' | '-' => {
+                    self.pos += 1;
+                    let term = self.parse_term()?;
+                    let term = if c == '-' { Expr::Neg(Box::new(term)) } else { term };
+                    spec.modifier = Some(match spec.modifier.take() {
+                        None => term,
+                        Some(prev) => match term {
+                            Expr::Neg(inner) => Expr::Binary(Box::new(prev), Op::Sub, inner),
+                            term => Expr::Binary(Box::new(prev), Op::Add, Box::new(term)),
+                        },
+                    });
+                }
+                _ => break,
+            }
+        }
+
+        if spec.critical < 3 {
+            return Err(DiceError::InvalidCritical(spec.critical));
+        }
+        Ok(spec)
+    }
+
+    fn parse_comparison(&mut self) -> Option<Comparison> {
+        let cmp = match (self.peek()?, self.peek_at(1)) {
+            ('>', Some('=')) => (Comparison::GreaterOrEqual, 2),
+            ('<', Some('=')) => (Comparison::LessOrEqual, 2),
+            ('<', Some('>')) => (Comparison::NotEqual, 2),
+            ('>', _) => (Comparison::Greater, 1),
+            ('<', _) => (Comparison::Less, 1),
+            ('=', _) => (Comparison::Equal, 1),
+            _ => return None,
+        };
+        self.pos += cmp.1;
+        Some(cmp.0)
+    }
+
+    fn parse_expr(&mut self) -> Result<Expr, DiceError> {
+        let mut left = self.parse_term()?;
+        loop {
+            let op = match self.peek() {
+                Some('+') => Op::Add,
+                Some('-') => Op::Sub,
+                _ => return Ok(left),
+            };
+            self.pos += 1;
+            let right = self.parse_term()?;
+            left = Expr::Binary(Box::new(left), op, Box::new(right));
+        }
+    }
+
+    fn parse_term(&mut self) -> Result<Expr, DiceError> {
+        let mut left = self.parse_factor()?;
+        loop {
+            let op = match self.peek() {
+                Some('*') | Some('×') => Op::Mul,
+                Some('/') | Some('÷') => Op::Div,
+                _ => return Ok(left),
+            };
+            self.pos += 1;
+            let right = self.parse_factor()?;
+            left = Expr::Binary(Box::new(left), op, Box::new(right));
+        }
+    }
+
+    fn parse_factor(&mut self) -> Result<Expr, DiceError> {
+        match self.peek() {
+            Some('-') => {
+                self.pos += 1;
+                Ok(Expr::Neg(Box::new(self.parse_factor()?)))
+            }
+            Some('(') => {
+                self.pos += 1;
+                let inner = self.parse_expr()?;
+                self.expect(')')?;
+                Ok(Expr::Paren(Box::new(inner)))
+            }
+            Some('C') if self.peek_at(1) == Some('(') => {
+                self.pos += 2;
+                let inner = self.parse_expr()?;
+                self.expect(')')?;
+                Ok(Expr::Calc(Box::new(inner)))
+            }
+            Some('D') => {
+                self.pos += 1;
+                self.parse_dice(1)
+            }
+            Some(c) if c.is_ascii_digit() => {
+                let n = self.expect_number()?;
+                if self.eat('D') {
+                    self.parse_dice(n)
+                } else {
+                    i32::try_from(n).map(Expr::Number).map_err(|_| self.error("数値が大きすぎます"))
+                }
+            }
+            _ => Err(self.error("数値・ダイス・括弧のいずれかが必要です")),
+        }
+    }
+
+    /// `D` の直後から面数を読む（省略時は 6 面）
+    fn parse_dice(&mut self, count: u32) -> Result<Expr, DiceError> {
+        let sides = self.number().unwrap_or(6);
+        if count == 0 || sides == 0 {
+            return Err(self.error("ダイスの個数と面数は 1 以上で指定してください"));
+        }
+        if count > MAX_DICE_COUNT || sides > MAX_DICE_SIDES {
+            return Err(DiceError::TooManyDice(count, sides));
+        }
+        Ok(Expr::Dice { count, sides })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars(pairs: &[(&str, i32)]) -> HashMap<String, i32> {
+        pairs.iter().map(|(k, v)| (k.to_string(), *v)).collect()
+    }
+
+    #[test]
+    fn test_parse_normalizes_expression() {
+        assert_eq!(parse("2d6+5").unwrap().to_string(), "2D6+5");
+        assert_eq!(parse("2d+3").unwrap().to_string(), "2D6+3");
+        assert_eq!(parse("２Ｄ６＋１").unwrap().to_string(), "2D6+1");
+        assert_eq!(parse("(1d6+2)*2").unwrap().to_string(), "(1D6+2)*2");
+        assert_eq!(parse("3d6>=10").unwrap().to_string(), "3D6>=10");
+        assert_eq!(parse("k20+10@9").unwrap().to_string(), "K20+10@9");
+        assert_eq!(parse("K30[11]-2$+1").unwrap().to_string(), "K30-2@11$+1");
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(matches!(parse(""), Err(DiceError::Parse { .. })));
+        assert!(matches!(parse("2d6+"), Err(DiceError::Parse { .. })));
+        assert!(matches!(parse("2d6)"), Err(DiceError::Parse { .. })));
+        assert!(matches!(parse("5+3"), Err(DiceError::Parse { .. })));
+        assert_eq!(parse("K120"), Err(DiceError::InvalidPower(120)));
+        assert_eq!(parse("K20@2"), Err(DiceError::InvalidCritical(2)));
+        assert_eq!(parse("K20$13"), Err(DiceError::InvalidFixedRoll(13)));
+        assert_eq!(parse("500d6"), Err(DiceError::TooManyDice(500, 6)));
+    }
+
+    #[test]
+    fn test_sum_roll_is_reproducible() {
+        let command = parse("2d6+5").unwrap();
+        let a = command.roll(&mut DiceRng::seeded(1)).unwrap();
+        let b = command.roll(&mut DiceRng::seeded(1)).unwrap();
+        assert_eq!(a, b);
+        assert!((7..=17).contains(&a.total));
+        assert!(a.steps[0].ends_with("+5"));
+    }
+
+    #[test]
+    fn test_calculation_and_precedence() {
+        let mut rng = DiceRng::seeded(0);
+        assert_eq!(parse("C(10+3*2)").unwrap().roll(&mut rng).unwrap().total, 16);
+        assert_eq!(parse("C((10+2)/5)").unwrap().roll(&mut rng).unwrap().total, 2);
+        assert_eq!(parse("C(-7/2)").unwrap().roll(&mut rng).unwrap().total, -4);
+        assert_eq!(parse("C(1/0)").unwrap().roll(&mut rng), Err(DiceError::DivisionByZero));
+    }
+
+    #[test]
+    fn test_success_check() {
+        let mut rng = DiceRng::seeded(3);
+        assert_eq!(parse("2d6>=2").unwrap().roll(&mut rng).unwrap().success, Some(true));
+        assert_eq!(parse("2d6>12").unwrap().roll(&mut rng).unwrap().success, Some(false));
+    }
+
+    #[test]
+    fn test_power_roll_with_fixed_roll() {
+        // 出目固定 9 はクリティカル値 10 未満なので回転しない
+        let result = parse("K20+10$9").unwrap().roll(&mut DiceRng::seeded(0)).unwrap();
+        assert_eq!(result.total, 7 + 10);
+        assert_eq!(result.critical_count, 0);
+        assert!(!result.fumble);
+    }
+
+    #[test]
+    fn test_power_roll_critical_and_dice_modifier() {
+        // 出目固定 10 はクリティカル値 10 以上なので必ず回転する
+        let result = parse("K20$10").unwrap().roll(&mut DiceRng::seeded(0)).unwrap();
+        assert!(result.critical_count >= 1);
+        assert!(result.total > 8);
+
+        // 出目修正で 12 を超えても 12 の列を引く（@13 でクリティカルなし）
+        for seed in 0..50 {
+            let result = parse("K20$+20@13").unwrap().roll(&mut DiceRng::seeded(seed)).unwrap();
+            if !result.fumble {
+                assert_eq!(result.total, 10);
+                assert_eq!(result.critical_count, 0);
+            }
+        }
+    }
+
+    #[test]
+    fn test_power_roll_fumble_and_critical_over_many_seeds() {
+        let command = parse("K10+3@10").unwrap();
+        let mut saw_fumble = false;
+        let mut saw_critical = false;
+        for seed in 0..500 {
+            let result = command.roll(&mut DiceRng::seeded(seed)).unwrap();
+            if result.fumble {
+                saw_fumble = true;
+                assert_eq!(result.total, 0);
+                assert_eq!(result.critical_count, 0);
+            } else {
+                // 1回あたり威力10の値は 1〜7
+                let rolls = result.critical_count as i32 + 1;
+                assert!(result.total >= rolls + 3 && result.total <= rolls * 7 + 3);
+            }
+            saw_critical |= result.critical_count > 0;
+        }
+        assert!(saw_fumble);
+        assert!(saw_critical);
+    }
+
+    #[test]
+    fn test_resolve_placeholders() {
+        let v = vars(&[("神聖魔法", 5), ("行使修正", -1)]);
+        assert_eq!(resolve_placeholders("2d+{神聖魔法}+{行使修正}", &v).unwrap(), "2d+5+(-1)");
+        assert_eq!(
+            resolve_placeholders("2d+{妖精魔法}", &v),
+            Err(DiceError::UnknownVariable("妖精魔法".to_string()))
+        );
+        assert!(matches!(resolve_placeholders("2d+{神聖魔法", &v), Err(DiceError::Parse { .. })));
+    }
+
+    #[test]
+    fn test_roll_palette_line() {
+        let v = vars(&[("神聖魔法", 5), ("行使修正", 2)]);
+        let line = "2d+{神聖魔法}+{行使修正}  ゴッド・ジャッジメント / MP:15";
+        let result = roll_line(line, &v, &mut DiceRng::seeded(9)).unwrap();
+        assert_eq!(result.command, "2D6+5+2");
+        assert_eq!(result.comment.as_deref(), Some("ゴッド・ジャッジメント / MP:15"));
+        assert!((9..=19).contains(&result.total));
+        assert!(result.to_string().starts_with("(2D6+5+2) ＞ "));
+        assert!(result.to_string().ends_with("  ゴッド・ジャッジメント / MP:15"));
+    }
+}
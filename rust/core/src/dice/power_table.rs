@@ -0,0 +1,161 @@
+/// ソード・ワールド2.5 威力表
+///
+/// 行は威力 0〜100、列は 2D の出目 3〜12（出目 2 は自動失敗のため列を持たない）。
+const TABLE: [[i32; 10]; 101] = [
+    //3   4   5   6   7   8   9  10  11  12
+    [0, 0, 0, 1, 2, 2, 3, 3, 4, 4],         // 0
+    [0, 0, 0, 1, 2, 3, 3, 3, 4, 4],         // 1
+    [0, 0, 0, 1, 2, 3, 4, 4, 4, 4],         // 2
+    [0, 0, 1, 1, 2, 3, 4, 4, 4, 5],         // 3
+    [0, 0, 1, 2, 2, 3, 4, 4, 5, 5],         // 4
+    [0, 1, 1, 2, 2, 3, 4, 5, 5, 5],         // 5
+    [0, 1, 1, 2, 3, 3, 4, 5, 5, 5],         // 6
+    [0, 1, 1, 2, 3, 4, 4, 5, 5, 6],         // 7
+    [0, 1, 2, 2, 3, 4, 4, 5, 6, 6],         // 8
+    [0, 1, 2, 3, 3, 4, 4, 5, 6, 7],         // 9
+    [1, 1, 2, 3, 3, 4, 5, 5, 6, 7],         // 10
+    [1, 2, 2, 3, 3, 4, 5, 6, 6, 7],         // 11
+    [1, 2, 2, 3, 4, 4, 5, 6, 6, 7],         // 12
+    [1, 2, 3, 3, 4, 4, 5, 6, 7, 7],         // 13
+    [1, 2, 3, 4, 4, 4, 5, 6, 7, 8],         // 14
+    [1, 2, 3, 4, 4, 5, 5, 6, 7, 8],         // 15
+    [1, 2, 3, 4, 4, 5, 6, 7, 7, 8],         // 16
+    [1, 2, 3, 4, 5, 5, 6, 7, 7, 8],         // 17
+    [1, 2, 3, 4, 5, 6, 6, 7, 7, 8],         // 18
+    [1, 2, 3, 4, 5, 6, 7, 7, 8, 9],         // 19
+    [1, 2, 3, 4, 5, 6, 7, 8, 9, 10],        // 20
+    [1, 2, 3, 4, 6, 6, 7, 8, 9, 10],        // 21
+    [1, 2, 3, 5, 6, 6, 7, 8, 9, 10],        // 22
+    [2, 2, 3, 5, 6, 7, 7, 8, 9, 10],        // 23
+    [2, 3, 4, 5, 6, 7, 7, 8, 9, 10],        // 24
+    [2, 3, 4, 5, 6, 7, 8, 8, 9, 10],        // 25
+    [2, 3, 4, 5, 6, 8, 8, 9, 9, 10],        // 26
+    [2, 3, 4, 6, 6, 8, 8, 9, 9, 10],        // 27
+    [2, 3, 4, 6, 6, 8, 9, 9, 10, 10],       // 28
+    [2, 3, 4, 6, 7, 8, 9, 9, 10, 10],       // 29
+    [2, 4, 4, 6, 7, 8, 9, 10, 10, 10],      // 30
+    [2, 4, 5, 6, 7, 8, 9, 10, 10, 11],      // 31
+    [3, 4, 5, 6, 7, 8, 10, 10, 10, 11],     // 32
+    [3, 4, 5, 6, 8, 8, 10, 10, 10, 11],     // 33
+    [3, 4, 5, 6, 8, 9, 10, 10, 11, 11],     // 34
+    [3, 4, 5, 7, 8, 9, 10, 10, 11, 12],     // 35
+    [3, 5, 5, 7, 8, 9, 10, 11, 11, 12],     // 36
+    [3, 5, 6, 7, 8, 9, 10, 11, 12, 12],     // 37
+    [3, 5, 6, 7, 8, 10, 10, 11, 12, 13],    // 38
+    [4, 5, 6, 7, 8, 10, 11, 11, 12, 13],    // 39
+    [4, 5, 6, 7, 9, 10, 11, 11, 12, 13],    // 40
+    [4, 6, 6, 7, 9, 10, 11, 12, 12, 13],    // 41
+    [4, 6, 7, 7, 9, 10, 11, 12, 13, 13],    // 42
+    [4, 6, 7, 8, 9, 10, 11, 12, 13, 14],    // 43
+    [4, 6, 7, 8, 10, 10, 11, 12, 13, 14],   // 44
+    [4, 6, 7, 9, 10, 10, 11, 12, 13, 14],   // 45
+    [4, 6, 7, 9, 10, 10, 12, 13, 13, 14],   // 46
+    [4, 6, 7, 9, 10, 11, 12, 13, 13, 15],   // 47
+    [4, 6, 7, 9, 10, 12, 12, 13, 13, 15],   // 48
+    [4, 6, 7, 10, 10, 12, 12, 13, 14, 15],  // 49
+    [4, 6, 8, 10, 10, 12, 12, 13, 15, 15],  // 50
+    [5, 7, 8, 10, 10, 12, 12, 13, 15, 15],  // 51
+    [5, 7, 8, 10, 11, 12, 12, 13, 15, 15],  // 52
+    [5, 7, 9, 10, 11, 12, 12, 14, 15, 15],  // 53
+    [5, 7, 9, 10, 11, 12, 13, 14, 15, 16],  // 54
+    [5, 7, 10, 10, 11, 12, 13, 14, 16, 16], // 55
+    [5, 8, 10, 10, 11, 12, 13, 15, 16, 16], // 56
+    [5, 8, 10, 11, 11, 12, 13, 15, 16, 17], // 57
+    [5, 8, 10, 11, 12, 12, 13, 15, 16, 17], // 58
+    [5, 9, 10, 11, 12, 12, 14, 15, 16, 17], // 59
+    [5, 9, 10, 11, 12, 13, 14, 15, 16, 18], // 60
+    [5, 9, 10, 11, 12, 13, 14, 16, 17, 18], // 61
+    [5, 9, 10, 11, 13, 13, 14, 16, 17, 18], // 62
+    [5, 9, 10, 11, 13, 13, 15, 17, 17, 18], // 63
+    [5, 9, 10, 11, 13, 14, 15, 17, 17, 18], // 64
+    [5, 9, 10, 12, 13, 14, 15, 17, 18, 18], // 65
+    [5, 9, 10, 12, 13, 15, 15, 17, 18, 19], // 66
+    [5, 9, 10, 12, 13, 15, 16, 17, 19, 19], // 67
+    [5, 9, 10, 12, 14, 15, 16, 17, 19, 19], // 68
+    [5, 9, 10, 12, 14, 16, 16, 17, 19, 19], // 69
+    [5, 9, 10, 12, 14, 16, 17, 18, 19, 19], // 70
+    [5, 9, 10, 13, 14, 16, 17, 18, 19, 20], // 71
+    [5, 9, 10, 13, 15, 16, 17, 18, 19, 20], // 72
+    [5, 9, 10, 13, 15, 16, 17, 19, 20, 21], // 73
+    [6, 9, 10, 13, 15, 16, 18, 19, 20, 21], // 74
+    [6, 9, 10, 13, 16, 16, 18, 19, 20, 21], // 75
+    [6, 9, 10, 13, 16, 17, 18, 19, 20, 21], // 76
+    [6, 9, 10, 13, 16, 17, 18, 20, 21, 22], // 77
+    [6, 9, 10, 13, 16, 17, 19, 20, 22, 23], // 78
+    [6, 9, 10, 13, 16, 18, 19, 20, 22, 23], // 79
+    [6, 9, 10, 13, 16, 18, 20, 21, 22, 23], // 80
+    [6, 9, 10, 13, 17, 18, 20, 21, 22, 23], // 81
+    [6, 9, 10, 14, 17, 18, 20, 21, 22, 24], // 82
+    [6, 9, 11, 14, 17, 18, 20, 21, 23, 24], // 83
+    [6, 9, 11, 14, 17, 19, 20, 21, 23, 24], // 84
+    [6, 9, 11, 14, 17, 19, 21, 22, 23, 24], // 85
+    [7, 10, 11, 14, 17, 19, 21, 22, 23, 25], // 86
+    [7, 10, 12, 14, 17, 19, 21, 22, 24, 25], // 87
+    [7, 10, 12, 14, 18, 19, 21, 22, 24, 25], // 88
+    [7, 10, 12, 15, 18, 19, 21, 22, 24, 26], // 89
+    [7, 10, 12, 15, 18, 19, 21, 23, 25, 26], // 90
+    [7, 11, 13, 15, 18, 19, 21, 23, 25, 26], // 91
+    [7, 11, 13, 15, 18, 20, 21, 23, 25, 27], // 92
+    [8, 11, 13, 15, 18, 20, 22, 23, 25, 27], // 93
+    [8, 11, 13, 16, 18, 20, 22, 23, 25, 28], // 94
+    [8, 11, 14, 16, 18, 20, 22, 23, 26, 28], // 95
+    [8, 11, 14, 16, 19, 20, 22, 23, 26, 28], // 96
+    [8, 12, 14, 16, 19, 20, 22, 24, 26, 28], // 97
+    [8, 12, 15, 16, 19, 20, 22, 24, 27, 28], // 98
+    [8, 12, 15, 17, 19, 20, 22, 24, 27, 29], // 99
+    [8, 12, 15, 18, 19, 20, 22, 24, 27, 30], // 100
+];
+
+/// 威力表の最大威力
+pub const MAX_POWER: u32 = 100;
+
+/// 威力表を引く
+///
+/// # 引数
+/// * `power` - 威力（0〜100）
+/// * `roll` - 2D の出目（12 を超える値は 12 として扱う）
+///
+/// # 戻り値
+/// 威力表の値。威力が範囲外、または出目が 2 以下（自動失敗）の場合は `None`
+pub fn lookup(power: u32, roll: i32) -> Option<i32> {
+    if power > MAX_POWER || roll <= 2 {
+        return None;
+    }
+    let column = (roll.min(12) - 3) as usize;
+    Some(TABLE[power as usize][column])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_known_rows() {
+        let row = |power: u32| (3..=12).map(|r| lookup(power, r).unwrap()).collect::<Vec<_>>();
+        assert_eq!(row(0), vec![0, 0, 0, 1, 2, 2, 3, 3, 4, 4]);
+        assert_eq!(row(10), vec![1, 1, 2, 3, 3, 4, 5, 5, 6, 7]);
+        assert_eq!(row(20), vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10]);
+        assert_eq!(row(100), vec![8, 12, 15, 18, 19, 20, 22, 24, 27, 30]);
+    }
+
+    #[test]
+    fn test_table_is_monotonic() {
+        for power in 0..=MAX_POWER as usize {
+            for column in 0..10 {
+                if column > 0 {
+                    assert!(TABLE[power][column] >= TABLE[power][column - 1], "威力{} 列{}", power, column);
+                }
+                if power > 0 {
+                    assert!(TABLE[power][column] >= TABLE[power - 1][column], "威力{} 列{}", power, column);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_out_of_range() {
+        assert_eq!(lookup(20, 2), None);
+        assert_eq!(lookup(101, 7), None);
+        assert_eq!(lookup(20, 14), Some(10));
+    }
+}
@@ -0,0 +1,582 @@
+//! エンカウンター（戦闘で登場させる魔物の組み合わせ）の作成
+//!
+//! 魔物ごとの脅威度を「レベル × 部位数 × HP 補正」で算出し、
+//! パーティの平均レベル × 人数 × 難易度補正を予算として、
+//! 予算に近い組み合わせを複数の候補として返します。
+//! HP 補正は同じレベルの魔物の平均 HP（1部位あたり）との比で、0.5〜2.0 に制限します。
+
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::Monster;
+
+/// 予算に対する許容誤差（±15%）
+const BUDGET_TOLERANCE: f64 = 0.15;
+
+/// 1体目（リーダー）ごとの探索ノード数の上限
+const SEARCH_LIMIT_PER_LEADER: usize = 20_000;
+
+/// 1体目（リーダー）ごとに保持する解の上限
+const SOLUTIONS_PER_LEADER: usize = 20;
+
+/// エンカウンター作成時のエラー型
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub enum EncounterError {
+    #[error("無効なパーティ設定: {0}")]
+    InvalidParty(String),
+
+    #[error("条件に合う魔物がいません（レベル {min}〜{max}）")]
+    NoMonsters { min: i32, max: i32 },
+
+    #[error("予算 {budget:.1} に合う組み合わせが見つかりません")]
+    NoCandidates { budget: f64 },
+}
+
+/// 難易度
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Difficulty {
+    Easy,
+    #[default]
+    Normal,
+    Hard,
+    Deadly,
+}
+
+impl Difficulty {
+    /// 予算に掛ける補正
+    pub fn multiplier(self) -> f64 {
+        match self {
+            Difficulty::Easy => 0.75,
+            Difficulty::Normal => 1.0,
+            Difficulty::Hard => 1.25,
+            Difficulty::Deadly => 1.5,
+        }
+    }
+}
+
+impl FromStr for Difficulty {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "easy" => Ok(Difficulty::Easy),
+            "normal" => Ok(Difficulty::Normal),
+            "hard" => Ok(Difficulty::Hard),
+            "deadly" => Ok(Difficulty::Deadly),
+            _ => Err(format!(
+                "不明な難易度: {}（easy, normal, hard, deadly のいずれかを指定してください）",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Difficulty::Easy => "easy",
+            Difficulty::Normal => "normal",
+            Difficulty::Hard => "hard",
+            Difficulty::Deadly => "deadly",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+/// エンカウンター作成の条件
+#[derive(Debug, Clone, PartialEq)]
+pub struct EncounterParams {
+    /// パーティの平均レベル
+    pub party_level: i32,
+
+    /// パーティの人数
+    pub party_size: u32,
+
+    /// 難易度
+    pub difficulty: Difficulty,
+
+    /// 魔物の最低レベル（未指定時はパーティレベル - 3）
+    pub min_level: Option<i32>,
+
+    /// 魔物の最高レベル（未指定時はパーティレベル + 2）
+    pub max_level: Option<i32>,
+
+    /// 1つのエンカウンターに含める魔物の最大数（同じ魔物の複数体を含む）
+    pub max_monsters: usize,
+
+    /// 返す候補の数
+    pub candidates: usize,
+}
+
+impl EncounterParams {
+    /// パーティレベルと人数からデフォルト条件を作成
+    pub fn new(party_level: i32, party_size: u32) -> Self {
+        EncounterParams {
+            party_level,
+            party_size,
+            difficulty: Difficulty::Normal,
+            min_level: None,
+            max_level: None,
+            max_monsters: party_size as usize + 2,
+            candidates: 5,
+        }
+    }
+
+    /// 脅威度の予算（パーティレベル × 人数 × 難易度補正）
+    pub fn budget(&self) -> f64 {
+        self.party_level as f64 * self.party_size as f64 * self.difficulty.multiplier()
+    }
+
+    /// 対象とする魔物のレベル範囲
+    pub fn level_range(&self) -> (i32, i32) {
+        (
+            self.min_level.unwrap_or((self.party_level - 3).max(1)),
+            self.max_level.unwrap_or(self.party_level + 2),
+        )
+    }
+
+    fn validate(&self) -> Result<(), EncounterError> {
+        if self.party_level < 1 {
+            return Err(EncounterError::InvalidParty("パーティレベルは1以上を指定してください".to_string()));
+        }
+        if self.party_size == 0 {
+            return Err(EncounterError::InvalidParty("パーティ人数は1以上を指定してください".to_string()));
+        }
+        if self.max_monsters == 0 {
+            return Err(EncounterError::InvalidParty("魔物の最大数は1以上を指定してください".to_string()));
+        }
+        let (min, max) = self.level_range();
+        if min > max {
+            return Err(EncounterError::InvalidParty(format!(
+                "レベル範囲が不正です（{}〜{}）",
+                min, max
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// エンカウンターに含まれる魔物
+#[derive(Debug, Clone)]
+pub struct EncounterMember<'a> {
+    pub monster: &'a Monster,
+
+    /// 登場数
+    pub count: u32,
+
+    /// 1体あたりの脅威度
+    pub threat: f64,
+}
+
+/// エンカウンター候補
+#[derive(Debug, Clone)]
+pub struct Encounter<'a> {
+    /// 魔物（脅威度の高い順）
+    pub members: Vec<EncounterMember<'a>>,
+
+    /// 合計脅威度
+    pub threat: f64,
+
+    /// 予算
+    pub budget: f64,
+}
+
+impl Encounter<'_> {
+    /// 魔物の数（複数体を含む）
+    pub fn monster_count(&self) -> u32 {
+        self.members.iter().map(|m| m.count).sum()
+    }
+
+    /// 合計部位数
+    pub fn part_total(&self) -> i32 {
+        self.members.iter().map(|m| part_count(m.monster) * m.count as i32).sum()
+    }
+
+    /// 合計 HP
+    pub fn hp_total(&self) -> i32 {
+        self.members.iter().map(|m| hp_total(m.monster) * m.count as i32).sum()
+    }
+
+    /// 合計レベル
+    pub fn level_total(&self) -> i32 {
+        self.members.iter().map(|m| m.monster.level * m.count as i32).sum()
+    }
+
+    /// 予算との差（割合、正なら予算超過）
+    pub fn deviation(&self) -> f64 {
+        (self.threat - self.budget) / self.budget
+    }
+
+    /// エクスポート用に魔物を展開（複数体は名前に A, B, ... を付けて区別）
+    pub fn to_monsters(&self) -> Vec<Monster> {
+        let mut monsters = Vec::new();
+        for member in &self.members {
+            for i in 0..member.count {
+                let mut monster = member.monster.clone();
+                if member.count > 1 {
+                    monster.name = format!("{}{}", monster.name, copy_label(i as usize));
+                }
+                monsters.push(monster);
+            }
+        }
+        monsters
+    }
+}
+
+/// 複数体を区別するためのラベル（0 → "A", 25 → "Z", 26 → "AA"）
+pub fn copy_label(index: usize) -> String {
+    let mut n = index + 1;
+    let mut label = Vec::new();
+    while n > 0 {
+        n -= 1;
+        label.push((b'A' + (n % 26) as u8) as char);
+        n /= 26;
+    }
+    label.iter().rev().collect()
+}
+
+/// 魔物の合計部位数（部位数の合計、最低1）
+pub fn part_count(monster: &Monster) -> i32 {
+    monster.part.iter().map(|p| p.part_count.max(1)).sum::<i32>().max(1)
+}
+
+/// 魔物の合計 HP（各部位の HP × 部位数）
+pub fn hp_total(monster: &Monster) -> i32 {
+    monster
+        .part
+        .iter()
+        .map(|p| p.hp.unwrap_or(0) * p.part_count.max(1))
+        .sum()
+}
+
+/// レベルごとの 1部位あたり平均 HP（HP が設定された魔物のみ）
+fn reference_hp(monsters: &[&Monster]) -> HashMap<i32, f64> {
+    let mut sums: HashMap<i32, (f64, u32)> = HashMap::new();
+    for monster in monsters {
+        let hp = hp_total(monster);
+        if hp > 0 {
+            let entry = sums.entry(monster.level).or_insert((0.0, 0));
+            entry.0 += hp as f64 / part_count(monster) as f64;
+            entry.1 += 1;
+        }
+    }
+    sums.into_iter()
+        .map(|(level, (sum, n))| (level, sum / n as f64))
+        .collect()
+}
+
+/// 魔物1体の脅威度（レベル × 部位数 × HP 補正）
+fn threat(monster: &Monster, reference: &HashMap<i32, f64>) -> f64 {
+    let parts = part_count(monster) as f64;
+    let hp = hp_total(monster);
+    let factor = match reference.get(&monster.level) {
+        Some(&avg) if hp > 0 && avg > 0.0 => (hp as f64 / parts / avg).clamp(0.5, 2.0),
+        _ => 1.0,
+    };
+    monster.level.max(1) as f64 * parts * factor
+}
+
+/// エンカウンター候補を作成
+///
+/// # 引数
+/// * `monsters` - 候補となる魔物（カテゴリ等で絞り込み済み）。HP 補正の基準にも使用
+/// * `params` - 作成条件
+///
+/// # 戻り値
+/// * `Ok(Vec<Encounter>)` - 予算に近い順の候補（最大 `params.candidates` 件）
+/// * `Err(EncounterError)` - 条件が不正、または候補が見つからない場合
+pub fn build<'a>(monsters: &[&'a Monster], params: &EncounterParams) -> Result<Vec<Encounter<'a>>, EncounterError> {
+    params.validate()?;
+
+    let (min_level, max_level) = params.level_range();
+    let reference = reference_hp(monsters);
+
+    // レベル範囲内の魔物（同名は1つにまとめる）を脅威度の高い順に並べる
+    let mut pool: Vec<(&'a Monster, f64)> = Vec::new();
+    for &monster in monsters {
+        if monster.level < min_level || monster.level > max_level {
+            continue;
+        }
+        if pool.iter().any(|(m, _)| m.name == monster.name) {
+            continue;
+        }
+        pool.push((monster, threat(monster, &reference)));
+    }
+    if pool.is_empty() {
+        return Err(EncounterError::NoMonsters { min: min_level, max: max_level });
+    }
+    pool.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.name.cmp(&b.0.name)));
+
+    let budget = params.budget();
+    let search = Search {
+        threats: pool.iter().map(|(_, t)| *t).collect(),
+        low: budget * (1.0 - BUDGET_TOLERANCE),
+        high: budget * (1.0 + BUDGET_TOLERANCE),
+        max_monsters: params.max_monsters,
+    };
+
+    // リーダー（最も脅威度の高い魔物）ごとに探索して候補を集める
+    let mut solutions: Vec<Vec<usize>> = Vec::new();
+    for leader in 0..pool.len() {
+        let mut state = SearchState {
+            chosen: vec![leader],
+            nodes: 0,
+            found: Vec::new(),
+        };
+        search.extend(&mut state, leader, search.threats[leader]);
+        solutions.extend(state.found);
+    }
+    if solutions.is_empty() {
+        return Err(EncounterError::NoCandidates { budget });
+    }
+
+    let mut encounters: Vec<Encounter<'a>> = solutions
+        .into_iter()
+        .map(|chosen| {
+            let mut members: Vec<EncounterMember<'a>> = Vec::new();
+            for index in chosen {
+                let (monster, threat) = pool[index];
+                match members.last_mut() {
+                    Some(last) if std::ptr::eq(last.monster, monster) => last.count += 1,
+                    _ => members.push(EncounterMember { monster, count: 1, threat }),
+                }
+            }
+            let threat = members.iter().map(|m| m.threat * m.count as f64).sum();
+            Encounter { members, threat, budget }
+        })
+        .collect();
+
+    // 予算との差が小さく、部位数がパーティ人数に近いものを優先
+    let score = |e: &Encounter| e.deviation().abs() + 0.05 * (e.part_total() - params.party_size as i32).abs() as f64;
+    encounters.sort_by(|a, b| score(a).total_cmp(&score(b)));
+
+    // リーダーが異なる候補を優先して選び、足りなければ残りから補う
+    let mut selected: Vec<Encounter<'a>> = Vec::new();
+    let mut rest = Vec::new();
+    for encounter in encounters {
+        let leader = encounter.members[0].monster;
+        if selected.iter().any(|s| std::ptr::eq(s.members[0].monster, leader)) {
+            rest.push(encounter);
+        } else {
+            selected.push(encounter);
+        }
+    }
+    selected.truncate(params.candidates);
+    let missing = params.candidates.saturating_sub(selected.len());
+    selected.extend(rest.into_iter().take(missing));
+    selected.sort_by(|a, b| score(a).total_cmp(&score(b)));
+
+    Ok(selected)
+}
+
+/// 組み合わせ探索の条件
+struct Search {
+    threats: Vec<f64>,
+    low: f64,
+    high: f64,
+    max_monsters: usize,
+}
+
+/// 組み合わせ探索の状態
+struct SearchState {
+    chosen: Vec<usize>,
+    nodes: usize,
+    found: Vec<Vec<usize>>,
+}
+
+impl Search {
+    /// `start` 以降（脅威度が同じか低い魔物）を追加して組み合わせを探索
+    fn extend(&self, state: &mut SearchState, start: usize, total: f64) {
+        state.nodes += 1;
+        if state.nodes > SEARCH_LIMIT_PER_LEADER || state.found.len() >= SOLUTIONS_PER_LEADER {
+            return;
+        }
+        if total >= self.low {
+            state.found.push(state.chosen.clone());
+        }
+        if state.chosen.len() >= self.max_monsters {
+            return;
+        }
+
+        let remaining = (self.max_monsters - state.chosen.len()) as f64;
+        for index in start..self.threats.len() {
+            let threat = self.threats[index];
+            if total + threat > self.high {
+                continue;
+            }
+            // 脅威度の高い順なので、以降の魔物で枠を埋めても下限に届かなければ打ち切る
+            if total + threat * remaining < self.low {
+                break;
+            }
+            state.chosen.push(index);
+            self.extend(state, index, total + threat);
+            state.chosen.pop();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Part;
+
+    fn monster(name: &str, level: i32, parts: &[(i32, i32)]) -> Monster {
+        Monster {
+            category: "蛮族".to_string(),
+            level,
+            revision: 2.5,
+            data: String::new(),
+            illust: String::new(),
+            movein: 10,
+            movein_description: String::new(),
+            moveon: -1,
+            moveon_description: String::new(),
+            name: name.to_string(),
+            part: parts
+                .iter()
+                .map(|&(hp, count)| Part {
+                    hp: Some(hp),
+                    mp: -1,
+                    name: String::new(),
+                    core: None,
+                    hit_rate: None,
+                    dodge: None,
+                    damage: None,
+                    part_count: count,
+                    special_abilities: String::new(),
+                    armor: 0,
+                })
+                .collect(),
+            notes: String::new(),
+            initiative: 10,
+            common_abilities: String::new(),
+            weakness: String::new(),
+            weakness_value: 10,
+            life_resistance: 5,
+            fame: 8,
+            mental_resistance: 5,
+            extra: HashMap::new(),
+        }
+    }
+
+    fn sample() -> Vec<Monster> {
+        vec![
+            monster("ゴブリン", 2, &[(16, 1)]),
+            monster("ボガード", 4, &[(30, 1)]),
+            monster("トロール", 6, &[(50, 1)]),
+            monster("ヒュドラ", 6, &[(40, 3), (60, 1)]),
+            monster("ドラゴン", 12, &[(200, 1)]),
+        ]
+    }
+
+    #[test]
+    fn test_budget_and_level_range() {
+        let mut params = EncounterParams::new(5, 4);
+        assert_eq!(params.budget(), 20.0);
+        assert_eq!(params.level_range(), (2, 7));
+        params.difficulty = Difficulty::Hard;
+        assert_eq!(params.budget(), 25.0);
+        assert_eq!(EncounterParams::new(1, 4).level_range(), (1, 3));
+    }
+
+    #[test]
+    fn test_difficulty_from_str() {
+        assert_eq!("HARD".parse::<Difficulty>(), Ok(Difficulty::Hard));
+        assert!("impossible".parse::<Difficulty>().is_err());
+        assert_eq!(Difficulty::Deadly.to_string(), "deadly");
+    }
+
+    #[test]
+    fn test_part_count_and_hp_total() {
+        let hydra = monster("ヒュドラ", 6, &[(40, 3), (60, 1)]);
+        assert_eq!(part_count(&hydra), 4);
+        assert_eq!(hp_total(&hydra), 180);
+    }
+
+    #[test]
+    fn test_threat_uses_hp_relative_to_level() {
+        let weak = monster("弱", 4, &[(20, 1)]);
+        let strong = monster("強", 4, &[(60, 1)]);
+        let pool = vec![&weak, &strong];
+        let reference = reference_hp(&pool);
+        assert_eq!(reference[&4], 40.0);
+        assert_eq!(threat(&weak, &reference), 2.0);
+        assert_eq!(threat(&strong, &reference), 6.0);
+    }
+
+    #[test]
+    fn test_build_candidates_within_budget() {
+        let monsters = sample();
+        let refs: Vec<&Monster> = monsters.iter().collect();
+        let params = EncounterParams::new(5, 4);
+        let encounters = build(&refs, &params).unwrap();
+
+        assert!(!encounters.is_empty());
+        assert!(encounters.len() <= params.candidates);
+        for encounter in &encounters {
+            assert!(encounter.deviation().abs() <= BUDGET_TOLERANCE + 1e-9, "{:?}", encounter.threat);
+            assert!(encounter.monster_count() as usize <= params.max_monsters);
+            // レベル範囲外の魔物は含まれない
+            assert!(encounter.members.iter().all(|m| m.monster.name != "ドラゴン"));
+        }
+    }
+
+    #[test]
+    fn test_build_prefers_different_leaders() {
+        let monsters = sample();
+        let refs: Vec<&Monster> = monsters.iter().collect();
+        let encounters = build(&refs, &EncounterParams::new(5, 4)).unwrap();
+        let leaders: Vec<&str> = encounters.iter().map(|e| e.members[0].monster.name.as_str()).collect();
+        let unique: std::collections::HashSet<&str> = leaders.iter().copied().collect();
+        assert!(unique.len() >= 3, "{:?}", leaders);
+    }
+
+    #[test]
+    fn test_build_errors() {
+        let monsters = sample();
+        let refs: Vec<&Monster> = monsters.iter().collect();
+
+        assert!(matches!(
+            build(&refs, &EncounterParams::new(0, 4)),
+            Err(EncounterError::InvalidParty(_))
+        ));
+
+        let mut params = EncounterParams::new(5, 4);
+        params.min_level = Some(8);
+        params.max_level = Some(10);
+        assert_eq!(build(&refs, &params).unwrap_err(), EncounterError::NoMonsters { min: 8, max: 10 });
+
+        // 予算 50 に対して1体では届かない
+        let mut params = EncounterParams::new(5, 10);
+        params.max_monsters = 1;
+        assert!(matches!(build(&refs, &params), Err(EncounterError::NoCandidates { .. })));
+    }
+
+    #[test]
+    fn test_to_monsters_labels_copies() {
+        let goblin = monster("ゴブリン", 2, &[(16, 1)]);
+        let troll = monster("トロール", 6, &[(50, 1)]);
+        let encounter = Encounter {
+            members: vec![
+                EncounterMember { monster: &troll, count: 1, threat: 6.0 },
+                EncounterMember { monster: &goblin, count: 2, threat: 2.0 },
+            ],
+            threat: 10.0,
+            budget: 10.0,
+        };
+        let names: Vec<String> = encounter.to_monsters().into_iter().map(|m| m.name).collect();
+        assert_eq!(names, vec!["トロール", "ゴブリンA", "ゴブリンB"]);
+        assert_eq!(encounter.monster_count(), 3);
+        assert_eq!(encounter.level_total(), 10);
+        assert_eq!(encounter.hp_total(), 82);
+    }
+
+    #[test]
+    fn test_copy_label() {
+        assert_eq!(copy_label(0), "A");
+        assert_eq!(copy_label(25), "Z");
+        assert_eq!(copy_label(26), "AA");
+        assert_eq!(copy_label(27), "AB");
+    }
+}
@@ -1,11 +1,27 @@
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::fs;
-use std::path::PathBuf;
+use std::io::BufRead;
+use std::path::{Path, PathBuf};
 use std::time::{SystemTime, UNIX_EPOCH};
 use thiserror::Error;
 use tiny_http::{Server, Response};
 use url::Url;
 
+/// Google の認可エンドポイント
+pub const DEFAULT_AUTH_URI: &str = "https://accounts.google.com/o/oauth2/v2/auth";
+
+/// Google のトークンエンドポイント
+pub const DEFAULT_TOKEN_URI: &str = "https://oauth2.googleapis.com/token";
+
+/// 既定のリダイレクト URI（コールバックを受け取るローカルサーバー）
+pub const DEFAULT_REDIRECT_URI: &str = "http://localhost:8080/callback";
+
+/// Google Sheets API のスコープ
+const SHEETS_SCOPE: &str = "https://www.googleapis.com/auth/spreadsheets";
+
 /// OAuth 2.0 認証エラー型
 #[derive(Error, Debug)]
 pub enum AuthError {
@@ -86,6 +102,66 @@ impl OAuthCredentials {
             .as_secs() as i64;
         self.expires_at <= now + 60 // 60秒前に期限切れと判定
     }
+
+    /// 有効期限までの秒数（期限切れの場合は負の値）
+    pub fn expires_in(&self) -> i64 {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64;
+        self.expires_at - now
+    }
+
+    /// トークンレスポンスから認証情報を作成
+    ///
+    /// リフレッシュ時のレスポンスにはリフレッシュトークンが含まれないことがあるため、
+    /// その場合は `previous_refresh_token` を引き継ぐ。
+    fn from_response(response: TokenResponse, previous_refresh_token: Option<String>) -> Self {
+        let expires_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64
+            + response.expires_in as i64;
+
+        OAuthCredentials {
+            access_token: response.access_token,
+            token_type: response.token_type,
+            expires_at,
+            refresh_token: response.refresh_token.or(previous_refresh_token),
+        }
+    }
+}
+
+/// PKCE（RFC 7636）のコード検証子とコードチャレンジ（S256）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pkce {
+    /// トークン交換時に送るコード検証子
+    pub verifier: String,
+    /// 認可リクエストに含めるコードチャレンジ（検証子の SHA-256 を base64url で表したもの）
+    pub challenge: String,
+}
+
+impl Pkce {
+    /// ランダムなコード検証子から作成
+    pub fn generate() -> Self {
+        let verifier = format!("{}{}", uuid::Uuid::new_v4().simple(), uuid::Uuid::new_v4().simple());
+        Self::from_verifier(verifier)
+    }
+
+    /// コード検証子から作成
+    pub fn from_verifier(verifier: String) -> Self {
+        let challenge = URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()));
+        Pkce { verifier, challenge }
+    }
+}
+
+/// `gm auth login` の動作設定
+#[derive(Debug, Clone, Default)]
+pub struct LoginOptions {
+    /// コールバックを受け取るポート（None の場合はリダイレクト URI のポート）
+    pub port: Option<u16>,
+    /// ブラウザとコールバックサーバーを使わず、リダイレクト先の URL を貼り付けて認証する（SSH 接続時など）
+    pub no_browser: bool,
 }
 
 /// OAuth 2.0 設定
@@ -98,11 +174,32 @@ pub struct OAuthConfig {
     pub client_secret: String,
 
     /// リダイレクトURI (通常は http://localhost:8080/callback)
+    #[serde(default = "default_redirect_uri")]
     pub redirect_uri: String,
 
     /// Google Sheets API のスコープ
     #[serde(skip_serializing_if = "Option::is_none")]
     pub scopes: Option<Vec<String>>,
+
+    /// 認可エンドポイント（テスト用のスタブに差し替えられる）
+    #[serde(default = "default_auth_uri")]
+    pub auth_uri: String,
+
+    /// トークンエンドポイント（テスト用のスタブに差し替えられる）
+    #[serde(default = "default_token_uri")]
+    pub token_uri: String,
+}
+
+fn default_redirect_uri() -> String {
+    DEFAULT_REDIRECT_URI.to_string()
+}
+
+fn default_auth_uri() -> String {
+    DEFAULT_AUTH_URI.to_string()
+}
+
+fn default_token_uri() -> String {
+    DEFAULT_TOKEN_URI.to_string()
 }
 
 impl OAuthConfig {
@@ -113,9 +210,51 @@ impl OAuthConfig {
             client_secret,
             redirect_uri,
             scopes: Some(vec![
-                "https://www.googleapis.com/auth/spreadsheets".to_string(),
+                SHEETS_SCOPE.to_string(),
             ]),
+            auth_uri: default_auth_uri(),
+            token_uri: default_token_uri(),
+        }
+    }
+
+    /// 環境変数（GOOGLE_CLIENT_ID など）または設定ファイルから OAuth 設定を読み込む
+    ///
+    /// どちらの場合も GOOGLE_AUTH_URI / GOOGLE_TOKEN_URI でエンドポイントを上書きできる。
+    pub fn load(config_path: &Path) -> Result<Self, AuthError> {
+        let config = Self::from_env()
+            .or_else(|_| Self::from_file(&config_path.to_path_buf()))
+            .map_err(|_| AuthError::AuthenticationFailed(format!(
+                "Failed to load OAuth config. Please set GOOGLE_CLIENT_ID and GOOGLE_CLIENT_SECRET environment variables, or create {}",
+                config_path.display()
+            )))?;
+        Ok(config.with_env_endpoints())
+    }
+
+    /// GOOGLE_AUTH_URI / GOOGLE_TOKEN_URI が設定されていればエンドポイントを上書き
+    fn with_env_endpoints(mut self) -> Self {
+        if let Ok(auth_uri) = std::env::var("GOOGLE_AUTH_URI") {
+            self.auth_uri = auth_uri;
+        }
+        if let Ok(token_uri) = std::env::var("GOOGLE_TOKEN_URI") {
+            self.token_uri = token_uri;
         }
+        self
+    }
+
+    /// リダイレクト URI のポートを変更
+    pub fn with_callback_port(mut self, port: u16) -> Result<Self, AuthError> {
+        let mut url = Url::parse(&self.redirect_uri)?;
+        url.set_port(Some(port))
+            .map_err(|_| AuthError::AuthenticationFailed(format!("Cannot set a port on redirect URI {}", self.redirect_uri)))?;
+        self.redirect_uri = url.to_string();
+        Ok(self)
+    }
+
+    /// コールバックを受け取るポート（リダイレクト URI のポート）
+    pub fn callback_port(&self) -> Result<u16, AuthError> {
+        Url::parse(&self.redirect_uri)?
+            .port_or_known_default()
+            .ok_or_else(|| AuthError::AuthenticationFailed(format!("Redirect URI has no port: {}", self.redirect_uri)))
     }
 
     /// 環境変数から OAuth 設定を読み込む
@@ -138,15 +277,27 @@ impl OAuthConfig {
     }
 
     /// OAuth 認可エンドポイントのURL を生成
+    ///
+    /// リフレッシュトークンを受け取るため、オフラインアクセスと同意画面の表示を要求する。
     pub fn authorization_url(&self, state: &str) -> String {
         format!(
-            "https://accounts.google.com/o/oauth2/v2/auth?response_type=code&client_id={}&redirect_uri={}&scope={}&state={}",
+            "{}?response_type=code&client_id={}&redirect_uri={}&scope={}&state={}&access_type=offline&prompt=consent",
+            self.auth_uri,
             self.client_id,
             urlencoding::encode(&self.redirect_uri),
-            urlencoding::encode("https://www.googleapis.com/auth/spreadsheets"),
+            urlencoding::encode(SHEETS_SCOPE),
             state
         )
     }
+
+    /// PKCE のコードチャレンジ付きの認可 URL を生成
+    pub fn authorization_url_with_pkce(&self, state: &str, pkce: &Pkce) -> String {
+        format!(
+            "{}&code_challenge={}&code_challenge_method=S256",
+            self.authorization_url(state),
+            pkce.challenge
+        )
+    }
 }
 
 /// Google Sheets API 認証マネージャー
@@ -191,14 +342,34 @@ impl GoogleSheetsAuth {
         Ok(trpg_config_dir.join("oauth_config.json"))
     }
 
-    /// 保存された認証情報を読み込む
-    pub fn load_credentials(&self) -> Result<OAuthCredentials, AuthError> {
+    /// 認証情報ファイルと OAuth 設定ファイルのパスを指定して作成
+    pub fn with_paths(credentials_path: PathBuf, config_path: PathBuf) -> Self {
+        GoogleSheetsAuth { credentials_path, config_path }
+    }
+
+    /// 認証情報ファイルのパス
+    pub fn credentials_path(&self) -> &Path {
+        &self.credentials_path
+    }
+
+    /// OAuth 設定ファイルのパス
+    pub fn config_path(&self) -> &Path {
+        &self.config_path
+    }
+
+    /// 保存された認証情報を読み込む（有効期限は確認しない）
+    pub fn read_credentials(&self) -> Result<OAuthCredentials, AuthError> {
         if !self.credentials_path.exists() {
             return Err(AuthError::MissingCredentials);
         }
 
         let content = fs::read_to_string(&self.credentials_path)?;
-        let credentials: OAuthCredentials = serde_json::from_str(&content)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    /// 保存された認証情報を読み込む
+    pub fn load_credentials(&self) -> Result<OAuthCredentials, AuthError> {
+        let credentials = self.read_credentials()?;
 
         if credentials.is_expired() {
             return Err(AuthError::TokenRefreshFailed(
@@ -209,6 +380,47 @@ impl GoogleSheetsAuth {
         Ok(credentials)
     }
 
+    /// 有効な認証情報を取得
+    ///
+    /// 保存されたトークンが期限切れの場合は、リフレッシュトークンで更新して保存する。
+    ///
+    /// # エラー
+    /// 認証情報が無い場合は `MissingCredentials`、更新できない場合は `TokenRefreshFailed`
+    pub fn valid_credentials(&self) -> Result<OAuthCredentials, AuthError> {
+        let credentials = self.read_credentials()?;
+        if !credentials.is_expired() {
+            return Ok(credentials);
+        }
+
+        let Some(refresh_token) = credentials.refresh_token else {
+            return Err(AuthError::TokenRefreshFailed(
+                "Token has expired and no refresh token is stored. Run `gm auth login`.".to_string(),
+            ));
+        };
+
+        let config = OAuthConfig::load(&self.config_path)
+            .map_err(|e| AuthError::TokenRefreshFailed(e.to_string()))?;
+        let credentials = self.refresh(&config, &refresh_token)?;
+        self.save_credentials(&credentials)?;
+
+        Ok(credentials)
+    }
+
+    /// リフレッシュトークンでアクセストークンを更新
+    pub fn refresh(&self, config: &OAuthConfig, refresh_token: &str) -> Result<OAuthCredentials, AuthError> {
+        let params = [
+            ("refresh_token", refresh_token),
+            ("client_id", &config.client_id),
+            ("client_secret", &config.client_secret),
+            ("grant_type", "refresh_token"),
+        ];
+
+        let response = Self::request_token(config, &params)
+            .map_err(|e| AuthError::TokenRefreshFailed(format!("{}. Run `gm auth login` to sign in again.", e)))?;
+
+        Ok(OAuthCredentials::from_response(response, Some(refresh_token.to_string())))
+    }
+
     /// 認証情報を保存
     pub fn save_credentials(&self, credentials: &OAuthCredentials) -> Result<(), AuthError> {
         let config_dir = self.credentials_path.parent().ok_or(AuthError::InvalidCredentialsFormat)?;
@@ -222,169 +434,160 @@ impl GoogleSheetsAuth {
         Ok(())
     }
 
-     /// OAuth 2.0 フロー全体を実行して認証情報を取得
-     ///
-     /// # 処理フロー
-     /// 1. OAuth 設定を読み込む（環境変数またはファイル）
-     /// 2. ブラウザで Google 認可画面を開く
-     /// 3. ユーザー認可後、ローカルHTTPサーバーで認可コードを受け取る
-     /// 4. トークンエンドポイントでアクセストークンを取得
-     /// 5. 認証情報を保存して返す
-     /// 
-     /// 認証が失敗した場合は、無効な認証情報を削除します。
-     pub fn authenticate(&self) -> Result<OAuthCredentials, AuthError> {
-         // OAuth 設定を読み込む
-         let config = OAuthConfig::from_env()
-             .or_else(|_| OAuthConfig::from_file(&self.config_path))
-             .map_err(|_| AuthError::AuthenticationFailed(
-                 "Failed to load OAuth config. Please set GOOGLE_CLIENT_ID and GOOGLE_CLIENT_SECRET environment variables, or create ~/.config/trpg-json/oauth_config.json".to_string()
-             ))?;
-
-         // 認可コードを取得
-         let auth_code = self.get_authorization_code(&config)
-             .map_err(|e| {
-                 // 認可コード取得失敗時は credentials を削除
-                 eprintln!("⚠ Authorization code retrieval failed. Removing invalid credentials...");
-                 let _ = self.clear_credentials();
-                 e
-             })?;
-
-         // トークンを取得
-         let credentials = self.exchange_code_for_token(&config, &auth_code)
-             .map_err(|e| {
-                 // トークン取得失敗時は credentials を削除
-                 eprintln!("⚠ Token exchange failed. Removing invalid credentials...");
-                 let _ = self.clear_credentials();
-                 e
-             })?;
-
-         // 認証情報を保存
-         self.save_credentials(&credentials)?;
-
-         Ok(credentials)
-     }
+    /// OAuth 2.0 フロー全体を実行して認証情報を取得（既定の設定）
+    pub fn authenticate(&self) -> Result<OAuthCredentials, AuthError> {
+        self.authenticate_with(&LoginOptions::default())
+    }
 
-    /// ユーザー認可画面を開き、認可コードを取得
-    fn get_authorization_code(&self, config: &OAuthConfig) -> Result<String, AuthError> {
-        // ランダムな state を生成
-        let state = format!("{}", uuid::Uuid::new_v4());
+    /// OAuth 2.0 フロー全体を実行して認証情報を取得
+    ///
+    /// # 処理フロー
+    /// 1. OAuth 設定を読み込む（環境変数またはファイル）
+    /// 2. PKCE のコード検証子を生成し、ブラウザで Google 認可画面を開く
+    /// 3. ユーザー認可後、ローカルHTTPサーバーで認可コードを受け取る
+    ///    （`no_browser` の場合は、リダイレクト先の URL をターミナルに貼り付けてもらう）
+    /// 4. トークンエンドポイントでアクセストークンを取得
+    /// 5. 認証情報を保存して返す
+    ///
+    /// 認証が失敗した場合は、無効な認証情報を削除します。
+    pub fn authenticate_with(&self, options: &LoginOptions) -> Result<OAuthCredentials, AuthError> {
+        // OAuth 設定を読み込む
+        let mut config = OAuthConfig::load(&self.config_path)?;
+        if let Some(port) = options.port {
+            config = config.with_callback_port(port)?;
+        }
 
+        let state = uuid::Uuid::new_v4().to_string();
+        let pkce = Pkce::generate();
+
+        // 認可コードを取得
+        let auth_code = if options.no_browser {
+            self.read_pasted_code(&config, &state, &pkce)
+        } else {
+            self.get_authorization_code(&config, &state, &pkce)
+        }
+        .inspect_err(|_| {
+            // 認可コード取得失敗時は credentials を削除
+            eprintln!("⚠ Authorization code retrieval failed. Removing invalid credentials...");
+            let _ = self.clear_credentials();
+        })?;
+
+        // トークンを取得
+        let credentials = self.exchange_code_for_token(&config, &auth_code, &pkce)
+            .inspect_err(|_| {
+                // トークン取得失敗時は credentials を削除
+                eprintln!("⚠ Token exchange failed. Removing invalid credentials...");
+                let _ = self.clear_credentials();
+            })?;
+
+        // 認証情報を保存
+        self.save_credentials(&credentials)?;
+
+        Ok(credentials)
+    }
+
+    /// ユーザー認可画面を開き、認可コードを取得
+    ///
+    /// ブラウザを開けない場合は URL を表示して、そのままコールバックを待つ。
+    fn get_authorization_code(&self, config: &OAuthConfig, state: &str, pkce: &Pkce) -> Result<String, AuthError> {
         // 認可エンドポイントの URL を生成
-        let auth_url = config.authorization_url(&state);
+        let auth_url = config.authorization_url_with_pkce(state, pkce);
 
         // ブラウザで URL を開く
-        webbrowser::open(&auth_url)
-            .map_err(|e| AuthError::AuthenticationFailed(
-                format!("Failed to open browser: {}. Please visit: {}", e, auth_url)
-            ))?;
-
-        eprintln!("Opening browser for authentication...");
+        match webbrowser::open(&auth_url) {
+            Ok(()) => eprintln!("Opening browser for authentication..."),
+            Err(e) => eprintln!("Failed to open browser: {}", e),
+        }
         eprintln!("If it doesn't open automatically, please visit: {}", auth_url);
 
         // ローカルHTTPサーバーで認可コードを受け取る
-        let code = self.start_callback_server(&state)?;
+        self.start_callback_server(config, state)
+    }
 
-        Ok(code)
+    /// 認可 URL を表示し、リダイレクト先の URL（または認可コード）の貼り付けを受け付ける
+    ///
+    /// ブラウザを開けない環境（SSH 接続など）向け。別の端末のブラウザで認可すると
+    /// リダイレクト先のページは開けないが、アドレスバーの URL に認可コードが含まれている。
+    fn read_pasted_code(&self, config: &OAuthConfig, state: &str, pkce: &Pkce) -> Result<String, AuthError> {
+        eprintln!("Open this URL in a browser on any device and grant access:");
+        eprintln!();
+        eprintln!("  {}", config.authorization_url_with_pkce(state, pkce));
+        eprintln!();
+        eprintln!("The browser is then redirected to {} (the page may fail to load).", config.redirect_uri);
+        eprintln!("Paste the full URL from the address bar here:");
+
+        let mut line = String::new();
+        std::io::stdin().lock().read_line(&mut line)?;
+        if line.trim().is_empty() {
+            return Err(AuthError::AuthenticationCancelled);
+        }
+        parse_callback(&line, state)
     }
 
     /// ローカルHTTPサーバーを起動して認可コールバックを受け取る
-    fn start_callback_server(&self, expected_state: &str) -> Result<String, AuthError> {
-        let server = Server::http("127.0.0.1:8080")
-            .map_err(|e| AuthError::ServerError(format!("Failed to start server: {}", e)))?;
-
-        eprintln!("Waiting for authorization callback on http://localhost:8080/callback");
-
-        for request in server.incoming_requests().take(1) {
-            let uri = request.url().to_string();
-
-            // コールバックURLをパース
-            let full_url = format!("http://127.0.0.1:8080{}", uri);
-            let parsed_url = Url::parse(&full_url)
-                .map_err(|e| AuthError::UrlParseError(e))?;
-
-            let query_pairs: std::collections::HashMap<_, _> = parsed_url
-                .query_pairs()
-                .into_owned()
-                .collect();
-
-            // エラーチェック
-            if let Some(error) = query_pairs.get("error") {
-                let error_desc = query_pairs.get("error_description")
-                    .cloned()
-                    .unwrap_or_else(|| "Unknown error".to_string());
-                let _ = request.respond(Response::from_string("Authentication failed. You can close this window."));
-                return Err(AuthError::AuthenticationFailed(
-                    format!("{}: {}", error, error_desc)
-                ));
-            }
-
-            // State チェック
-            if let Some(state) = query_pairs.get("state") {
-                if state != expected_state {
-                    let _ = request.respond(Response::from_string("State mismatch. You can close this window."));
-                    return Err(AuthError::AuthenticationFailed("State mismatch".to_string()));
-                }
-            } else {
-                let _ = request.respond(Response::from_string("Missing state parameter. You can close this window."));
-                return Err(AuthError::AuthenticationFailed("Missing state parameter".to_string()));
-            }
-
-            // 認可コードを取得
-            if let Some(code) = query_pairs.get("code") {
-                let _ = request.respond(Response::from_string("Authentication successful! You can close this window and return to the terminal."));
-                return Ok(code.clone());
-            } else {
-                let _ = request.respond(Response::from_string("Missing authorization code. You can close this window."));
-                return Err(AuthError::AuthenticationFailed("Missing authorization code".to_string()));
-            }
-        }
+    fn start_callback_server(&self, config: &OAuthConfig, expected_state: &str) -> Result<String, AuthError> {
+        let port = config.callback_port()?;
+        let server = Server::http(("127.0.0.1", port))
+            .map_err(|e| AuthError::ServerError(format!("Failed to start server on port {}: {}", port, e)))?;
+
+        eprintln!("Waiting for authorization callback on {}", config.redirect_uri);
+
+        let Some(request) = server.incoming_requests().next() else {
+            return Err(AuthError::AuthenticationFailed("Server did not receive a request".to_string()));
+        };
 
-        Err(AuthError::AuthenticationFailed("Server did not receive a request".to_string()))
+        let result = parse_callback(request.url(), expected_state);
+        let message = match &result {
+            Ok(_) => "Authentication successful! You can close this window and return to the terminal.".to_string(),
+            Err(e) => format!("{} You can close this window.", e),
+        };
+        let _ = request.respond(Response::from_string(message));
+        result
     }
 
     /// 認可コードをアクセストークンに交換
-    #[tokio::main]
-    async fn exchange_code_for_token(&self, config: &OAuthConfig, code: &str) -> Result<OAuthCredentials, AuthError> {
-        let client = reqwest::Client::new();
-
+    fn exchange_code_for_token(&self, config: &OAuthConfig, code: &str, pkce: &Pkce) -> Result<OAuthCredentials, AuthError> {
         let params = [
             ("code", code),
             ("client_id", &config.client_id),
             ("client_secret", &config.client_secret),
             ("redirect_uri", &config.redirect_uri),
             ("grant_type", "authorization_code"),
+            ("code_verifier", &pkce.verifier),
         ];
 
+        let response = Self::request_token(config, &params)?;
+        Ok(OAuthCredentials::from_response(response, None))
+    }
+
+    /// トークンエンドポイントにリクエストを送る
+    #[tokio::main]
+    async fn request_token(config: &OAuthConfig, params: &[(&str, &str)]) -> Result<TokenResponse, AuthError> {
+        let client = reqwest::Client::new();
+
         let response = client
-            .post("https://oauth2.googleapis.com/token")
-            .form(&params)
+            .post(&config.token_uri)
+            .form(params)
             .send()
             .await
-            .map_err(|e| AuthError::HttpError(format!("Token exchange failed: {}", e)))?;
+            .map_err(|e| AuthError::HttpError(format!("Token request failed: {}", e)))?;
 
+        let status = response.status();
         let body = response
             .text()
             .await
             .map_err(|e| AuthError::HttpError(format!("Failed to read response: {}", e)))?;
 
-        let token_response: TokenResponse = serde_json::from_str(&body)
+        if !status.is_success() {
+            return Err(AuthError::AuthenticationFailed(
+                format!("Token endpoint returned {}: {}", status, body)
+            ));
+        }
+
+        serde_json::from_str(&body)
             .map_err(|e| AuthError::AuthenticationFailed(
                 format!("Invalid token response: {}. Response: {}", e, body)
-            ))?;
-
-        // アクセストークンを OAuthCredentials に変換
-        let expires_at = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_secs() as i64
-            + token_response.expires_in as i64;
-
-        Ok(OAuthCredentials {
-            access_token: token_response.access_token,
-            token_type: token_response.token_type,
-            expires_at,
-            refresh_token: token_response.refresh_token,
-        })
+            ))
     }
 
     /// 認証情報をクリア（ログアウト）
@@ -396,6 +599,47 @@ impl GoogleSheetsAuth {
     }
 }
 
+/// 認可コールバックの URL から認可コードを取り出す
+///
+/// `input` はリダイレクト先の URL、パスとクエリ（`/callback?code=...`）、または認可コードそのもの。
+/// URL の場合はエラー応答と state を確認する。
+pub fn parse_callback(input: &str, expected_state: &str) -> Result<String, AuthError> {
+    let input = input.trim();
+    let query = match input.split_once('?') {
+        Some((_, query)) => query,
+        None if input.contains('=') => input,
+        // 認可コードだけが貼り付けられた場合
+        None => return Ok(input.to_string()),
+    };
+
+    let parsed_url = Url::parse(&format!("http://localhost/?{}", query))?;
+    let query_pairs: std::collections::HashMap<_, _> = parsed_url
+        .query_pairs()
+        .into_owned()
+        .collect();
+
+    // エラーチェック
+    if let Some(error) = query_pairs.get("error") {
+        let error_desc = query_pairs.get("error_description")
+            .cloned()
+            .unwrap_or_else(|| "Unknown error".to_string());
+        return Err(AuthError::AuthenticationFailed(format!("{}: {}", error, error_desc)));
+    }
+
+    // State チェック
+    match query_pairs.get("state") {
+        Some(state) if state == expected_state => {}
+        Some(_) => return Err(AuthError::AuthenticationFailed("State mismatch".to_string())),
+        None => return Err(AuthError::AuthenticationFailed("Missing state parameter".to_string())),
+    }
+
+    // 認可コードを取得
+    query_pairs
+        .get("code")
+        .cloned()
+        .ok_or_else(|| AuthError::AuthenticationFailed("Missing authorization code".to_string()))
+}
+
 impl Default for GoogleSheetsAuth {
     fn default() -> Self {
         Self::new().expect("Failed to initialize GoogleSheetsAuth")
@@ -619,4 +863,133 @@ mod tests {
                 "Parent directory should be 'trpg-json'");
         }
     }
+
+    #[test]
+    fn test_pkce_challenge() {
+        let pkce = Pkce::from_verifier("dBjftJeZ4CVP-mJ0kzyDdKDFcvLMsRnLfLIB-wQx8eg".to_string());
+        assert_eq!(pkce.challenge, "YMZf8eopaAYFgliE1z4vfUzL5gQzbJVaM1OhSPo0os0");
+
+        let generated = Pkce::generate();
+        assert_eq!(generated.verifier.len(), 64);
+        assert_eq!(generated.challenge.len(), 43);
+        assert_ne!(generated, Pkce::generate());
+    }
+
+    #[test]
+    fn test_authorization_url_with_pkce_and_custom_endpoint() {
+        let mut config = OAuthConfig::new(
+            "test_client_id".to_string(),
+            "test_client_secret".to_string(),
+            DEFAULT_REDIRECT_URI.to_string(),
+        );
+        config.auth_uri = "http://127.0.0.1:9000/auth".to_string();
+        let pkce = Pkce::from_verifier("verifier".to_string());
+
+        let auth_url = config.authorization_url_with_pkce("s1", &pkce);
+
+        assert!(auth_url.starts_with("http://127.0.0.1:9000/auth?response_type=code"));
+        assert!(auth_url.contains("access_type=offline"));
+        assert!(auth_url.contains(&format!("code_challenge={}&code_challenge_method=S256", pkce.challenge)));
+    }
+
+    #[test]
+    fn test_callback_port() {
+        let config = OAuthConfig::new("id".to_string(), "secret".to_string(), DEFAULT_REDIRECT_URI.to_string());
+        assert_eq!(config.callback_port().unwrap(), 8080);
+
+        let config = config.with_callback_port(9123).unwrap();
+        assert_eq!(config.redirect_uri, "http://localhost:9123/callback");
+        assert_eq!(config.callback_port().unwrap(), 9123);
+    }
+
+    #[test]
+    fn test_oauth_config_file_defaults() {
+        let config: OAuthConfig = serde_json::from_str(r#"{"client_id": "id", "client_secret": "secret"}"#).unwrap();
+
+        assert_eq!(config.redirect_uri, DEFAULT_REDIRECT_URI);
+        assert_eq!(config.auth_uri, DEFAULT_AUTH_URI);
+        assert_eq!(config.token_uri, DEFAULT_TOKEN_URI);
+    }
+
+    #[test]
+    fn test_parse_callback() {
+        assert_eq!(parse_callback("/callback?state=s1&code=4%2Fabc", "s1").unwrap(), "4/abc");
+        assert_eq!(parse_callback("  http://localhost:8080/callback?code=xyz&state=s1&scope=a\n", "s1").unwrap(), "xyz");
+        assert_eq!(parse_callback("4/0Abc-def\n", "s1").unwrap(), "4/0Abc-def");
+
+        let err = parse_callback("/callback?code=xyz&state=other", "s1").unwrap_err();
+        assert!(err.to_string().contains("State mismatch"));
+        let err = parse_callback("/callback?error=access_denied&state=s1", "s1").unwrap_err();
+        assert!(err.to_string().contains("access_denied"));
+        assert!(parse_callback("/callback?code=xyz", "s1").is_err());
+    }
+
+    /// 1回だけトークンレスポンスを返し、受け取ったフォームの本文を返すスタブ
+    fn token_stub(status: u16, body: &'static str) -> (String, std::thread::JoinHandle<String>) {
+        let server = Server::http("127.0.0.1:0").unwrap();
+        let token_uri = format!("http://{}/token", server.server_addr().to_ip().unwrap());
+        let handle = std::thread::spawn(move || {
+            let mut request = server.recv().unwrap();
+            let mut form = String::new();
+            request.as_reader().read_to_string(&mut form).unwrap();
+            request.respond(Response::from_string(body).with_status_code(status)).unwrap();
+            form
+        });
+        (token_uri, handle)
+    }
+
+    fn write_config(dir: &Path, token_uri: &str) -> PathBuf {
+        let path = dir.join("oauth_config.json");
+        let config = serde_json::json!({"client_id": "id", "client_secret": "secret", "token_uri": token_uri});
+        fs::write(&path, config.to_string()).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_valid_credentials_refreshes_expired_token() {
+        let (token_uri, stub) = token_stub(200, r#"{"access_token": "new_token", "token_type": "Bearer", "expires_in": 3600}"#);
+        let dir = tempfile::tempdir().unwrap();
+        let auth = GoogleSheetsAuth::with_paths(dir.path().join("credentials.json"), write_config(dir.path(), &token_uri));
+        auth.save_credentials(&OAuthCredentials {
+            access_token: "old_token".to_string(),
+            token_type: "Bearer".to_string(),
+            expires_at: 0,
+            refresh_token: Some("refresh_1".to_string()),
+        })
+        .unwrap();
+
+        let credentials = auth.valid_credentials().unwrap();
+        let form = stub.join().unwrap();
+
+        assert!(form.contains("grant_type=refresh_token"));
+        assert!(form.contains("refresh_token=refresh_1"));
+        assert_eq!(credentials.access_token, "new_token");
+        assert_eq!(credentials.refresh_token.as_deref(), Some("refresh_1"));
+        assert!(!credentials.is_expired());
+        assert_eq!(auth.read_credentials().unwrap().access_token, "new_token");
+    }
+
+    #[test]
+    fn test_valid_credentials_refresh_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let auth = GoogleSheetsAuth::with_paths(dir.path().join("credentials.json"), dir.path().join("oauth_config.json"));
+        assert!(matches!(auth.valid_credentials(), Err(AuthError::MissingCredentials)));
+
+        let expired = OAuthCredentials {
+            access_token: "old_token".to_string(),
+            token_type: "Bearer".to_string(),
+            expires_at: 0,
+            refresh_token: None,
+        };
+        auth.save_credentials(&expired).unwrap();
+        let err = auth.valid_credentials().unwrap_err();
+        assert!(err.to_string().contains("no refresh token"));
+
+        let (token_uri, stub) = token_stub(400, r#"{"error": "invalid_grant"}"#);
+        let config = OAuthConfig::load(&write_config(dir.path(), &token_uri)).unwrap();
+        let err = auth.refresh(&config, "revoked").unwrap_err();
+        stub.join().unwrap();
+        assert!(matches!(err, AuthError::TokenRefreshFailed(_)));
+        assert!(err.to_string().contains("invalid_grant"));
+    }
 }
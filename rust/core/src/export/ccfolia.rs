@@ -0,0 +1,256 @@
+//! ココフォリア（CCFOLIA）のキャラクター駒
+//!
+//! ココフォリアは `{"kind": "character", "data": {...}}` 形式の JSON をクリップボードから
+//! 貼り付けると駒を作成します。Udonarium と同じく部位ごとに1つの駒を作り、
+//! チャットパレットも Udonarium の XML に埋め込むものと同じ内容を使います。
+
+use super::udonarium::data_transformer::{DataTransformer, TransformedMonster, TransformedPart};
+use super::udonarium::part_namer::PartNamer;
+use super::udonarium::chat_palette::PaletteDefinition;
+use super::{DataExporter, ExportConfig, ExportError};
+use crate::Monster;
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+
+/// クリップボードに貼り付ける JSON の最上位
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CcfoliaClipboard {
+    /// 常に "character"
+    pub kind: &'static str,
+    pub data: CcfoliaCharacter,
+}
+
+/// ココフォリアのキャラクター
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CcfoliaCharacter {
+    pub name: String,
+    pub initiative: i32,
+    pub external_url: String,
+    /// メモ（分類・魔物知識・弱点・特殊能力）
+    pub memo: String,
+    /// チャットパレット
+    pub commands: String,
+    /// ステータス（HP / MP / 防護点。ゲージとして表示される）
+    pub status: Vec<CcfoliaStatus>,
+    /// パラメータ（チャットパレットから {名前} で参照する）
+    pub params: Vec<CcfoliaParam>,
+    pub secret: bool,
+    pub invisible: bool,
+    pub hide_status: bool,
+}
+
+/// 現在値と最大値を持つステータス
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CcfoliaStatus {
+    pub label: String,
+    pub value: i32,
+    pub max: i32,
+}
+
+/// パラメータ（値は文字列）
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CcfoliaParam {
+    pub label: String,
+    pub value: String,
+}
+
+/// ココフォリア用のデータ変換器
+pub struct CcfoliaGenerator;
+
+impl CcfoliaGenerator {
+    /// モンスターを部位ごとの駒に変換
+    ///
+    /// # 引数
+    /// * `monster` - 変換するモンスター
+    /// * `palette` - チャットパレットの定義（ダイスボットはココフォリア側で選ぶため使わない）
+    ///
+    /// # 戻り値
+    /// (ファイル名（拡張子なし）, 駒) の組（部位の順）
+    pub fn generate(monster: &Monster, palette: &PaletteDefinition) -> Vec<(String, CcfoliaClipboard)> {
+        let mut namer = PartNamer::new(&monster.part);
+        let part_names = namer.generate_names(&monster.part, &monster.name);
+        let display_names = part_names.iter().map(|pn| pn.display_name.clone()).collect();
+        let transformed = DataTransformer::transform(monster, display_names);
+
+        part_names
+            .into_iter()
+            .zip(&transformed.parts)
+            .map(|(name, part)| {
+                let character = Self::character(&transformed, part, palette);
+                (name.filename, CcfoliaClipboard { kind: "character", data: character })
+            })
+            .collect()
+    }
+
+    /// 1部位分のキャラクター
+    ///
+    /// 命中力・回避力・抵抗力は Udonarium と同じく基準値（固定値 - 7）にする。
+    fn character(monster: &TransformedMonster, part: &TransformedPart, palette: &PaletteDefinition) -> CcfoliaCharacter {
+        let status = |label: &str, value: i32| CcfoliaStatus { label: label.to_string(), value, max: value };
+        let param = |label: &str, value: i32| CcfoliaParam { label: label.to_string(), value: value.to_string() };
+
+        CcfoliaCharacter {
+            // ココフォリアの駒名は1行で表示されるため、部位名の前の改行を詰める
+            name: part.display_name.replace('\n', ""),
+            initiative: monster.initiative,
+            external_url: String::new(),
+            memo: Self::memo(monster, part),
+            commands: palette.render(monster, part).trim_end().to_string(),
+            status: vec![status("HP", part.hp), status("MP", part.mp), status("防護点", part.armor)],
+            params: vec![
+                param("命中力", DataTransformer::adjust_value(part.hit_rate)),
+                param("打撃点", part.damage),
+                param("回避力", DataTransformer::adjust_value(part.dodge)),
+                param("生命抵抗力", DataTransformer::adjust_value(part.life_resistance)),
+                param("精神抵抗力", DataTransformer::adjust_value(part.mental_resistance)),
+                param("ダメージ軽減", 0),
+            ],
+            secret: false,
+            invisible: false,
+            hide_status: false,
+        }
+    }
+
+    /// メモ欄の内容
+    ///
+    /// コア部位には Udonarium の「戦闘準備」「情報」「魔物知識」に相当する内容も入れる。
+    fn memo(monster: &TransformedMonster, part: &TransformedPart) -> String {
+        let mut lines = Vec::new();
+        if part.is_core {
+            lines.push(format!("{} Lv.{}", monster.category, monster.level));
+            lines.push(format!("知名度/弱点値: {}/{}", monster.fame, part.weakness_value));
+            lines.push(format!("先制値: {}", monster.initiative));
+            if !part.weakness.is_empty() {
+                lines.push(format!("弱点: {}", DataTransformer::transform_weakness(&part.weakness)));
+            }
+        }
+        if !monster.common_abilities.is_empty() {
+            lines.push(format!("特殊能力: {}", monster.common_abilities));
+        }
+        if !part.special_abilities.is_empty() {
+            lines.push(format!("部位特殊能力: {}", part.special_abilities));
+        }
+        lines.join("\n")
+    }
+
+    /// クリップボードに貼り付ける JSON 文字列（1行）
+    pub fn to_clipboard_json(piece: &CcfoliaClipboard) -> Result<String, ExportError> {
+        Ok(serde_json::to_string(piece)?)
+    }
+}
+
+/// ココフォリアエクスポーター
+///
+/// 出力先ディレクトリに部位ごとの JSON ファイル（`{ファイル名}.json`）を書き出す。
+#[derive(Default)]
+pub struct CcfoliaExporter {
+    palette: PaletteDefinition,
+}
+
+impl CcfoliaExporter {
+    /// チャットパレットの定義を設定
+    pub fn with_palette(mut self, palette: PaletteDefinition) -> Self {
+        self.palette = palette;
+        self
+    }
+}
+
+impl DataExporter for CcfoliaExporter {
+    fn export(&self, data: &[Monster], config: &ExportConfig) -> Result<(), ExportError> {
+        let dir = Path::new(&config.destination);
+        if dir.is_file() {
+            return Err(ExportError::InvalidDestination(format!(
+                "Output must be a directory: {}",
+                dir.display()
+            )));
+        }
+        fs::create_dir_all(dir)?;
+
+        for monster in data {
+            for (filename, piece) in CcfoliaGenerator::generate(monster, &self.palette) {
+                let json = CcfoliaGenerator::to_clipboard_json(&piece)?;
+                fs::write(dir.join(format!("{}.json", filename)), json)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn name(&self) -> &str {
+        "CCFOLIA Exporter"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn trent() -> Monster {
+        serde_json::from_value(json!({
+            "Category": "植物", "Lv": 15, "Revision": 2.5, "data": "SAMPLE", "illust": "",
+            "movein": -1, "movein_des": "", "moveon": -1, "moveon_des": "", "name": "トレント",
+            "part": [
+                {"HP": 105, "MP": 45, "name": "幹", "コア": true, "命中力": 21, "回避力": 18, "打撃点": 13, "部位数": 1, "部位特殊能力": "再生＝5", "防護点": 9},
+                {"HP": 75, "MP": -1, "name": "根", "コア": false, "命中力": 19, "回避力": 15, "打撃点": 10, "部位数": 2, "部位特殊能力": "拘束攻撃", "防護点": 7}
+            ],
+            "備考": "", "先制値": 13, "共通特殊能力": "魔法適性", "弱点": "炎属性ダメージ+3", "弱点値": 21,
+            "生命抵抗力": 21, "知名度": 16, "精神抵抗力": 19
+        }))
+        .expect("Failed to build test monster")
+    }
+
+    #[test]
+    fn test_generate_one_piece_per_part() {
+        let pieces = CcfoliaGenerator::generate(&trent(), &PaletteDefinition::default());
+
+        assert_eq!(pieces.len(), 2);
+        assert_eq!(pieces[0].0, "トレント_幹");
+        let core = &pieces[0].1.data;
+        assert_eq!(core.name, "トレント(幹)");
+        assert_eq!(core.initiative, 13);
+        assert_eq!(core.status[0], CcfoliaStatus { label: "HP".to_string(), value: 105, max: 105 });
+        assert_eq!(core.params[0], CcfoliaParam { label: "命中力".to_string(), value: "14".to_string() });
+        assert_eq!(core.params[3], CcfoliaParam { label: "生命抵抗力".to_string(), value: "14".to_string() });
+        assert!(core.commands.starts_with("//-----計算\n"));
+        assert!(core.commands.ends_with("//-----部位特殊能力\n【再生＝5】"));
+        assert_eq!(
+            core.memo,
+            "植物 Lv.15\n知名度/弱点値: 16/21\n先制値: 13\n弱点: 炎ダメ+3\n特殊能力: 魔法適性\n部位特殊能力: 再生＝5"
+        );
+
+        let root = &pieces[1].1.data;
+        assert_eq!(root.status[1].value, 0);
+        assert_eq!(root.memo, "特殊能力: 魔法適性\n部位特殊能力: 拘束攻撃");
+    }
+
+    #[test]
+    fn test_clipboard_json_shape() {
+        let pieces = CcfoliaGenerator::generate(&trent(), &PaletteDefinition::default());
+        let json = CcfoliaGenerator::to_clipboard_json(&pieces[0].1).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+        assert!(!json.contains('\n'));
+        assert_eq!(value["kind"], "character");
+        assert_eq!(value["data"]["externalUrl"], "");
+        assert_eq!(value["data"]["hideStatus"], false);
+        assert_eq!(value["data"]["params"][1], json!({"label": "打撃点", "value": "13"}));
+    }
+
+    #[test]
+    fn test_export_writes_json_per_part() {
+        let dir = tempfile::tempdir().unwrap();
+        let out = dir.path().join("ccfolia");
+        let config = ExportConfig {
+            destination: out.to_string_lossy().to_string(),
+            format: super::super::ExportFormat::Ccfolia,
+        };
+
+        CcfoliaExporter::default().export(&[trent()], &config).unwrap();
+
+        let content = fs::read_to_string(out.join("トレント_根.json")).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&content).unwrap();
+        assert_eq!(value["data"]["name"], "トレント(根)");
+    }
+}
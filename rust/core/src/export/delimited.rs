@@ -0,0 +1,442 @@
+//! CSV / TSV 形式の表データ
+//!
+//! モンスターは1部位1行（モンスターの列は部位ごとに繰り返す）、スペルは1スペル1行に展開します。
+//! 列は見出し行のキー名で対応付けるため、表計算ソフトで列を並べ替えても
+//! [`crate::import::delimited`] で元の JSON に戻せます。
+
+use super::{DataExporter, ExportConfig, ExportError, SpellExporter};
+use crate::spell::{MpCost, Scalar, SpellLevel, Target};
+use crate::{Monster, Spell};
+use serde_json::{Map, Value};
+use std::fs;
+use std::path::Path;
+
+/// 部位番号の列（1始まり。部位の無いモンスターは 0）
+pub const PART_INDEX_COLUMN: &str = "部位番号";
+
+/// 部位の列の接頭辞
+pub const PART_PREFIX: &str = "part.";
+
+/// モンスターの既知の列（この順に出力し、未知のキーは名前順で後ろに続ける）
+const MONSTER_COLUMNS: &[&str] = &[
+    "name", "Category", "Lv", "Revision", "data", "illust", "movein", "movein_des", "moveon", "moveon_des",
+    "備考", "先制値", "共通特殊能力", "弱点", "弱点値", "生命抵抗力", "知名度", "精神抵抗力",
+];
+
+/// 部位の既知の列
+const PART_COLUMNS: &[&str] = &["name", "コア", "部位数", "HP", "MP", "命中力", "回避力", "打撃点", "防護点", "部位特殊能力"];
+
+/// モンスターの文字列項目（空欄は空文字列として読み込む）
+pub(crate) const MONSTER_TEXT_FIELDS: &[&str] =
+    &["name", "Category", "data", "illust", "movein_des", "moveon_des", "備考", "共通特殊能力", "弱点"];
+
+/// 部位の文字列項目
+pub(crate) const PART_TEXT_FIELDS: &[&str] = &["name", "部位特殊能力"];
+
+/// スペルの既知の列
+///
+/// `Lv` `MP` `対象` `時間` は `キー.項目` の列に分けて出力する（[`SPELL_GROUPS`]）。
+const SPELL_COLUMNS: &[&str] = &[
+    "name", "school", "schoolVariant", "god", "Lv.kind", "Lv.value", "MP.kind", "MP.value",
+    "対象.kind", "対象.value", "対象.半径(m)", "対象.末尾", "時間.value", "時間.unit",
+    "射程", "射程(m)", "形状", "抵抗", "属性", "効果", "補助", "準備", "犯罪", "マギスフィア", "オプション",
+    "Revision", "reference",
+];
+
+/// 列を分けて出力するスペルの項目
+pub const SPELL_GROUPS: &[&str] = &["Lv", "MP", "対象", "時間"];
+
+/// スペルの文字列項目（数値として読めるセルも文字列に戻す）
+pub(crate) const SPELL_TEXT_FIELDS: &[&str] =
+    &["name", "school", "schoolVariant", "god", "形状", "抵抗", "属性", "効果", "マギスフィア", "オプション"];
+
+/// 見出し行とデータ行
+#[derive(Debug, Clone, PartialEq)]
+pub struct Table {
+    pub headers: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+}
+
+/// JSON の値をセルの文字列に変換する
+///
+/// 文字列はそのまま書き出し、空文字列や JSON として読めてしまう文字列（`"5"` `"true"` など）だけを
+/// JSON の文字列表記にする。それ以外の値は JSON 表記にする。
+pub fn encode_cell(value: &Value) -> String {
+    match value {
+        Value::String(s) if !s.is_empty() && serde_json::from_str::<Value>(s).is_err() => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// セルの文字列を JSON の値に戻す（空欄は `None`）
+pub fn decode_cell(cell: &str) -> Option<Value> {
+    if cell.is_empty() {
+        return None;
+    }
+    Some(serde_json::from_str(cell).unwrap_or_else(|_| Value::String(cell.to_string())))
+}
+
+/// 既知の列のセル（null と欠落は空欄）
+fn typed_cell(value: Option<&Value>) -> String {
+    match value {
+        None | Some(Value::Null) => String::new(),
+        Some(value) => encode_cell(value),
+    }
+}
+
+/// モンスター・部位の既知の列のセル
+///
+/// 文字列項目の空文字列も空欄にする（読み込み時に空文字列で補うため）。
+fn monster_cell(value: Option<&Value>, column: &str, text_fields: &[&str]) -> String {
+    match value {
+        Some(Value::String(s)) if s.is_empty() && text_fields.contains(&column) => String::new(),
+        other => typed_cell(other),
+    }
+}
+
+/// 追加の列のセル（欠落のみ空欄）
+fn extra_cell(value: Option<&Value>) -> String {
+    value.map(encode_cell).unwrap_or_default()
+}
+
+fn to_object<T: serde::Serialize>(value: &T) -> Result<Map<String, Value>, ExportError> {
+    match serde_json::to_value(value)? {
+        Value::Object(map) => Ok(map),
+        _ => Ok(Map::new()),
+    }
+}
+
+/// 既知の列に含まれないキーを名前順で集める
+fn extra_keys<'a>(objects: impl Iterator<Item = &'a Map<String, Value>>, known: &[&str]) -> Vec<String> {
+    let mut keys: Vec<String> = objects
+        .flat_map(|obj| obj.keys())
+        .filter(|key| !known.contains(&key.as_str()))
+        .cloned()
+        .collect();
+    keys.sort();
+    keys.dedup();
+    keys
+}
+
+/// モンスターを1部位1行の表に変換する
+///
+/// # 引数
+/// * `monsters` - 変換するモンスター
+/// * `repeat_monster_columns` - 2部位目以降の行にもモンスターの列を出力するか（false の場合は空欄）
+pub fn monster_table(monsters: &[Monster], repeat_monster_columns: bool) -> Result<Table, ExportError> {
+    let mut records = Vec::new();
+    for monster in monsters {
+        let mut obj = to_object(monster)?;
+        let parts = match obj.remove("part") {
+            Some(Value::Array(parts)) => parts
+                .into_iter()
+                .map(|part| match part {
+                    Value::Object(map) => map,
+                    _ => Map::new(),
+                })
+                .collect(),
+            _ => Vec::new(),
+        };
+        records.push((obj, parts));
+    }
+
+    let monster_extras = extra_keys(records.iter().map(|(obj, _)| obj), MONSTER_COLUMNS);
+    let part_extras = extra_keys(records.iter().flat_map(|(_, parts)| parts), PART_COLUMNS);
+
+    let mut headers = vec![PART_INDEX_COLUMN.to_string()];
+    headers.extend(MONSTER_COLUMNS.iter().map(|c| c.to_string()));
+    headers.extend(monster_extras.iter().cloned());
+    headers.extend(
+        PART_COLUMNS
+            .iter()
+            .copied()
+            .chain(part_extras.iter().map(String::as_str))
+            .map(|c| format!("{}{}", PART_PREFIX, c)),
+    );
+
+    let mut rows = Vec::new();
+    for (obj, parts) in &records {
+        let part_rows: Vec<Option<&Map<String, Value>>> =
+            if parts.is_empty() { vec![None] } else { parts.iter().map(Some).collect() };
+
+        for (i, part) in part_rows.into_iter().enumerate() {
+            let index = if part.is_some() { i + 1 } else { 0 };
+            let mut row = vec![index.to_string()];
+
+            if i == 0 || repeat_monster_columns {
+                row.extend(MONSTER_COLUMNS.iter().map(|c| monster_cell(obj.get(*c), c, MONSTER_TEXT_FIELDS)));
+                row.extend(monster_extras.iter().map(|c| extra_cell(obj.get(c))));
+            } else {
+                row.extend(std::iter::repeat_n(String::new(), MONSTER_COLUMNS.len() + monster_extras.len()));
+            }
+
+            row.extend(PART_COLUMNS.iter().map(|c| monster_cell(part.and_then(|p| p.get(*c)), c, PART_TEXT_FIELDS)));
+            row.extend(part_extras.iter().map(|c| extra_cell(part.and_then(|p| p.get(c)))));
+            rows.push(row);
+        }
+    }
+
+    Ok(Table { headers, rows })
+}
+
+/// スペルを1スペル1行の表に変換する
+///
+/// `Lv` `MP` `対象` `時間` は型付きの値から `Lv.kind` `Lv.value` などの列に分ける。
+/// 型に合わず読み込めなかった値は元のキー名の列に JSON のまま出力する。
+pub fn spell_table(spells: &[Spell]) -> Result<Table, ExportError> {
+    let mut objects = Vec::new();
+    for spell in spells {
+        let mut obj = to_object(spell)?;
+        let mut grouped = Map::new();
+
+        if let Some(level) = &spell.level {
+            obj.remove("Lv");
+            let value = match level {
+                SpellLevel::Value(v) | SpellLevel::ValuePlus(v) | SpellLevel::Rank(v) => *v,
+            };
+            grouped.insert("Lv.kind".to_string(), level.kind().into());
+            grouped.insert("Lv.value".to_string(), value.into());
+        }
+        if let Some(mp) = &spell.mp {
+            obj.remove("MP");
+            let value = match mp {
+                MpCost::Value(v) | MpCost::ValuePlus(v) => Value::from(*v),
+                MpCost::Special(s) => Value::from(s.as_str()),
+            };
+            grouped.insert("MP.kind".to_string(), mp.kind().into());
+            grouped.insert("MP.value".to_string(), value);
+        }
+        if let Some(target) = &spell.target {
+            obj.remove("対象");
+            grouped.insert("対象.kind".to_string(), target.kind().into());
+            match target {
+                Target::Individual(value) => {
+                    grouped.insert("対象.value".to_string(), value.as_str().into());
+                }
+                Target::Area { value, radius, suffix } => {
+                    grouped.insert("対象.value".to_string(), value.as_str().into());
+                    if let Some(radius) = radius {
+                        grouped.insert("対象.半径(m)".to_string(), scalar_value(radius));
+                    }
+                    if let Some(suffix) = suffix {
+                        grouped.insert("対象.末尾".to_string(), scalar_value(suffix));
+                    }
+                }
+            }
+        }
+        if let Some(duration) = &spell.duration {
+            obj.remove("時間");
+            grouped.insert("時間.value".to_string(), scalar_value(&duration.value));
+            if let Some(unit) = &duration.unit {
+                grouped.insert("時間.unit".to_string(), unit.as_str().into());
+            }
+        }
+
+        obj.extend(grouped);
+        objects.push(obj);
+    }
+
+    let extras = extra_keys(objects.iter(), SPELL_COLUMNS);
+
+    let mut headers: Vec<String> = SPELL_COLUMNS.iter().map(|c| c.to_string()).collect();
+    headers.extend(extras.iter().cloned());
+
+    let rows = objects
+        .iter()
+        .map(|obj| {
+            SPELL_COLUMNS
+                .iter()
+                .map(|c| typed_cell(obj.get(*c)))
+                .chain(extras.iter().map(|c| extra_cell(obj.get(c))))
+                .collect()
+        })
+        .collect();
+
+    Ok(Table { headers, rows })
+}
+
+fn scalar_value(scalar: &Scalar) -> Value {
+    match scalar {
+        Scalar::Int(i) => Value::from(*i),
+        Scalar::Text(s) => Value::from(s.as_str()),
+    }
+}
+
+/// CSV / TSV エクスポーター
+pub struct DelimitedExporter {
+    delimiter: u8,
+    repeat_monster_columns: bool,
+}
+
+impl DelimitedExporter {
+    /// カンマ区切り（CSV）
+    pub fn csv() -> Self {
+        Self { delimiter: b',', repeat_monster_columns: true }
+    }
+
+    /// タブ区切り（TSV）
+    pub fn tsv() -> Self {
+        Self { delimiter: b'\t', repeat_monster_columns: true }
+    }
+
+    /// 2部位目以降の行にもモンスターの列を出力するか（デフォルト: true）
+    pub fn repeat_monster_columns(mut self, repeat: bool) -> Self {
+        self.repeat_monster_columns = repeat;
+        self
+    }
+
+    /// 表をファイルに書き出す
+    ///
+    /// Excel が文字コードを判別できるように UTF-8 の BOM を付ける。
+    fn write_table(&self, table: &Table, config: &ExportConfig) -> Result<(), ExportError> {
+        let path = Path::new(&config.destination);
+        if let Some(parent) = path.parent()
+            && !parent.as_os_str().is_empty() && !parent.exists()
+        {
+            return Err(ExportError::InvalidDestination(format!(
+                "Output directory does not exist: {}",
+                parent.display()
+            )));
+        }
+
+        let mut writer = csv::WriterBuilder::new()
+            .delimiter(self.delimiter)
+            .from_writer(b"\xEF\xBB\xBF".to_vec());
+        writer.write_record(&table.headers).map_err(std::io::Error::from)?;
+        for row in &table.rows {
+            writer.write_record(row).map_err(std::io::Error::from)?;
+        }
+        let content = writer.into_inner().map_err(|e| e.into_error())?;
+
+        fs::write(path, content)?;
+        Ok(())
+    }
+}
+
+impl DataExporter for DelimitedExporter {
+    fn export(&self, data: &[Monster], config: &ExportConfig) -> Result<(), ExportError> {
+        let table = monster_table(data, self.repeat_monster_columns)?;
+        self.write_table(&table, config)
+    }
+
+    fn name(&self) -> &str {
+        if self.delimiter == b'\t' { "TSV Exporter" } else { "CSV Exporter" }
+    }
+}
+
+impl SpellExporter for DelimitedExporter {
+    fn export_spells(&self, data: &[Spell], config: &ExportConfig) -> Result<(), ExportError> {
+        let table = spell_table(data)?;
+        self.write_table(&table, config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn monster(value: Value) -> Monster {
+        serde_json::from_value(value).expect("Failed to build test monster")
+    }
+
+    fn trent() -> Monster {
+        monster(serde_json::json!({
+            "Category": "植物", "Lv": 7, "Revision": 2.5, "data": "ML100", "illust": "",
+            "movein": -1, "movein_des": "", "moveon": 10, "moveon_des": "",
+            "name": "トレント",
+            "part": [
+                {"HP": 60, "MP": 20, "name": "幹", "コア": true, "命中力": 12, "回避力": 10, "打撃点": 8, "部位数": 1, "部位特殊能力": "", "防護点": 6},
+                {"HP": null, "MP": -1, "name": "根", "コア": null, "命中力": 11, "回避力": 9, "打撃点": 6, "部位数": 2, "部位特殊能力": "絡みつき", "防護点": 4}
+            ],
+            "備考": "", "先制値": 11, "共通特殊能力": "", "弱点": "炎属性ダメージ+3", "弱点値": 14,
+            "生命抵抗力": 10, "知名度": 12, "精神抵抗力": 9,
+            "出典": "123"
+        }))
+    }
+
+    #[test]
+    fn test_cell_codec() {
+        assert_eq!(encode_cell(&Value::from("ゴブリン")), "ゴブリン");
+        assert_eq!(encode_cell(&Value::from("")), "\"\"");
+        assert_eq!(encode_cell(&Value::from("123")), "\"123\"");
+        assert_eq!(encode_cell(&Value::from("true")), "\"true\"");
+        assert_eq!(encode_cell(&Value::from(5)), "5");
+        assert_eq!(encode_cell(&Value::Null), "null");
+
+        for value in [Value::from("ゴブリン"), Value::from(""), Value::from("123"), Value::from(2.5), Value::Null, serde_json::json!([1, "a"])] {
+            assert_eq!(decode_cell(&encode_cell(&value)), Some(value));
+        }
+        assert_eq!(decode_cell(""), None);
+    }
+
+    #[test]
+    fn test_monster_table_repeats_monster_columns() {
+        let table = monster_table(&[trent()], true).unwrap();
+        let column = |name: &str| table.headers.iter().position(|h| h == name).unwrap();
+
+        assert_eq!(table.rows.len(), 2);
+        assert_eq!(table.rows[0][column(PART_INDEX_COLUMN)], "1");
+        assert_eq!(table.rows[1][column(PART_INDEX_COLUMN)], "2");
+        assert_eq!(table.rows[1][column("name")], "トレント");
+        assert_eq!(table.rows[1][column("part.name")], "根");
+        assert_eq!(table.rows[1][column("part.HP")], "");
+        assert_eq!(table.rows[0][column("illust")], "");
+        assert_eq!(table.rows[0][column("part.部位特殊能力")], "");
+        assert_eq!(table.rows[0][column("出典")], "\"123\"");
+        assert!(column("出典") < column("part.name"));
+    }
+
+    #[test]
+    fn test_monster_table_without_repeat() {
+        let table = monster_table(&[trent()], false).unwrap();
+        let name = table.headers.iter().position(|h| h == "name").unwrap();
+
+        assert_eq!(table.rows[0][name], "トレント");
+        assert_eq!(table.rows[1][name], "");
+    }
+
+    #[test]
+    fn test_spell_table_typed_columns() {
+        let spell: Spell = serde_json::from_value(serde_json::json!({
+            "name": "ファイアボール", "school": "真語",
+            "Lv": {"kind": "value", "value": 5},
+            "MP": {"kind": "special", "special": "効果参照"},
+            "対象": {"kind": "エリア", "エリア": {"value": "1エリア", "半径(m)": 3, "末尾": "すべて"}},
+            "時間": {"value": "一瞬"},
+            "射程(m)": 30
+        }))
+        .unwrap();
+
+        let table = spell_table(&[spell]).unwrap();
+        let cell = |name: &str| table.rows[0][table.headers.iter().position(|h| h == name).unwrap()].as_str();
+
+        assert_eq!(cell("Lv.kind"), "value");
+        assert_eq!(cell("Lv.value"), "5");
+        assert_eq!(cell("MP.kind"), "special");
+        assert_eq!(cell("MP.value"), "効果参照");
+        assert_eq!(cell("対象.kind"), "エリア");
+        assert_eq!(cell("対象.半径(m)"), "3");
+        assert_eq!(cell("時間.value"), "一瞬");
+        assert_eq!(cell("時間.unit"), "");
+        assert_eq!(cell("射程(m)"), "30");
+        assert!(!table.headers.iter().any(|h| h == "Lv"));
+    }
+
+    #[test]
+    fn test_export_writes_bom_and_delimiter() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("monsters.tsv");
+        let config = ExportConfig {
+            destination: path.to_string_lossy().to_string(),
+            format: super::super::ExportFormat::Tsv,
+        };
+
+        DelimitedExporter::tsv().export(&[trent()], &config).unwrap();
+
+        let content = fs::read(&path).unwrap();
+        assert!(content.starts_with(b"\xEF\xBB\xBF"));
+        let text = String::from_utf8(content[3..].to_vec()).unwrap();
+        assert!(text.starts_with("部位番号\tname\tCategory\t"));
+        assert_eq!(text.lines().count(), 3);
+    }
+}
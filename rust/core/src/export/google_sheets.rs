@@ -1,10 +1,47 @@
-use crate::Monster;
-use super::{DataExporter, ExportConfig, ExportError};
+use crate::{Monster, Spell};
+use super::{DataExporter, ExportConfig, ExportError, SpellExporter};
 use super::auth::{GoogleSheetsAuth, AuthError};
+use super::sheets::{SheetLayout, SpellSheet};
 use super::sheets_api::GoogleSheetsClient;
 
 /// Google Sheets エクスポーター
-pub struct GoogleSheetsExporter;
+#[derive(Debug, Clone, Default)]
+pub struct GoogleSheetsExporter {
+    /// モンスターの書き込みに使うレイアウト
+    layout: SheetLayout,
+    /// スペルの書き込み先のシート名と開始行
+    spell_sheet: SpellSheet,
+    /// 書き込む範囲を消去してから先頭の行から書き込むか
+    overwrite: bool,
+    /// Sheets API のベース URL（None の場合は Google の API）
+    api_base_url: Option<String>,
+}
+
+impl GoogleSheetsExporter {
+    /// モンスターの書き込みに使うレイアウトを指定
+    pub fn with_layout(mut self, layout: SheetLayout) -> Self {
+        self.layout = layout;
+        self
+    }
+
+    /// スペルの書き込み先のシート名と開始行を指定
+    pub fn with_spell_sheet(mut self, spell_sheet: SpellSheet) -> Self {
+        self.spell_sheet = spell_sheet;
+        self
+    }
+
+    /// 既存の行を消去してから書き込むか
+    pub fn with_overwrite(mut self, overwrite: bool) -> Self {
+        self.overwrite = overwrite;
+        self
+    }
+
+    /// Sheets API のベース URL を指定（ローカルのモックサーバーでの確認用）
+    pub fn with_api_base_url(mut self, api_base_url: Option<String>) -> Self {
+        self.api_base_url = api_base_url;
+        self
+    }
+}
 
 impl DataExporter for GoogleSheetsExporter {
     fn export(&self, data: &[Monster], config: &ExportConfig) -> Result<(), ExportError> {
@@ -18,66 +55,23 @@ impl DataExporter for GoogleSheetsExporter {
             ));
         }
 
-        // 初回認証フロー
-        eprintln!("Initializing Google Sheets export...");
-        
-        let auth = GoogleSheetsAuth::new()
-            .map_err(|e| ExportError::GoogleSheetsError(format!("Authentication error: {}", e)))?;
-
-        // 認証情報を読み込む
-         let _credentials = match auth.load_credentials() {
-             Ok(_creds) => {
-                 eprintln!("✓ Loaded existing credentials");
-             }
-             Err(AuthError::MissingCredentials) => {
-                 eprintln!("! No credentials found. Starting OAuth flow...");
-                 eprintln!();
-                 
-                 auth.authenticate()
-                     .map_err(|e| ExportError::GoogleSheetsError(
-                         format!("OAuth authentication failed: {}\n\nSetup required:\n1. Ensure GOOGLE_CLIENT_ID and GOOGLE_CLIENT_SECRET are set\n2. Check that http://localhost:8080/callback is registered as a redirect URI", e)
-                     ))?;
-             }
-             Err(e) => {
-                 eprintln!("⚠ Authentication error: {}. Removing invalid credentials...", e);
-                 let _ = auth.clear_credentials();
-                 return Err(ExportError::GoogleSheetsError(
-                     format!("Failed to load credentials: {}", e)
-                 ));
-             }
-         };
-
-        eprintln!("✓ Authenticated");
-
-        // Google Sheets API クライアントを作成
-        let client = GoogleSheetsClient::new()
+        let client = connect(self.api_base_url.as_deref())?;
+
+        // 全モンスターをまとめてスプレッドシートに追加
+        eprintln!("Writing {} monsters to spreadsheet...", data.len());
+
+        // 非同期処理を実行
+        let rt = tokio::runtime::Runtime::new()
             .map_err(|e| ExportError::GoogleSheetsError(
-                format!("Failed to create Sheets client: {}", e)
+                format!("Failed to create async runtime: {}", e)
             ))?;
 
-        eprintln!("✓ Created Google Sheets client");
+        let start_rows = rt
+            .block_on(client.append_monsters(&config.destination, &self.layout, data, self.overwrite))
+            .map_err(|e| ExportError::GoogleSheetsError(format!("Export failed: {}", e)))?;
 
-        // 各モンスターをスプレッドシートに追加
-        eprintln!("Writing {} monsters to spreadsheet...", data.len());
-        
-        for (i, monster) in data.iter().enumerate() {
-            eprintln!("  [{}/{}] Exporting: {}", i + 1, data.len(), monster.name);
-            
-            // 非同期処理を実行
-            let rt = tokio::runtime::Runtime::new()
-                .map_err(|e| ExportError::GoogleSheetsError(
-                    format!("Failed to create async runtime: {}", e)
-                ))?;
-
-            rt.block_on(async {
-                client.append_monster_data(
-                    &config.destination,
-                    "search",
-                    monster,
-                )
-                .await
-                .map_err(|e| ExportError::GoogleSheetsError(format!("Export failed: {}", e)))
-            })?;
+        for (monster, row) in data.iter().zip(start_rows) {
+            eprintln!("  {}!A{}: {}", self.layout.sheet_name, row, monster.name);
         }
 
         eprintln!("✓ Successfully exported {} monsters to Google Sheets", data.len());
@@ -91,6 +85,87 @@ impl DataExporter for GoogleSheetsExporter {
     }
 }
 
+impl SpellExporter for GoogleSheetsExporter {
+    fn export_spells(&self, data: &[Spell], config: &ExportConfig) -> Result<(), ExportError> {
+        validate_spreadsheet_id(&config.destination)?;
+
+        if data.is_empty() {
+            return Err(ExportError::GoogleSheetsError(
+                "Cannot export empty spell list. Please filter data first.".to_string(),
+            ));
+        }
+
+        let client = connect(self.api_base_url.as_deref())?;
+
+        eprintln!("Writing {} spells to sheet '{}'...", data.len(), self.spell_sheet.sheet_name);
+
+        let rt = tokio::runtime::Runtime::new()
+            .map_err(|e| ExportError::GoogleSheetsError(
+                format!("Failed to create async runtime: {}", e)
+            ))?;
+
+        rt.block_on(client.append_spell_data(&config.destination, &self.spell_sheet, data, self.overwrite))
+            .map_err(|e| ExportError::GoogleSheetsError(format!("Export failed: {}", e)))?;
+
+        eprintln!("✓ Successfully exported {} spells to Google Sheets", data.len());
+        eprintln!("  Spreadsheet: https://docs.google.com/spreadsheets/d/{}/edit", config.destination);
+
+        Ok(())
+    }
+}
+
+/// 認証情報を読み込み（無ければ OAuth フローを実行し）、Sheets API クライアントを作成
+///
+/// `api_base_url` を指定した場合はそのサーバーに接続する（シートからのインポートでも使う）
+pub(crate) fn connect(api_base_url: Option<&str>) -> Result<GoogleSheetsClient, ExportError> {
+    // 初回認証フロー
+    eprintln!("Connecting to Google Sheets...");
+
+    let auth = GoogleSheetsAuth::new()
+        .map_err(|e| ExportError::GoogleSheetsError(format!("Authentication error: {}", e)))?;
+
+    // 認証情報を読み込む（期限切れの場合はリフレッシュトークンで更新）
+    let credentials = match auth.valid_credentials() {
+        Ok(credentials) => {
+            eprintln!("✓ Loaded existing credentials");
+            credentials
+        }
+        Err(AuthError::MissingCredentials) => {
+            eprintln!("! No credentials found. Starting OAuth flow...");
+            eprintln!();
+
+            auth.authenticate()
+                .map_err(|e| ExportError::GoogleSheetsError(
+                    format!("OAuth authentication failed: {}\n\nSetup required:\n1. Ensure GOOGLE_CLIENT_ID and GOOGLE_CLIENT_SECRET are set\n2. Check that the redirect URI (default http://localhost:8080/callback) is registered\n3. Without a browser (e.g. over SSH), run `gm auth login --no-browser` first", e)
+                ))?
+        }
+        Err(e @ (AuthError::CredentialsParsError(_) | AuthError::InvalidCredentialsFormat)) => {
+            eprintln!("⚠ Authentication error: {}. Removing invalid credentials...", e);
+            let _ = auth.clear_credentials();
+            return Err(ExportError::GoogleSheetsError(
+                format!("Failed to load credentials: {}", e)
+            ));
+        }
+        Err(e) => {
+            return Err(ExportError::GoogleSheetsError(
+                format!("Failed to load credentials: {}", e)
+            ));
+        }
+    };
+
+    eprintln!("✓ Authenticated");
+
+    // Google Sheets API クライアントを作成
+    let mut client = GoogleSheetsClient::with_credentials(auth, credentials);
+    if let Some(api_base_url) = api_base_url {
+        client = client.with_base_url(api_base_url);
+    }
+
+    eprintln!("✓ Created Google Sheets client");
+
+    Ok(client)
+}
+
 /// スプレッドシートID を検証
 fn validate_spreadsheet_id(id: &str) -> Result<(), ExportError> {
     if id.is_empty() {
@@ -123,13 +198,13 @@ mod tests {
 
     #[test]
     fn test_google_sheets_exporter_name() {
-        let exporter = GoogleSheetsExporter;
+        let exporter = GoogleSheetsExporter::default();
         assert_eq!(exporter.name(), "Google Sheets Exporter");
     }
 
     #[test]
     fn test_export_empty_data() {
-        let exporter = GoogleSheetsExporter;
+        let exporter = GoogleSheetsExporter::default();
         let config = ExportConfig {
             destination: "1BxiMVs0XRA5nFMKUVfIz487hJblLvZQvq_fHM9GjMhs".to_string(),
             format: crate::export::ExportFormat::GoogleSheets,
@@ -144,6 +219,18 @@ mod tests {
             .contains("empty monster list"));
     }
 
+    #[test]
+    fn test_export_empty_spells() {
+        let exporter = GoogleSheetsExporter::default();
+        let config = ExportConfig {
+            destination: "1BxiMVs0XRA5nFMKUVfIz487hJblLvZQvq_fHM9GjMhs".to_string(),
+            format: crate::export::ExportFormat::GoogleSheets,
+        };
+
+        let result = exporter.export_spells(&[], &config);
+        assert!(result.unwrap_err().to_string().contains("empty spell list"));
+    }
+
     #[test]
     fn test_validate_spreadsheet_id_empty() {
         let result = validate_spreadsheet_id("");
@@ -168,7 +255,7 @@ mod tests {
 
     #[test]
     fn test_google_sheets_export_with_valid_data() {
-        let exporter = GoogleSheetsExporter;
+        let exporter = GoogleSheetsExporter::default();
         let monster_json = r#"
         {
             "Category": "蛮族",
@@ -220,7 +307,7 @@ mod tests {
 
     #[test]
     fn test_google_sheets_exporter_with_multiple_monsters() {
-        let exporter = GoogleSheetsExporter;
+        let exporter = GoogleSheetsExporter::default();
         let monster_json = r#"
         {
             "Category": "蛮族",
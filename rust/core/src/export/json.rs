@@ -1,5 +1,6 @@
-use super::{DataExporter, ExportConfig, ExportError};
-use crate::Monster;
+use super::{DataExporter, ExportConfig, ExportError, SpellExporter};
+use crate::{Monster, Spell};
+use serde::Serialize;
 use std::fs;
 use std::path::Path;
 
@@ -8,26 +9,7 @@ pub struct JsonExporter;
 
 impl DataExporter for JsonExporter {
     fn export(&self, data: &[Monster], config: &ExportConfig) -> Result<(), ExportError> {
-        // 出力先パスの検証
-        let path = Path::new(&config.destination);
-
-        // ディレクトリが存在するか確認
-        if let Some(parent) = path.parent() {
-            if !parent.as_os_str().is_empty() && !parent.exists() {
-                return Err(ExportError::InvalidDestination(format!(
-                    "Output directory does not exist: {}",
-                    parent.display()
-                )));
-            }
-        }
-
-        // JSONにシリアライズ
-        let json_content = serde_json::to_string_pretty(&data)?;
-
-        // ファイルに書き込み
-        fs::write(path, json_content)?;
-
-        Ok(())
+        write_json(data, config)
     }
 
     fn name(&self) -> &str {
@@ -35,6 +17,36 @@ impl DataExporter for JsonExporter {
     }
 }
 
+impl SpellExporter for JsonExporter {
+    fn export_spells(&self, data: &[Spell], config: &ExportConfig) -> Result<(), ExportError> {
+        write_json(data, config)
+    }
+}
+
+/// レコード配列を整形済み JSON として書き出す
+fn write_json<T: Serialize>(data: &[T], config: &ExportConfig) -> Result<(), ExportError> {
+    // 出力先パスの検証
+    let path = Path::new(&config.destination);
+
+    // ディレクトリが存在するか確認
+    if let Some(parent) = path.parent()
+        && !parent.as_os_str().is_empty() && !parent.exists()
+    {
+        return Err(ExportError::InvalidDestination(format!(
+            "Output directory does not exist: {}",
+            parent.display()
+        )));
+    }
+
+    // JSONにシリアライズ
+    let json_content = serde_json::to_string_pretty(&data)?;
+
+    // ファイルに書き込み
+    fs::write(path, json_content)?;
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -251,7 +263,7 @@ mod tests {
     fn test_export_large_dataset() {
         let exporter = JsonExporter;
         let monsters: Vec<Monster> = (0..100)
-            .map(|i| create_test_monster(&format!("モンスター{}", i), 6 + (i % 5) as i32))
+            .map(|i| create_test_monster(&format!("モンスター{}", i), 6 + (i % 5)))
             .collect();
 
         let temp_file = NamedTempFile::new().unwrap();
@@ -269,4 +281,32 @@ mod tests {
 
         assert_eq!(loaded_monsters.len(), 100);
     }
+
+    #[test]
+    fn test_export_spells_roundtrip() {
+        let exporter = JsonExporter;
+        let spell: Spell = serde_json::from_value(serde_json::json!({
+            "name": "エネルギー・ボルト",
+            "school": "真語",
+            "Lv": {"kind": "value", "value": 1},
+            "MP": {"kind": "value", "value": 5},
+            "独自キー": "保持される"
+        }))
+        .unwrap();
+
+        let temp_file = NamedTempFile::new().unwrap();
+        let output_path = temp_file.path().to_string_lossy().to_string();
+
+        let config = ExportConfig {
+            destination: output_path.clone(),
+            format: super::super::ExportFormat::Json,
+        };
+
+        exporter.export_spells(std::slice::from_ref(&spell), &config).unwrap();
+
+        let content = fs::read_to_string(&output_path).unwrap();
+        let loaded: Vec<serde_json::Value> = serde_json::from_str(&content).unwrap();
+        assert_eq!(loaded, vec![serde_json::to_value(&spell).unwrap()]);
+        assert_eq!(loaded[0]["独自キー"], "保持される");
+    }
 }
@@ -1,12 +1,19 @@
-use crate::Monster;
+use crate::{Monster, Spell};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
 use thiserror::Error;
+use udonarium::chat_palette::PaletteDefinition;
 
 pub mod auth;
+pub mod ccfolia;
+pub mod delimited;
 pub mod google_sheets;
 pub mod json;
 pub mod palette;
 pub mod sheets;
 pub mod sheets_api;
+pub mod statblock;
+pub mod template;
 pub mod udonarium;
 
 /// エクスポート形式の定義
@@ -15,6 +22,14 @@ pub enum ExportFormat {
     Json,
     GoogleSheets,
     Udonarium,
+    UdonariumRoom,
+    Csv,
+    Tsv,
+    Markdown,
+    Html,
+    Ccfolia,
+    /// 設定ファイルに登録したテンプレート（形式名の代わりにテンプレート名で指定する）
+    Template,
 }
 
 impl std::str::FromStr for ExportFormat {
@@ -25,8 +40,14 @@ impl std::str::FromStr for ExportFormat {
             "json" => Ok(ExportFormat::Json),
             "sheets" | "google-sheets" | "googlesheets" => Ok(ExportFormat::GoogleSheets),
             "udonarium" => Ok(ExportFormat::Udonarium),
+            "udonarium-room" | "room" => Ok(ExportFormat::UdonariumRoom),
+            "csv" => Ok(ExportFormat::Csv),
+            "tsv" => Ok(ExportFormat::Tsv),
+            "markdown" | "md" => Ok(ExportFormat::Markdown),
+            "html" => Ok(ExportFormat::Html),
+            "ccfolia" | "cocofolia" => Ok(ExportFormat::Ccfolia),
             _ => Err(format!(
-                "Unknown export format: '{}'. Supported: json, sheets, udonarium",
+                "Unknown export format: '{}'. Supported: json, sheets, udonarium, udonarium-room, csv, tsv, markdown, html, ccfolia",
                 s
             )),
         }
@@ -42,6 +63,118 @@ pub struct ExportConfig {
     pub format: ExportFormat,
 }
 
+/// エクスポーターの動作設定（設定ファイルの [export] セクション）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExportOptions {
+    /// テンプレート名 → テンプレートファイルのパス
+    pub templates: BTreeMap<String, PathBuf>,
+    /// CSV / TSV で2部位目以降の行にもモンスターの列を出力するか
+    pub csv_repeat_monster_columns: bool,
+    /// Udonarium ルームデータの駒の配置と山札
+    pub udonarium_room: udonarium::room::RoomLayout,
+    /// Udonarium の駒に埋め込む画像の探し方
+    pub udonarium_images: udonarium::images::IllustrationSource,
+    /// チャットパレットのプリセット名 → 定義（組み込みの standard / classic を含む）
+    pub palette_presets: BTreeMap<String, PaletteDefinition>,
+    /// Udonarium / ココフォリアの駒に使うチャットパレットのプリセット名
+    pub palette_preset: String,
+    /// スペル名 → 系統（チャットパレットで「呪文」のスペルの魔力を選ぶのに使う）
+    pub spell_schools: BTreeMap<String, String>,
+    /// Google Sheets のレイアウトファイルとシート名・開始行の上書き
+    pub sheets: sheets::SheetLayoutSource,
+    /// Google Sheets のスペルの書き込み先（シート名・開始行）
+    pub spell_sheet: sheets::SpellSheet,
+    /// Google Sheets で既存の行を消去してから先頭の行から書き込むか
+    pub sheets_overwrite: bool,
+    /// Google Sheets API のベース URL（None の場合は Google の API）
+    pub sheets_api_base_url: Option<String>,
+}
+
+impl Default for ExportOptions {
+    fn default() -> Self {
+        Self {
+            templates: BTreeMap::new(),
+            csv_repeat_monster_columns: true,
+            udonarium_room: udonarium::room::RoomLayout::default(),
+            udonarium_images: udonarium::images::IllustrationSource::default(),
+            palette_presets: PaletteDefinition::builtin_presets(),
+            palette_preset: udonarium::chat_palette::DEFAULT_PRESET.to_string(),
+            spell_schools: BTreeMap::new(),
+            sheets: sheets::SheetLayoutSource::default(),
+            spell_sheet: sheets::SpellSheet::default(),
+            sheets_overwrite: false,
+            sheets_api_base_url: None,
+        }
+    }
+}
+
+impl ExportOptions {
+    /// チャットパレットのプリセットを切り替える（None の場合はそのまま）
+    pub fn with_palette_preset(mut self, preset: Option<&str>) -> Self {
+        if let Some(preset) = preset {
+            self.palette_preset = preset.to_string();
+        }
+        self
+    }
+
+    /// チャットパレットで参照するスペルの系統を設定する
+    pub fn with_spells(mut self, spells: &[Spell]) -> Self {
+        self.spell_schools = spells.iter().map(|s| (s.name.clone(), s.school.clone())).collect();
+        self
+    }
+
+    /// Google Sheets のレイアウトファイル・シート名・開始行を上書きする（None の項目はそのまま）
+    pub fn with_sheet_layout(mut self, layout: Option<PathBuf>, sheet_name: Option<String>, start_row: Option<usize>) -> Self {
+        if layout.is_some() {
+            self.sheets.layout = layout;
+        }
+        if sheet_name.is_some() {
+            self.sheets.sheet_name = sheet_name;
+        }
+        if start_row.is_some() {
+            self.sheets.start_row = start_row;
+        }
+        self
+    }
+
+    /// Google Sheets のスペルの書き込み先のシート名・開始行を上書きする（None の項目はそのまま）
+    pub fn with_spell_sheet(mut self, sheet_name: Option<String>, start_row: Option<usize>) -> Self {
+        if let Some(sheet_name) = sheet_name {
+            self.spell_sheet.sheet_name = sheet_name;
+        }
+        if let Some(start_row) = start_row {
+            self.spell_sheet.start_row = start_row;
+        }
+        self
+    }
+
+    /// Google Sheets で既存の行を消去してから書き込むかを指定
+    pub fn with_sheets_overwrite(mut self, overwrite: bool) -> Self {
+        self.sheets_overwrite = overwrite;
+        self
+    }
+
+    /// Google Sheets のレイアウト
+    ///
+    /// # エラー
+    /// レイアウトファイルを読み込めない場合、レイアウトが不正な場合
+    pub fn sheet_layout(&self) -> Result<sheets::SheetLayout, ExportError> {
+        self.sheets.resolve().map_err(ExportError::LayoutError)
+    }
+
+    /// 選択されているチャットパレットの定義（スペルの系統を設定済み）
+    ///
+    /// # エラー
+    /// プリセットが定義されていない場合
+    pub fn palette(&self) -> Result<PaletteDefinition, ExportError> {
+        let palette = self.palette_presets.get(&self.palette_preset).ok_or_else(|| {
+            let presets: Vec<&str> = self.palette_presets.keys().map(String::as_str).collect();
+            ExportError::UnknownPalette(format!("{} (available: {})", self.palette_preset, presets.join(", ")))
+        })?;
+        Ok(palette.clone().with_spell_schools(self.spell_schools.clone()))
+    }
+}
+
 /// エクスポート操作のエラー型
 #[derive(Error, Debug)]
 pub enum ExportError {
@@ -60,11 +193,23 @@ pub enum ExportError {
     #[error("Google Sheets error: {0}")]
     GoogleSheetsError(String),
 
+    #[error("Generation error: {0}")]
+    GenerationError(String),
+
     #[error("Invalid export destination: {0}")]
     InvalidDestination(String),
 
     #[error("Export format not supported: {0}")]
     UnsupportedFormat(String),
+
+    #[error("Template error: {0}")]
+    TemplateError(String),
+
+    #[error("Unknown chat palette preset: {0}")]
+    UnknownPalette(String),
+
+    #[error("Sheet layout error: {0}")]
+    LayoutError(String),
 }
 
 /// データエクスポーターのトレイト
@@ -76,16 +221,136 @@ pub trait DataExporter {
     fn name(&self) -> &str;
 }
 
+/// スペルデータエクスポーターのトレイト
+///
+/// モンスター用の [`DataExporter`] と同じ出力先・形式の設定を使う。
+pub trait SpellExporter: DataExporter {
+    /// スペル配列をエクスポートする
+    fn export_spells(&self, data: &[Spell], config: &ExportConfig) -> Result<(), ExportError>;
+}
+
 /// エクスポーターファクトリ
 pub struct ExporterFactory;
 
 impl ExporterFactory {
     /// 指定されたフォーマットに対応するエクスポーターを取得
     pub fn create_exporter(format: ExportFormat) -> Result<Box<dyn DataExporter>, ExportError> {
+        Self::create_exporter_with_options(format, &ExportOptions::default())
+    }
+
+    /// 形式名またはテンプレート名からエクスポーターを取得
+    ///
+    /// 組み込みの形式名を優先し、該当しなければ設定ファイルに登録したテンプレートを探す。
+    ///
+    /// # 戻り値
+    /// (形式, エクスポーター) のタプル
+    pub fn create_named_exporter(
+        name: &str,
+        options: &ExportOptions,
+    ) -> Result<(ExportFormat, Box<dyn DataExporter>), ExportError> {
+        match name.parse::<ExportFormat>() {
+            Ok(format) => Ok((format, Self::create_exporter_with_options(format, options)?)),
+            Err(_) => Ok((ExportFormat::Template, Box::new(Self::template_exporter(name, options)?))),
+        }
+    }
+
+    /// 形式名またはテンプレート名からスペル用エクスポーターを取得
+    pub fn create_named_spell_exporter(
+        name: &str,
+        options: &ExportOptions,
+    ) -> Result<(ExportFormat, Box<dyn SpellExporter>), ExportError> {
+        match name.parse::<ExportFormat>() {
+            Ok(format) => Ok((format, Self::create_spell_exporter_with_options(format, options)?)),
+            Err(_) => Ok((ExportFormat::Template, Box::new(Self::template_exporter(name, options)?))),
+        }
+    }
+
+    /// 登録済みのテンプレートを読み込む
+    fn template_exporter(name: &str, options: &ExportOptions) -> Result<template::TemplateExporter, ExportError> {
+        match options.templates.get(name) {
+            Some(path) => template::TemplateExporter::from_file(path),
+            None => {
+                let templates: Vec<&str> = options.templates.keys().map(String::as_str).collect();
+                Err(ExportError::UnsupportedFormat(format!(
+                    "'{}'. Supported: json, sheets, udonarium, udonarium-room, csv, tsv, markdown, html, ccfolia{}",
+                    name,
+                    if templates.is_empty() { String::new() } else { format!("; templates: {}", templates.join(", ")) }
+                )))
+            }
+        }
+    }
+
+    /// Google Sheets エクスポーター（上書きモードと API の接続先を設定済み）
+    fn google_sheets_exporter(options: &ExportOptions) -> google_sheets::GoogleSheetsExporter {
+        google_sheets::GoogleSheetsExporter::default()
+            .with_overwrite(options.sheets_overwrite)
+            .with_api_base_url(options.sheets_api_base_url.clone())
+    }
+
+    /// 動作設定を指定してエクスポーターを取得
+    pub fn create_exporter_with_options(
+        format: ExportFormat,
+        options: &ExportOptions,
+    ) -> Result<Box<dyn DataExporter>, ExportError> {
         match format {
             ExportFormat::Json => Ok(Box::new(json::JsonExporter)),
-            ExportFormat::GoogleSheets => Ok(Box::new(google_sheets::GoogleSheetsExporter)),
-            ExportFormat::Udonarium => Ok(Box::new(udonarium::UdonariumExporter)),
+            ExportFormat::GoogleSheets => Ok(Box::new(
+                Self::google_sheets_exporter(options).with_layout(options.sheet_layout()?),
+            )),
+            ExportFormat::Udonarium => Ok(Box::new(
+                udonarium::UdonariumExporter::default()
+                    .with_palette(options.palette()?)
+                    .with_images(options.udonarium_images.clone()),
+            )),
+            ExportFormat::UdonariumRoom => Ok(Box::new(
+                udonarium::UdonariumRoomExporter::new(options.udonarium_room.clone(), options.palette()?)
+                    .with_images(options.udonarium_images.clone()),
+            )),
+            ExportFormat::Csv => Ok(Box::new(
+                delimited::DelimitedExporter::csv().repeat_monster_columns(options.csv_repeat_monster_columns),
+            )),
+            ExportFormat::Tsv => Ok(Box::new(
+                delimited::DelimitedExporter::tsv().repeat_monster_columns(options.csv_repeat_monster_columns),
+            )),
+            ExportFormat::Markdown => Ok(Box::new(statblock::StatBlockExporter::markdown())),
+            ExportFormat::Html => Ok(Box::new(statblock::StatBlockExporter::html())),
+            ExportFormat::Ccfolia => Ok(Box::new(ccfolia::CcfoliaExporter::default().with_palette(options.palette()?))),
+            ExportFormat::Template => Err(ExportError::UnsupportedFormat(
+                "template (specify the template name instead)".to_string(),
+            )),
+        }
+    }
+
+    /// 指定されたフォーマットに対応するスペル用エクスポーターを取得
+    pub fn create_spell_exporter(format: ExportFormat) -> Result<Box<dyn SpellExporter>, ExportError> {
+        Self::create_spell_exporter_with_options(format, &ExportOptions::default())
+    }
+
+    /// 動作設定を指定してスペル用エクスポーターを取得
+    pub fn create_spell_exporter_with_options(
+        format: ExportFormat,
+        options: &ExportOptions,
+    ) -> Result<Box<dyn SpellExporter>, ExportError> {
+        match format {
+            ExportFormat::Json => Ok(Box::new(json::JsonExporter)),
+            ExportFormat::GoogleSheets => {
+                options.spell_sheet.validate().map_err(ExportError::LayoutError)?;
+                Ok(Box::new(Self::google_sheets_exporter(options).with_spell_sheet(options.spell_sheet.clone())))
+            }
+            ExportFormat::Udonarium => Ok(Box::new(udonarium::UdonariumExporter::default().with_palette(options.palette()?))),
+            ExportFormat::Csv => Ok(Box::new(delimited::DelimitedExporter::csv())),
+            ExportFormat::Tsv => Ok(Box::new(delimited::DelimitedExporter::tsv())),
+            ExportFormat::Markdown => Ok(Box::new(statblock::StatBlockExporter::markdown())),
+            ExportFormat::Html => Ok(Box::new(statblock::StatBlockExporter::html())),
+            ExportFormat::UdonariumRoom => Err(ExportError::UnsupportedFormat(
+                "udonarium-room (use udonarium to export spell palettes)".to_string(),
+            )),
+            ExportFormat::Ccfolia => Err(ExportError::UnsupportedFormat(
+                "ccfolia (spells cannot be exported as CCFOLIA characters)".to_string(),
+            )),
+            ExportFormat::Template => Err(ExportError::UnsupportedFormat(
+                "template (specify the template name instead)".to_string(),
+            )),
         }
     }
 }
@@ -113,6 +378,13 @@ mod tests {
             "udonarium".parse::<ExportFormat>().unwrap(),
             ExportFormat::Udonarium
         );
+        assert_eq!("udonarium-room".parse::<ExportFormat>().unwrap(), ExportFormat::UdonariumRoom);
+        assert_eq!("csv".parse::<ExportFormat>().unwrap(), ExportFormat::Csv);
+        assert_eq!("tsv".parse::<ExportFormat>().unwrap(), ExportFormat::Tsv);
+        assert_eq!("markdown".parse::<ExportFormat>().unwrap(), ExportFormat::Markdown);
+        assert_eq!("md".parse::<ExportFormat>().unwrap(), ExportFormat::Markdown);
+        assert_eq!("html".parse::<ExportFormat>().unwrap(), ExportFormat::Html);
+        assert_eq!("ccfolia".parse::<ExportFormat>().unwrap(), ExportFormat::Ccfolia);
     }
 
     #[test]
@@ -127,7 +399,7 @@ mod tests {
 
     #[test]
     fn test_export_format_invalid() {
-        let result = "csv".parse::<ExportFormat>();
+        let result = "xlsx".parse::<ExportFormat>();
         assert!(result.is_err());
     }
 
@@ -162,4 +434,66 @@ mod tests {
         assert!(exporter.is_ok());
         assert_eq!(exporter.unwrap().name(), "Udonarium Exporter");
     }
+
+    #[test]
+    fn test_palette_preset_selection() {
+        let options = ExportOptions::default();
+        assert_eq!(options.palette().unwrap(), PaletteDefinition::standard());
+        assert_eq!(options.clone().with_palette_preset(None).palette_preset, "standard");
+
+        let classic = options.clone().with_palette_preset(Some("classic"));
+        assert_eq!(classic.palette().unwrap(), PaletteDefinition::classic());
+
+        let spells: Vec<Spell> =
+            serde_json::from_value(serde_json::json!([{"name": "ブラスト", "school": "真語"}])).unwrap();
+        let with_spells = options.clone().with_spells(&spells);
+        assert_eq!(with_spells.palette().unwrap().spell_schools["ブラスト"], "真語");
+
+        let unknown = options.with_palette_preset(Some("vtt"));
+        let err = unknown.palette().unwrap_err().to_string();
+        assert_eq!(err, "Unknown chat palette preset: vtt (available: classic, standard)");
+        assert!(ExporterFactory::create_exporter_with_options(ExportFormat::Ccfolia, &unknown).is_err());
+        assert!(ExporterFactory::create_exporter_with_options(ExportFormat::Json, &unknown).is_ok());
+    }
+
+    #[test]
+    fn test_spell_exporter_factory() {
+        for (format, name) in [
+            (ExportFormat::Json, "JSON Exporter"),
+            (ExportFormat::GoogleSheets, "Google Sheets Exporter"),
+            (ExportFormat::Udonarium, "Udonarium Exporter"),
+            (ExportFormat::Csv, "CSV Exporter"),
+            (ExportFormat::Tsv, "TSV Exporter"),
+            (ExportFormat::Markdown, "Markdown Exporter"),
+            (ExportFormat::Html, "HTML Exporter"),
+        ] {
+            let exporter = ExporterFactory::create_spell_exporter(format).unwrap();
+            assert_eq!(exporter.name(), name);
+        }
+        assert!(ExporterFactory::create_spell_exporter(ExportFormat::Ccfolia).is_err());
+
+        let options = ExportOptions::default().with_spell_sheet(Some("神聖".to_string()), Some(0));
+        assert_eq!(options.spell_sheet.sheet_name, "神聖");
+        let err = ExporterFactory::create_spell_exporter_with_options(ExportFormat::GoogleSheets, &options).err().unwrap();
+        assert!(matches!(err, ExportError::LayoutError(_)));
+    }
+
+    #[test]
+    fn test_named_exporter_uses_templates() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("memo.txt");
+        std::fs::write(&path, "{% for m in monsters %}{{ m.name }}{% endfor %}").unwrap();
+        let mut options = ExportOptions::default();
+        options.templates.insert("memo".to_string(), path);
+
+        let (format, exporter) = ExporterFactory::create_named_exporter("json", &options).unwrap();
+        assert_eq!((format, exporter.name()), (ExportFormat::Json, "JSON Exporter"));
+
+        let (format, exporter) = ExporterFactory::create_named_exporter("memo", &options).unwrap();
+        assert_eq!((format, exporter.name()), (ExportFormat::Template, "Template Exporter"));
+        assert!(ExporterFactory::create_named_spell_exporter("memo", &options).is_ok());
+
+        let err = ExporterFactory::create_named_exporter("xlsx", &options).err().unwrap();
+        assert!(err.to_string().ends_with("; templates: memo"));
+    }
 }
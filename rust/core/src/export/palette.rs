@@ -1,8 +1,9 @@
-/// Spell Chat Palette Generation Module
-/// 
-/// このモジュールは呪文データをチャットパレット形式に変換します。
-/// 補助フラグに応じて異なる出力形式を生成します。
+//! Spell Chat Palette Generation Module
+//!
+//! このモジュールは呪文データをチャットパレット形式に変換します。
+//! 補助フラグに応じて異なる出力形式を生成します。
 
+use crate::spell::{SpellLevel, Target};
 use crate::Spell;
 
 const ERR_MISSING_NAME: &str = "チャットパレット出力のためにはスペル名が必要です。";
@@ -28,167 +29,79 @@ pub fn format_magic_category(school: &str) -> String {
 // Helper Functions（Claude 実装済み予定）
 // ============================================================================
 
+/// 習得レベルをフォーマットする
+///
+/// 固定: 3 → "3"、以上（value+）: 3 → "3～"、ランク: 2 → "ランク2"
+pub(crate) fn format_level(spell: &Spell) -> Option<String> {
+    spell.level.as_ref().map(|level| match level {
+        SpellLevel::Value(v) => v.to_string(),
+        SpellLevel::ValuePlus(v) => format!("{}～", v),
+        SpellLevel::Rank(r) => format!("ランク{}", r),
+    })
+}
+
 /// MP値をフォーマットする
 /// 
-/// Spell.extra["MP"] から MP値を抽出してフォーマットする
-/// 期待される形式: {"value": 3} または {"value+": 3} または {"special": "文字列"}
-fn format_mp(spell: &Spell) -> Result<String, String> {
-    let mp_obj = spell.extra.get("MP")
-        .ok_or_else(|| ERR_MISSING_MP.to_string())?;
-    
-    let mp_obj = mp_obj.as_object()
-        .ok_or_else(|| ERR_MISSING_MP.to_string())?;
-    
-    // value フィールド（固定MP）
-    if let Some(value) = mp_obj.get("value") {
-        if let Some(v) = value.as_i64() {
-            return Ok(v.to_string());
-        }
-    }
-    
-    // value+ フィールド（最小MP）
-    if let Some(value_plus) = mp_obj.get("value+") {
-        if let Some(v) = value_plus.as_i64() {
-            return Ok(format!("{}～", v));
-        }
-    }
-    
-    // special フィールド（特殊MP）
-    if let Some(special) = mp_obj.get("special") {
-        if let Some(s) = special.as_str() {
-            return Ok(s.to_string());
-        }
-    }
-    
-    // どれにも該当しない
-    Err(ERR_MISSING_MP.to_string())
+/// Spell.mp から MP値をフォーマットする
+/// 固定: 3 → "3"、最小（value+）: 3 → "3～"、特殊: 文字列をそのまま出力
+pub(crate) fn format_mp(spell: &Spell) -> Result<String, String> {
+    spell.mp
+        .as_ref()
+        .map(|mp| mp.to_string())
+        .ok_or_else(|| ERR_MISSING_MP.to_string())
 }
 
 /// 対象をフォーマットする
 /// 
-/// Spell.extra["対象"] から対象情報を抽出
-/// 個別: {"kind": "個別", "個別": "1体全"} → "1体全"
-/// エリア: {"kind": "エリア", "value": "2エリア", "半径(m)": "10", "末尾": "空間"} → "2エリア(半径10m空間)"
-fn format_target(spell: &Spell) -> Result<String, String> {
-    let target_obj = spell.extra.get("対象")
-        .ok_or_else(|| ERR_MISSING_TARGET.to_string())?;
-    
-    let target_obj = target_obj.as_object()
-        .ok_or_else(|| ERR_MISSING_TARGET.to_string())?;
-    
-    let kind = target_obj.get("kind")
-        .and_then(|k| k.as_str())
-        .ok_or_else(|| ERR_INVALID_TARGET_KIND.to_string())?;
-    
-    match kind {
-        "個別" => {
-            let individual = target_obj.get("個別")
-                .and_then(|v| v.as_str())
-                .ok_or_else(|| ERR_MISSING_TARGET.to_string())?;
-            Ok(individual.to_string())
+/// Spell.target から対象情報を抽出
+/// 個別: Individual("1体全") → "1体全"
+/// エリア: Area { value: "2エリア", radius: 10, suffix: "空間" } → "2エリア(半径10m空間)"
+pub(crate) fn format_target(spell: &Spell) -> Result<String, String> {
+    let Some(target) = &spell.target else {
+        // 対象はあるが型に合わない場合（kind が不正など）
+        if spell.extra.contains_key("対象") {
+            return Err(ERR_INVALID_TARGET_KIND.to_string());
         }
-        "エリア" => {
-            // スキーマに合わせて、エリア対象は {"エリア": {...}} の形式
-            let area_obj = target_obj.get("エリア")
-                .and_then(|v| v.as_object())
-                .ok_or_else(|| ERR_MISSING_TARGET.to_string())?;
-            
-            let value = area_obj.get("value")
-                .and_then(|v| v.as_str())
-                .ok_or_else(|| ERR_MISSING_TARGET.to_string())?;
-            
-            // 半径(m)は整数または文字列の可能性がある
-            let radius_m = if let Some(v) = area_obj.get("半径(m)") {
-                if let Some(i) = v.as_i64() {
-                    i.to_string()
-                } else if let Some(s) = v.as_str() {
-                    s.to_string()
-                } else {
-                    return Err(ERR_MISSING_TARGET.to_string());
-                }
-            } else {
-                return Err(ERR_MISSING_TARGET.to_string());
-            };
-            
-            // 末尾は文字列または整数の可能性がある
-            let suffix = if let Some(v) = area_obj.get("末尾") {
-                if let Some(s) = v.as_str() {
-                    s.to_string()
-                } else if let Some(i) = v.as_i64() {
-                    i.to_string()
-                } else {
-                    return Err(ERR_MISSING_TARGET.to_string());
-                }
-            } else {
-                return Err(ERR_MISSING_TARGET.to_string());
-            };
-            
+        return Err(ERR_MISSING_TARGET.to_string());
+    };
+    
+    match target {
+        Target::Individual(individual) => Ok(individual.clone()),
+        Target::Area { value, radius, suffix } => {
+            // 半径と末尾はエリア対象に必須
+            let radius_m = radius.as_ref().ok_or_else(|| ERR_MISSING_TARGET.to_string())?;
+            let suffix = suffix.as_ref().ok_or_else(|| ERR_MISSING_TARGET.to_string())?;
             Ok(format!("{}(半径{}m{})", value, radius_m, suffix))
         }
-        _ => Err(ERR_INVALID_TARGET_KIND.to_string()),
     }
 }
 
 /// 射程をフォーマットする
 /// 
-/// Spell.extra["射程"] または Spell.extra["射程(m)"] から射程情報を抽出
+/// Spell.range（射程）または Spell.range_m（射程(m)）から射程情報を抽出
 /// "射程"を優先、なければ"射程(m)"にフォールバック
 /// 値をそのまま出力（変換なし）
 /// 文字列または整数の両方に対応
-fn format_range(spell: &Spell) -> Result<String, String> {
-    // "射程"フィールドを優先チェック
-    if let Some(range) = spell.extra.get("射程") {
-        if let Some(r) = range.as_str() {
-            return Ok(r.to_string());
-        }
-        // 文字列でなくても、as_i64()で試す
-        if let Some(i) = range.as_i64() {
-            return Ok(i.to_string());
-        }
-    }
-    
-    // "射程(m)"フィールドにフォールバック
-    if let Some(range) = spell.extra.get("射程(m)") {
-        if let Some(r) = range.as_str() {
-            return Ok(r.to_string());
-        }
-        // 文字列でなくても、as_i64()で試す
-        if let Some(i) = range.as_i64() {
-            return Ok(i.to_string());
-        }
-    }
-    
-    Err(ERR_MISSING_RANGE.to_string())
+pub(crate) fn format_range(spell: &Spell) -> Result<String, String> {
+    spell.range
+        .as_ref()
+        .or(spell.range_m.as_ref())
+        .map(|range| range.to_string())
+        .ok_or_else(|| ERR_MISSING_RANGE.to_string())
 }
 
 /// 時間をフォーマットする
 /// 
-/// Spell.extra["時間"] から時間情報を抽出
+/// Spell.duration から時間情報を抽出
 /// 文字列: {"value": "一瞬"} → "一瞬"
 /// 整数: {"value": 3, "unit": "年"} → "3年"
-fn format_duration(spell: &Spell) -> Result<String, String> {
-    let time_obj = spell.extra.get("時間")
-        .ok_or_else(|| ERR_MISSING_TIME.to_string())?;
-    
-    let time_obj = time_obj.as_object()
-        .ok_or_else(|| ERR_MISSING_TIME.to_string())?;
-    
-    // value フィールドが文字列の場合
-    if let Some(value) = time_obj.get("value") {
-        if let Some(s) = value.as_str() {
-            return Ok(s.to_string());
-        }
-        
-        // value フィールドが整数の場合
-        if let Some(i) = value.as_i64() {
-            let unit = time_obj.get("unit")
-                .and_then(|u| u.as_str())
-                .unwrap_or("");
-            return Ok(format!("{}{}", i, unit));
-        }
+pub(crate) fn format_duration(spell: &Spell) -> Result<String, String> {
+    match &spell.duration {
+        Some(duration) => Ok(duration.to_string()),
+        // 時間はあるが値が文字列でも整数でもない場合
+        None if spell.extra.contains_key("時間") => Err(ERR_INVALID_TIME_VALUE.to_string()),
+        None => Err(ERR_MISSING_TIME.to_string()),
     }
-    
-    Err(ERR_INVALID_TIME_VALUE.to_string())
 }
 
 // ============================================================================
@@ -205,8 +118,8 @@ fn generate_support_palette(spell: &Spell) -> Result<String, String> {
     let duration = format_duration(spell)?;
     let range = format_range(spell)?;
     
-    let effect = spell.extra.get("効果")
-        .and_then(|v| v.as_str())
+    let effect = spell.effect
+        .as_deref()
         .ok_or_else(|| ERR_MISSING_EFFECT.to_string())?;
     
     Ok(format!(
@@ -230,8 +143,8 @@ fn generate_regular_palette(spell: &Spell) -> Result<String, String> {
     let duration = format_duration(spell)?;
     let range = format_range(spell)?;
     
-    let effect = spell.extra.get("効果")
-        .and_then(|v| v.as_str())
+    let effect = spell.effect
+        .as_deref()
         .ok_or_else(|| ERR_MISSING_EFFECT.to_string())?;
     
     let magic_category = format_magic_category(&spell.school);
@@ -256,11 +169,7 @@ pub fn generate_spell_palette(spell: &Spell) -> Result<String, String> {
     }
     
     // 補助フラグの確認
-    let is_support = spell.extra.get("補助")
-        .and_then(|v| v.as_bool())
-        .unwrap_or(false);
-    
-    if is_support {
+    if spell.is_support() {
         generate_support_palette(spell)
     } else {
         generate_regular_palette(spell)
@@ -275,6 +184,18 @@ pub fn generate_spell_palette(spell: &Spell) -> Result<String, String> {
 mod tests {
     use super::*;
 
+    /// extra に格納した JSON 値からスペルを構築する
+    fn spell_from(
+        name: &str,
+        school: &str,
+        extra: std::collections::HashMap<String, serde_json::Value>,
+    ) -> Spell {
+        let mut obj: serde_json::Map<String, serde_json::Value> = extra.into_iter().collect();
+        obj.insert("name".to_string(), name.into());
+        obj.insert("school".to_string(), school.into());
+        serde_json::from_value(serde_json::Value::Object(obj)).expect("Failed to build test spell")
+    }
+
     // ========================================================================
     // その他のテスト（Claude 実装予定）
     // ========================================================================
@@ -284,11 +205,7 @@ mod tests {
     fn test_mp_format_value() {
         let mut extra = std::collections::HashMap::new();
         extra.insert("MP".to_string(), serde_json::json!({"value": 5}));
-        let spell = Spell {
-            name: "テスト".to_string(),
-            school: "魔法".to_string(),
-            extra,
-        };
+        let spell = spell_from("テスト", "魔法", extra);
         assert_eq!(format_mp(&spell), Ok("5".to_string()));
     }
 
@@ -297,11 +214,7 @@ mod tests {
     fn test_mp_format_value_plus() {
         let mut extra = std::collections::HashMap::new();
         extra.insert("MP".to_string(), serde_json::json!({"value+": 3}));
-        let spell = Spell {
-            name: "テスト".to_string(),
-            school: "魔法".to_string(),
-            extra,
-        };
+        let spell = spell_from("テスト", "魔法", extra);
         assert_eq!(format_mp(&spell), Ok("3～".to_string()));
     }
 
@@ -310,11 +223,7 @@ mod tests {
     fn test_mp_format_special() {
         let mut extra = std::collections::HashMap::new();
         extra.insert("MP".to_string(), serde_json::json!({"special": "複雑な計算式"}));
-        let spell = Spell {
-            name: "テスト".to_string(),
-            school: "魔法".to_string(),
-            extra,
-        };
+        let spell = spell_from("テスト", "魔法", extra);
         assert_eq!(format_mp(&spell), Ok("複雑な計算式".to_string()));
     }
 
@@ -322,11 +231,7 @@ mod tests {
     #[test]
     fn test_mp_format_error_missing_all() {
         let extra = std::collections::HashMap::new();
-        let spell = Spell {
-            name: "テスト".to_string(),
-            school: "魔法".to_string(),
-            extra,
-        };
+        let spell = spell_from("テスト", "魔法", extra);
         assert!(format_mp(&spell).is_err());
     }
 
@@ -342,11 +247,7 @@ mod tests {
             "kind": "個別",
             "個別": "1体"
         }));
-        let spell = Spell {
-            name: "テスト".to_string(),
-            school: "魔法".to_string(),
-            extra,
-        };
+        let spell = spell_from("テスト", "魔法", extra);
         assert_eq!(format_target(&spell), Ok("1体".to_string()));
     }
 
@@ -358,11 +259,7 @@ mod tests {
             "kind": "個別",
             "個別": "1体全"
         }));
-        let spell = Spell {
-            name: "テスト".to_string(),
-            school: "魔法".to_string(),
-            extra,
-        };
+        let spell = spell_from("テスト", "魔法", extra);
         assert_eq!(format_target(&spell), Ok("1体全".to_string()));
     }
 
@@ -378,11 +275,7 @@ mod tests {
                 "末尾": "すべて"
             }
         }));
-        let spell = Spell {
-            name: "テスト".to_string(),
-            school: "魔法".to_string(),
-            extra,
-        };
+        let spell = spell_from("テスト", "魔法", extra);
         assert_eq!(format_target(&spell), Ok("1エリア(半径5mすべて)".to_string()));
     }
 
@@ -398,11 +291,7 @@ mod tests {
                 "末尾": "空間"
             }
         }));
-        let spell = Spell {
-            name: "テスト".to_string(),
-            school: "魔法".to_string(),
-            extra,
-        };
+        let spell = spell_from("テスト", "魔法", extra);
         assert_eq!(format_target(&spell), Ok("2エリア(半径10m空間)".to_string()));
     }
 
@@ -413,11 +302,7 @@ mod tests {
         extra.insert("対象".to_string(), serde_json::json!({
             "kind": "不正"
         }));
-        let spell = Spell {
-            name: "テスト".to_string(),
-            school: "魔法".to_string(),
-            extra,
-        };
+        let spell = spell_from("テスト", "魔法", extra);
         assert!(format_target(&spell).is_err());
     }
 
@@ -432,11 +317,7 @@ mod tests {
         extra.insert("時間".to_string(), serde_json::json!({
             "value": "一瞬"
         }));
-        let spell = Spell {
-            name: "テスト".to_string(),
-            school: "魔法".to_string(),
-            extra,
-        };
+        let spell = spell_from("テスト", "魔法", extra);
         assert_eq!(format_duration(&spell), Ok("一瞬".to_string()));
     }
 
@@ -448,11 +329,7 @@ mod tests {
             "value": 3,
             "unit": "年"
         }));
-        let spell = Spell {
-            name: "テスト".to_string(),
-            school: "魔法".to_string(),
-            extra,
-        };
+        let spell = spell_from("テスト", "魔法", extra);
         assert_eq!(format_duration(&spell), Ok("3年".to_string()));
     }
 
@@ -464,11 +341,7 @@ mod tests {
             "value": 10,
             "unit": "ラウンド"
         }));
-        let spell = Spell {
-            name: "テスト".to_string(),
-            school: "魔法".to_string(),
-            extra,
-        };
+        let spell = spell_from("テスト", "魔法", extra);
         assert_eq!(format_duration(&spell), Ok("10ラウンド".to_string()));
     }
 
@@ -513,11 +386,7 @@ mod tests {
     fn test_range_format_with_seishou_field() {
         let mut extra = std::collections::HashMap::new();
         extra.insert("射程".to_string(), serde_json::json!("接触"));
-        let spell = Spell {
-            name: "テスト".to_string(),
-            school: "魔法".to_string(),
-            extra,
-        };
+        let spell = spell_from("テスト", "魔法", extra);
         assert_eq!(format_range(&spell), Ok("接触".to_string()));
     }
 
@@ -526,11 +395,7 @@ mod tests {
     fn test_range_format_with_seishou_field_complex() {
         let mut extra = std::collections::HashMap::new();
         extra.insert("射程".to_string(), serde_json::json!("10m(起点指定)"));
-        let spell = Spell {
-            name: "テスト".to_string(),
-            school: "魔法".to_string(),
-            extra,
-        };
+        let spell = spell_from("テスト", "魔法", extra);
         assert_eq!(format_range(&spell), Ok("10m(起点指定)".to_string()));
     }
 
@@ -539,11 +404,7 @@ mod tests {
     fn test_range_format_with_seishou_m_field() {
         let mut extra = std::collections::HashMap::new();
         extra.insert("射程(m)".to_string(), serde_json::json!("20"));
-        let spell = Spell {
-            name: "テスト".to_string(),
-            school: "魔法".to_string(),
-            extra,
-        };
+        let spell = spell_from("テスト", "魔法", extra);
         assert_eq!(format_range(&spell), Ok("20".to_string()));
     }
 
@@ -553,11 +414,7 @@ mod tests {
         let mut extra = std::collections::HashMap::new();
         extra.insert("射程".to_string(), serde_json::json!("接触"));
         extra.insert("射程(m)".to_string(), serde_json::json!("20"));
-        let spell = Spell {
-            name: "テスト".to_string(),
-            school: "魔法".to_string(),
-            extra,
-        };
+        let spell = spell_from("テスト", "魔法", extra);
         // 「射程」を優先
         assert_eq!(format_range(&spell), Ok("接触".to_string()));
     }
@@ -566,11 +423,7 @@ mod tests {
     #[test]
     fn test_range_format_error_missing_both() {
         let extra = std::collections::HashMap::new();
-        let spell = Spell {
-            name: "テスト".to_string(),
-            school: "魔法".to_string(),
-            extra,
-        };
+        let spell = spell_from("テスト", "魔法", extra);
         assert!(format_range(&spell).is_err());
     }
 
@@ -579,11 +432,7 @@ mod tests {
     fn test_range_format_with_seishou_m_integer() {
         let mut extra = std::collections::HashMap::new();
         extra.insert("射程(m)".to_string(), serde_json::json!(30));
-        let spell = Spell {
-            name: "テスト".to_string(),
-            school: "魔法".to_string(),
-            extra,
-        };
+        let spell = spell_from("テスト", "魔法", extra);
         assert_eq!(format_range(&spell), Ok("30".to_string()));
     }
 
@@ -592,11 +441,7 @@ mod tests {
     fn test_range_format_with_seishou_integer() {
         let mut extra = std::collections::HashMap::new();
         extra.insert("射程".to_string(), serde_json::json!(50));
-        let spell = Spell {
-            name: "テスト".to_string(),
-            school: "魔法".to_string(),
-            extra,
-        };
+        let spell = spell_from("テスト", "魔法", extra);
         assert_eq!(format_range(&spell), Ok("50".to_string()));
     }
 
@@ -618,11 +463,7 @@ mod tests {
         extra.insert("時間".to_string(), serde_json::json!({"value": "一瞬"}));
         extra.insert("効果".to_string(), serde_json::json!("光源を生成する。"));
 
-        let spell = Spell {
-            name: "ライト".to_string(),
-            school: "".to_string(),
-            extra,
-        };
+        let spell = spell_from("ライト", "", extra);
         let result = generate_spell_palette(&spell).unwrap();
         assert!(result.contains("ライト"));
         assert!(result.contains("MP:3"));
@@ -644,11 +485,7 @@ mod tests {
         extra.insert("時間".to_string(), serde_json::json!({"value": "一瞬"}));
         extra.insert("効果".to_string(), serde_json::json!("魔法を打ち消す。"));
 
-        let spell = Spell {
-            name: "魔法解除".to_string(),
-            school: "".to_string(),
-            extra,
-        };
+        let spell = spell_from("魔法解除", "", extra);
         let result = generate_spell_palette(&spell).unwrap();
         assert!(result.contains("MP:8～"));
     }
@@ -667,11 +504,7 @@ mod tests {
         extra.insert("時間".to_string(), serde_json::json!({"value": "一瞬"}));
         // 効果フィールドを欠落
 
-        let spell = Spell {
-            name: "テスト".to_string(),
-            school: "".to_string(),
-            extra,
-        };
+        let spell = spell_from("テスト", "", extra);
         assert!(generate_spell_palette(&spell).is_err());
     }
 
@@ -697,11 +530,7 @@ mod tests {
         extra.insert("時間".to_string(), serde_json::json!({"value": "一瞬"}));
         extra.insert("効果".to_string(), serde_json::json!("物理的に神の捌きを下す。"));
 
-        let spell = Spell {
-            name: "ゴッド・ジャッジメント".to_string(),
-            school: "神聖".to_string(),
-            extra,
-        };
+        let spell = spell_from("ゴッド・ジャッジメント", "神聖", extra);
         let result = generate_spell_palette(&spell).unwrap();
         assert!(result.starts_with("2d+"));
         assert!(result.contains("神聖魔法"));
@@ -723,11 +552,7 @@ mod tests {
         extra.insert("時間".to_string(), serde_json::json!({"value": "一瞬"}));
         extra.insert("効果".to_string(), serde_json::json!("テスト効果"));
 
-        let spell = Spell {
-            name: "テスト".to_string(),
-            school: "プリエスト".to_string(),
-            extra,
-        };
+        let spell = spell_from("テスト", "プリエスト", extra);
         let result = generate_spell_palette(&spell).unwrap();
         assert!(result.contains("{行使修正}"));
     }
@@ -746,11 +571,7 @@ mod tests {
         extra.insert("時間".to_string(), serde_json::json!({"value": "一瞬"}));
         extra.insert("効果".to_string(), serde_json::json!("テスト"));
 
-        let spell = Spell {
-            name: "テスト".to_string(),
-            school: "".to_string(),  // 空の school
-            extra,
-        };
+        let spell = spell_from("テスト", "", extra);  // 空の school
         assert!(generate_spell_palette(&spell).is_err());
     }
 
@@ -772,11 +593,7 @@ mod tests {
         extra.insert("時間".to_string(), serde_json::json!({"value": "一瞬"}));
         extra.insert("効果".to_string(), serde_json::json!("テスト効果"));
 
-        let spell = Spell {
-            name: "テスト".to_string(),
-            school: "".to_string(),
-            extra,
-        };
+        let spell = spell_from("テスト", "", extra);
         let result = generate_spell_palette(&spell).unwrap();
         assert!(!result.contains("2d+"));  // Support spells don't have dice rolls
     }
@@ -795,11 +612,7 @@ mod tests {
         extra.insert("時間".to_string(), serde_json::json!({"value": "一瞬"}));
         extra.insert("効果".to_string(), serde_json::json!("テスト効果"));
 
-        let spell = Spell {
-            name: "テスト".to_string(),
-            school: "神聖".to_string(),
-            extra,
-        };
+        let spell = spell_from("テスト", "神聖", extra);
         let result = generate_spell_palette(&spell).unwrap();
         assert!(result.starts_with("2d+"));  // Regular spells start with dice roll
     }
@@ -818,11 +631,7 @@ mod tests {
         extra.insert("射程".to_string(), serde_json::json!("接触"));
         extra.insert("時間".to_string(), serde_json::json!({"value": "一瞬"}));
 
-        let spell = Spell {
-            name: "テスト".to_string(),
-            school: "".to_string(),
-            extra,
-        };
+        let spell = spell_from("テスト", "", extra);
         assert!(generate_spell_palette(&spell).is_err());
     }
 
@@ -848,11 +657,7 @@ mod tests {
         extra.insert("時間".to_string(), serde_json::json!({"value": "一瞬"}));
         extra.insert("効果".to_string(), serde_json::json!("妖精の祝福を与える。"));
 
-        let spell = Spell {
-            name: "フェアリーズブレッシング".to_string(),
-            school: "妖精".to_string(),
-            extra,
-        };
+        let spell = spell_from("フェアリーズブレッシング", "妖精", extra);
         let result = generate_spell_palette(&spell).unwrap();
         assert!(result.contains("妖精魔法"));
         assert!(result.contains("フェアリーズブレッシング"));
@@ -873,11 +678,7 @@ mod tests {
         extra.insert("時間".to_string(), serde_json::json!({"value": 5, "unit": "ラウンド"}));
         extra.insert("効果".to_string(), serde_json::json!("神聖な力で対象を強化する。"));
 
-        let spell = Spell {
-            name: "ホーリーシールド".to_string(),
-            school: "神聖".to_string(),
-            extra,
-        };
+        let spell = spell_from("ホーリーシールド", "神聖", extra);
         let result = generate_spell_palette(&spell).unwrap();
         assert!(result.contains("神聖魔法"));
         assert!(result.contains("ホーリーシールド"));
@@ -898,11 +699,7 @@ mod tests {
         extra.insert("時間".to_string(), serde_json::json!({"value": "永続"}));
         extra.insert("効果".to_string(), serde_json::json!("対象に日本語の呪いを与える。特に危険な技能。"));
 
-        let spell = Spell {
-            name: "日本語呪い".to_string(),
-            school: "".to_string(),
-            extra,
-        };
+        let spell = spell_from("日本語呪い", "", extra);
         let result = generate_spell_palette(&spell).unwrap();
         assert!(result.contains("日本語呪い"));
         assert!(result.contains("任意の標的"));
@@ -923,11 +720,7 @@ mod tests {
         extra.insert("時間".to_string(), serde_json::json!({"value": "一瞬"}));
         extra.insert("効果".to_string(), serde_json::json!("光源を生成する。"));
 
-        let spell = Spell {
-            name: "ライト".to_string(),
-            school: "".to_string(),
-            extra,
-        };
+        let spell = spell_from("ライト", "", extra);
         let result = generate_spell_palette(&spell).unwrap();
         assert!(result.contains("ライト"));
         assert!(result.contains("射程:30"));  // 「射程(m)」の値が使われること
@@ -947,11 +740,7 @@ mod tests {
         extra.insert("時間".to_string(), serde_json::json!({"value": "一瞬"}));
         extra.insert("効果".to_string(), serde_json::json!("テスト効果"));
 
-        let spell = Spell {
-            name: "テスト".to_string(),
-            school: "神聖".to_string(),
-            extra,
-        };
+        let spell = spell_from("テスト", "神聖", extra);
         let result = generate_spell_palette(&spell).unwrap();
         assert!(result.contains("射程:50"));  // 「射程(m)」の値が使われること
         assert!(result.contains("神聖魔法"));
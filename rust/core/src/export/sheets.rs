@@ -1,5 +1,8 @@
-use crate::Monster;
+use crate::{Monster, Spell};
+use super::palette;
 use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
 
 /// Google Sheets の行データ（0インデックスの列）
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -21,179 +24,366 @@ pub struct SheetOutput {
     pub is_merged_row: bool,
 }
 
-/// データ変換エンジン
-pub struct DataTransformer;
-
-impl DataTransformer {
-    /// Monster をスプレッドシートの行に変換
-    /// 各パートについて2行を生成（奇数行と偶数行）
-    pub fn transform_monster(monster: &Monster, start_row: usize) -> Vec<SheetOutput> {
-        let mut rows = Vec::new();
+/// スペルの1行の列数（A 名前 〜 K チャットパレット）
+pub const SPELL_COLUMNS: usize = 11;
+
+/// 組み込みのレイアウト（クレート直下の config/sheets/default.toml）
+const DEFAULT_LAYOUT: &str = include_str!("../../config/sheets/default.toml");
+
+/// セルに書き込むモンスター・部位の値（名前は JSON のキー）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SheetField {
+    /// 名前（コア部位は先頭に★、部位名は改行して括弧書き）
+    #[serde(rename = "name")]
+    Name,
+    #[serde(rename = "Lv")]
+    Level,
+    #[serde(rename = "Category")]
+    Category,
+    #[serde(rename = "data")]
+    Data,
+    #[serde(rename = "HP")]
+    Hp,
+    /// MP（-1 は "-"）
+    #[serde(rename = "MP")]
+    Mp,
+    #[serde(rename = "防護点")]
+    Armor,
+    #[serde(rename = "命中力")]
+    HitRate,
+    #[serde(rename = "回避力")]
+    Dodge,
+    #[serde(rename = "打撃点")]
+    Damage,
+    #[serde(rename = "部位数")]
+    PartCount,
+    #[serde(rename = "部位特殊能力")]
+    SpecialAbilities,
+    #[serde(rename = "共通特殊能力")]
+    CommonAbilities,
+    /// 移動速度（"12\n(四足)"、移動不可は "-"）
+    #[serde(rename = "moveon")]
+    Moveon,
+    /// 水中・飛行などの移動速度（形式は moveon と同じ）
+    #[serde(rename = "movein")]
+    Movein,
+    #[serde(rename = "先制値")]
+    Initiative,
+    #[serde(rename = "生命抵抗力")]
+    LifeResistance,
+    #[serde(rename = "精神抵抗力")]
+    MentalResistance,
+    /// "知名度/弱点値"
+    #[serde(rename = "知名度/弱点値")]
+    FameWeaknessValue,
+    /// 弱点（"炎ダメ+3" のように短縮）
+    #[serde(rename = "弱点")]
+    Weakness,
+    #[serde(rename = "備考")]
+    Notes,
+}
 
-        for (part_index, part) in monster.part.iter().enumerate() {
-            let is_first_part = part_index == 0;
+/// レイアウトの1セル
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SheetCell {
+    /// 列名（A1 形式: "A", "AB"）
+    pub column: String,
+    /// 部位の中の行（0 始まり、rows_per_part 未満）
+    #[serde(default)]
+    pub row: usize,
+    /// 書き込む値（text とどちらか一方を指定）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub field: Option<SheetField>,
+    /// 固定の文字列
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+}
 
-            // 奇数行（first line of part）
-            let odd_row = Self::create_odd_row(monster, part, is_first_part);
-            rows.push(SheetOutput {
-                row_number: start_row + part_index * 2,
-                values: odd_row,
-                is_merged_row: false,
-            });
-
-            // 偶数行（second line of part）
-            let even_row = Self::create_even_row(monster, part, is_first_part);
-            rows.push(SheetOutput {
-                row_number: start_row + part_index * 2 + 1,
-                values: even_row,
-                is_merged_row: false,
-            });
-        }
+/// Google Sheets のモンスター出力レイアウト（レイアウトファイルの内容）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SheetLayout {
+    /// 書き込み先のシート名
+    pub sheet_name: String,
+    /// 最初のモンスターを書き込む行（1 始まり）
+    pub start_row: usize,
+    /// 1部位あたりの行数
+    pub rows_per_part: usize,
+    /// 書き込むセル
+    pub cells: Vec<SheetCell>,
+}
 
-        rows
+impl Default for SheetLayout {
+    fn default() -> Self {
+        toml::from_str(DEFAULT_LAYOUT).expect("bundled sheet layout must be valid")
     }
+}
 
-    /// 奇数行を生成
-    fn create_odd_row(monster: &Monster, part: &crate::Part, is_first_part: bool) -> Vec<Option<String>> {
-        let mut row = vec![None; 54]; // A-AZ列（26） + AA-AZ列（26） = 52列程度
-
-        // A列: name with part.name
-        let name_value = if part.name.is_empty() {
-            monster.name.clone()
-        } else {
-            format!("{}\n({})", monster.name, part.name)
-        };
-        let name_value = if part.core.unwrap_or(false) {
-            format!("★{}", name_value)
-        } else {
-            name_value
-        };
-        row[0] = Some(name_value);
+impl SheetLayout {
+    /// レイアウトファイル（TOML）を読み込む
+    ///
+    /// # エラー
+    /// ファイルを読み込めない場合、形式が誤っている場合、[`SheetLayout::validate`] に失敗した場合
+    pub fn from_file(path: &Path) -> Result<Self, String> {
+        let content =
+            fs::read_to_string(path).map_err(|e| format!("Cannot read sheet layout {}: {}", path.display(), e))?;
+        let layout: SheetLayout =
+            toml::from_str(&content).map_err(|e| format!("Invalid sheet layout {}: {}", path.display(), e))?;
+        layout.validate()?;
+        Ok(layout)
+    }
 
-        // L列（11）: part.HP
-        if let Some(hp) = part.hp {
-            row[11] = Some(hp.to_string());
+    /// 行数・列名・セルの指定を検証
+    pub fn validate(&self) -> Result<(), String> {
+        if self.sheet_name.is_empty() {
+            return Err("sheet_name cannot be empty".to_string());
         }
-
-        // P列（15）: part.MP
-        if part.mp >= 0 {
-            row[15] = Some(part.mp.to_string());
-        } else {
-            row[15] = Some("-".to_string());
+        if self.start_row == 0 {
+            return Err("start_row must be 1 or greater".to_string());
+        }
+        if self.rows_per_part == 0 {
+            return Err("rows_per_part must be 1 or greater".to_string());
         }
+        for cell in &self.cells {
+            if column_index(&cell.column).is_none() {
+                return Err(format!("Invalid column '{}'", cell.column));
+            }
+            if cell.row >= self.rows_per_part {
+                return Err(format!(
+                    "Cell {} uses row {} but rows_per_part is {}",
+                    cell.column, cell.row, self.rows_per_part
+                ));
+            }
+            if cell.field.is_some() == cell.text.is_some() {
+                return Err(format!("Cell {} must have exactly one of field or text", cell.column));
+            }
+        }
+        Ok(())
+    }
 
-        // R列（17）: part.防護点
-        row[17] = Some(part.armor.to_string());
+    /// 1行の列数（レイアウトで使う最も右の列まで）
+    pub fn width(&self) -> usize {
+        self.cells
+            .iter()
+            .filter_map(|cell| column_index(&cell.column))
+            .max()
+            .map_or(0, |max| max + 1)
+    }
 
-        // T列（19）: 先制値
-        if is_first_part {
-            row[19] = Some(monster.initiative.to_string());
-        } else {
-            row[19] = Some("-".to_string());
-        }
+    /// 空き行の判定に使う列（name を書き込む列、無ければ最初のセルの列）
+    pub fn key_column(&self) -> usize {
+        self.cells
+            .iter()
+            .find(|cell| cell.field == Some(SheetField::Name))
+            .or_else(|| self.cells.first())
+            .and_then(|cell| column_index(&cell.column))
+            .unwrap_or(0)
+    }
+}
 
-        // V列（21）: 生命抵抗力
-        if is_first_part {
-            row[21] = Some(monster.life_resistance.to_string());
-        } else {
-            row[21] = Some("-".to_string());
-        }
+/// スペルの書き込み先（列は固定で、1スペル1行）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpellSheet {
+    /// 書き込み先のシート名
+    pub sheet_name: String,
+    /// 最初のスペルを書き込む行（1 始まり。上の行は見出し行）
+    pub start_row: usize,
+}
 
-        // X列（23）: 精神抵抗力
-        if is_first_part {
-            row[23] = Some(monster.mental_resistance.to_string());
-        } else {
-            row[23] = Some("-".to_string());
+impl Default for SpellSheet {
+    fn default() -> Self {
+        Self { sheet_name: "spell".to_string(), start_row: 2 }
+    }
+}
+
+impl SpellSheet {
+    /// シート名・開始行を検証
+    pub fn validate(&self) -> Result<(), String> {
+        if self.sheet_name.is_empty() {
+            return Err("sheet_name cannot be empty".to_string());
         }
+        if self.start_row == 0 {
+            return Err("start_row must be 1 or greater".to_string());
+        }
+        Ok(())
+    }
+}
 
-        // Z列（25）: Fixed "3"
-        row[25] = Some("3".to_string());
+/// レイアウトの指定（設定ファイルの [export.sheets] とコマンドラインのオプション）
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SheetLayoutSource {
+    /// レイアウトファイル（None の場合は組み込みのレイアウト）
+    pub layout: Option<PathBuf>,
+    /// シート名の上書き
+    pub sheet_name: Option<String>,
+    /// 開始行の上書き
+    pub start_row: Option<usize>,
+}
 
-        // AB列（27）: moveon\n(moveon_des)
-        if monster.moveon == -1 {
-            row[27] = Some("-".to_string());
-        } else {
-            let moveon_str = if monster.moveon_description.is_empty() {
-                monster.moveon.to_string()
-            } else {
-                format!("{}\n({})", monster.moveon, monster.moveon_description)
-            };
-            row[27] = Some(moveon_str);
+impl SheetLayoutSource {
+    /// レイアウトを読み込み、シート名・開始行の上書きを適用
+    ///
+    /// # エラー
+    /// レイアウトファイルを読み込めない場合、上書き後のレイアウトが不正な場合
+    pub fn resolve(&self) -> Result<SheetLayout, String> {
+        let mut layout = match &self.layout {
+            Some(path) => SheetLayout::from_file(path)?,
+            None => SheetLayout::default(),
+        };
+        if let Some(sheet_name) = &self.sheet_name {
+            layout.sheet_name = sheet_name.clone();
         }
-
-        // AD列（29）: movein\n(movein_des)
-        if monster.movein == -1 {
-            row[29] = Some("-".to_string());
-        } else {
-            let movein_str = if monster.movein_description.is_empty() {
-                monster.movein.to_string()
-            } else {
-                format!("{}\n({})", monster.movein, monster.movein_description)
-            };
-            row[29] = Some(movein_str);
+        if let Some(start_row) = self.start_row {
+            layout.start_row = start_row;
         }
+        layout.validate()?;
+        Ok(layout)
+    }
+}
 
-        // AF列（31）: part.命中力
-        if let Some(hit_rate) = part.hit_rate {
-            row[31] = Some(hit_rate.to_string());
-        }
+/// A1 形式の列名を0始まりの列番号に変換（"A" → 0、"AB" → 27）
+pub fn column_index(column: &str) -> Option<usize> {
+    if column.is_empty() || !column.chars().all(|c| c.is_ascii_alphabetic()) {
+        return None;
+    }
+    let number = column
+        .to_ascii_uppercase()
+        .bytes()
+        .fold(0usize, |acc, b| acc * 26 + (b - b'A' + 1) as usize);
+    Some(number - 1)
+}
 
-        // AH列（33）: part.回避力
-        if let Some(dodge) = part.dodge {
-            row[33] = Some(dodge.to_string());
-        }
+/// データ変換エンジン
+pub struct DataTransformer;
 
-        // AJ列（35）: data
-        row[35] = Some(monster.data.clone());
+impl DataTransformer {
+    /// Monster をスプレッドシートの行に変換（組み込みのレイアウト）
+    /// 各パートについて2行を生成（奇数行と偶数行）
+    pub fn transform_monster(monster: &Monster, start_row: usize) -> Vec<SheetOutput> {
+        Self::transform_monster_with_layout(monster, start_row, &SheetLayout::default())
+    }
 
-         // AM列（38）: 共通特殊能力（奇数行）
-         if !monster.common_abilities.is_empty() {
-             row[38] = Some(monster.common_abilities.clone());
-         }
+    /// Monster をレイアウトに従ってスプレッドシートの行に変換
+    ///
+    /// 各パートについて `rows_per_part` 行を生成する。
+    /// 行の長さはレイアウトで使う最も右の列まで（値の無いセルは None）。
+    pub fn transform_monster_with_layout(monster: &Monster, start_row: usize, layout: &SheetLayout) -> Vec<SheetOutput> {
+        let width = layout.width();
 
-        // AW列（48）: 知名度 / 弱点値
-        if is_first_part {
-            row[48] = Some(format!("{}/{}", monster.fame, monster.weakness_value));
-        } else {
-            row[48] = Some("-/-".to_string());
+        let mut rows = Vec::new();
+        for (part_index, part) in monster.part.iter().enumerate() {
+            let is_first_part = part_index == 0;
+
+            for row_in_part in 0..layout.rows_per_part {
+                let mut values = vec![None; width];
+                for cell in layout.cells.iter().filter(|cell| cell.row == row_in_part) {
+                    let Some(column) = column_index(&cell.column) else { continue };
+                    values[column] = match (&cell.text, cell.field) {
+                        (Some(text), _) => Some(text.clone()),
+                        (None, Some(field)) => Self::field_value(field, monster, part, is_first_part),
+                        (None, None) => None,
+                    };
+                }
+
+                rows.push(SheetOutput {
+                    row_number: start_row + part_index * layout.rows_per_part + row_in_part,
+                    values,
+                    is_merged_row: false,
+                });
+            }
         }
 
-        row
+        rows
     }
 
-     /// 偶数行を生成
-    fn create_even_row(monster: &Monster, part: &crate::Part, is_first_part: bool) -> Vec<Option<String>> {
-        let mut row = vec![None; 54];
-
-        // AM列（38）: 部位特殊能力（偶数行）
-        if !part.special_abilities.is_empty() {
-            row[38] = Some(part.special_abilities.clone());
+    /// セルの値（モンスター単位の値は2番目以降の部位では "-"）
+    fn field_value(field: SheetField, monster: &Monster, part: &crate::Part, is_first_part: bool) -> Option<String> {
+        let first_part_only = |value: String| Some(if is_first_part { value } else { "-".to_string() });
+        let non_empty = |text: &str| (!text.is_empty()).then(|| text.to_string());
+
+        match field {
+            SheetField::Name => {
+                let name = if part.name.is_empty() {
+                    monster.name.clone()
+                } else {
+                    format!("{}\n({})", monster.name, part.name)
+                };
+                Some(if part.core.unwrap_or(false) { format!("★{}", name) } else { name })
+            }
+            SheetField::Level => Some(monster.level.to_string()),
+            SheetField::Category => Some(monster.category.clone()),
+            SheetField::Data => Some(monster.data.clone()),
+            SheetField::Hp => part.hp.map(|hp| hp.to_string()),
+            SheetField::Mp => Some(if part.mp >= 0 { part.mp.to_string() } else { "-".to_string() }),
+            SheetField::Armor => Some(part.armor.to_string()),
+            SheetField::HitRate => part.hit_rate.map(|value| value.to_string()),
+            SheetField::Dodge => part.dodge.map(|value| value.to_string()),
+            SheetField::Damage => part.damage.map(|value| value.to_string()),
+            SheetField::PartCount => Some(part.part_count.to_string()),
+            SheetField::SpecialAbilities => non_empty(&part.special_abilities),
+            SheetField::CommonAbilities => non_empty(&monster.common_abilities),
+            SheetField::Moveon => Some(Self::format_movement(monster.moveon, &monster.moveon_description)),
+            SheetField::Movein => Some(Self::format_movement(monster.movein, &monster.movein_description)),
+            SheetField::Initiative => first_part_only(monster.initiative.to_string()),
+            SheetField::LifeResistance => first_part_only(monster.life_resistance.to_string()),
+            SheetField::MentalResistance => first_part_only(monster.mental_resistance.to_string()),
+            SheetField::FameWeaknessValue => Some(if is_first_part {
+                format!("{}/{}", monster.fame, monster.weakness_value)
+            } else {
+                "-/-".to_string()
+            }),
+            SheetField::Weakness if monster.weakness.is_empty() => Some("-".to_string()),
+            SheetField::Weakness => first_part_only(Self::transform_weakness(&monster.weakness)),
+            SheetField::Notes => non_empty(&monster.notes),
         }
+    }
 
-        // AW列（48）: 弱点（偶数行）
-        if is_first_part {
-            if !monster.weakness.is_empty() {
-                let weakness = Self::transform_weakness(&monster.weakness);
-                row[48] = Some(weakness);
-            } else {
-                row[48] = Some("-".to_string());
-            }
+    /// 移動速度（-1 は "-"、説明があれば改行して括弧書き）
+    fn format_movement(value: i32, description: &str) -> String {
+        if value == -1 {
+            "-".to_string()
+        } else if description.is_empty() {
+            value.to_string()
         } else {
-            row[48] = Some("-".to_string());
+            format!("{}\n({})", value, description)
         }
+    }
 
-        row
+    /// Spell をスプレッドシートの1行に変換
+    ///
+    /// 列: A 名前 / B 系統 / C 習得 / D MP / E 対象 / F 射程 / G 時間 / H 抵抗 / I 属性 / J 効果 / K チャットパレット
+    /// 値が無い項目は "-" を出力する
+    pub fn transform_spell(spell: &Spell, row_number: usize) -> SheetOutput {
+        let or_dash = |value: Result<String, String>| Some(value.unwrap_or_else(|_| "-".to_string()));
+        let text = |value: &Option<String>| Some(value.clone().unwrap_or_else(|| "-".to_string()));
+
+        let values = vec![
+            Some(spell.name.clone()),
+            Some(palette::format_magic_category(&spell.school)),
+            Some(palette::format_level(spell).unwrap_or_else(|| "-".to_string())),
+            or_dash(palette::format_mp(spell)),
+            or_dash(palette::format_target(spell)),
+            or_dash(palette::format_range(spell)),
+            or_dash(palette::format_duration(spell)),
+            text(&spell.resistance),
+            text(&spell.element),
+            text(&spell.effect),
+            or_dash(palette::generate_spell_palette(spell)),
+        ];
+
+        SheetOutput {
+            row_number,
+            values,
+            is_merged_row: false,
+        }
     }
 
     /// 弱点フィールドの変換
     /// "エネルギー" → "E", "ダメージ" → "ダメ", "属性" を削除
-    fn transform_weakness(weakness: &str) -> String {
-        let transformed = weakness
+    pub(crate) fn transform_weakness(weakness: &str) -> String {
+        weakness
             .replace("エネルギー", "E")
             .replace("ダメージ", "ダメ")
-            .replace("属性", "");
-
-        transformed
+            .replace("属性", "")
     }
 }
 
@@ -458,4 +648,164 @@ mod tests {
             "Eとダメ"
         );
     }
+
+    #[test]
+    fn test_transform_spell() {
+        let spell: Spell = serde_json::from_value(serde_json::json!({
+            "name": "エネルギー・ボルト",
+            "school": "真語",
+            "Lv": {"kind": "value", "value": 1},
+            "MP": {"kind": "value", "value": 5},
+            "対象": {"kind": "個別", "個別": "1体"},
+            "射程": "30m",
+            "時間": {"value": "一瞬"},
+            "抵抗": "半減",
+            "効果": "純エネルギー属性の魔法ダメージを与えます。"
+        }))
+        .unwrap();
+
+        let output = DataTransformer::transform_spell(&spell, 4);
+        let cell = |i: usize| output.values[i].as_deref().unwrap();
+
+        assert_eq!(output.row_number, 4);
+        assert_eq!(output.values.len(), 11);
+        assert_eq!(cell(0), "エネルギー・ボルト");
+        assert_eq!(cell(1), "真語魔法");
+        assert_eq!(cell(2), "1");
+        assert_eq!(cell(3), "5");
+        assert_eq!(cell(4), "1体");
+        assert_eq!(cell(7), "半減");
+        assert_eq!(cell(8), "-");
+        assert!(cell(10).starts_with("2d+{真語魔法}+{行使修正}  エネルギー・ボルト"));
+    }
+
+    #[test]
+    fn test_transform_spell_missing_fields() {
+        let spell = Spell::new("未完成", "妖精");
+        let output = DataTransformer::transform_spell(&spell, 2);
+
+        assert_eq!(output.values[1].as_deref(), Some("妖精魔法"));
+        assert_eq!(output.values[3].as_deref(), Some("-"));
+        assert_eq!(output.values[10].as_deref(), Some("-"));
+    }
+
+    fn layout_test_monster() -> Monster {
+        serde_json::from_value(serde_json::json!({
+            "Category": "幻獣", "Lv": 8, "Revision": 2.5, "data": "TEST003", "illust": "",
+            "movein": -1, "movein_des": "", "moveon": 20, "moveon_des": "四足", "name": "グリフォン",
+            "part": [
+                {"HP": 50, "MP": 20, "name": "胴体", "コア": true, "命中力": 14, "回避力": 13, "打撃点": 8, "部位数": 1, "部位特殊能力": "", "防護点": 6},
+                {"HP": 40, "MP": -1, "name": "翼", "命中力": 13, "回避力": 14, "打撃点": 6, "部位数": 2, "部位特殊能力": "飛翔", "防護点": 4}
+            ],
+            "備考": "", "先制値": 16, "共通特殊能力": "", "弱点": "雷属性ダメージ+3", "弱点値": 18,
+            "生命抵抗力": 14, "知名度": 12, "精神抵抗力": 13
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_column_index() {
+        assert_eq!(column_index("A"), Some(0));
+        assert_eq!(column_index("z"), Some(25));
+        assert_eq!(column_index("AB"), Some(27));
+        assert_eq!(column_index("AW"), Some(48));
+        assert_eq!(column_index(""), None);
+        assert_eq!(column_index("A1"), None);
+    }
+
+    #[test]
+    fn test_default_layout() {
+        let layout = SheetLayout::default();
+
+        assert_eq!(layout.sheet_name, "search");
+        assert_eq!(layout.start_row, 3);
+        assert_eq!(layout.rows_per_part, 2);
+        assert_eq!(layout.key_column(), 0);
+        assert!(layout.validate().is_ok());
+
+        let rows = DataTransformer::transform_monster(&layout_test_monster(), 3);
+        assert_eq!(rows.len(), 4);
+        assert_eq!(rows[0].values.len(), 49);
+        assert_eq!(rows[0].values[25].as_deref(), Some("3"));
+        assert_eq!(rows[0].values[27].as_deref(), Some("20\n(四足)"));
+        assert_eq!(rows[0].values[29].as_deref(), Some("-"));
+        assert_eq!(rows[0].values[38], None);
+        assert_eq!(rows[2].values[15].as_deref(), Some("-"));
+        assert_eq!(rows[2].values[48].as_deref(), Some("-/-"));
+        assert_eq!(rows[3].values[38].as_deref(), Some("飛翔"));
+    }
+
+    #[test]
+    fn test_custom_layout() {
+        let layout: SheetLayout = toml::from_str(
+            r#"
+sheet_name = "enemies"
+start_row = 2
+rows_per_part = 1
+cells = [
+    { column = "B", field = "name" },
+    { column = "C", field = "HP" },
+    { column = "D", field = "弱点" },
+    { column = "E", text = "済" },
+]
+"#,
+        )
+        .unwrap();
+        layout.validate().unwrap();
+
+        let rows = DataTransformer::transform_monster_with_layout(&layout_test_monster(), 2, &layout);
+
+        assert_eq!(layout.key_column(), 1);
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[1].row_number, 3);
+        assert_eq!(rows[0].values.len(), 5);
+        assert_eq!(rows[0].values[0], None);
+        assert_eq!(rows[0].values[1].as_deref(), Some("★グリフォン\n(胴体)"));
+        assert_eq!(rows[0].values[3].as_deref(), Some("雷ダメ+3"));
+        assert_eq!(rows[1].values[2].as_deref(), Some("40"));
+        assert_eq!(rows[1].values[3].as_deref(), Some("-"));
+        assert_eq!(rows[1].values[4].as_deref(), Some("済"));
+    }
+
+    #[test]
+    fn test_layout_validation() {
+        let mut layout = SheetLayout::default();
+        layout.cells.push(SheetCell { column: "B".to_string(), row: 2, field: Some(SheetField::Level), text: None });
+        assert!(layout.validate().unwrap_err().contains("rows_per_part is 2"));
+
+        let mut layout = SheetLayout::default();
+        layout.cells.push(SheetCell { column: "1".to_string(), row: 0, field: None, text: Some("x".to_string()) });
+        assert!(layout.validate().unwrap_err().contains("Invalid column '1'"));
+
+        let mut layout = SheetLayout::default();
+        layout.cells.push(SheetCell { column: "B".to_string(), row: 0, field: None, text: None });
+        assert!(layout.validate().unwrap_err().contains("exactly one of field or text"));
+
+        let layout = SheetLayout { rows_per_part: 0, ..SheetLayout::default() };
+        assert!(layout.validate().is_err());
+
+        assert!(toml::from_str::<SheetLayout>("sheet_name = \"s\"\nstart_row = 1\nrows_per_part = 1\ncells = [{ column = \"A\", field = \"unknown\" }]").is_err());
+    }
+
+    #[test]
+    fn test_layout_source_overrides() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("layout.toml");
+        std::fs::write(&path, "sheet_name = \"list\"\nstart_row = 5\nrows_per_part = 1\ncells = [{ column = \"C\", field = \"name\" }]\n").unwrap();
+
+        let source = SheetLayoutSource { layout: Some(path), sheet_name: None, start_row: Some(10) };
+        let layout = source.resolve().unwrap();
+        assert_eq!(layout.sheet_name, "list");
+        assert_eq!(layout.start_row, 10);
+        assert_eq!(layout.key_column(), 2);
+
+        let source = SheetLayoutSource { sheet_name: Some("敵".to_string()), ..Default::default() };
+        assert_eq!(source.resolve().unwrap().sheet_name, "敵");
+
+        let source = SheetLayoutSource { start_row: Some(0), ..Default::default() };
+        assert!(source.resolve().is_err());
+
+        let source = SheetLayoutSource { layout: Some(dir.path().join("missing.toml")), ..Default::default() };
+        assert!(source.resolve().unwrap_err().contains("Cannot read sheet layout"));
+    }
 }
@@ -1,6 +1,6 @@
-use crate::Monster;
+use crate::{Monster, Spell};
 use super::auth::{AuthError, GoogleSheetsAuth, OAuthCredentials};
-use super::sheets::{DataTransformer, SheetOutput};
+use super::sheets::{DataTransformer, SheetLayout, SheetOutput, SpellSheet, SPELL_COLUMNS};
 use thiserror::Error;
 
 /// Google Sheets API エラー型
@@ -25,77 +25,168 @@ pub enum SheetsApiError {
     TransformationError(String),
 }
 
+/// Google Sheets API v4 の既定のベース URL
+pub const DEFAULT_API_BASE_URL: &str = "https://sheets.googleapis.com/v4";
+
 /// Google Sheets API クライアント
 #[allow(dead_code)]
 pub struct GoogleSheetsClient {
     auth: GoogleSheetsAuth,
     credentials: OAuthCredentials,
+    /// API のベース URL（テスト用のモックサーバーに差し替えられる）
+    base_url: String,
 }
 
 impl GoogleSheetsClient {
     /// 新しいクライアントを作成
     /// 
-    /// # 注意
-    /// 実装時に確認が必要な点：
-    /// 1. OAuth認証フローの実装（初回ユーザーの認証）
-    /// 2. アクセストークンのリフレッシュロジック
-    /// 3. Google Sheets API v4 のエンドポイント統合
+    /// 保存されたトークンが期限切れの場合は、リフレッシュトークンで更新してから使う。
     pub fn new() -> Result<Self, SheetsApiError> {
         let auth = GoogleSheetsAuth::new()?;
-        let credentials = auth.load_credentials()?;
+        let credentials = auth.valid_credentials()?;
 
-        if credentials.is_expired() {
-            return Err(SheetsApiError::AuthError(AuthError::TokenRefreshFailed(
-                "Token expired. Please re-authenticate.".to_string(),
-            )));
-        }
+        Ok(Self::with_credentials(auth, credentials))
+    }
 
-        Ok(GoogleSheetsClient { auth, credentials })
+    /// 読み込み済みの認証情報からクライアントを作成
+    pub fn with_credentials(auth: GoogleSheetsAuth, credentials: OAuthCredentials) -> Self {
+        GoogleSheetsClient { auth, credentials, base_url: DEFAULT_API_BASE_URL.to_string() }
     }
 
-    /// Monster データをスプレッドシートに追加
-    pub async fn append_monster_data(
+    /// API のベース URL を変更（末尾の "/" は取り除く）
+    pub fn with_base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Monster データをまとめてスプレッドシートに追加
+    ///
+    /// 名前の列の実際の内容から、各モンスターの部位がすべて収まる空き行を開始行以降で探し、
+    /// 全モンスターの行を1回の `values:batchUpdate` で書き込む。
+    /// `overwrite` の場合は開始行以降のレイアウトの列を消去してから開始行から書き込む。
+    ///
+    /// # 戻り値
+    /// モンスターごとの書き込み開始行
+    pub async fn append_monsters(
         &self,
         spreadsheet_id: &str,
-        sheet_name: &str,
-        monster: &Monster,
-    ) -> Result<(), SheetsApiError> {
+        layout: &SheetLayout,
+        monsters: &[Monster],
+        overwrite: bool,
+    ) -> Result<Vec<usize>, SheetsApiError> {
         // スプレッドシートIDの検証
         Self::validate_spreadsheet_id(spreadsheet_id)?;
 
-        // 空き行を探す（Column A から row 3 から検索）
-        let start_row = self
-            .find_empty_row(spreadsheet_id, sheet_name)
-            .await?;
+        let occupied = if overwrite {
+            let last_column = Self::index_to_column(layout.width().saturating_sub(1));
+            let range = format!("{}!A{}:{}", layout.sheet_name, layout.start_row, last_column);
+            self.clear_ranges(spreadsheet_id, &[range]).await?;
+            Vec::new()
+        } else {
+            self.column_values(spreadsheet_id, &layout.sheet_name, layout.key_column(), layout.start_row)
+                .await?
+        };
+
+        // 各モンスターの行数（部位数 × 1部位あたりの行数）から空き行を割り当てる
+        let sizes: Vec<usize> = monsters.iter().map(|m| m.part.len() * layout.rows_per_part).collect();
+        let start_rows = find_free_rows(&occupied, layout.start_row, layout.rows_per_part, &sizes);
 
         // Monster データを行に変換
-        let rows = DataTransformer::transform_monster(monster, start_row);
+        let rows = monsters
+            .iter()
+            .zip(&start_rows)
+            .flat_map(|(monster, &row)| DataTransformer::transform_monster_with_layout(monster, row, layout))
+            .collect();
 
         // Google Sheets API に書き込み
-        self.write_rows_to_sheet(spreadsheet_id, sheet_name, rows)
+        self.write_rows_to_sheet(spreadsheet_id, &layout.sheet_name, rows)
             .await?;
 
-        Ok(())
+        Ok(start_rows)
     }
 
-    /// 空き行を探す（Column A で空いている行を検索）
-    /// 
-    /// Google Sheets API の `values.get` メソッドでColumn Aの値を取得し、
-    /// Row 3 から検索開始してアクセストークンを使用して検索します。
-    /// 奇数行のみをチェック（row 3, 5, 7, 9...）
-    async fn find_empty_row(
+    /// Spell データをスプレッドシートに追加
+    ///
+    /// 開始行より上を見出し行とみなし、開始行以降の Column A の最後の値の次の行から1スペル1行で書き込む。
+    /// `overwrite` の場合は開始行以降を消去してから開始行から書き込む。
+    pub async fn append_spell_data(
+        &self,
+        spreadsheet_id: &str,
+        sheet: &SpellSheet,
+        spells: &[Spell],
+        overwrite: bool,
+    ) -> Result<(), SheetsApiError> {
+        Self::validate_spreadsheet_id(spreadsheet_id)?;
+        let sheet_name = sheet.sheet_name.as_str();
+
+        let start_row = if overwrite {
+            let last_column = Self::index_to_column(SPELL_COLUMNS - 1);
+            self.clear_ranges(spreadsheet_id, &[format!("{}!A{}:{}", sheet_name, sheet.start_row, last_column)])
+                .await?;
+            sheet.start_row
+        } else {
+            sheet.start_row + self.column_values(spreadsheet_id, sheet_name, 0, sheet.start_row).await?.len()
+        };
+
+        let rows = spells
+            .iter()
+            .enumerate()
+            .map(|(i, spell)| DataTransformer::transform_spell(spell, start_row + i))
+            .collect();
+
+        self.write_rows_to_sheet(spreadsheet_id, sheet_name, rows)
+            .await
+    }
+
+    /// レイアウトの列（A 列から最も右の列まで）の開始行以降の値を読み込む
+    ///
+    /// 開始行から順に1行ずつ、セルは表示されている文字列のまま返す。
+    /// 最後の値より後ろの行・セルは含まない（API が末尾の空セルを返さないため）。
+    pub async fn read_monster_rows(
+        &self,
+        spreadsheet_id: &str,
+        layout: &SheetLayout,
+    ) -> Result<Vec<Vec<String>>, SheetsApiError> {
+        Self::validate_spreadsheet_id(spreadsheet_id)?;
+
+        let last_column = Self::index_to_column(layout.width().saturating_sub(1));
+        let range = format!("{}!A{}:{}", layout.sheet_name, layout.start_row, last_column);
+        let rows = self.get_values(spreadsheet_id, &range).await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| {
+                row.as_array()
+                    .map(|cells| cells.iter().map(Self::cell_text).collect())
+                    .unwrap_or_default()
+            })
+            .collect())
+    }
+
+    /// `column` 列の `first_row` 行目以降について、各行に値が入っているかを取得
+    ///
+    /// 最後の値より後ろの行は含まない（API が末尾の空セルを返さないため）。
+    async fn column_values(
         &self,
         spreadsheet_id: &str,
         sheet_name: &str,
-    ) -> Result<usize, SheetsApiError> {
+        column: usize,
+        first_row: usize,
+    ) -> Result<Vec<bool>, SheetsApiError> {
+        // 列の値を取得（first_row からシートの最後まで）
+        let column = Self::index_to_column(column);
+        let range = format!("{}!{}{}:{}", sheet_name, column, first_row, column);
+        let rows = self.get_values(spreadsheet_id, &range).await?;
+
+        Ok(rows.iter().map(Self::has_value).collect())
+    }
+
+    /// `values.get` で範囲の値を行ごとに取得（空の行は [] になる）
+    async fn get_values(&self, spreadsheet_id: &str, range: &str) -> Result<Vec<serde_json::Value>, SheetsApiError> {
         let client = reqwest::Client::new();
-        
-        // Column A の値を取得（Row 3 から検索）
-        // A3:A1000 の範囲で値を取得
-        let range = format!("{}!A3:A1000", sheet_name);
         let url = format!(
-            "https://sheets.googleapis.com/v4/spreadsheets/{}/values/{}",
-            spreadsheet_id, urlencoding::encode(&range)
+            "{}/spreadsheets/{}/values/{}",
+            self.base_url, spreadsheet_id, urlencoding::encode(range)
         );
 
         let response = client
@@ -105,6 +196,49 @@ impl GoogleSheetsClient {
             .await
             .map_err(|e| SheetsApiError::RequestFailed(format!("Failed to get values: {}", e)))?;
 
+        let response_json = Self::parse_response(response).await?;
+
+        Ok(response_json
+            .get("values")
+            .and_then(|v| v.as_array())
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    /// `values.get` の1セルを文字列に（数値・真偽値はそのまま文字列にする）
+    fn cell_text(cell: &serde_json::Value) -> String {
+        match cell {
+            serde_json::Value::String(text) => text.clone(),
+            serde_json::Value::Null => String::new(),
+            other => other.to_string(),
+        }
+    }
+
+    /// `values.get` の1行分に空でない値があるか
+    fn has_value(row: &serde_json::Value) -> bool {
+        row.as_array().is_some_and(|cells| {
+            cells.iter().any(|cell| !cell.is_null() && cell.as_str().is_none_or(|text| !text.trim().is_empty()))
+        })
+    }
+
+    /// 範囲の値を消去（書式は残す）
+    async fn clear_ranges(&self, spreadsheet_id: &str, ranges: &[String]) -> Result<(), SheetsApiError> {
+        let client = reqwest::Client::new();
+        let url = format!("{}/spreadsheets/{}/values:batchClear", self.base_url, spreadsheet_id);
+
+        let response = client
+            .post(&url)
+            .header("Authorization", format!("{} {}", self.credentials.token_type, self.credentials.access_token))
+            .json(&serde_json::json!({ "ranges": ranges }))
+            .send()
+            .await
+            .map_err(|e| SheetsApiError::RequestFailed(format!("Failed to clear values: {}", e)))?;
+
+        Self::parse_response(response).await.map(|_| ())
+    }
+
+    /// レスポンスのステータスを確認して JSON を取り出す
+    async fn parse_response(response: reqwest::Response) -> Result<serde_json::Value, SheetsApiError> {
         if !response.status().is_success() {
             let status = response.status();
             let body = response
@@ -116,44 +250,17 @@ impl GoogleSheetsClient {
             ));
         }
 
-        let response_json: serde_json::Value = response
+        response
             .json()
             .await
-            .map_err(|e| SheetsApiError::RequestFailed(format!("Failed to parse response: {}", e)))?;
-
-        // response から values を抽出
-        let values = response_json
-            .get("values")
-            .and_then(|v| v.as_array())
-            .map(|v| v.len())
-            .unwrap_or(0);
-
-        // 最初の空き奇数行を返す（Row 3 は rows[0]、Row 5 は rows[2], ...）
-        // values の中から最初の空のセルを探す
-        if values == 0 {
-            // Column A に値が無い = Row 3 が空いている
-            return Ok(3);
-        }
-
-        // 値がある場合は、その後の奇数行を探す
-        // Row 3, 5, 7, 9... の中から、Column A が空の行を探す
-        for i in (0..1000).step_by(2) {
-            let row = 3 + i;
-            // API レスポンスで確認するロジック
-            // ここでは簡略化のため、values 数 + 1 の奇数行を返す
-            if i >= values {
-                return Ok(row);
-            }
-        }
-
-        Err(SheetsApiError::NoEmptyRows)
+            .map_err(|e| SheetsApiError::RequestFailed(format!("Failed to parse response: {}", e)))
     }
 
     /// スプレッドシートに行データを書き込む
     /// 
-    /// Google Sheets API の `values.update` メソッドを使用して、
-    /// 複数の行データをスプレッドシートに書き込みます。
-    /// 各行について A1 notation に変換され、バッチで送信されます。
+    /// Google Sheets API の `values:batchUpdate` メソッドを使用して、
+    /// 複数の行データを1回のリクエストでスプレッドシートに書き込みます。
+    /// 各行について A1 notation に変換されます（値の無いセルは変更しない）。
     async fn write_rows_to_sheet(
         &self,
         spreadsheet_id: &str,
@@ -167,41 +274,24 @@ impl GoogleSheetsClient {
         let client = reqwest::Client::new();
 
         // バッチアップデートリクエストを構築
-        let mut requests = Vec::new();
-
-        for row in rows {
-            // 各行について A1 notation を生成
-            // 例：row 3, columns 0-53 → A3:AZ3
-            let start_col = Self::index_to_column(0);
-            let end_col = Self::index_to_column(row.values.len().saturating_sub(1));
-            let range = format!("{}!{}{}:{}{}",
-                sheet_name,
-                start_col, row.row_number,
-                end_col, row.row_number
-            );
-
-            // セルの値を構築
-            let mut cell_values = Vec::new();
-            for value_opt in &row.values {
-                if let Some(value) = value_opt {
-                    cell_values.push(serde_json::json!(value));
-                } else {
-                    cell_values.push(serde_json::json!(null));
-                }
-            }
-
-            requests.push(serde_json::json!({
-                "range": range,
-                "majorDimension": "ROWS",
-                "values": [cell_values]
-            }));
-        }
+        let requests: Vec<serde_json::Value> = rows
+            .iter()
+            .map(|row| {
+                // 各行について A1 notation を生成
+                // 例：row 3, columns 0-48 → A3:AW3
+                let end_col = Self::index_to_column(row.values.len().saturating_sub(1));
+                let range = format!("{}!A{}:{}{}", sheet_name, row.row_number, end_col, row.row_number);
+
+                serde_json::json!({
+                    "range": range,
+                    "majorDimension": "ROWS",
+                    "values": [row.values]
+                })
+            })
+            .collect();
 
         // Google Sheets API の batchUpdate を使用
-        let url = format!(
-            "https://sheets.googleapis.com/v4/spreadsheets/{}/values:batchUpdate",
-            spreadsheet_id
-        );
+        let url = format!("{}/spreadsheets/{}/values:batchUpdate", self.base_url, spreadsheet_id);
 
         let body = serde_json::json!({
             "data": requests,
@@ -217,18 +307,7 @@ impl GoogleSheetsClient {
             .await
             .map_err(|e| SheetsApiError::RequestFailed(format!("Failed to write values: {}", e)))?;
 
-        if !response.status().is_success() {
-            let status = response.status();
-            let error_body = response
-                .text()
-                .await
-                .unwrap_or_else(|_| "Unknown error".to_string());
-            return Err(SheetsApiError::RequestFailed(
-                format!("API request failed with status {}: {}", status, error_body)
-            ));
-        }
-
-        Ok(())
+        Self::parse_response(response).await.map(|_| ())
     }
 
     /// 列インデックスを A1 notation の列文字に変換
@@ -265,6 +344,32 @@ impl GoogleSheetsClient {
     }
 }
 
+/// モンスターごとの書き込み開始行を割り当てる
+///
+/// `occupied[i]` は `start_row + i` 行目に値があるか。各モンスターには、部位の区切り
+/// （`start_row` から `rows_per_part` 行ごと）から始まり、`sizes` の行数がすべて空いている
+/// 最初の範囲を、前のモンスターに割り当てた範囲と重ならないように入力の順に割り当てる。
+pub fn find_free_rows(occupied: &[bool], start_row: usize, rows_per_part: usize, sizes: &[usize]) -> Vec<usize> {
+    let rows_per_part = rows_per_part.max(1);
+    let mut occupied = occupied.to_vec();
+    let mut start_rows = Vec::with_capacity(sizes.len());
+
+    for &size in sizes {
+        let offset = (0..)
+            .step_by(rows_per_part)
+            .find(|&offset| (offset..offset + size).all(|i| !occupied.get(i).copied().unwrap_or(false)))
+            .unwrap_or(occupied.len());
+
+        if occupied.len() < offset + size {
+            occupied.resize(offset + size, false);
+        }
+        occupied[offset..offset + size].fill(true);
+        start_rows.push(start_row + offset);
+    }
+
+    start_rows
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -349,4 +454,181 @@ mod tests {
         assert_eq!(output.values[0], Some("A1".to_string()));
         assert_eq!(output.values[2], None);
     }
+
+    #[test]
+    fn test_find_free_rows_fills_gaps() {
+        // rows 3-4 used, 5-6 free, 7-8 used, 9- free
+        let occupied = [true, false, false, false, true];
+        assert_eq!(find_free_rows(&occupied, 3, 2, &[2, 4, 2]), vec![5, 9, 13]);
+
+        // a part boundary is only every rows_per_part rows
+        let occupied = [true, true, false, true];
+        assert_eq!(find_free_rows(&occupied, 3, 2, &[2]), vec![7]);
+
+        assert_eq!(find_free_rows(&[], 2, 1, &[1, 3]), vec![2, 3]);
+    }
+
+    /// 受け取ったリクエスト（メソッド, URL, 本文）
+    type Received = Vec<(String, String, String)>;
+
+    /// 決まった順にレスポンスを返し、受け取ったリクエストを返すモックサーバー
+    fn mock_server(responses: Vec<&'static str>) -> (String, std::thread::JoinHandle<Received>) {
+        let server = tiny_http::Server::http("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}/v4", server.server_addr().to_ip().unwrap());
+        let handle = std::thread::spawn(move || {
+            responses
+                .into_iter()
+                .map(|body| {
+                    let mut request = server.recv().unwrap();
+                    let mut content = String::new();
+                    request.as_reader().read_to_string(&mut content).unwrap();
+                    let received = (request.method().to_string(), request.url().to_string(), content);
+                    request.respond(tiny_http::Response::from_string(body)).unwrap();
+                    received
+                })
+                .collect()
+        });
+        (base_url, handle)
+    }
+
+    fn test_client(base_url: &str) -> GoogleSheetsClient {
+        let credentials = OAuthCredentials {
+            access_token: "test_token".to_string(),
+            token_type: "Bearer".to_string(),
+            expires_at: i64::MAX,
+            refresh_token: None,
+        };
+        GoogleSheetsClient::with_credentials(GoogleSheetsAuth::new().unwrap(), credentials).with_base_url(base_url)
+    }
+
+    fn test_monster(name: &str, parts: usize) -> Monster {
+        let part = serde_json::json!({"HP": 10, "MP": -1, "name": "", "命中力": 5, "回避力": 5, "打撃点": 3, "部位数": 1, "部位特殊能力": "", "防護点": 2});
+        serde_json::from_value(serde_json::json!({
+            "Category": "蛮族", "Lv": 1, "Revision": 2.5, "data": "", "illust": "",
+            "movein": -1, "movein_des": "", "moveon": 10, "moveon_des": "", "name": name,
+            "part": vec![part; parts],
+            "備考": "", "先制値": 10, "共通特殊能力": "", "弱点": "", "弱点値": 10,
+            "生命抵抗力": 5, "知名度": 5, "精神抵抗力": 5
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_append_monsters_single_batch() {
+        let (base_url, server) = mock_server(vec![
+            r#"{"range": "search!A3:A9", "values": [["ゴブリン"], [], [], [], ["オーガ"]]}"#,
+            "{}",
+        ]);
+        let client = test_client(&base_url);
+        let monsters = [test_monster("コボルド", 1), test_monster("トロール", 2)];
+
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let start_rows = rt
+            .block_on(client.append_monsters("1BxiMVs0XRA5nFMKUVfIz487hJblLvZQvq", &SheetLayout::default(), &monsters, false))
+            .unwrap();
+        let requests = server.join().unwrap();
+
+        assert_eq!(start_rows, vec![5, 9]);
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0].0, "GET");
+        assert!(requests[0].1.starts_with("/v4/spreadsheets/1BxiMVs0XRA5nFMKUVfIz487hJblLvZQvq/values/search%21A3%3AA"));
+        assert_eq!(requests[1].1, "/v4/spreadsheets/1BxiMVs0XRA5nFMKUVfIz487hJblLvZQvq/values:batchUpdate");
+
+        let body: serde_json::Value = serde_json::from_str(&requests[1].2).unwrap();
+        let ranges: Vec<&str> = body["data"].as_array().unwrap().iter().map(|d| d["range"].as_str().unwrap()).collect();
+        assert_eq!(ranges, vec!["search!A5:AW5", "search!A6:AW6", "search!A9:AW9", "search!A10:AW10", "search!A11:AW11", "search!A12:AW12"]);
+        assert_eq!(body["data"][0]["values"][0][0], "コボルド");
+    }
+
+    #[test]
+    fn test_append_monsters_overwrite() {
+        let (base_url, server) = mock_server(vec!["{}", "{}"]);
+        let client = test_client(&base_url);
+
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let start_rows = rt
+            .block_on(client.append_monsters("1BxiMVs0XRA5nFMKUVfIz487hJblLvZQvq", &SheetLayout::default(), &[test_monster("コボルド", 1)], true))
+            .unwrap();
+        let requests = server.join().unwrap();
+
+        assert_eq!(start_rows, vec![3]);
+        assert_eq!(requests[0].1, "/v4/spreadsheets/1BxiMVs0XRA5nFMKUVfIz487hJblLvZQvq/values:batchClear");
+        let body: serde_json::Value = serde_json::from_str(&requests[0].2).unwrap();
+        assert_eq!(body["ranges"], serde_json::json!(["search!A3:AW"]));
+        assert!(requests[1].1.ends_with("values:batchUpdate"));
+    }
+
+    #[test]
+    fn test_read_monster_rows() {
+        let (base_url, server) = mock_server(vec![
+            r#"{"range": "search!A3:AW6", "values": [["★ゴブリン", "", 12], [], ["オーガ"]]}"#,
+        ]);
+        let client = test_client(&base_url);
+
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let rows = rt
+            .block_on(client.read_monster_rows("1BxiMVs0XRA5nFMKUVfIz487hJblLvZQvq", &SheetLayout::default()))
+            .unwrap();
+        let requests = server.join().unwrap();
+
+        assert_eq!(requests[0].0, "GET");
+        assert_eq!(requests[0].1, "/v4/spreadsheets/1BxiMVs0XRA5nFMKUVfIz487hJblLvZQvq/values/search%21A3%3AAW");
+        assert_eq!(rows, vec![vec!["★ゴブリン".to_string(), String::new(), "12".to_string()], vec![], vec!["オーガ".to_string()]]);
+    }
+
+    #[test]
+    fn test_append_spell_data_sheet_and_start_row() {
+        let (base_url, server) = mock_server(vec![r#"{"range": "呪文!A4:A5", "values": [["ライト"], ["ブレス"]]}"#, "{}"]);
+        let client = test_client(&base_url);
+        let sheet = SpellSheet { sheet_name: "呪文".to_string(), start_row: 4 };
+
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        rt.block_on(client.append_spell_data("1BxiMVs0XRA5nFMKUVfIz487hJblLvZQvq", &sheet, &[Spell::new("キュア・ウーンズ", "神聖")], false))
+            .unwrap();
+        let requests = server.join().unwrap();
+
+        assert!(requests[0].1.starts_with(&format!(
+            "/v4/spreadsheets/1BxiMVs0XRA5nFMKUVfIz487hJblLvZQvq/values/{}",
+            urlencoding::encode("呪文!A4:A")
+        )));
+        let body: serde_json::Value = serde_json::from_str(&requests[1].2).unwrap();
+        assert_eq!(body["data"][0]["range"], "呪文!A6:K6");
+        assert_eq!(body["data"][0]["values"][0][0], "キュア・ウーンズ");
+    }
+
+    #[test]
+    fn test_append_spell_data_overwrite_clears_from_start_row() {
+        let (base_url, server) = mock_server(vec!["{}", "{}"]);
+        let client = test_client(&base_url);
+        let sheet = SpellSheet { sheet_name: "呪文".to_string(), start_row: 4 };
+
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        rt.block_on(client.append_spell_data("1BxiMVs0XRA5nFMKUVfIz487hJblLvZQvq", &sheet, &[Spell::new("ライト", "神聖")], true))
+            .unwrap();
+        let requests = server.join().unwrap();
+
+        let body: serde_json::Value = serde_json::from_str(&requests[0].2).unwrap();
+        assert_eq!(body["ranges"], serde_json::json!(["呪文!A4:K"]));
+        let body: serde_json::Value = serde_json::from_str(&requests[1].2).unwrap();
+        assert_eq!(body["data"][0]["range"], "呪文!A4:K4");
+    }
+
+    #[test]
+    fn test_api_error_status() {
+        let server = tiny_http::Server::http("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}/v4/", server.server_addr().to_ip().unwrap());
+        let handle = std::thread::spawn(move || {
+            let request = server.recv().unwrap();
+            request.respond(tiny_http::Response::from_string("denied").with_status_code(403)).unwrap();
+        });
+        let client = test_client(&base_url);
+
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let err = rt
+            .block_on(client.append_spell_data("1BxiMVs0XRA5nFMKUVfIz487hJblLvZQvq", &SpellSheet::default(), &[Spell::new("ライト", "神聖")], false))
+            .unwrap_err();
+        handle.join().unwrap();
+
+        assert!(err.to_string().contains("status 403 Forbidden: denied"));
+    }
 }
@@ -0,0 +1,591 @@
+//! Markdown / HTML のステータスブロック
+//!
+//! モンスターはルールブックのデータ欄に近い形（基本データ・部位ごとの表・特殊能力）、
+//! スペルはカード形式で出力します。HTML は CSS を埋め込んだ1ファイルで、先頭に目次を付けます。
+
+use super::palette;
+use super::udonarium::data_transformer::DataTransformer;
+use super::{DataExporter, ExportConfig, ExportError, SpellExporter};
+use crate::{Monster, Part, Spell};
+use std::fs;
+use std::path::Path;
+
+/// 部位の表の列
+const PART_HEADERS: [&str; 7] = ["部位", "命中力", "打撃点", "回避力", "防護点", "HP", "MP"];
+
+/// HTML に埋め込むスタイルシート
+const HTML_STYLE: &str = "\
+body { font-family: sans-serif; max-width: 60em; margin: 2em auto; padding: 0 1em; line-height: 1.5; color: #222; }
+nav ol { columns: 2; }
+.block { border: 1px solid #888; border-radius: 4px; margin: 1.5em 0; padding: 0 1em 1em; page-break-inside: avoid; }
+.block h2 { border-bottom: 2px solid #444; margin-top: 0.8em; }
+table { border-collapse: collapse; margin: 0.5em 0; }
+th, td { border: 1px solid #aaa; padding: 0.2em 0.6em; }
+th { background: #eee; }
+td.num { text-align: right; }
+.card { border-color: #468; }
+.card h2 { border-color: #468; }
+.tags { color: #666; font-size: 0.9em; }
+.source { color: #666; font-size: 0.9em; text-align: right; }
+";
+
+/// 出力するマークアップ
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatBlockFormat {
+    Markdown,
+    Html,
+}
+
+/// モンスター1体分のステータスブロック
+#[derive(Debug, Clone, PartialEq)]
+pub struct MonsterBlock {
+    pub name: String,
+    /// 基本データ（見出し, 値）
+    pub header: Vec<(&'static str, String)>,
+    /// 部位ごとの行（[`PART_HEADERS`] の順）
+    pub parts: Vec<Vec<String>>,
+    /// 特殊能力（対象（「共通」または部位名）, 内容）
+    pub abilities: Vec<(String, String)>,
+    pub notes: String,
+    pub source: String,
+}
+
+impl MonsterBlock {
+    /// モンスターからステータスブロックを組み立てる
+    ///
+    /// 命中力・回避力・抵抗力は「基準値 (固定値)」で表示する。
+    pub fn from_monster(monster: &Monster) -> Self {
+        let with_base = |value: i32| format!("{} ({})", DataTransformer::adjust_value(value), value);
+        let multi_part = monster.part.len() > 1;
+
+        let header = vec![
+            ("Lv", monster.level.to_string()),
+            ("分類", monster.category.clone()),
+            ("知名度/弱点値", format!("{}/{}", monster.fame, monster.weakness_value)),
+            ("弱点", or_dash(&monster.weakness)),
+            ("先制値", monster.initiative.to_string()),
+            ("移動速度", format_movement(monster)),
+            ("生命抵抗力", with_base(monster.life_resistance)),
+            ("精神抵抗力", with_base(monster.mental_resistance)),
+        ];
+
+        let parts = monster
+            .part
+            .iter()
+            .map(|part| {
+                vec![
+                    part_label(part, multi_part),
+                    part.hit_rate.map_or_else(|| "-".to_string(), with_base),
+                    part.damage.map_or_else(|| "-".to_string(), |damage| format!("2d+{}", damage)),
+                    part.dodge.map_or_else(|| "-".to_string(), with_base),
+                    part.armor.to_string(),
+                    part.hp.map_or_else(|| "-".to_string(), |hp| hp.to_string()),
+                    if part.mp >= 0 { part.mp.to_string() } else { "-".to_string() },
+                ]
+            })
+            .collect();
+
+        let mut abilities = Vec::new();
+        if !monster.common_abilities.is_empty() {
+            abilities.push(("共通".to_string(), monster.common_abilities.clone()));
+        }
+        for part in monster.part.iter().filter(|part| !part.special_abilities.is_empty()) {
+            abilities.push((part_label(part, multi_part), part.special_abilities.clone()));
+        }
+
+        Self {
+            name: monster.name.clone(),
+            header,
+            parts,
+            abilities,
+            notes: monster.notes.clone(),
+            source: monster.data.clone(),
+        }
+    }
+}
+
+/// スペル1つ分のカード
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpellCard {
+    pub name: String,
+    /// データ欄（見出し, 値）。値が無い項目は "-"
+    pub fields: Vec<(&'static str, String)>,
+    /// 補助動作・事前準備などの付記
+    pub tags: Vec<&'static str>,
+    pub effect: String,
+}
+
+impl SpellCard {
+    /// スペルからカードを組み立てる
+    pub fn from_spell(spell: &Spell) -> Self {
+        let or_dash = |value: Result<String, String>| value.unwrap_or_else(|_| "-".to_string());
+        let text = |value: &Option<String>| value.clone().unwrap_or_else(|| "-".to_string());
+
+        let range = or_dash(palette::format_range(spell));
+        let range = match &spell.shape {
+            Some(shape) => format!("{}／{}", range, shape),
+            None => range,
+        };
+
+        let fields = vec![
+            ("系統", palette::format_magic_category(&spell.school)),
+            ("習得", palette::format_level(spell).unwrap_or_else(|| "-".to_string())),
+            ("MP", or_dash(palette::format_mp(spell))),
+            ("対象", or_dash(palette::format_target(spell))),
+            ("射程／形状", range),
+            ("時間", or_dash(palette::format_duration(spell))),
+            ("抵抗", text(&spell.resistance)),
+            ("属性", text(&spell.element)),
+        ];
+
+        let mut tags = Vec::new();
+        if spell.is_support() {
+            tags.push("補助動作");
+        }
+        if spell.requires_preparation() {
+            tags.push("事前準備");
+        }
+        if spell.is_crime() {
+            tags.push("犯罪");
+        }
+
+        Self { name: spell.name.clone(), fields, tags, effect: spell.effect.clone().unwrap_or_default() }
+    }
+}
+
+/// 部位の表示名（複数部位のときはコア部位に★、部位数が2以上なら ×N を付ける）
+fn part_label(part: &Part, multi_part: bool) -> String {
+    let mut label = if part.name.is_empty() { "-".to_string() } else { part.name.clone() };
+    if multi_part && part.core.unwrap_or(false) {
+        label = format!("★{}", label);
+    }
+    if part.part_count > 1 {
+        label = format!("{}×{}", label, part.part_count);
+    }
+    label
+}
+
+/// 移動速度（"12（四足）"、2つ目の移動方法があれば "12／20（飛行）"）
+fn format_movement(monster: &Monster) -> String {
+    let format = |value: i32, description: &str| {
+        if value == -1 {
+            "-".to_string()
+        } else if description.is_empty() {
+            value.to_string()
+        } else {
+            format!("{}（{}）", value, description)
+        }
+    };
+
+    let on = format(monster.moveon, &monster.moveon_description);
+    if monster.movein == -1 {
+        on
+    } else {
+        format!("{}／{}", on, format(monster.movein, &monster.movein_description))
+    }
+}
+
+fn or_dash(value: &str) -> String {
+    if value.is_empty() { "-".to_string() } else { value.to_string() }
+}
+
+// ============================================================================
+// Markdown
+// ============================================================================
+
+/// Markdown の表のセル（`|` をエスケープし、改行を `<br>` にする）
+fn md_cell(value: &str) -> String {
+    value.replace('|', "\\|").replace("\r\n", "<br>").replace('\n', "<br>")
+}
+
+/// 見出し行と1行以上のデータ行から Markdown の表を作る
+fn md_table(headers: &[&str], rows: &[Vec<String>]) -> String {
+    let mut out = format!("| {} |\n", headers.join(" | "));
+    out.push_str(&format!("|{}\n", "---|".repeat(headers.len())));
+    for row in rows {
+        let cells: Vec<String> = row.iter().map(|cell| md_cell(cell)).collect();
+        out.push_str(&format!("| {} |\n", cells.join(" | ")));
+    }
+    out
+}
+
+/// モンスターのステータスブロックを Markdown で書き出す
+pub fn monster_markdown(monster: &Monster) -> String {
+    let block = MonsterBlock::from_monster(monster);
+    let (headers, values): (Vec<&str>, Vec<String>) = block.header.iter().cloned().unzip();
+
+    let mut out = format!("## {}\n\n", block.name);
+    out.push_str(&md_table(&headers, &[values]));
+    if !block.parts.is_empty() {
+        out.push('\n');
+        out.push_str(&md_table(&PART_HEADERS, &block.parts));
+    }
+    if !block.abilities.is_empty() {
+        out.push_str("\n### 特殊能力\n\n");
+        for (owner, text) in &block.abilities {
+            out.push_str(&format!("- **{}**: {}\n", owner, md_cell(text)));
+        }
+    }
+    if !block.notes.is_empty() {
+        out.push_str(&format!("\n### 備考\n\n{}\n", block.notes));
+    }
+    if !block.source.is_empty() {
+        out.push_str(&format!("\n出典: {}\n", block.source));
+    }
+    out
+}
+
+/// スペルのカードを Markdown で書き出す
+pub fn spell_markdown(spell: &Spell) -> String {
+    let card = SpellCard::from_spell(spell);
+    let (headers, values): (Vec<&str>, Vec<String>) = card.fields.iter().cloned().unzip();
+
+    let mut out = format!("## {}\n\n", card.name);
+    if !card.tags.is_empty() {
+        out.push_str(&format!("*{}*\n\n", card.tags.join("・")));
+    }
+    out.push_str(&md_table(&headers, &[values]));
+    if !card.effect.is_empty() {
+        out.push_str(&format!("\n{}\n", card.effect));
+    }
+    out
+}
+
+// ============================================================================
+// HTML
+// ============================================================================
+
+/// HTML のテキストと属性値をエスケープする（改行は `<br>` にする）
+pub fn escape_html(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            '\n' => out.push_str("<br>"),
+            '\r' => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// 見出し行とデータ行から HTML の表を作る（数値のセルは右寄せ）
+fn html_table(headers: &[&str], rows: &[Vec<String>]) -> String {
+    let mut out = String::from("<table>\n<tr>");
+    for header in headers {
+        out.push_str(&format!("<th>{}</th>", escape_html(header)));
+    }
+    out.push_str("</tr>\n");
+    for row in rows {
+        out.push_str("<tr>");
+        for cell in row {
+            let numeric = cell.starts_with(|c: char| c.is_ascii_digit()) || cell.starts_with("2d+");
+            let class = if numeric { " class=\"num\"" } else { "" };
+            out.push_str(&format!("<td{}>{}</td>", class, escape_html(cell)));
+        }
+        out.push_str("</tr>\n");
+    }
+    out.push_str("</table>\n");
+    out
+}
+
+/// モンスターのステータスブロックを HTML の section 要素で書き出す
+///
+/// # 引数
+/// * `monster` - 対象のモンスター
+/// * `id` - 目次から参照するアンカー名
+pub fn monster_html(monster: &Monster, id: &str) -> String {
+    let block = MonsterBlock::from_monster(monster);
+    let (headers, values): (Vec<&str>, Vec<String>) = block.header.iter().cloned().unzip();
+
+    let mut out = format!("<section class=\"block\" id=\"{}\">\n<h2>{}</h2>\n", escape_html(id), escape_html(&block.name));
+    out.push_str(&html_table(&headers, &[values]));
+    if !block.parts.is_empty() {
+        out.push_str(&html_table(&PART_HEADERS, &block.parts));
+    }
+    if !block.abilities.is_empty() {
+        out.push_str("<h3>特殊能力</h3>\n<dl>\n");
+        for (owner, text) in &block.abilities {
+            out.push_str(&format!("<dt>{}</dt><dd>{}</dd>\n", escape_html(owner), escape_html(text)));
+        }
+        out.push_str("</dl>\n");
+    }
+    if !block.notes.is_empty() {
+        out.push_str(&format!("<h3>備考</h3>\n<p>{}</p>\n", escape_html(&block.notes)));
+    }
+    if !block.source.is_empty() {
+        out.push_str(&format!("<p class=\"source\">出典: {}</p>\n", escape_html(&block.source)));
+    }
+    out.push_str("</section>\n");
+    out
+}
+
+/// スペルのカードを HTML の section 要素で書き出す
+///
+/// # 引数
+/// * `spell` - 対象のスペル
+/// * `id` - 目次から参照するアンカー名
+pub fn spell_html(spell: &Spell, id: &str) -> String {
+    let card = SpellCard::from_spell(spell);
+    let (headers, values): (Vec<&str>, Vec<String>) = card.fields.iter().cloned().unzip();
+
+    let mut out = format!("<section class=\"block card\" id=\"{}\">\n<h2>{}</h2>\n", escape_html(id), escape_html(&card.name));
+    if !card.tags.is_empty() {
+        out.push_str(&format!("<p class=\"tags\">{}</p>\n", escape_html(&card.tags.join("・"))));
+    }
+    out.push_str(&html_table(&headers, &[values]));
+    if !card.effect.is_empty() {
+        out.push_str(&format!("<p>{}</p>\n", escape_html(&card.effect)));
+    }
+    out.push_str("</section>\n");
+    out
+}
+
+/// 目次付きの HTML 文書を組み立てる
+///
+/// # 引数
+/// * `title` - ページの見出し
+/// * `entries` - (アンカー名, 目次の表示, 本文の section 要素) の組
+fn html_document(title: &str, entries: &[(String, String, String)]) -> String {
+    let mut out = String::from("<!DOCTYPE html>\n<html lang=\"ja\">\n<head>\n<meta charset=\"utf-8\">\n");
+    out.push_str(&format!("<title>{}</title>\n<style>\n{}</style>\n</head>\n<body>\n", escape_html(title), HTML_STYLE));
+    out.push_str(&format!("<h1>{}（{}件）</h1>\n<nav>\n<ol>\n", escape_html(title), entries.len()));
+    for (id, label, _) in entries {
+        out.push_str(&format!("<li><a href=\"#{}\">{}</a></li>\n", escape_html(id), escape_html(label)));
+    }
+    out.push_str("</ol>\n</nav>\n");
+    for (_, _, section) in entries {
+        out.push_str(section);
+    }
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+// ============================================================================
+// Exporter
+// ============================================================================
+
+/// Markdown / HTML のステータスブロックを1ファイルに書き出すエクスポーター
+pub struct StatBlockExporter {
+    format: StatBlockFormat,
+}
+
+impl StatBlockExporter {
+    /// Markdown で書き出すエクスポーター
+    pub fn markdown() -> Self {
+        Self { format: StatBlockFormat::Markdown }
+    }
+
+    /// HTML で書き出すエクスポーター
+    pub fn html() -> Self {
+        Self { format: StatBlockFormat::Html }
+    }
+
+    /// モンスター一覧の文書を生成
+    pub fn render_monsters(&self, monsters: &[Monster]) -> String {
+        match self.format {
+            StatBlockFormat::Markdown => {
+                let blocks: Vec<String> = monsters.iter().map(monster_markdown).collect();
+                blocks.join("\n")
+            }
+            StatBlockFormat::Html => {
+                let entries: Vec<(String, String, String)> = monsters
+                    .iter()
+                    .enumerate()
+                    .map(|(i, monster)| {
+                        let id = format!("monster-{}", i + 1);
+                        let label = format!("{}（Lv{} {}）", monster.name, monster.level, monster.category);
+                        let section = monster_html(monster, &id);
+                        (id, label, section)
+                    })
+                    .collect();
+                html_document("モンスター一覧", &entries)
+            }
+        }
+    }
+
+    /// スペル一覧の文書を生成
+    pub fn render_spells(&self, spells: &[Spell]) -> String {
+        match self.format {
+            StatBlockFormat::Markdown => {
+                let cards: Vec<String> = spells.iter().map(spell_markdown).collect();
+                cards.join("\n")
+            }
+            StatBlockFormat::Html => {
+                let entries: Vec<(String, String, String)> = spells
+                    .iter()
+                    .enumerate()
+                    .map(|(i, spell)| {
+                        let id = format!("spell-{}", i + 1);
+                        let label = format!("{}（{}）", spell.name, palette::format_magic_category(&spell.school));
+                        let section = spell_html(spell, &id);
+                        (id, label, section)
+                    })
+                    .collect();
+                html_document("スペル一覧", &entries)
+            }
+        }
+    }
+
+    fn write(&self, content: &str, config: &ExportConfig) -> Result<(), ExportError> {
+        let path = Path::new(&config.destination);
+        if let Some(parent) = path.parent()
+            && !parent.as_os_str().is_empty() && !parent.exists()
+        {
+            return Err(ExportError::InvalidDestination(format!(
+                "Output directory does not exist: {}",
+                parent.display()
+            )));
+        }
+
+        fs::write(path, content)?;
+        Ok(())
+    }
+}
+
+impl DataExporter for StatBlockExporter {
+    fn export(&self, data: &[Monster], config: &ExportConfig) -> Result<(), ExportError> {
+        self.write(&self.render_monsters(data), config)
+    }
+
+    fn name(&self) -> &str {
+        match self.format {
+            StatBlockFormat::Markdown => "Markdown Exporter",
+            StatBlockFormat::Html => "HTML Exporter",
+        }
+    }
+}
+
+impl SpellExporter for StatBlockExporter {
+    fn export_spells(&self, data: &[Spell], config: &ExportConfig) -> Result<(), ExportError> {
+        self.write(&self.render_spells(data), config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn monster(value: serde_json::Value) -> Monster {
+        serde_json::from_value(value).expect("Failed to build test monster")
+    }
+
+    fn goblin() -> Monster {
+        monster(json!({
+            "Category": "蛮族", "Lv": 1, "Revision": 2.5, "data": "SAMPLE", "illust": "",
+            "movein": -1, "movein_des": "", "moveon": 10, "moveon_des": "", "name": "ゴブリン",
+            "part": [{"HP": 10, "MP": 8, "name": "", "コア": true, "命中力": 10, "回避力": 9, "打撃点": 1, "部位数": 1, "部位特殊能力": "", "防護点": 1}],
+            "備考": "", "先制値": 8, "共通特殊能力": "", "弱点": "魔法ダメージ+2", "弱点値": 10,
+            "生命抵抗力": 8, "知名度": 5, "精神抵抗力": 8
+        }))
+    }
+
+    fn trent() -> Monster {
+        monster(json!({
+            "Category": "植物", "Lv": 15, "Revision": 2.5, "data": "SAMPLE", "illust": "",
+            "movein": 20, "movein_des": "飛行", "moveon": 12, "moveon_des": "", "name": "トレント",
+            "part": [
+                {"HP": 105, "MP": 45, "name": "幹", "コア": true, "命中力": 21, "回避力": 18, "打撃点": 13, "部位数": 1, "部位特殊能力": "再生＝5", "防護点": 9},
+                {"HP": null, "MP": -1, "name": "根", "コア": false, "命中力": 19, "回避力": 15, "打撃点": 10, "部位数": 2, "部位特殊能力": "拘束攻撃|<強>", "防護点": 7}
+            ],
+            "備考": "森の奥に棲む", "先制値": 13, "共通特殊能力": "魔法適性", "弱点": "炎属性ダメージ+3", "弱点値": 21,
+            "生命抵抗力": 21, "知名度": 16, "精神抵抗力": 19
+        }))
+    }
+
+    fn bolt() -> Spell {
+        serde_json::from_value(json!({
+            "name": "エネルギー・ボルト",
+            "school": "真語",
+            "Lv": {"kind": "value", "value": 1},
+            "MP": {"kind": "value", "value": 5},
+            "対象": {"kind": "個別", "個別": "1体"},
+            "射程": "30m",
+            "形状": "射撃",
+            "時間": {"value": "一瞬"},
+            "抵抗": "半減",
+            "効果": "純エネルギー属性の魔法ダメージを与えます。"
+        }))
+        .expect("Failed to build test spell")
+    }
+
+    #[test]
+    fn test_monster_block() {
+        let block = MonsterBlock::from_monster(&trent());
+
+        assert_eq!(block.header[2], ("知名度/弱点値", "16/21".to_string()));
+        assert_eq!(block.header[5], ("移動速度", "12／20（飛行）".to_string()));
+        assert_eq!(block.header[6], ("生命抵抗力", "14 (21)".to_string()));
+        assert_eq!(block.parts[0], vec!["★幹", "14 (21)", "2d+13", "11 (18)", "9", "105", "45"]);
+        assert_eq!(block.parts[1], vec!["根×2", "12 (19)", "2d+10", "8 (15)", "7", "-", "-"]);
+        assert_eq!(block.abilities[0], ("共通".to_string(), "魔法適性".to_string()));
+        assert_eq!(block.abilities[1], ("★幹".to_string(), "再生＝5".to_string()));
+    }
+
+    #[test]
+    fn test_single_part_has_no_core_marker() {
+        let block = MonsterBlock::from_monster(&goblin());
+
+        assert_eq!(block.parts[0][0], "-");
+        assert_eq!(block.header[5], ("移動速度", "10".to_string()));
+        assert!(block.abilities.is_empty());
+    }
+
+    #[test]
+    fn test_monster_markdown() {
+        let md = monster_markdown(&trent());
+
+        assert!(md.starts_with("## トレント\n\n| Lv | 分類 | 知名度/弱点値 |"));
+        assert!(md.contains("| 15 | 植物 | 16/21 | 炎属性ダメージ+3 | 13 | 12／20（飛行） | 14 (21) | 12 (19) |"));
+        assert!(md.contains("| 部位 | 命中力 | 打撃点 | 回避力 | 防護点 | HP | MP |"));
+        assert!(md.contains("- **根×2**: 拘束攻撃\\|<強>"));
+        assert!(md.contains("### 備考\n\n森の奥に棲む"));
+        assert!(md.ends_with("出典: SAMPLE\n"));
+    }
+
+    #[test]
+    fn test_spell_card() {
+        let card = SpellCard::from_spell(&bolt());
+        let md = spell_markdown(&bolt());
+
+        assert_eq!(card.fields[0], ("系統", "真語魔法".to_string()));
+        assert_eq!(card.fields[4], ("射程／形状", "30m／射撃".to_string()));
+        assert_eq!(card.fields[7], ("属性", "-".to_string()));
+        assert!(md.contains("| 真語魔法 | 1 | 5 | 1体 | 30m／射撃 | 一瞬 | 半減 | - |"));
+        assert!(md.ends_with("\n純エネルギー属性の魔法ダメージを与えます。\n"));
+    }
+
+    #[test]
+    fn test_html_document_is_escaped_and_indexed() {
+        let html = StatBlockExporter::html().render_monsters(&[goblin(), trent()]);
+
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<h1>モンスター一覧（2件）</h1>"));
+        assert!(html.contains("<li><a href=\"#monster-2\">トレント（Lv15 植物）</a></li>"));
+        assert!(html.contains("<section class=\"block\" id=\"monster-2\">"));
+        assert!(html.contains("<dd>拘束攻撃|&lt;強&gt;</dd>"));
+        assert!(!html.contains("<強>"));
+        assert!(!html.contains("<link") && !html.contains("<script"));
+    }
+
+    #[test]
+    fn test_export_spells_html() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("spells.html");
+        let config = ExportConfig {
+            destination: path.to_string_lossy().to_string(),
+            format: super::super::ExportFormat::Html,
+        };
+
+        StatBlockExporter::html().export_spells(&[bolt()], &config).unwrap();
+        let html = fs::read_to_string(&path).unwrap();
+
+        assert!(html.contains("<li><a href=\"#spell-1\">エネルギー・ボルト（真語魔法）</a></li>"));
+        assert!(html.contains("<section class=\"block card\" id=\"spell-1\">"));
+    }
+}
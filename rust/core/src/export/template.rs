@@ -0,0 +1,224 @@
+//! テンプレートによるカスタム出力
+//!
+//! 設定ファイルの `[export.templates]` に登録したテンプレートファイル（Jinja2 形式）で
+//! モンスターやスペルを描画します。新しい VTT や文書の形式を再コンパイルせずに追加できます。
+//!
+//! テンプレートには `monsters`（モンスター一覧）または `spells`（スペル一覧）が渡され、
+//! 各レコードは JSON と同じキーで参照できます（`monster.name`、`part["命中力"]` など）。
+//! 拡張子が `.html` / `.xml` のテンプレートでは値が自動でエスケープされます。
+
+use super::palette;
+use super::udonarium::data_transformer::DataTransformer;
+use super::{DataExporter, ExportConfig, ExportError, SpellExporter};
+use crate::{Monster, Spell};
+use minijinja::value::ViaDeserialize;
+use minijinja::{context, Environment};
+use std::fs;
+use std::path::Path;
+
+/// テンプレートエクスポーター
+pub struct TemplateExporter {
+    /// テンプレート名（ファイル名。拡張子で自動エスケープの有無が決まる）
+    name: String,
+    source: String,
+}
+
+impl TemplateExporter {
+    /// テンプレートの名前と内容からエクスポーターを作成
+    pub fn new(name: impl Into<String>, source: impl Into<String>) -> Self {
+        Self { name: name.into(), source: source.into() }
+    }
+
+    /// テンプレートファイルを読み込む
+    ///
+    /// # エラー
+    /// ファイルを読み込めない場合、またはテンプレートの構文が誤っている場合
+    pub fn from_file(path: &Path) -> Result<Self, ExportError> {
+        let source = fs::read_to_string(path).map_err(|e| {
+            ExportError::TemplateError(format!("Cannot read template {}: {}", path.display(), e))
+        })?;
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| path.display().to_string());
+
+        let exporter = Self::new(name, source);
+        environment()
+            .template_from_named_str(&exporter.name, &exporter.source)
+            .map_err(template_error)?;
+        Ok(exporter)
+    }
+
+    /// モンスター一覧を描画（テンプレート変数 `monsters`）
+    pub fn render_monsters(&self, monsters: &[Monster]) -> Result<String, ExportError> {
+        self.render(context! { monsters => monsters })
+    }
+
+    /// スペル一覧を描画（テンプレート変数 `spells`）
+    pub fn render_spells(&self, spells: &[Spell]) -> Result<String, ExportError> {
+        self.render(context! { spells => spells })
+    }
+
+    fn render(&self, ctx: minijinja::Value) -> Result<String, ExportError> {
+        environment().render_named_str(&self.name, &self.source, ctx).map_err(template_error)
+    }
+
+    fn write(&self, content: &str, config: &ExportConfig) -> Result<(), ExportError> {
+        let path = Path::new(&config.destination);
+        if let Some(parent) = path.parent()
+            && !parent.as_os_str().is_empty() && !parent.exists()
+        {
+            return Err(ExportError::InvalidDestination(format!(
+                "Output directory does not exist: {}",
+                parent.display()
+            )));
+        }
+
+        fs::write(path, content)?;
+        Ok(())
+    }
+}
+
+impl DataExporter for TemplateExporter {
+    fn export(&self, data: &[Monster], config: &ExportConfig) -> Result<(), ExportError> {
+        self.write(&self.render_monsters(data)?, config)
+    }
+
+    fn name(&self) -> &str {
+        "Template Exporter"
+    }
+}
+
+impl SpellExporter for TemplateExporter {
+    fn export_spells(&self, data: &[Spell], config: &ExportConfig) -> Result<(), ExportError> {
+        self.write(&self.render_spells(data)?, config)
+    }
+}
+
+/// テンプレートのエラーを行番号などの詳細付きで ExportError に変換
+fn template_error(e: minijinja::Error) -> ExportError {
+    ExportError::TemplateError(format!("{:#}", e))
+}
+
+/// フィルタを登録したテンプレート環境
+///
+/// 制御タグの行が空行として残らないように trim_blocks / lstrip_blocks を有効にする。
+fn environment() -> Environment<'static> {
+    let mut env = Environment::new();
+    env.set_trim_blocks(true);
+    env.set_lstrip_blocks(true);
+    env.set_keep_trailing_newline(true);
+
+    env.add_filter("adjust_value", |value: i32| DataTransformer::adjust_value(value));
+    env.add_filter("transform_weakness", |weakness: String| DataTransformer::transform_weakness(&weakness));
+    env.add_filter("format_movement", |value: i32, description: Option<String>| {
+        DataTransformer::format_movement(value, description.as_deref().unwrap_or(""))
+    });
+    env.add_filter("format_magic_category", |school: String| palette::format_magic_category(&school));
+
+    // スペル（レコード全体）を受け取るフィルタ。値が無い・不正な場合は "-"
+    env.add_filter("format_level", |spell: ViaDeserialize<Spell>| {
+        palette::format_level(&spell).unwrap_or_else(|| "-".to_string())
+    });
+    env.add_filter("format_mp", |spell: ViaDeserialize<Spell>| or_dash(palette::format_mp(&spell)));
+    env.add_filter("format_target", |spell: ViaDeserialize<Spell>| or_dash(palette::format_target(&spell)));
+    env.add_filter("format_range", |spell: ViaDeserialize<Spell>| or_dash(palette::format_range(&spell)));
+    env.add_filter("format_duration", |spell: ViaDeserialize<Spell>| or_dash(palette::format_duration(&spell)));
+    env.add_filter("spell_palette", |spell: ViaDeserialize<Spell>| {
+        or_dash(palette::generate_spell_palette(&spell))
+    });
+    env
+}
+
+fn or_dash(value: Result<String, String>) -> String {
+    value.unwrap_or_else(|_| "-".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn trent() -> Monster {
+        serde_json::from_value(json!({
+            "Category": "植物", "Lv": 15, "Revision": 2.5, "data": "SAMPLE", "illust": "",
+            "movein": -1, "movein_des": "", "moveon": 12, "moveon_des": "四足", "name": "トレント",
+            "part": [
+                {"HP": 105, "MP": 45, "name": "幹", "コア": true, "命中力": 21, "回避力": 18, "打撃点": 13, "部位数": 1, "部位特殊能力": "", "防護点": 9},
+                {"HP": 75, "MP": -1, "name": "根", "コア": false, "命中力": 19, "回避力": 15, "打撃点": 10, "部位数": 2, "部位特殊能力": "", "防護点": 7}
+            ],
+            "備考": "", "先制値": 13, "共通特殊能力": "", "弱点": "炎属性ダメージ+3", "弱点値": 21,
+            "生命抵抗力": 21, "知名度": 16, "精神抵抗力": 19
+        }))
+        .expect("Failed to build test monster")
+    }
+
+    fn bolt() -> Spell {
+        serde_json::from_value(json!({
+            "name": "エネルギー・ボルト",
+            "school": "真語",
+            "Lv": {"kind": "value", "value": 1},
+            "MP": {"kind": "value", "value": 5},
+            "対象": {"kind": "個別", "個別": "1体"},
+            "射程": "30m",
+            "時間": {"value": "一瞬"},
+            "効果": "純エネルギー属性の魔法ダメージを与えます。"
+        }))
+        .expect("Failed to build test spell")
+    }
+
+    #[test]
+    fn test_render_monsters_with_loops_and_filters() {
+        let template = "\
+{% for m in monsters %}
+{{ m.name }} Lv{{ m.Lv }} 弱点:{{ m[\"弱点\"] | transform_weakness }} 移動:{{ m.moveon | format_movement(m.moveon_des) }}
+{% for p in m.part %}
+  {{ p.name }}{% if p[\"コア\"] %}(コア){% endif %} 命中{{ p[\"命中力\"] | adjust_value }} HP{{ p.HP }}
+{% endfor %}
+{% endfor %}
+";
+        let out = TemplateExporter::new("memo.txt", template).render_monsters(&[trent()]).unwrap();
+
+        assert_eq!(
+            out,
+            "トレント Lv15 弱点:炎ダメ+3 移動:12\n(四足)\n  幹(コア) 命中14 HP105\n  根 命中12 HP75\n"
+        );
+    }
+
+    #[test]
+    fn test_render_spells_with_spell_filters() {
+        let template = "{% for s in spells %}{{ s.school | format_magic_category }}|{{ s | format_level }}|{{ s | format_mp }}|{{ s | format_target }}|{{ s | format_duration }}\n{{ s | spell_palette }}{% endfor %}";
+        let out = TemplateExporter::new("spells.txt", template).render_spells(&[bolt()]).unwrap();
+
+        assert_eq!(
+            out,
+            "真語魔法|1|5|1体|一瞬\n2d+{真語魔法}+{行使修正}  エネルギー・ボルト / MP:5 / 対象:1体 / 射程:30m / 時間:一瞬 / 純エネルギー属性の魔法ダメージを与えます。"
+        );
+    }
+
+    #[test]
+    fn test_xml_template_escapes_values() {
+        let mut monster = trent();
+        monster.name = "<トレント&>".to_string();
+
+        let out = TemplateExporter::new("piece.xml", "<name>{{ monsters[0].name }}</name>").render_monsters(&[monster]).unwrap();
+
+        assert_eq!(out, "<name>&lt;トレント&amp;&gt;</name>");
+    }
+
+    #[test]
+    fn test_template_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("broken.txt");
+        fs::write(&path, "{% for m in monsters %}{{ m.name }}").unwrap();
+
+        let err = TemplateExporter::from_file(&path).err().unwrap();
+        assert!(matches!(err, ExportError::TemplateError(_)));
+
+        let err = TemplateExporter::from_file(&dir.path().join("missing.txt")).err().unwrap();
+        assert!(err.to_string().contains("Cannot read template"));
+
+        let err = TemplateExporter::new("x.txt", "{{ monsters | unknown_filter }}").render_monsters(&[]).unwrap_err();
+        assert!(err.to_string().contains("unknown_filter"));
+    }
+}
@@ -0,0 +1,387 @@
+use super::data_transformer::{DataTransformer, TransformedMonster, TransformedPart};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// 既定のダイスボット
+pub const DEFAULT_DICEBOT: &str = "SwordWorld2.5";
+
+/// 既定のプリセット名
+pub const DEFAULT_PRESET: &str = "standard";
+
+/// チャットパレットの定義（設定ファイルの [export.palette.presets.<名前>]）
+///
+/// 行の中の `{HP}` などは駒のデータ名を参照する（Udonarium / ココフォリアが展開する）。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PaletteDefinition {
+    /// ダイスボット名
+    #[serde(default = "default_dicebot")]
+    pub dicebot: String,
+    /// セクション（省略時は standard と同じ）
+    #[serde(default = "standard_sections")]
+    pub sections: Vec<PaletteSection>,
+    /// 部位特殊能力を1能力1行で加えるか
+    #[serde(default)]
+    pub abilities: bool,
+    /// 特殊能力の「○○魔法NLv／M」と「呪文」キーのスペル一覧から行使判定を加えるか
+    #[serde(default)]
+    pub magic: bool,
+    /// スペル名 → 系統（「呪文」のスペルの判定に使う魔力を選ぶ。設定ファイルではなくスペルデータから設定する）
+    #[serde(skip)]
+    pub spell_schools: BTreeMap<String, String>,
+}
+
+/// チャットパレットのセクション（`//-----{title}` の見出しと行）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PaletteSection {
+    pub title: String,
+    /// stat_line で1行ずつ展開する能力値
+    #[serde(default)]
+    pub stats: Vec<String>,
+    /// 能力値ごとの行（`$stat` を能力値名に置き換える）
+    #[serde(default)]
+    pub stat_line: Option<String>,
+    /// そのまま出力する行（能力値の行の後ろに続ける）
+    #[serde(default)]
+    pub lines: Vec<String>,
+}
+
+fn default_dicebot() -> String {
+    DEFAULT_DICEBOT.to_string()
+}
+
+impl PaletteSection {
+    fn new(title: &str, lines: &[&str]) -> Self {
+        Self {
+            title: title.to_string(),
+            stats: Vec::new(),
+            stat_line: None,
+            lines: lines.iter().map(|line| line.to_string()).collect(),
+        }
+    }
+
+    /// 能力値の行と固定の行を展開
+    fn expand(&self) -> Vec<String> {
+        let stat_lines = self
+            .stat_line
+            .iter()
+            .flat_map(|line| self.stats.iter().map(move |stat| line.replace("$stat", stat)));
+        stat_lines.chain(self.lines.iter().cloned()).collect()
+    }
+}
+
+/// 計算・固定値判定・ダイス判定の3セクション（従来のパレットと同じ内容）
+fn standard_sections() -> Vec<PaletteSection> {
+    vec![
+        PaletteSection::new(
+            "計算",
+            &[
+                "C({HP}+{防護点}+{ダメージ軽減}-()) 　【残HP（物理ダメージ）】",
+                "C({HP}+{ダメージ軽減}-())　【残HP（魔法ダメージ）】",
+                "C({MP}-())　【MP消費】",
+                "C{HP}　【現在HP】",
+                "C{MP}　【現在MP】",
+            ],
+        ),
+        PaletteSection::new(
+            "固定値判定",
+            &[
+                "C({命中力}+7) 命中判定（固定値）",
+                "C({回避力}+7) 回避判定（固定値）",
+                "C({生命抵抗力}+7) 生命抵抗判定（固定値）",
+                "C({精神抵抗力}+7) 精神抵抗判定（固定値）",
+            ],
+        ),
+        PaletteSection::new(
+            "ダイス判定",
+            &[
+                "2d+{命中力}　命中判定",
+                "2d+{打撃点}　ダメージロール",
+                "2d+{回避力}　回避判定",
+                "2d+{生命抵抗力}　生命抵抗判定",
+                "2d+{精神抵抗力}　精神抵抗判定",
+            ],
+        ),
+    ]
+}
+
+impl Default for PaletteDefinition {
+    fn default() -> Self {
+        Self::standard()
+    }
+}
+
+impl PaletteDefinition {
+    /// 組み込みプリセット standard（従来の3セクション + 部位特殊能力 + 魔法）
+    pub fn standard() -> Self {
+        Self {
+            dicebot: default_dicebot(),
+            sections: standard_sections(),
+            abilities: true,
+            magic: true,
+            spell_schools: BTreeMap::new(),
+        }
+    }
+
+    /// 組み込みプリセット classic（従来の3セクションのみ）
+    pub fn classic() -> Self {
+        Self { abilities: false, magic: false, ..Self::standard() }
+    }
+
+    /// 組み込みプリセットの一覧（名前順）
+    pub fn builtin_presets() -> BTreeMap<String, PaletteDefinition> {
+        BTreeMap::from([
+            ("classic".to_string(), Self::classic()),
+            (DEFAULT_PRESET.to_string(), Self::standard()),
+        ])
+    }
+
+    /// 「呪文」のスペルの系統を設定（スペル名 → 系統）
+    pub fn with_spell_schools(mut self, spell_schools: BTreeMap<String, String>) -> Self {
+        self.spell_schools = spell_schools;
+        self
+    }
+
+    /// 部位のチャットパレットを生成
+    ///
+    /// 各セクションは `//-----{見出し}` の行で始まり、セクションの間は空行で区切る。
+    /// 部位特殊能力・魔法のセクションは該当する行がある場合のみ加える。
+    pub fn render(&self, monster: &TransformedMonster, part: &TransformedPart) -> String {
+        let mut sections: Vec<(String, Vec<String>)> =
+            self.sections.iter().map(|section| (section.title.clone(), section.expand())).collect();
+
+        if self.abilities {
+            let lines = ability_lines(&part.special_abilities);
+            if !lines.is_empty() {
+                sections.push(("部位特殊能力".to_string(), lines));
+            }
+        }
+        if self.magic {
+            let lines = magic_lines(monster, part, &self.spell_schools);
+            if !lines.is_empty() {
+                sections.push(("魔法".to_string(), lines));
+            }
+        }
+
+        sections
+            .iter()
+            .map(|(title, lines)| {
+                let mut text = format!("//-----{}\n", title);
+                for line in lines {
+                    text.push_str(line);
+                    text.push('\n');
+                }
+                text
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// 部位のチャットパレットを、データ名の参照（`{HP}` など）を部位の値に置き換えて生成
+    ///
+    /// 他の駒のデータを参照できない共有パレット用。値は駒に書き込むデータと同じもの。
+    pub fn render_with_values(&self, monster: &TransformedMonster, part: &TransformedPart) -> String {
+        let values = [
+            ("HP", part.hp),
+            ("MP", part.mp),
+            ("防護点", part.armor),
+            ("命中力", DataTransformer::adjust_value(part.hit_rate)),
+            ("打撃点", part.damage),
+            ("回避力", DataTransformer::adjust_value(part.dodge)),
+            ("生命抵抗力", DataTransformer::adjust_value(part.life_resistance)),
+            ("精神抵抗力", DataTransformer::adjust_value(part.mental_resistance)),
+            ("先制値", monster.initiative),
+        ];
+        values.iter().fold(self.render(monster, part), |text, (name, value)| {
+            text.replace(&format!("{{{}}}", name), &value.to_string())
+        })
+    }
+}
+
+/// 特殊能力のテキストを1能力ずつに分ける（「、」または「,」区切り）
+fn split_abilities(text: &str) -> impl Iterator<Item = &str> {
+    text.split(['、', ',']).map(str::trim).filter(|item| !item.is_empty())
+}
+
+/// 部位特殊能力の行
+///
+/// "火のブレス／20" → "C(20)　【火のブレス】達成値"、値の無い能力は "【再生＝5】"
+fn ability_lines(abilities: &str) -> Vec<String> {
+    split_abilities(abilities)
+        .map(|item| match item.rsplit_once('／') {
+            Some((name, value)) if !value.is_empty() && value.chars().all(|c| c.is_ascii_digit()) => {
+                format!("C({})　【{}】達成値", value, name)
+            }
+            _ => format!("【{}】", item),
+        })
+        .collect()
+}
+
+/// 魔法の能力（"真語・操霊魔法10Lv／16" → ("真語・操霊魔法", 10, 16)）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MagicAbility {
+    pub school: String,
+    pub level: i32,
+    pub power: i32,
+}
+
+impl MagicAbility {
+    /// スペルの系統（"真語" や "真語魔法"）がこの魔法に含まれるか
+    ///
+    /// "真語・操霊魔法" は真語と操霊の両方を含む。
+    pub fn covers(&self, school: &str) -> bool {
+        let school = school.strip_suffix("魔法").unwrap_or(school);
+        let schools = self.school.strip_suffix("魔法").unwrap_or(&self.school);
+        schools.split('・').any(|s| s == school)
+    }
+}
+
+/// 特殊能力のテキストから「○○魔法NLv／M」の形の能力を取り出す
+pub fn parse_magic(abilities: &str) -> Vec<MagicAbility> {
+    split_abilities(abilities)
+        .filter_map(|item| {
+            let (head, power) = item.split_once("Lv／")?;
+            let power = power.parse().ok()?;
+            let digits = head.len() - head.trim_end_matches(|c: char| c.is_ascii_digit()).len();
+            let (school, level) = head.split_at(head.len() - digits);
+            if !school.ends_with("魔法") {
+                return None;
+            }
+            Some(MagicAbility { school: school.to_string(), level: level.parse().ok()?, power })
+        })
+        .collect()
+}
+
+/// 魔法の行（行使判定と、モンスターの「呪文」キーに挙げられたスペル）
+///
+/// スペルの判定にはスペルの系統を含む魔法の魔力を使う。
+/// 系統が分からない、または該当する魔法が無いスペルは "【スペル名】" とする。
+fn magic_lines(monster: &TransformedMonster, part: &TransformedPart, spell_schools: &BTreeMap<String, String>) -> Vec<String> {
+    let mut magic = parse_magic(&monster.common_abilities);
+    magic.extend(parse_magic(&part.special_abilities));
+
+    let mut lines: Vec<String> = magic
+        .iter()
+        .map(|m| format!("2d+{}　{}行使（{}Lv）", m.power, m.school, m.level))
+        .collect();
+    for spell in &monster.spells {
+        let ability = spell_schools
+            .get(spell)
+            .and_then(|school| magic.iter().find(|m| m.covers(school)));
+        match ability {
+            Some(m) => lines.push(format!("2d+{}　{}", m.power, spell)),
+            None => lines.push(format!("【{}】", spell)),
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Monster;
+
+    fn transformed(common: &str, special: &str, spells: Option<&[&str]>) -> TransformedMonster {
+        let mut value = serde_json::json!({
+            "Category": "魔神", "Lv": 9, "Revision": 2.5, "data": "", "illust": "",
+            "movein": -1, "movein_des": "", "moveon": 12, "moveon_des": "", "name": "デーモン",
+            "part": [{"HP": 60, "MP": 35, "name": "", "コア": true, "命中力": 18, "回避力": 16, "打撃点": 9, "部位数": 1, "部位特殊能力": special, "防護点": 7}],
+            "備考": "", "先制値": 15, "共通特殊能力": common, "弱点": "", "弱点値": 17,
+            "生命抵抗力": 16, "知名度": 13, "精神抵抗力": 15
+        });
+        if let Some(spells) = spells {
+            value["呪文"] = serde_json::json!(spells);
+        }
+        let monster: Monster = serde_json::from_value(value).expect("Failed to build test monster");
+        DataTransformer::transform(&monster, vec!["デーモン".to_string()])
+    }
+
+    #[test]
+    fn test_classic_matches_previous_palette() {
+        let monster = transformed("飛行", "悪魔の力／23", None);
+        let palette = PaletteDefinition::classic().render(&monster, &monster.parts[0]);
+
+        assert!(palette.starts_with("//-----計算\nC({HP}+{防護点}+{ダメージ軽減}-()) 　【残HP（物理ダメージ）】\n"));
+        assert!(palette.contains("C{MP}　【現在MP】\n\n//-----固定値判定\n"));
+        assert!(palette.ends_with("2d+{精神抵抗力}　精神抵抗判定\n"));
+        assert!(!palette.contains("悪魔の力"));
+    }
+
+    #[test]
+    fn test_standard_adds_ability_and_magic_lines() {
+        let monster = transformed("飛行、神聖魔法4Lv／7、真語・操霊魔法10Lv／16", "悪魔の力／23,再生＝5", Some(&["ブラスト"]));
+        let schools = BTreeMap::from([("ブラスト".to_string(), "真語".to_string())]);
+        let palette = PaletteDefinition::standard().with_spell_schools(schools).render(&monster, &monster.parts[0]);
+
+        assert!(palette.contains("\n//-----部位特殊能力\nC(23)　【悪魔の力】達成値\n【再生＝5】\n"));
+        assert!(palette.ends_with(
+            "//-----魔法\n2d+7　神聖魔法行使（4Lv）\n2d+16　真語・操霊魔法行使（10Lv）\n2d+16　ブラスト\n"
+        ));
+    }
+
+    #[test]
+    fn test_spell_without_known_school_has_no_roll() {
+        let monster = transformed("神聖魔法4Lv／7", "", Some(&["ブラスト", "キュア・ウーンズ"]));
+        let schools = BTreeMap::from([
+            ("ブラスト".to_string(), "真語".to_string()),
+            ("キュア・ウーンズ".to_string(), "神聖".to_string()),
+        ]);
+
+        assert!(PaletteDefinition::standard()
+            .with_spell_schools(schools)
+            .render(&monster, &monster.parts[0])
+            .ends_with("//-----魔法\n2d+7　神聖魔法行使（4Lv）\n【ブラスト】\n2d+7　キュア・ウーンズ\n"));
+        assert!(PaletteDefinition::standard()
+            .render(&monster, &monster.parts[0])
+            .ends_with("\n【ブラスト】\n【キュア・ウーンズ】\n"));
+    }
+
+    #[test]
+    fn test_standard_omits_empty_sections() {
+        let monster = transformed("", "", None);
+
+        assert_eq!(
+            PaletteDefinition::standard().render(&monster, &monster.parts[0]),
+            PaletteDefinition::classic().render(&monster, &monster.parts[0])
+        );
+    }
+
+    #[test]
+    fn test_custom_definition_from_toml() {
+        let definition: PaletteDefinition = toml::from_str(
+            r#"
+dicebot = "SwordWorld2.0"
+
+[[sections]]
+title = "判定"
+stats = ["命中力", "回避力"]
+stat_line = "2d+{$stat}　$stat判定"
+lines = ["2d+{打撃点}　ダメージ"]
+"#,
+        )
+        .unwrap();
+        let monster = transformed("", "", None);
+
+        assert_eq!(definition.dicebot, "SwordWorld2.0");
+        assert_eq!(
+            definition.render(&monster, &monster.parts[0]),
+            "//-----判定\n2d+{命中力}　命中力判定\n2d+{回避力}　回避力判定\n2d+{打撃点}　ダメージ\n"
+        );
+
+        let defaults: PaletteDefinition = toml::from_str("abilities = true").unwrap();
+        assert_eq!(defaults.sections, PaletteDefinition::standard().sections);
+        assert_eq!(defaults.dicebot, DEFAULT_DICEBOT);
+    }
+
+    #[test]
+    fn test_parse_magic() {
+        assert_eq!(
+            parse_magic("飛行、真語魔法7Lv／11、禁呪12Lv／18、魔法適性"),
+            vec![MagicAbility { school: "真語魔法".to_string(), level: 7, power: 11 }]
+        );
+
+        let ability = MagicAbility { school: "真語・操霊魔法".to_string(), level: 10, power: 16 };
+        assert!(ability.covers("真語"));
+        assert!(ability.covers("操霊魔法"));
+        assert!(!ability.covers("神聖"));
+    }
+}
@@ -1,4 +1,5 @@
 use crate::Monster;
+use serde::{Deserialize, Serialize};
 
 /// Udonarium用に変換されたモンスター表現
 #[derive(Debug, Clone)]
@@ -15,12 +16,16 @@ pub struct TransformedMonster {
     pub initiative: i32,
     /// 共通特殊能力
     pub common_abilities: String,
+    /// 使用するスペル（「呪文」キーの一覧。チャットパレット用）
+    pub spells: Vec<String>,
+    /// 駒の画像の識別子（画像を埋め込まない場合は空）
+    pub image_identifier: String,
     /// 各部位の変換データ
     pub parts: Vec<TransformedPart>,
 }
 
 /// Udonarium用に変換された部位表現
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TransformedPart {
     /// 部位名（XML用表示名）
     pub display_name: String,
@@ -70,16 +75,8 @@ impl DataTransformer {
                 hit_rate: part.hit_rate.unwrap_or(0),
                 dodge: part.dodge.unwrap_or(0),
                 damage: part.damage.unwrap_or(0),
-                life_resistance: if is_core {
-                    monster.life_resistance
-                } else {
-                    monster.life_resistance
-                },
-                mental_resistance: if is_core {
-                    monster.mental_resistance
-                } else {
-                    monster.mental_resistance
-                },
+                life_resistance: monster.life_resistance,
+                mental_resistance: monster.mental_resistance,
                 special_abilities: part.special_abilities.clone(),
                 is_core,
                 weakness: if is_core {
@@ -104,10 +101,30 @@ impl DataTransformer {
             fame: monster.fame,
             initiative: monster.initiative,
             common_abilities: monster.common_abilities.clone(),
+            spells: Self::spell_list(monster),
+            image_identifier: String::new(),
             parts,
         }
     }
 
+    /// 「呪文」キーのスペル一覧を取得（文字列の配列、または「、」区切りの文字列）
+    fn spell_list(monster: &Monster) -> Vec<String> {
+        match monster.extra.get("呪文") {
+            Some(serde_json::Value::Array(items)) => items
+                .iter()
+                .filter_map(|item| item.as_str())
+                .map(|item| item.trim().to_string())
+                .filter(|item| !item.is_empty())
+                .collect(),
+            Some(serde_json::Value::String(text)) => text
+                .split('、')
+                .map(|item| item.trim().to_string())
+                .filter(|item| !item.is_empty())
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
     /// Weakness テキストを変換（Udonarium用）
     /// "炎属性ダメージ+3" → "炎ダメ+3"
     /// XML生成時に使用
@@ -133,6 +150,33 @@ impl DataTransformer {
         (value - 7).max(0) // 負の値は0に
     }
 
+    /// adjust_value の逆変換（7を足す）
+    ///
+    /// adjust_value は負の値を0にするため、0 からは元の値が 7 以下だったことしか分からない（7 を返す）。
+    /// インポート時に使用
+    pub fn restore_value(value: i32) -> i32 {
+        value + 7
+    }
+
+    /// transform_weakness の逆変換
+    /// "炎ダメ+3" → "炎属性ダメージ+3"、"純Eダメ+3" → "純エネルギー属性ダメージ+3"
+    ///
+    /// 削除された「属性」は既知の属性名の後ろにのみ補う。
+    /// インポート時に使用
+    pub fn restore_weakness(weakness: &str) -> String {
+        const ATTRIBUTES: [&str; 11] =
+            ["土", "水・氷", "炎", "風", "雷", "純エネルギー", "断空", "衝撃", "毒", "病気", "呪い"];
+
+        let mut result = weakness.replace("純E", "純エネルギー");
+        if !result.contains("ダメージ") {
+            result = result.replace("ダメ", "ダメージ");
+        }
+        for attribute in ATTRIBUTES {
+            result = result.replace(&format!("{}ダメージ", attribute), &format!("{}属性ダメージ", attribute));
+        }
+        result
+    }
+
     /// Movement情報をフォーマット
     /// moveon/moveinが-1の場合は"-"、それ以外は値を返す
     pub fn format_movement(value: i32, description: &str) -> String {
@@ -268,6 +312,28 @@ mod tests {
         assert_eq!(DataTransformer::adjust_value(30), 23); // 30 - 7 = 23
     }
 
+    #[test]
+    fn test_restore_value() {
+        assert_eq!(DataTransformer::restore_value(DataTransformer::adjust_value(15)), 15);
+        assert_eq!(DataTransformer::restore_value(0), 7);
+    }
+
+    #[test]
+    fn test_restore_weakness() {
+        for original in [
+            "炎属性ダメージ+3",
+            "純エネルギー属性ダメージ+3",
+            "水・氷属性ダメージ+3",
+            "魔法ダメージ+2",
+            "物理ダメージ+2",
+            "回復効果ダメージ+3",
+            "命中力+1",
+        ] {
+            let exported = DataTransformer::transform_weakness(original);
+            assert_eq!(DataTransformer::restore_weakness(&exported), original);
+        }
+    }
+
     #[test]
     fn test_format_movement_normal() {
         let result = DataTransformer::format_movement(22, "飛行");
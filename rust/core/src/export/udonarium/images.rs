@@ -0,0 +1,235 @@
+//! Udonarium の駒に埋め込む画像
+//!
+//! Udonarium は画像ファイルの内容の SHA-256（16進数）を識別子として扱い、
+//! ZIP 内の `{識別子}.{拡張子}` のファイルを駒の `imageIdentifier` から参照します。
+//! モンスターの `illust`（書籍の掲載コードなど）または名前から画像ファイルを探し、
+//! 見つからない場合は設定に応じて名前入りのプレースホルダー画像（SVG）を生成します。
+
+use crate::Monster;
+use super::xml_writer::XmlElement;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// 画像ディレクトリで探す拡張子（この順に探す）
+const IMAGE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "gif", "webp", "svg"];
+
+/// 画像の探し方（設定ファイルの [export.udonarium]）
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IllustrationSource {
+    /// 画像ディレクトリ（`{illust}.png` または `{モンスター名}.png` などを探す）
+    pub image_dir: Option<PathBuf>,
+    /// illust コード → 画像ファイル（画像ディレクトリより優先）
+    pub illust: BTreeMap<String, PathBuf>,
+    /// 画像が見つからないモンスターにプレースホルダー画像を生成するか
+    pub placeholder: bool,
+}
+
+/// ZIP に格納する画像
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UdonariumImage {
+    /// 画像の識別子（内容の SHA-256）
+    pub identifier: String,
+    /// ZIP 内のファイル名（`{識別子}.{拡張子}`）
+    pub filename: String,
+    pub data: Vec<u8>,
+}
+
+impl UdonariumImage {
+    /// 画像の内容と拡張子から作成
+    pub fn new(data: Vec<u8>, extension: &str) -> Self {
+        let identifier: String = Sha256::digest(&data).iter().map(|b| format!("{:02x}", b)).collect();
+        let filename = format!("{}.{}", identifier, extension.to_lowercase());
+        Self { identifier, filename, data }
+    }
+
+    /// 名前入りのプレースホルダー画像（SVG）
+    pub fn placeholder(name: &str) -> Self {
+        let initial: String = name.chars().next().map(String::from).unwrap_or_default();
+        let label = |y: u32, size: u32, fill: &str, text: &str| {
+            XmlElement::new("text")
+                .attr("x", 100)
+                .attr("y", y)
+                .attr("font-size", size)
+                .attr("text-anchor", "middle")
+                .attr("fill", fill)
+                .text(text)
+        };
+        let svg = XmlElement::new("svg")
+            .attr("xmlns", "http://www.w3.org/2000/svg")
+            .attr("width", 200)
+            .attr("height", 200)
+            .attr("viewBox", "0 0 200 200")
+            .child(
+                XmlElement::new("rect")
+                    .attr("x", 4)
+                    .attr("y", 4)
+                    .attr("width", 192)
+                    .attr("height", 192)
+                    .attr("rx", 24)
+                    .attr("fill", "#3c3c46")
+                    .attr("stroke", "#a0a0b4")
+                    .attr("stroke-width", 8),
+            )
+            .child(label(128, 96, "#ffffff", &initial))
+            .child(label(180, 20, "#d0d0dc", name));
+        Self::new(svg.to_document().into_bytes(), "svg")
+    }
+}
+
+/// ZIP に埋め込む画像の一覧
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EmbeddedImages {
+    /// モンスターごとの画像の識別子（入力の順。画像なしは空文字列）
+    pub identifiers: Vec<String>,
+    /// ZIP に格納する画像
+    pub files: Vec<UdonariumImage>,
+}
+
+impl IllustrationSource {
+    /// モンスターごとの画像をまとめて取得
+    ///
+    /// # 戻り値
+    /// モンスターごとの識別子（画像なしは空文字列）と、ZIP に格納する画像（同じ内容は1つにまとめる）
+    ///
+    /// # エラー
+    /// 対応表に指定したファイルを読み込めない場合
+    pub fn embed(&self, monsters: &[Monster]) -> Result<EmbeddedImages, String> {
+        let mut embedded = EmbeddedImages::default();
+        for monster in monsters {
+            match self.resolve(monster)? {
+                Some(image) => {
+                    embedded.identifiers.push(image.identifier.clone());
+                    if !embedded.files.iter().any(|file| file.identifier == image.identifier) {
+                        embedded.files.push(image);
+                    }
+                }
+                None => embedded.identifiers.push(String::new()),
+            }
+        }
+        Ok(embedded)
+    }
+
+    /// モンスターの画像を取得
+    ///
+    /// 探す順番: illust コードの対応表 → 画像ディレクトリの `{illust}.*` → `{モンスター名}.*`。
+    /// 見つからない場合は、placeholder が有効ならプレースホルダー画像を返す。
+    ///
+    /// # エラー
+    /// 対応表に指定したファイルを読み込めない場合
+    pub fn resolve(&self, monster: &Monster) -> Result<Option<UdonariumImage>, String> {
+        if let Some(path) = self.illust.get(&monster.illust) {
+            return Self::load(path).map(Some);
+        }
+
+        if let Some(path) = self.find_in_dir(monster) {
+            return Self::load(&path).map(Some);
+        }
+
+        Ok(self.placeholder.then(|| UdonariumImage::placeholder(&monster.name)))
+    }
+
+    /// 画像ディレクトリから探す（illust に拡張子が含まれる場合はそのファイル名）
+    fn find_in_dir(&self, monster: &Monster) -> Option<PathBuf> {
+        let dir = self.image_dir.as_ref()?;
+        let stems = [monster.illust.as_str(), monster.name.as_str()];
+        stems
+            .iter()
+            .filter(|stem| !stem.is_empty())
+            .flat_map(|stem| {
+                let exact = Path::new(stem).extension().is_some().then(|| dir.join(stem));
+                exact.into_iter().chain(IMAGE_EXTENSIONS.iter().map(move |ext| dir.join(format!("{}.{}", stem, ext))))
+            })
+            .find(|path| path.is_file())
+    }
+
+    fn load(path: &Path) -> Result<UdonariumImage, String> {
+        let data = fs::read(path).map_err(|e| format!("Cannot read image {}: {}", path.display(), e))?;
+        let extension = path.extension().map(|ext| ext.to_string_lossy().to_string()).unwrap_or_default();
+        Ok(UdonariumImage::new(data, &extension))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn monster(name: &str, illust: &str) -> Monster {
+        serde_json::from_value(serde_json::json!({
+            "Category": "蛮族", "Lv": 1, "Revision": 2.5, "data": "", "illust": illust,
+            "movein": -1, "movein_des": "", "moveon": 10, "moveon_des": "", "name": name,
+            "part": [{"HP": 16, "MP": 10, "name": "", "コア": true, "命中力": 10, "回避力": 10, "打撃点": 2, "部位数": 1, "部位特殊能力": "", "防護点": 2}],
+            "備考": "", "先制値": 10, "共通特殊能力": "", "弱点": "", "弱点値": 10,
+            "生命抵抗力": 10, "知名度": 5, "精神抵抗力": 9
+        }))
+        .expect("Failed to build test monster")
+    }
+
+    #[test]
+    fn test_identifier_is_sha256_of_content() {
+        let image = UdonariumImage::new(b"abc".to_vec(), "PNG");
+
+        assert_eq!(image.identifier, "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+        assert_eq!(image.filename, format!("{}.png", image.identifier));
+    }
+
+    #[test]
+    fn test_resolve_order() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("BT114.png"), b"by code").unwrap();
+        fs::write(dir.path().join("オーガ.jpg"), b"by name").unwrap();
+        let mapped = dir.path().join("mapped.gif");
+        fs::write(&mapped, b"mapped").unwrap();
+
+        let source = IllustrationSource {
+            image_dir: Some(dir.path().to_path_buf()),
+            illust: BTreeMap::from([("BT200".to_string(), mapped)]),
+            placeholder: false,
+        };
+
+        let by_code = source.resolve(&monster("ゴブリン", "BT114")).unwrap().unwrap();
+        assert_eq!(by_code.data, b"by code");
+        assert!(by_code.filename.ends_with(".png"));
+
+        let by_name = source.resolve(&monster("オーガ", "")).unwrap().unwrap();
+        assert_eq!(by_name.data, b"by name");
+
+        let mapped = source.resolve(&monster("オーガ", "BT200")).unwrap().unwrap();
+        assert_eq!(mapped.data, b"mapped");
+
+        assert_eq!(source.resolve(&monster("トロール", "")).unwrap(), None);
+    }
+
+    #[test]
+    fn test_embed_deduplicates_files() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("BT114.png"), b"goblin").unwrap();
+        let source = IllustrationSource { image_dir: Some(dir.path().to_path_buf()), ..Default::default() };
+
+        let embedded = source
+            .embed(&[monster("ゴブリン", "BT114"), monster("トロール", ""), monster("ゴブリン2", "BT114")])
+            .unwrap();
+
+        assert_eq!(embedded.files.len(), 1);
+        assert_eq!(embedded.identifiers, vec![embedded.files[0].identifier.clone(), String::new(), embedded.files[0].identifier.clone()]);
+    }
+
+    #[test]
+    fn test_placeholder_and_missing_mapping() {
+        let source = IllustrationSource { placeholder: true, ..Default::default() };
+        let image = source.resolve(&monster("<ゴブリン>", "")).unwrap().unwrap();
+        let svg = String::from_utf8(image.data).unwrap();
+
+        assert!(image.filename.ends_with(".svg"));
+        assert!(svg.contains(">&lt;</text>"));
+        assert!(svg.contains(">&lt;ゴブリン&gt;</text>"));
+
+        let source = IllustrationSource {
+            illust: BTreeMap::from([("BT1".to_string(), PathBuf::from("/nonexistent/x.png"))]),
+            ..Default::default()
+        };
+        let err = source.resolve(&monster("ゴブリン", "BT1")).unwrap_err();
+        assert!(err.contains("Cannot read image /nonexistent/x.png"));
+    }
+}
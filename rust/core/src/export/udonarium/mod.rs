@@ -1,19 +1,46 @@
+pub mod chat_palette;
 pub mod data_transformer;
+pub mod images;
 pub mod part_namer;
+pub mod room;
+pub mod spell_palette;
 pub mod xml_generator;
+pub mod xml_writer;
 pub mod zip_writer;
 
-use crate::Monster;
-use super::{DataExporter, ExportConfig, ExportError};
+use crate::{Monster, Spell};
+use super::{DataExporter, ExportConfig, ExportError, SpellExporter};
+use chat_palette::PaletteDefinition;
 use data_transformer::DataTransformer;
+use images::IllustrationSource;
 use part_namer::PartNamer;
+use room::{RoomGenerator, RoomLayout};
+use spell_palette::SpellPaletteGenerator;
 use xml_generator::XmlGenerator;
 use zip_writer::ZipFileWriter;
 use std::fs;
 use std::path::Path;
 
 /// Udonarium エクスポーター
-pub struct UdonariumExporter;
+#[derive(Default)]
+pub struct UdonariumExporter {
+    palette: PaletteDefinition,
+    images: IllustrationSource,
+}
+
+impl UdonariumExporter {
+    /// チャットパレットの定義を設定
+    pub fn with_palette(mut self, palette: PaletteDefinition) -> Self {
+        self.palette = palette;
+        self
+    }
+
+    /// 駒に埋め込む画像の探し方を設定
+    pub fn with_images(mut self, images: IllustrationSource) -> Self {
+        self.images = images;
+        self
+    }
+}
 
 impl DataExporter for UdonariumExporter {
     fn export(&self, data: &[Monster], config: &ExportConfig) -> Result<(), ExportError> {
@@ -31,10 +58,16 @@ impl DataExporter for UdonariumExporter {
             fs::create_dir_all(parent)?;
         }
 
+        // 駒に埋め込む画像
+        let images = self
+            .images
+            .embed(data)
+            .map_err(|e| ExportError::GenerationError(format!("Image embedding failed: {}", e)))?;
+
         // 全モンスターのXMLを収集
         let mut all_xml_files: Vec<(String, String)> = Vec::new();
 
-        for monster in data {
+        for (monster, image_identifier) in data.iter().zip(&images.identifiers) {
             // Part名を生成
             let mut namer = PartNamer::new(&monster.part);
             let part_names = namer.generate_names(&monster.part, &monster.name);
@@ -46,11 +79,12 @@ impl DataExporter for UdonariumExporter {
                 .collect();
 
             // データ変換
-            let transformed = DataTransformer::transform(monster, display_names);
+            let mut transformed = DataTransformer::transform(monster, display_names);
+            transformed.image_identifier = image_identifier.clone();
 
             // 各Part用XMLを生成
             for (i, part) in transformed.parts.iter().enumerate() {
-                match XmlGenerator::generate_xml(&transformed, i) {
+                match XmlGenerator::generate_xml_with_palette(&transformed, i, &self.palette) {
                     Ok(xml) => {
                         // XMLファイル名をPartNameから取得
                         let xml_filename = format!("{}.xml", part_names[i].filename);
@@ -67,13 +101,14 @@ impl DataExporter for UdonariumExporter {
             }
         }
 
-        // 全XMLファイルを1つのZIPに格納
-        let zip_xml_files: Vec<(&str, &str)> = all_xml_files
+        // 全XMLファイルと画像を1つのZIPに格納
+        let zip_files: Vec<(&str, &[u8])> = all_xml_files
             .iter()
-            .map(|(name, content)| (name.as_str(), content.as_str()))
+            .map(|(name, content)| (name.as_str(), content.as_bytes()))
+            .chain(images.files.iter().map(|image| (image.filename.as_str(), image.data.as_slice())))
             .collect();
 
-        ZipFileWriter::create_zip(zip_path, zip_xml_files).map_err(|e| {
+        ZipFileWriter::create_archive(zip_path, zip_files).map_err(|e| {
             ExportError::GoogleSheetsError(format!(
                 "Failed to create ZIP file: {}",
                 e
@@ -93,13 +128,229 @@ impl DataExporter for UdonariumExporter {
     }
 }
 
+impl SpellExporter for UdonariumExporter {
+    /// 魔法カテゴリごとに、チャットパレットを持つキャラクターを1つずつZIPに格納する
+    fn export_spells(&self, data: &[Spell], config: &ExportConfig) -> Result<(), ExportError> {
+        if data.is_empty() {
+            return Err(ExportError::GenerationError(
+                "Cannot export empty spell list".to_string(),
+            ));
+        }
+
+        let (palettes, warnings) = SpellPaletteGenerator::group(data);
+        for warning in &warnings {
+            eprintln!("Skipped spell {}", warning);
+        }
+        if palettes.is_empty() {
+            return Err(ExportError::GenerationError(
+                "No spell could be converted to a chat palette".to_string(),
+            ));
+        }
+
+        let zip_path = Path::new(&config.destination);
+        if let Some(parent) = zip_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let xml_files: Vec<(String, String)> = palettes
+            .iter()
+            .map(|palette| {
+                eprintln!("Generated XML for: {} ({} spells)", palette.category, palette.entries.len());
+                (format!("{}.xml", palette.category), SpellPaletteGenerator::generate_xml(palette, &self.palette.dicebot))
+            })
+            .collect();
+
+        let zip_xml_files: Vec<(&str, &str)> = xml_files
+            .iter()
+            .map(|(name, content)| (name.as_str(), content.as_str()))
+            .collect();
+
+        ZipFileWriter::create_zip(zip_path, zip_xml_files).map_err(|e| {
+            ExportError::GenerationError(format!(
+                "Failed to create ZIP file: {}",
+                e
+            ))
+        })?;
+
+        eprintln!(
+            "Successfully exported {} spells to {}",
+            data.len() - warnings.len(),
+            zip_path.display()
+        );
+        Ok(())
+    }
+}
+
+/// Udonarium ルームデータエクスポーター
+///
+/// 部位ごとの駒をまとめて1つのルームデータ（data.xml など）にし、ZIPで出力する。
+pub struct UdonariumRoomExporter {
+    layout: RoomLayout,
+    palette: PaletteDefinition,
+    images: IllustrationSource,
+}
+
+impl UdonariumRoomExporter {
+    /// 配置設定とチャットパレットの定義を指定してエクスポーターを作成
+    pub fn new(layout: RoomLayout, palette: PaletteDefinition) -> Self {
+        Self { layout, palette, images: IllustrationSource::default() }
+    }
+
+    /// 駒に埋め込む画像の探し方を設定
+    pub fn with_images(mut self, images: IllustrationSource) -> Self {
+        self.images = images;
+        self
+    }
+}
+
+impl DataExporter for UdonariumRoomExporter {
+    fn export(&self, data: &[Monster], config: &ExportConfig) -> Result<(), ExportError> {
+        if data.is_empty() {
+            return Err(ExportError::GenerationError(
+                "Cannot export empty monster list".to_string(),
+            ));
+        }
+
+        let zip_path = Path::new(&config.destination);
+        if let Some(parent) = zip_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let images = self
+            .images
+            .embed(data)
+            .map_err(|e| ExportError::GenerationError(format!("Image embedding failed: {}", e)))?;
+        let files = RoomGenerator::generate(data, &self.layout, &self.palette, &images.identifiers).map_err(|e| {
+            ExportError::GenerationError(format!("XML generation failed: {}", e))
+        })?;
+        let zip_files: Vec<(&str, &[u8])> = files
+            .iter()
+            .map(|(name, content)| (name.as_str(), content.as_bytes()))
+            .chain(images.files.iter().map(|image| (image.filename.as_str(), image.data.as_slice())))
+            .collect();
+
+        ZipFileWriter::create_archive(zip_path, zip_files).map_err(|e| {
+            ExportError::GenerationError(format!(
+                "Failed to create ZIP file: {}",
+                e
+            ))
+        })?;
+
+        eprintln!(
+            "Successfully exported a room with {} monsters to {}",
+            data.len(),
+            zip_path.display()
+        );
+        Ok(())
+    }
+
+    fn name(&self) -> &str {
+        "Udonarium Room Exporter"
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn test_udonarium_exporter_name() {
-        let exporter = UdonariumExporter;
+        let exporter = UdonariumExporter::default();
         assert_eq!(exporter.name(), "Udonarium Exporter");
     }
+
+    #[test]
+    fn test_export_embeds_images() {
+        let monster: Monster = serde_json::from_value(serde_json::json!({
+            "Category": "蛮族", "Lv": 1, "Revision": 2.5, "data": "", "illust": "BT114",
+            "movein": -1, "movein_des": "", "moveon": 10, "moveon_des": "", "name": "ゴブリン",
+            "part": [{"HP": 16, "MP": 10, "name": "", "コア": true, "命中力": 10, "回避力": 10, "打撃点": 2, "部位数": 1, "部位特殊能力": "", "防護点": 2}],
+            "備考": "", "先制値": 10, "共通特殊能力": "", "弱点": "", "弱点値": 10,
+            "生命抵抗力": 10, "知名度": 5, "精神抵抗力": 9
+        }))
+        .unwrap();
+        let mut without_art = monster.clone();
+        without_art.name = "トロール".to_string();
+        without_art.illust = String::new();
+
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("BT114.png"), b"goblin art").unwrap();
+        let zip_path = dir.path().join("monsters.zip");
+        let config = ExportConfig {
+            destination: zip_path.to_string_lossy().to_string(),
+            format: crate::export::ExportFormat::Udonarium,
+        };
+        let images = IllustrationSource { image_dir: Some(dir.path().to_path_buf()), placeholder: true, ..Default::default() };
+
+        UdonariumExporter::default().with_images(images).export(&[monster.clone(), without_art], &config).unwrap();
+
+        let mut archive = zip::ZipArchive::new(fs::File::open(&zip_path).unwrap()).unwrap();
+        assert_eq!(archive.len(), 4);
+        let art = images::UdonariumImage::new(b"goblin art".to_vec(), "png");
+        assert!(archive.by_name(&art.filename).is_ok());
+        let placeholder = images::UdonariumImage::placeholder("トロール");
+        assert!(archive.by_name(&placeholder.filename).is_ok());
+
+        let mut xml = String::new();
+        std::io::Read::read_to_string(&mut archive.by_name("ゴブリン.xml").unwrap(), &mut xml).unwrap();
+        assert!(xml.contains(&format!("<data type=\"image\" name=\"imageIdentifier\">{}</data>", art.identifier)));
+
+        let missing = IllustrationSource {
+            illust: std::collections::BTreeMap::from([("BT114".to_string(), dir.path().join("missing.png"))]),
+            ..Default::default()
+        };
+        let err = UdonariumExporter::default().with_images(missing).export(&[monster], &config).unwrap_err();
+        assert!(matches!(err, ExportError::GenerationError(_)));
+    }
+
+    #[test]
+    fn test_room_export_empty() {
+        let config = ExportConfig {
+            destination: "room.zip".to_string(),
+            format: crate::export::ExportFormat::UdonariumRoom,
+        };
+        let err = UdonariumRoomExporter::new(RoomLayout::default(), PaletteDefinition::default())
+            .export(&[], &config)
+            .unwrap_err();
+        assert!(matches!(err, ExportError::GenerationError(_)));
+    }
+
+    #[test]
+    fn test_export_spells_zip() {
+        let spell: Spell = serde_json::from_value(serde_json::json!({
+            "name": "プロテクション",
+            "school": "神聖",
+            "MP": {"kind": "value", "value": 2},
+            "対象": {"kind": "個別", "個別": "1体"},
+            "射程": "接触",
+            "時間": {"value": 3, "unit": "分"},
+            "効果": "防護点を+1します。",
+            "補助": true
+        }))
+        .unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let zip_path = dir.path().join("spells.zip");
+        let config = ExportConfig {
+            destination: zip_path.to_string_lossy().to_string(),
+            format: crate::export::ExportFormat::Udonarium,
+        };
+
+        UdonariumExporter::default().export_spells(&[spell], &config).unwrap();
+
+        let mut archive = zip::ZipArchive::new(fs::File::open(&zip_path).unwrap()).unwrap();
+        assert_eq!(archive.len(), 1);
+        let mut xml = String::new();
+        std::io::Read::read_to_string(&mut archive.by_name("神聖魔法.xml").unwrap(), &mut xml).unwrap();
+        assert!(xml.contains("プロテクション / MP:2 / 対象:1体 / 射程:接触 / 時間:3分"));
+    }
+
+    #[test]
+    fn test_export_spells_empty() {
+        let config = ExportConfig {
+            destination: "spells.zip".to_string(),
+            format: crate::export::ExportFormat::Udonarium,
+        };
+        assert!(UdonariumExporter::default().export_spells(&[], &config).is_err());
+    }
 }
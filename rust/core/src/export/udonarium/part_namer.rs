@@ -37,11 +37,10 @@ impl PartNamer {
 
         for part in parts {
             let part_name_key = &part.name;
-            let index = current_indices
+            let index = *current_indices
                 .entry(part_name_key.clone())
                 .and_modify(|e| *e += 1)
-                .or_insert(0)
-                .clone();
+                .or_insert(0);
 
             let part_names = self.generate_name(monster_name, part, index);
             result.push(part_names);
@@ -87,6 +86,29 @@ impl PartNamer {
 
         PartName { filename, display_name }
     }
+
+    /// 表示名からモンスター名と部位名を取り出す（generate_names の逆変換）
+    ///
+    /// "トレント\n(根)" → ("トレント", "根")、非コア部位の "ゴブリン_1" → ("ゴブリン", "")、
+    /// それ以外は表示名全体をモンスター名とする。
+    pub fn parse_display_name(display_name: &str, is_core: bool) -> (String, String) {
+        if let Some((monster_name, rest)) = display_name.split_once("\n(")
+            && let Some(part_name) = rest.strip_suffix(')')
+        {
+            return (monster_name.to_string(), part_name.to_string());
+        }
+
+        if !is_core
+            && let Some((monster_name, index)) = display_name.rsplit_once('_')
+            && !monster_name.is_empty()
+            && !index.is_empty()
+            && index.chars().all(|c| c.is_ascii_digit())
+        {
+            return (monster_name.to_string(), String::new());
+        }
+
+        (display_name.to_string(), String::new())
+    }
 }
 
 #[cfg(test)]
@@ -198,4 +220,24 @@ mod tests {
         assert_eq!(names[1].filename, "アンシェント・ドラゴン_頭0_1");
         assert_eq!(names[2].filename, "アンシェント・ドラゴン_防護膜");
     }
+
+    #[test]
+    fn test_parse_display_name_reverses_generate_names() {
+        let parts = vec![
+            create_test_part("幹", Some(true)),
+            create_test_part("根", Some(false)),
+            create_test_part("根", Some(false)),
+        ];
+        let mut namer = PartNamer::new(&parts);
+        for (part, name) in parts.iter().zip(namer.generate_names(&parts, "トレント")) {
+            let (monster_name, part_name) =
+                PartNamer::parse_display_name(&name.display_name, part.core.unwrap_or(false));
+            assert_eq!(monster_name, "トレント");
+            assert_eq!(part_name, part.name);
+        }
+
+        assert_eq!(PartNamer::parse_display_name("ゴブリン_1", false), ("ゴブリン".to_string(), String::new()));
+        assert_eq!(PartNamer::parse_display_name("ゴブリン_1", true), ("ゴブリン_1".to_string(), String::new()));
+        assert_eq!(PartNamer::parse_display_name("ゴブリンA", false), ("ゴブリンA".to_string(), String::new()));
+    }
 }
@@ -0,0 +1,387 @@
+//! Udonarium のルームデータ（部屋のセーブデータ）
+//!
+//! Udonarium の「保存」で作られる ZIP と同じ構成（data.xml / chat.xml / summary.xml）を出力します。
+//! 全モンスターの駒をテーブルに格子状に並べ、共有インベントリに全部位の判定をまとめた
+//! チャットパレット用の駒、イニシアティブ表の設定、必要なら山札を加えます。
+
+use super::chat_palette::PaletteDefinition;
+use super::data_transformer::{DataTransformer, TransformedMonster};
+use super::part_namer::PartNamer;
+use super::xml_generator::{character, XmlGenerator};
+use super::xml_writer::XmlElement;
+use crate::Monster;
+use serde::{Deserialize, Serialize};
+
+/// テーブルの1マスの大きさ（px）
+const GRID_SIZE: i32 = 50;
+
+/// テーブルの最小の大きさ（マス）
+const MIN_TABLE_SIZE: usize = 20;
+
+/// Udonarium に同梱されているトランプの画像
+const TRUMP_IMAGE_DIR: &str = "./assets/images/trump";
+
+/// ルームに加える山札
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CardDeck {
+    /// トランプ（ジョーカー2枚を含む54枚、Udonarium 同梱の画像を使う）
+    Trump,
+}
+
+/// ルームデータの配置設定
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RoomLayout {
+    /// 1行に並べる駒の数（モンスターごとに行を改める）
+    pub columns: usize,
+    /// テーブルに置く山札
+    pub decks: Vec<CardDeck>,
+}
+
+impl Default for RoomLayout {
+    fn default() -> Self {
+        Self { columns: 8, decks: Vec::new() }
+    }
+}
+
+/// ルームデータ生成器
+pub struct RoomGenerator;
+
+impl RoomGenerator {
+    /// ルームデータの各ファイルを生成
+    ///
+    /// # 引数
+    /// * `monsters` - 駒にするモンスター
+    /// * `layout` - 駒の配置と山札
+    /// * `palette` - 各駒と共有パレットのチャットパレットの定義
+    /// * `image_identifiers` - モンスターごとの駒の画像の識別子（足りない分は画像なし）
+    ///
+    /// # 戻り値
+    /// (ファイル名, XML内容) の組（data.xml / chat.xml / summary.xml）
+    pub fn generate(
+        monsters: &[Monster],
+        layout: &RoomLayout,
+        palette: &PaletteDefinition,
+        image_identifiers: &[String],
+    ) -> Result<Vec<(String, String)>, String> {
+        let columns = layout.columns.max(1);
+        let transformed: Vec<TransformedMonster> = monsters
+            .iter()
+            .enumerate()
+            .map(|(i, monster)| {
+                let mut transformed = Self::transform(monster);
+                transformed.image_identifier = image_identifiers.get(i).cloned().unwrap_or_default();
+                transformed
+            })
+            .collect();
+
+        let mut characters = Vec::new();
+        let mut rows = 0;
+        for monster in &transformed {
+            for i in 0..monster.parts.len() {
+                let (col, row) = (i % columns, rows + i / columns);
+                let (x, y) = Self::cell_position(col, row);
+                let element = XmlGenerator::generate_element(monster, i, true, palette)?
+                    .set_attr("location.x", x)
+                    .set_attr("location.y", y);
+                characters.push(element);
+            }
+            rows += monster.parts.len().div_ceil(columns).max(1);
+        }
+
+        let width = (columns * 2 + 1 + if layout.decks.is_empty() { 0 } else { 4 }).max(MIN_TABLE_SIZE);
+        let height = (rows * 2 + 1).max(MIN_TABLE_SIZE);
+
+        let mut room = XmlElement::new("room").child(Self::game_table(width, height));
+        for element in characters {
+            room = room.child(element);
+        }
+        room = room.child(Self::shared_palette(&transformed, palette));
+        for (i, deck) in layout.decks.iter().enumerate() {
+            let (x, y) = Self::cell_position(columns, i * 3);
+            room = room.child(Self::deck_element(*deck, x, y));
+        }
+
+        Ok(vec![
+            ("data.xml".to_string(), room.to_document()),
+            ("chat.xml".to_string(), Self::chat_tabs().to_document()),
+            ("summary.xml".to_string(), Self::summary_setting().to_document()),
+        ])
+    }
+
+    /// 部位名を付けて Udonarium 用に変換
+    fn transform(monster: &Monster) -> TransformedMonster {
+        let mut namer = PartNamer::new(&monster.part);
+        let display_names = namer
+            .generate_names(&monster.part, &monster.name)
+            .into_iter()
+            .map(|pn| pn.display_name)
+            .collect();
+        DataTransformer::transform(monster, display_names)
+    }
+
+    /// 格子の位置（1マス空けて並べる）をテーブル上の座標に変換
+    fn cell_position(col: usize, row: usize) -> (i32, i32) {
+        ((col as i32 * 2 + 1) * GRID_SIZE, (row as i32 * 2 + 1) * GRID_SIZE)
+    }
+
+    /// テーブル（大きさはマス数）
+    fn game_table(width: usize, height: usize) -> XmlElement {
+        XmlElement::new("game-table")
+            .attr("name", "最初のテーブル")
+            .attr("width", width)
+            .attr("height", height)
+            .attr("gridSize", GRID_SIZE)
+            .attr("imageIdentifier", "")
+            .attr("backgroundImageIdentifier", "")
+            .attr("backgroundFilterType", "")
+            .attr("selected", "true")
+            .attr("gridType", 0)
+            .attr("gridColor", "#000000e6")
+    }
+
+    /// 共有インベントリに置く、全部位のチャットパレットをまとめた駒
+    ///
+    /// 他の駒のデータは参照できないため、判定値は数値で書き込む。
+    /// 部位ごとに `//=====部位名` の見出しを付け、どの部位の判定か分かるよう各行の末尾にも部位名を付ける。
+    fn shared_palette(monsters: &[TransformedMonster], palette: &PaletteDefinition) -> XmlElement {
+        let mut text = String::new();
+        for monster in monsters {
+            for part in &monster.parts {
+                let name = part.display_name.replace('\n', "");
+                text.push_str(&format!("//====={}\n", name));
+                for line in palette.render_with_values(monster, part).lines() {
+                    if line.is_empty() || line.starts_with("//") {
+                        text.push_str(line);
+                    } else {
+                        text.push_str(&format!("{}　{}", line, name));
+                    }
+                    text.push('\n');
+                }
+                text.push('\n');
+            }
+        }
+
+        let detail = XmlElement::new("data").attr("name", "detail");
+        character("共有パレット", "", detail, text.trim_end(), &palette.dicebot).set_attr("location.name", "common")
+    }
+
+    /// 山札（カードはすべて裏向きで重ねる）
+    fn deck_element(deck: CardDeck, x: i32, y: i32) -> XmlElement {
+        let (name, cards) = match deck {
+            CardDeck::Trump => ("トランプ山札", Self::trump_cards()),
+        };
+
+        let mut root = XmlElement::new("node").attr("name", "cardRoot");
+        for (card_name, front, back) in cards {
+            root = root.child(
+                XmlElement::new("card")
+                    .attr("location.name", "table")
+                    .attr("location.x", x)
+                    .attr("location.y", y)
+                    .attr("posZ", 0)
+                    .attr("state", 1)
+                    .attr("rotate", 0)
+                    .attr("owner", "")
+                    .attr("zindex", 0)
+                    .child(
+                        XmlElement::new("data")
+                            .attr("name", "card")
+                            .child(
+                                XmlElement::new("data")
+                                    .attr("name", "image")
+                                    .child(XmlElement::new("data").attr("type", "image").attr("name", "imageIdentifier"))
+                                    .child(XmlElement::new("data").attr("type", "image").attr("name", "front").text(front))
+                                    .child(XmlElement::new("data").attr("type", "image").attr("name", "back").text(back)),
+                            )
+                            .child(
+                                XmlElement::new("data")
+                                    .attr("name", "common")
+                                    .child(XmlElement::new("data").attr("name", "name").text(card_name))
+                                    .child(XmlElement::new("data").attr("name", "size").text(2)),
+                            )
+                            .child(XmlElement::new("data").attr("name", "detail")),
+                    ),
+            );
+        }
+
+        XmlElement::new("card-stack")
+            .attr("location.name", "table")
+            .attr("location.x", x)
+            .attr("location.y", y)
+            .attr("posZ", 0)
+            .attr("rotate", 0)
+            .attr("zindex", 0)
+            .attr("owner", "")
+            .attr("isShowTotal", "true")
+            .child(
+                XmlElement::new("data")
+                    .attr("name", "card-stack")
+                    .child(
+                        XmlElement::new("data")
+                            .attr("name", "image")
+                            .child(XmlElement::new("data").attr("type", "image").attr("name", "imageIdentifier")),
+                    )
+                    .child(
+                        XmlElement::new("data")
+                            .attr("name", "common")
+                            .child(XmlElement::new("data").attr("name", "name").text(name)),
+                    )
+                    .child(XmlElement::new("data").attr("name", "detail")),
+            )
+            .child(root)
+    }
+
+    /// トランプのカード (名前, 表の画像, 裏の画像)
+    fn trump_cards() -> Vec<(String, String, String)> {
+        let back = format!("{}/z02.gif", TRUMP_IMAGE_DIR);
+        let mut cards = Vec::new();
+        for suit in ["c", "d", "h", "s"] {
+            for number in 1..=13 {
+                let id = format!("{}{:02}", suit, number);
+                cards.push((id.clone(), format!("{}/{}.gif", TRUMP_IMAGE_DIR, id), back.clone()));
+            }
+        }
+        for joker in ["x01", "x02"] {
+            cards.push((joker.to_string(), format!("{}/{}.gif", TRUMP_IMAGE_DIR, joker), back.clone()));
+        }
+        cards
+    }
+
+    /// チャットタブ
+    fn chat_tabs() -> XmlElement {
+        XmlElement::new("chat-tab-list")
+            .child(XmlElement::new("chat-tab").attr("name", "メインタブ"))
+            .child(XmlElement::new("chat-tab").attr("name", "サブタブ"))
+    }
+
+    /// イニシアティブ表（先制値の高い順、HP / MP / 防護点を表示）
+    fn summary_setting() -> XmlElement {
+        XmlElement::new("summary-setting")
+            .attr("sortTag", "先制値")
+            .attr("sortOrder", "DESC")
+            .attr("dataTag", "先制値 HP MP 防護点")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn monster(name: &str, parts: usize) -> Monster {
+        let part = |i: usize| {
+            serde_json::json!({
+                "HP": 20, "MP": 10, "name": if parts > 1 { format!("部位{}", i) } else { String::new() },
+                "コア": i == 0, "命中力": 12, "回避力": 11, "打撃点": 4, "部位数": 1, "部位特殊能力": "", "防護点": 3
+            })
+        };
+        serde_json::from_value(serde_json::json!({
+            "Category": "蛮族", "Lv": 3, "Revision": 2.5, "data": "", "illust": "",
+            "movein": -1, "movein_des": "", "moveon": 10, "moveon_des": "", "name": name,
+            "part": (0..parts).map(part).collect::<Vec<_>>(),
+            "備考": "", "先制値": 9, "共通特殊能力": "", "弱点": "", "弱点値": 10,
+            "生命抵抗力": 10, "知名度": 5, "精神抵抗力": 9
+        }))
+        .expect("Failed to build test monster")
+    }
+
+    fn generate(monsters: &[Monster], layout: &RoomLayout) -> Vec<(String, String)> {
+        RoomGenerator::generate(monsters, layout, &PaletteDefinition::default(), &[]).unwrap()
+    }
+
+    fn file<'a>(files: &'a [(String, String)], name: &str) -> &'a str {
+        &files.iter().find(|(n, _)| n == name).unwrap().1
+    }
+
+    #[test]
+    fn test_room_files() {
+        let files = generate(&[monster("ゴブリン", 1)], &RoomLayout::default());
+        let names: Vec<&str> = files.iter().map(|(n, _)| n.as_str()).collect();
+
+        assert_eq!(names, vec!["data.xml", "chat.xml", "summary.xml"]);
+        assert!(file(&files, "summary.xml").contains("sortTag=\"先制値\" sortOrder=\"DESC\""));
+        assert!(file(&files, "chat.xml").contains("<chat-tab name=\"メインタブ\"></chat-tab>"));
+
+        let data = file(&files, "data.xml");
+        assert!(data.contains("<game-table name=\"最初のテーブル\" width=\"20\" height=\"20\" gridSize=\"50\""));
+        assert!(data.contains("<data name=\"先制値\" type=\"number\">9</data>"));
+    }
+
+    #[test]
+    fn test_grid_placement_wraps_and_starts_new_row_per_monster() {
+        let layout = RoomLayout { columns: 2, decks: Vec::new() };
+        let files = generate(&[monster("トレント", 3), monster("ゴブリン", 1)], &layout);
+        let data = file(&files, "data.xml");
+
+        // トレント: (1,1) (3,1) (1,3)、ゴブリンは次の行 (1,5)
+        for (x, y) in [(50, 50), (150, 50), (50, 150), (50, 250)] {
+            assert!(
+                data.contains(&format!("<character location.name=\"table\" location.x=\"{}\" location.y=\"{}\"", x, y)),
+                "missing character at ({}, {})",
+                x,
+                y
+            );
+        }
+    }
+
+    #[test]
+    fn test_shared_palette_uses_literal_values() {
+        let files = generate(&[monster("ゴブリン", 1)], &RoomLayout::default());
+        let data = file(&files, "data.xml");
+
+        assert!(data.contains("<character location.name=\"common\""));
+        assert!(data.contains("<data name=\"name\">共有パレット</data>"));
+        assert!(data.contains("//=====ゴブリン\n//-----計算\n"));
+        assert!(data.contains("//-----ダイス判定\n2d+5　命中判定　ゴブリン\n2d+4　ダメージロール　ゴブリン\n"));
+        assert!(!data.contains("{命中力}　命中判定　ゴブリン"));
+    }
+
+    #[test]
+    fn test_palette_definition_applies_to_all_characters() {
+        let palette = PaletteDefinition { dicebot: "SwordWorld2.0".to_string(), ..PaletteDefinition::classic() };
+        let files = RoomGenerator::generate(&[monster("ゴブリン", 1)], &RoomLayout::default(), &palette, &[]).unwrap();
+        let data = file(&files, "data.xml");
+
+        assert_eq!(data.matches("<chat-palette dicebot=\"SwordWorld2.0\">").count(), 2);
+        assert!(!data.contains("SwordWorld2.5"));
+    }
+
+    #[test]
+    fn test_shared_palette_follows_palette_definition() {
+        let section = super::super::chat_palette::PaletteSection {
+            title: "判定".to_string(),
+            stats: Vec::new(),
+            stat_line: None,
+            lines: vec!["2d+{回避力}　回避".to_string()],
+        };
+        let palette = PaletteDefinition { sections: vec![section], ..PaletteDefinition::classic() };
+        let files = RoomGenerator::generate(&[monster("ゴブリン", 1)], &RoomLayout::default(), &palette, &[]).unwrap();
+        let data = file(&files, "data.xml");
+
+        assert!(data.contains("//=====ゴブリン\n//-----判定\n2d+4　回避　ゴブリン</chat-palette>"), "{}", data);
+        assert!(!data.contains("命中判定"));
+    }
+
+    #[test]
+    fn test_image_identifiers_per_monster() {
+        let monsters = [monster("トレント", 2), monster("ゴブリン", 1)];
+        let files = RoomGenerator::generate(&monsters, &RoomLayout::default(), &PaletteDefinition::default(), &["abc".to_string()])
+            .unwrap();
+        let data = file(&files, "data.xml");
+
+        assert_eq!(data.matches("<data type=\"image\" name=\"imageIdentifier\">abc</data>").count(), 2);
+        assert_eq!(data.matches("<data type=\"image\" name=\"imageIdentifier\"></data>").count(), 2);
+    }
+
+    #[test]
+    fn test_trump_deck() {
+        let layout = RoomLayout { columns: 8, decks: vec![CardDeck::Trump] };
+        let files = generate(&[monster("ゴブリン", 1)], &layout);
+        let data = file(&files, "data.xml");
+
+        assert_eq!(data.matches("<card location.name").count(), 54);
+        assert!(data.contains("<data name=\"name\">トランプ山札</data>"));
+        assert!(data.contains("<data type=\"image\" name=\"front\">./assets/images/trump/s13.gif</data>"));
+        assert!(data.contains("<card-stack location.name=\"table\" location.x=\"850\" location.y=\"50\""));
+    }
+}
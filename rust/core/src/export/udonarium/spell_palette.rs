@@ -0,0 +1,152 @@
+use crate::Spell;
+use crate::export::palette;
+use super::xml_generator::{character, note, number};
+use super::xml_writer::XmlElement;
+
+/// 魔法カテゴリごとにまとめたスペルのチャットパレット
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpellPalette {
+    /// 魔法カテゴリ名（真語魔法など）。キャラクター名と判定値の参照名に使う
+    pub category: String,
+    /// (スペル名, パレット行) の組（入力順）
+    pub entries: Vec<(String, String)>,
+}
+
+/// スペルのチャットパレット用キャラクター生成器
+pub struct SpellPaletteGenerator;
+
+impl SpellPaletteGenerator {
+    /// スペルを魔法カテゴリごとにまとめる
+    ///
+    /// カテゴリは最初に現れた順に並ぶ。
+    ///
+    /// # 戻り値
+    /// (カテゴリ別パレット, パレットを生成できなかったスペルの警告) のタプル
+    pub fn group(spells: &[Spell]) -> (Vec<SpellPalette>, Vec<String>) {
+        let mut palettes: Vec<SpellPalette> = Vec::new();
+        let mut warnings = Vec::new();
+
+        for spell in spells {
+            let line = match palette::generate_spell_palette(spell) {
+                Ok(line) => line,
+                Err(e) => {
+                    warnings.push(format!("{}: {}", spell.name, e));
+                    continue;
+                }
+            };
+
+            let category = palette::format_magic_category(&spell.school);
+            let entry = (spell.name.clone(), line);
+            match palettes.iter_mut().find(|p| p.category == category) {
+                Some(existing) => existing.entries.push(entry),
+                None => palettes.push(SpellPalette { category, entries: vec![entry] }),
+            }
+        }
+
+        (palettes, warnings)
+    }
+
+    /// カテゴリ別パレットから character 要素のXML文字列を生成
+    ///
+    /// パレット行が参照する `{魔法カテゴリ}` と `{行使修正}` は 0 で用意し、
+    /// 各スペルの詳細は「呪文」セクションにメモとして入れる。
+    ///
+    /// # 引数
+    /// * `palette` - カテゴリ別パレット
+    /// * `dicebot` - チャットパレットのダイスボット名
+    pub fn generate_xml(palette: &SpellPalette, dicebot: &str) -> String {
+        let mut spells = XmlElement::new("data").attr("name", "呪文");
+        for (name, line) in &palette.entries {
+            spells = spells.child(note(name, line));
+        }
+
+        let detail = XmlElement::new("data")
+            .attr("name", "detail")
+            .child(
+                XmlElement::new("data")
+                    .attr("name", "魔力・行使")
+                    .child(number(&palette.category, 0))
+                    .child(number("行使修正", 0)),
+            )
+            .child(spells);
+
+        let chat_palette = Self::chat_palette_text(palette);
+        character(&palette.category, "", detail, &chat_palette, dicebot).to_document()
+    }
+
+    /// チャットパレットの内容（見出し行 + 1スペル1行）
+    fn chat_palette_text(palette: &SpellPalette) -> String {
+        let mut text = format!("//-----{}\n", palette.category);
+        for (_, line) in &palette.entries {
+            text.push_str(line);
+            text.push('\n');
+        }
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::chat_palette::DEFAULT_DICEBOT;
+
+    fn spell(value: serde_json::Value) -> Spell {
+        serde_json::from_value(value).expect("Failed to build test spell")
+    }
+
+    fn bolt() -> Spell {
+        spell(serde_json::json!({
+            "name": "エネルギー・ボルト",
+            "school": "真語",
+            "MP": {"kind": "value", "value": 5},
+            "対象": {"kind": "個別", "個別": "1体"},
+            "射程": "30m",
+            "時間": {"value": "一瞬"},
+            "効果": "純エネルギー属性の魔法ダメージを与えます。"
+        }))
+    }
+
+    #[test]
+    fn test_group_by_category() {
+        let mut heal = bolt();
+        heal.name = "キュア・ウーンズ".to_string();
+        heal.school = "神聖".to_string();
+        let mut blast = bolt();
+        blast.name = "ブラスト".to_string();
+
+        let (palettes, warnings) = SpellPaletteGenerator::group(&[bolt(), heal, blast]);
+
+        assert!(warnings.is_empty());
+        assert_eq!(palettes.len(), 2);
+        assert_eq!(palettes[0].category, "真語魔法");
+        assert_eq!(
+            palettes[0].entries.iter().map(|(n, _)| n.as_str()).collect::<Vec<_>>(),
+            vec!["エネルギー・ボルト", "ブラスト"]
+        );
+        assert_eq!(palettes[1].category, "神聖魔法");
+    }
+
+    #[test]
+    fn test_group_reports_invalid_spells() {
+        let (palettes, warnings) = SpellPaletteGenerator::group(&[Spell::new("未完成", "真語"), bolt()]);
+
+        assert_eq!(palettes.len(), 1);
+        assert_eq!(palettes[0].entries.len(), 1);
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].starts_with("未完成: "));
+    }
+
+    #[test]
+    fn test_generate_xml() {
+        let (palettes, _) = SpellPaletteGenerator::group(&[bolt()]);
+        let xml = SpellPaletteGenerator::generate_xml(&palettes[0], DEFAULT_DICEBOT);
+
+        assert!(xml.contains("<data name=\"name\">真語魔法</data>"));
+        assert!(xml.contains("<data name=\"真語魔法\" type=\"number\">0</data>"));
+        assert!(xml.contains("<data name=\"行使修正\" type=\"number\">0</data>"));
+        assert!(xml.contains("<data name=\"エネルギー・ボルト\" type=\"note\">2d+{真語魔法}+{行使修正}  エネルギー・ボルト"));
+        assert!(xml.contains("//-----真語魔法\n2d+{真語魔法}+{行使修正}  エネルギー・ボルト / MP:5"));
+        assert!(xml.contains("dicebot=\"SwordWorld2.5\""));
+        assert!(SpellPaletteGenerator::generate_xml(&palettes[0], "SwordWorld2.0").contains("dicebot=\"SwordWorld2.0\""));
+    }
+}
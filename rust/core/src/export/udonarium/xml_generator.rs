@@ -1,4 +1,6 @@
+use super::chat_palette::PaletteDefinition;
 use super::data_transformer::{TransformedMonster, TransformedPart, DataTransformer};
+use super::xml_writer::XmlElement;
 
 /// XML生成器
 pub struct XmlGenerator;
@@ -6,190 +8,111 @@ pub struct XmlGenerator;
 impl XmlGenerator {
     /// TransformedMonster からXML文字列を生成
     /// 各Partごとに独立したXML（character要素）を生成する
+    /// チャットパレットは従来の内容（classic プリセット）
     pub fn generate_xml(
         transformed: &TransformedMonster,
         part_index: usize,
     ) -> Result<String, String> {
+        Self::generate_xml_with_palette(transformed, part_index, &PaletteDefinition::classic())
+    }
+
+    /// チャットパレットの定義を指定してXML文字列を生成
+    pub fn generate_xml_with_palette(
+        transformed: &TransformedMonster,
+        part_index: usize,
+        palette: &PaletteDefinition,
+    ) -> Result<String, String> {
+        Self::generate_element(transformed, part_index, false, palette).map(|element| element.to_document())
+    }
+
+    /// TransformedMonster から character 要素を生成
+    ///
+    /// # 引数
+    /// * `transformed` - 変換済みモンスター
+    /// * `part_index` - 部位の番号（0始まり）
+    /// * `with_initiative` - ステータスに「先制値」を加えるか（ルームデータのイニシアティブ表用）
+    /// * `palette` - チャットパレットの定義
+    pub fn generate_element(
+        transformed: &TransformedMonster,
+        part_index: usize,
+        with_initiative: bool,
+        palette: &PaletteDefinition,
+    ) -> Result<XmlElement, String> {
         if part_index >= transformed.parts.len() {
             return Err(format!("Part index {} out of bounds", part_index));
         }
 
         let part = &transformed.parts[part_index];
-
+        let mut detail = Self::detail_element(transformed, part, with_initiative);
         if part.is_core {
-            Self::generate_core_part_xml(transformed, part)
-        } else {
-            Self::generate_non_core_part_xml(transformed, part)
+            detail = Self::with_core_sections(transformed, part, detail);
         }
+
+        Ok(character(
+            &part.display_name,
+            &transformed.image_identifier,
+            detail,
+            &palette.render(transformed, part),
+            &palette.dicebot,
+        ))
     }
 
-    /// コア部位用XML生成
-    fn generate_core_part_xml(
+    /// コア部位用の detail 要素
+    /// 非コア部位の内容に加えて、戦闘準備・情報・魔物知識を含む
+    fn with_core_sections(
         monster: &TransformedMonster,
         part: &TransformedPart,
-    ) -> Result<String, String> {
-        let xml = format!(
-            r#"<?xml version="1.0" encoding="utf-8"?>
-<character location.name="table" location.x="0" location.y="0" posZ="0" rotate="0" roll="0">
-  <data name="character">
-    <data name="image">
-      <data type="image" name="imageIdentifier"></data>
-    </data>
-    <data name="common">
-      <data name="name">{}</data>
-      <data name="size">1</data>
-    </data>
-    <data name="detail">
-      <data name="リソース">
-        <data type="numberResource" currentValue="{}" name="HP">{}</data>
-        <data type="numberResource" currentValue="{}" name="MP">{}</data>
-        <data type="numberResource" currentValue="{}" name="防護点">{}</data>
-      </data>
-      <data name="ステータス・バフ・デバフ">
-        <data name="命中力" type="number">{}</data>
-        <data name="打撃点" type="number">{}</data>
-        <data name="回避力" type="number">{}</data>
-        <data name="生命抵抗力" type="number">{}</data>
-        <data name="精神抵抗力" type="number">{}</data>
-      </data>
-      <data name="特殊能力">
-        <data name="特殊能力1" type="note">{}</data>
-        <data name="特殊能力2" type="note">{}</data>
-      </data>
-       <data name="戦闘準備">
-         <data name="魔物知識・先制判定" type="note">{}/{}
-{}</data>
-       </data>
-      <data name="情報">
-        <data name="弱点" type="note">{}</data>
-      </data>
-      <data name="魔物知識">
-        <data name="生態" type="note">{} Lv.{}</data>
-      </data>
-    </data>
-  </data>
-  <chat-palette dicebot="SwordWorld2.5">
-//-----計算
-C({{HP}}+{{防護点}}+{{ダメージ軽減}}-()) 　【残HP（物理ダメージ）】
-C({{HP}}+{{ダメージ軽減}}-())　【残HP（魔法ダメージ）】
-C({{MP}}-())　【MP消費】
-C{{HP}}　【現在HP】
-C{{MP}}　【現在MP】
-
-//-----固定値判定
-C({{命中力}}+7) 命中判定（固定値）
-C({{回避力}}+7) 回避判定（固定値）
-C({{生命抵抗力}}+7) 生命抵抗判定（固定値）
-C({{精神抵抗力}}+7) 精神抵抗判定（固定値）
-
-//-----ダイス判定
-2d+{{命中力}}　命中判定
-2d+{{打撃点}}　ダメージロール
-2d+{{回避力}}　回避判定
-2d+{{生命抵抗力}}　生命抵抗判定
-2d+{{精神抵抗力}}　精神抵抗判定
-  </chat-palette>
-</character>"#,
-            part.display_name,
-            part.hp,
-            part.hp,
-            part.mp,
-            part.mp,
-             part.armor,
-             part.armor,
-             DataTransformer::adjust_value(part.hit_rate),
-             part.damage,
-             DataTransformer::adjust_value(part.dodge),
-             DataTransformer::adjust_value(part.life_resistance),
-             DataTransformer::adjust_value(part.mental_resistance),
-             monster.common_abilities,
-            part.special_abilities,
-            monster.fame,
-            part.weakness_value,
-            monster.initiative,
-            Self::transform_weakness(&part.weakness),
-            monster.category,
-            monster.level,
-        );
-
-        Ok(xml)
-    }
-
-     /// 非コア部位用XML生成
-     fn generate_non_core_part_xml(
-         monster: &TransformedMonster,
-         part: &TransformedPart,
-     ) -> Result<String, String> {
-          let xml = format!(
-              r#"<?xml version="1.0" encoding="utf-8"?>
-  <character location.name="table" location.x="0" location.y="0" posZ="0" rotate="0" roll="0">
-    <data name="character">
-      <data name="image">
-        <data type="image" name="imageIdentifier"></data>
-      </data>
-      <data name="common">
-        <data name="name">{}</data>
-        <data name="size">1</data>
-      </data>
-      <data name="detail">
-       <data name="リソース">
-         <data type="numberResource" currentValue="{}" name="HP">{}</data>
-         <data type="numberResource" currentValue="{}" name="MP">{}</data>
-         <data type="numberResource" currentValue="{}" name="防護点">{}</data>
-       </data>
-       <data name="ステータス・バフ・デバフ">
-         <data name="命中力" type="number">{}</data>
-         <data name="打撃点" type="number">{}</data>
-         <data name="回避力" type="number">{}</data>
-         <data name="生命抵抗力" type="number">{}</data>
-         <data name="精神抵抗力" type="number">{}</data>
-       </data>
-       <data name="特殊能力">
-         <data name="特殊能力1" type="note">{}</data>
-         <data name="特殊能力2" type="note">{}</data>
-       </data>
-     </data>
-   </data>
-   <chat-palette dicebot="SwordWorld2.5">
-//-----計算
-C({{HP}}+{{防護点}}+{{ダメージ軽減}}-()) 　【残HP（物理ダメージ）】
-C({{HP}}+{{ダメージ軽減}}-())　【残HP（魔法ダメージ）】
-C({{MP}}-())　【MP消費】
-C{{HP}}　【現在HP】
-C{{MP}}　【現在MP】
-
-//-----固定値判定
-C({{命中力}}+7) 命中判定（固定値）
-C({{回避力}}+7) 回避判定（固定値）
-C({{生命抵抗力}}+7) 生命抵抗判定（固定値）
-C({{精神抵抗力}}+7) 精神抵抗判定（固定値）
-
-//-----ダイス判定
-2d+{{命中力}}　命中判定
-2d+{{打撃点}}　ダメージロール
-2d+{{回避力}}　回避判定
-2d+{{生命抵抗力}}　生命抵抗判定
-2d+{{精神抵抗力}}　精神抵抗判定
-   </chat-palette>
- </character>"#,
-             part.display_name,
-             part.hp,
-             part.hp,
-             part.mp,
-             part.mp,
-             part.armor,
-             part.armor,
-             DataTransformer::adjust_value(part.hit_rate),
-             part.damage,
-             DataTransformer::adjust_value(part.dodge),
-             DataTransformer::adjust_value(part.life_resistance),
-             DataTransformer::adjust_value(part.mental_resistance),
-             monster.common_abilities,
-             part.special_abilities,
-         );
-
-         Ok(xml)
-     }
+        detail: XmlElement,
+    ) -> XmlElement {
+        detail
+            .child(
+                XmlElement::new("data").attr("name", "戦闘準備").child(note(
+                    "魔物知識・先制判定",
+                    format!("{}/{}\n{}", monster.fame, part.weakness_value, monster.initiative),
+                )),
+            )
+            .child(
+                XmlElement::new("data")
+                    .attr("name", "情報")
+                    .child(note("弱点", Self::transform_weakness(&part.weakness))),
+            )
+            .child(
+                XmlElement::new("data")
+                    .attr("name", "魔物知識")
+                    .child(note("生態", format!("{} Lv.{}", monster.category, monster.level))),
+            )
+    }
+
+    /// detail 要素（リソース・ステータス・特殊能力）
+    fn detail_element(monster: &TransformedMonster, part: &TransformedPart, with_initiative: bool) -> XmlElement {
+        let mut status = XmlElement::new("data")
+            .attr("name", "ステータス・バフ・デバフ")
+            .child(number("命中力", DataTransformer::adjust_value(part.hit_rate)))
+            .child(number("打撃点", part.damage))
+            .child(number("回避力", DataTransformer::adjust_value(part.dodge)))
+            .child(number("生命抵抗力", DataTransformer::adjust_value(part.life_resistance)))
+            .child(number("精神抵抗力", DataTransformer::adjust_value(part.mental_resistance)));
+        if with_initiative {
+            status = status.child(number("先制値", monster.initiative));
+        }
+
+        XmlElement::new("data")
+            .attr("name", "detail")
+            .child(
+                XmlElement::new("data")
+                    .attr("name", "リソース")
+                    .child(resource("HP", part.hp))
+                    .child(resource("MP", part.mp))
+                    .child(resource("防護点", part.armor)),
+            )
+            .child(status)
+            .child(
+                XmlElement::new("data")
+                    .attr("name", "特殊能力")
+                    .child(note("特殊能力1", &monster.common_abilities))
+                    .child(note("特殊能力2", &part.special_abilities)),
+            )
+    }
 
     /// Weakness テキスト変換（XML埋め込み用）
     /// パターン: "炎属性ダメージ+3" → "炎ダメ+3"
@@ -198,6 +121,64 @@ C({{精神抵抗力}}+7) 精神抵抗判定（固定値）
     }
 }
 
+/// character 要素（画像・共通情報・詳細・チャットパレット）
+pub(super) fn character(
+    name: &str,
+    image_identifier: &str,
+    detail: XmlElement,
+    chat_palette: &str,
+    dicebot: &str,
+) -> XmlElement {
+    XmlElement::new("character")
+        .attr("location.name", "table")
+        .attr("location.x", 0)
+        .attr("location.y", 0)
+        .attr("posZ", 0)
+        .attr("rotate", 0)
+        .attr("roll", 0)
+        .child(
+            XmlElement::new("data")
+                .attr("name", "character")
+                .child(
+                    XmlElement::new("data")
+                        .attr("name", "image")
+                        .child(
+                            XmlElement::new("data")
+                                .attr("type", "image")
+                                .attr("name", "imageIdentifier")
+                                .text(image_identifier),
+                        ),
+                )
+                .child(
+                    XmlElement::new("data")
+                        .attr("name", "common")
+                        .child(XmlElement::new("data").attr("name", "name").text(name))
+                        .child(XmlElement::new("data").attr("name", "size").text(1)),
+                )
+                .child(detail),
+        )
+        .child(XmlElement::new("chat-palette").attr("dicebot", dicebot).text(chat_palette))
+}
+
+/// 現在値と最大値を持つリソース（currentValue 属性が現在値、内容が最大値）
+pub(super) fn resource(name: &str, value: i32) -> XmlElement {
+    XmlElement::new("data")
+        .attr("type", "numberResource")
+        .attr("currentValue", value)
+        .attr("name", name)
+        .text(value)
+}
+
+/// 数値ステータス
+pub(super) fn number(name: &str, value: i32) -> XmlElement {
+    XmlElement::new("data").attr("name", name).attr("type", "number").text(value)
+}
+
+/// 複数行テキスト
+pub(super) fn note(name: &str, text: impl ToString) -> XmlElement {
+    XmlElement::new("data").attr("name", name).attr("type", "note").text(text)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -848,4 +829,190 @@ mod tests {
         assert_eq!(core_calc, non_core_calc, "Core and non-core should have same number of calculation/fixed-value commands");
         assert_eq!(core_dice, non_core_dice, "Core and non-core should have same number of dice commands");
     }
+
+    /// 読み戻した XML 要素
+    #[derive(Debug)]
+    struct ParsedElement {
+        name: String,
+        attributes: HashMap<String, String>,
+        text: String,
+        children: Vec<ParsedElement>,
+    }
+
+    impl ParsedElement {
+        /// name 属性で子孫要素を探す
+        fn data(&self, name: &str) -> &ParsedElement {
+            self.find(name).unwrap_or_else(|| panic!("<data name=\"{}\"> が見つかりません", name))
+        }
+
+        fn find(&self, name: &str) -> Option<&ParsedElement> {
+            if self.attributes.get("name").map(String::as_str) == Some(name) {
+                return Some(self);
+            }
+            self.children.iter().find_map(|c| c.find(name))
+        }
+
+        fn child(&self, tag: &str) -> &ParsedElement {
+            self.children.iter().find(|c| c.name == tag).unwrap_or_else(|| panic!("<{}> が見つかりません", tag))
+        }
+    }
+
+    /// XML を読み戻す（整形式でなければ panic）
+    fn parse_document(xml: &str) -> ParsedElement {
+        use quick_xml::events::{BytesStart, Event};
+        use quick_xml::Reader;
+
+        fn element(start: &BytesStart) -> ParsedElement {
+            let attributes = start
+                .attributes()
+                .map(|a| {
+                    let a = a.expect("不正な属性");
+                    let key = String::from_utf8(a.key.as_ref().to_vec()).unwrap();
+                    (key, a.unescape_value().expect("不正な属性値").into_owned())
+                })
+                .collect();
+            ParsedElement {
+                name: String::from_utf8(start.name().as_ref().to_vec()).unwrap(),
+                attributes,
+                text: String::new(),
+                children: Vec::new(),
+            }
+        }
+
+        fn attach(stack: &mut [ParsedElement], root: &mut Option<ParsedElement>, el: ParsedElement) {
+            match stack.last_mut() {
+                Some(parent) => parent.children.push(el),
+                None => {
+                    assert!(root.is_none(), "ルート要素が複数あります");
+                    *root = Some(el);
+                }
+            }
+        }
+
+        let mut reader = Reader::from_str(xml);
+        let mut stack: Vec<ParsedElement> = Vec::new();
+        let mut root = None;
+        let mut saw_decl = false;
+        loop {
+            match reader.read_event().unwrap_or_else(|e| panic!("整形式ではありません: {}\n{}", e, xml)) {
+                Event::Decl(_) => saw_decl = true,
+                Event::Start(e) => stack.push(element(&e)),
+                Event::Empty(e) => attach(&mut stack, &mut root, element(&e)),
+                Event::End(_) => {
+                    let el = stack.pop().expect("対応しない終了タグ");
+                    attach(&mut stack, &mut root, el);
+                }
+                Event::Text(t) => {
+                    let text = t.unescape().expect("不正な文字参照");
+                    match stack.last_mut() {
+                        Some(top) => top.text.push_str(&text),
+                        None => assert!(text.trim().is_empty(), "ルート要素の外にテキストがあります"),
+                    }
+                }
+                Event::Eof => break,
+                other => panic!("想定外のイベント: {:?}", other),
+            }
+        }
+        assert!(saw_decl, "XML宣言がありません");
+        assert!(stack.is_empty(), "閉じられていない要素があります");
+        root.expect("ルート要素がありません")
+    }
+
+    /// 生成した全部位のXMLを読み戻し、値が元データと一致することを確認
+    fn assert_round_trip(monster: &Monster, display_names: Vec<String>) {
+        let transformed = DataTransformer::transform(monster, display_names);
+
+        for (i, part) in transformed.parts.iter().enumerate() {
+            let xml = XmlGenerator::generate_xml(&transformed, i).unwrap();
+            let root = parse_document(&xml);
+
+            assert_eq!(root.name, "character");
+            assert_eq!(root.data("name").text, part.display_name);
+            for (name, value) in [("HP", part.hp), ("MP", part.mp), ("防護点", part.armor)] {
+                let resource = root.data(name);
+                assert_eq!(resource.text, value.to_string(), "{}", name);
+                assert_eq!(resource.attributes["currentValue"], value.to_string(), "{}", name);
+            }
+            assert_eq!(root.data("命中力").text, DataTransformer::adjust_value(part.hit_rate).to_string());
+            assert_eq!(root.data("打撃点").text, part.damage.to_string());
+            assert_eq!(root.data("回避力").text, DataTransformer::adjust_value(part.dodge).to_string());
+            assert_eq!(root.data("生命抵抗力").text, DataTransformer::adjust_value(part.life_resistance).to_string());
+            assert_eq!(root.data("精神抵抗力").text, DataTransformer::adjust_value(part.mental_resistance).to_string());
+            assert_eq!(root.data("特殊能力1").text, transformed.common_abilities);
+            assert_eq!(root.data("特殊能力2").text, part.special_abilities);
+            assert_eq!(root.child("chat-palette").text, PaletteDefinition::classic().render(&transformed, part));
+
+            if part.is_core {
+                assert_eq!(
+                    root.data("魔物知識・先制判定").text,
+                    format!("{}/{}\n{}", transformed.fame, part.weakness_value, transformed.initiative)
+                );
+                assert_eq!(root.data("弱点").text, DataTransformer::transform_weakness(&part.weakness));
+                assert_eq!(root.data("生態").text, format!("{} Lv.{}", transformed.category, transformed.level));
+            } else {
+                assert!(root.find("戦闘準備").is_none());
+            }
+        }
+    }
+
+    #[test]
+    fn test_special_characters_are_escaped_and_round_trip() {
+        let mut monster = create_test_monster();
+        monster.category = "蛮族 & <亜種>".to_string();
+        monster.common_abilities = "▽拘束攻撃<2回>&\"毒\"'x'\n2行目\r\n3行目".to_string();
+        monster.weakness = "炎属性ダメージ+3 & <毒>".to_string();
+        monster.part[0].special_abilities = "]]> <!-- 注釈 --> <![CDATA[x]]>".to_string();
+        let mut second = monster.part[0].clone();
+        second.core = Some(false);
+        second.special_abilities = "&amp; はそのまま".to_string();
+        monster.part.push(second);
+
+        let names = vec!["<魔王> & \"頭\"".to_string(), "胴体 'A' > B".to_string()];
+        assert_round_trip(&monster, names.clone());
+
+        let transformed = DataTransformer::transform(&monster, names);
+        let xml = XmlGenerator::generate_xml(&transformed, 0).unwrap();
+        assert!(xml.contains("&lt;魔王&gt; &amp; \"頭\""));
+        assert!(!xml.contains("<!-- 注釈"));
+    }
+
+    #[test]
+    fn test_control_characters_keep_xml_well_formed() {
+        let mut monster = create_test_monster();
+        monster.common_abilities = "飛行\u{1}\u{8}".to_string();
+        let transformed = DataTransformer::transform(&monster, vec!["名前\u{0}".to_string()]);
+
+        let root = parse_document(&XmlGenerator::generate_xml(&transformed, 0).unwrap());
+        assert_eq!(root.data("特殊能力1").text, "飛行");
+        assert_eq!(root.data("name").text, "名前");
+    }
+
+    #[test]
+    fn test_plain_values_round_trip() {
+        let mut monster = create_test_monster();
+        monster.part[0].mp = -1;
+        assert_round_trip(&monster, vec!["テストモンスター".to_string()]);
+    }
+
+    #[test]
+    fn test_sample_monsters_generate_well_formed_xml() {
+        use super::super::part_namer::PartNamer;
+
+        let file_path = "../../data/sample/monsters_sample.json";
+        if !std::path::Path::new(file_path).exists() {
+            println!("monsters_sample.json not found at {}, skipping test", file_path);
+            return;
+        }
+        let monsters = crate::io::load_json_array(file_path).expect("Failed to load monsters_sample.json");
+
+        for monster in &monsters {
+            let mut namer = PartNamer::new(&monster.part);
+            let names = namer
+                .generate_names(&monster.part, &monster.name)
+                .into_iter()
+                .map(|n| n.display_name)
+                .collect();
+            assert_round_trip(monster, names);
+        }
+    }
 }
@@ -0,0 +1,175 @@
+/// XML要素（Udonarium のキャラクターデータ書き出し用）
+///
+/// 属性値とテキストは書き出し時にエスケープされるため、任意の文字列をそのまま渡せる。
+#[derive(Debug, Clone, PartialEq)]
+pub struct XmlElement {
+    name: String,
+    attributes: Vec<(String, String)>,
+    content: Content,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Content {
+    Text(String),
+    Children(Vec<XmlElement>),
+}
+
+impl XmlElement {
+    /// 空の要素を作成
+    pub fn new(name: &str) -> Self {
+        Self { name: name.to_string(), attributes: Vec::new(), content: Content::Text(String::new()) }
+    }
+
+    /// 属性を追加（追加した順に書き出す）
+    pub fn attr(mut self, key: &str, value: impl ToString) -> Self {
+        self.attributes.push((key.to_string(), value.to_string()));
+        self
+    }
+
+    /// 属性を設定（同じ名前の属性があれば値を置き換える）
+    pub fn set_attr(mut self, key: &str, value: impl ToString) -> Self {
+        match self.attributes.iter_mut().find(|(k, _)| k == key) {
+            Some((_, existing)) => *existing = value.to_string(),
+            None => self.attributes.push((key.to_string(), value.to_string())),
+        }
+        self
+    }
+
+    /// テキスト内容を設定（子要素は破棄される）
+    pub fn text(mut self, text: impl ToString) -> Self {
+        self.content = Content::Text(text.to_string());
+        self
+    }
+
+    /// 子要素を追加（テキスト内容は破棄される）
+    pub fn child(mut self, child: XmlElement) -> Self {
+        match &mut self.content {
+            Content::Children(children) => children.push(child),
+            Content::Text(_) => self.content = Content::Children(vec![child]),
+        }
+        self
+    }
+
+    /// XML宣言付きの文書として書き出す
+    pub fn to_document(&self) -> String {
+        let mut out = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+        self.write(&mut out, 0);
+        out
+    }
+
+    fn write(&self, out: &mut String, depth: usize) {
+        let indent = "  ".repeat(depth);
+        out.push_str(&indent);
+        out.push('<');
+        out.push_str(&self.name);
+        for (key, value) in &self.attributes {
+            out.push(' ');
+            out.push_str(key);
+            out.push_str("=\"");
+            out.push_str(&escape_attribute(value));
+            out.push('"');
+        }
+        out.push('>');
+
+        match &self.content {
+            Content::Text(text) => out.push_str(&escape_text(text)),
+            Content::Children(children) => {
+                out.push('\n');
+                for child in children {
+                    child.write(out, depth + 1);
+                    out.push('\n');
+                }
+                out.push_str(&indent);
+            }
+        }
+
+        out.push_str("</");
+        out.push_str(&self.name);
+        out.push('>');
+    }
+}
+
+/// テキスト内容をエスケープする
+///
+/// `&` `<` `>` を実体参照に、`\r` を文字参照に置き換え（読み込み時の改行正規化で失われないように）、
+/// XML 1.0 で使用できない制御文字は取り除く。
+pub fn escape_text(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '\r' => out.push_str("&#13;"),
+            c if is_xml_char(c) => out.push(c),
+            _ => {}
+        }
+    }
+    out
+}
+
+/// 属性値をエスケープする
+///
+/// テキストのエスケープに加えて引用符と、属性値の正規化で空白に置き換えられる改行・タブも文字参照にする。
+pub fn escape_attribute(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            '\n' => out.push_str("&#10;"),
+            '\r' => out.push_str("&#13;"),
+            '\t' => out.push_str("&#9;"),
+            c if is_xml_char(c) => out.push(c),
+            _ => {}
+        }
+    }
+    out
+}
+
+/// XML 1.0 で使用できる文字か
+fn is_xml_char(c: char) -> bool {
+    matches!(c, '\t' | '\n' | '\r' | '\u{20}'..='\u{D7FF}' | '\u{E000}'..='\u{FFFD}' | '\u{10000}'..)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape_text() {
+        assert_eq!(escape_text("a < b && c > d"), "a &lt; b &amp;&amp; c &gt; d");
+        assert_eq!(escape_text("\"引用\" 'x'"), "\"引用\" 'x'");
+        assert_eq!(escape_text("行1\r\n行2"), "行1&#13;\n行2");
+        assert_eq!(escape_text("制御\u{1}文字\u{FFFE}"), "制御文字");
+    }
+
+    #[test]
+    fn test_escape_attribute() {
+        assert_eq!(escape_attribute("<\"a\" & 'b'>"), "&lt;&quot;a&quot; &amp; &apos;b&apos;&gt;");
+        assert_eq!(escape_attribute("1\n2\t3"), "1&#10;2&#9;3");
+    }
+
+    #[test]
+    fn test_element_rendering() {
+        let xml = XmlElement::new("character")
+            .attr("location.name", "table")
+            .child(XmlElement::new("data").attr("name", "image").child(XmlElement::new("data").attr("type", "image")))
+            .child(XmlElement::new("data").attr("name", "名前").text("<ゴブリン>"))
+            .to_document();
+
+        assert_eq!(
+            xml,
+            "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n\
+             <character location.name=\"table\">\n\
+             \x20 <data name=\"image\">\n\
+             \x20   <data type=\"image\"></data>\n\
+             \x20 </data>\n\
+             \x20 <data name=\"名前\">&lt;ゴブリン&gt;</data>\n\
+             </character>"
+        );
+    }
+}
@@ -21,6 +21,26 @@ impl ZipFileWriter {
     pub fn create_zip<P: AsRef<Path>>(
         zip_path: P,
         xml_files: Vec<(&str, &str)>,
+    ) -> Result<(), String> {
+        let files = xml_files
+            .into_iter()
+            .map(|(filename, content)| (filename, content.as_bytes()))
+            .collect();
+        Self::create_archive(zip_path, files)
+    }
+
+    /// XMLと画像などのバイナリファイルをまとめてZIPファイルに保存
+    ///
+    /// # 引数
+    /// * `zip_path` - 出力ZIPファイルのパス
+    /// * `files` - (ファイル名, 内容) のタプルのベクトル
+    ///
+    /// # 戻り値
+    /// * `Ok(())` - 成功
+    /// * `Err(String)` - エラーメッセージ
+    pub fn create_archive<P: AsRef<Path>>(
+        zip_path: P,
+        files: Vec<(&str, &[u8])>,
     ) -> Result<(), String> {
         // ZIPファイルを作成
         let file = File::create(&zip_path).map_err(|e| {
@@ -34,13 +54,13 @@ impl ZipFileWriter {
         let mut zip = ZipWriter::new(file);
         let options = FileOptions::default();
 
-        // 各XMLファイルをZIPに追加
-        for (filename, content) in xml_files {
+        // 各ファイルをZIPに追加
+        for (filename, content) in files {
             zip.start_file(filename, options).map_err(|e| {
                 format!("Failed to add file '{}' to ZIP: {}", filename, e)
             })?;
 
-            zip.write_all(content.as_bytes()).map_err(|e| {
+            zip.write_all(content).map_err(|e| {
                 format!("Failed to write content for '{}': {}", filename, e)
             })?;
         }
@@ -148,6 +168,21 @@ mod tests {
         assert!(filenames.contains(&"part3.xml".to_string()));
     }
 
+    #[test]
+    fn test_create_archive_with_binary_file() {
+        let temp_file = NamedTempFile::new().expect("Failed to create temp file");
+        let image: &[u8] = &[0x89, b'P', b'N', b'G', 0x00, 0xff];
+
+        let files = vec![("monster.xml", "<character/>".as_bytes()), ("abc.png", image)];
+        ZipFileWriter::create_archive(temp_file.path(), files).expect("Failed to create ZIP");
+
+        let file = File::open(temp_file.path()).expect("Failed to open ZIP");
+        let mut archive = ZipArchive::new(file).expect("Failed to read ZIP");
+        let mut content = Vec::new();
+        archive.by_name("abc.png").expect("Missing image").read_to_end(&mut content).unwrap();
+        assert_eq!(content, image);
+    }
+
     #[test]
     fn test_invalid_zip_path() {
         let result = ZipFileWriter::create_zip_single(
@@ -0,0 +1,484 @@
+//! CSV / TSV の読み込み
+//!
+//! [`crate::export::delimited`] で書き出した表（または同じ見出しを持つ表）から
+//! モンスター・スペルの JSON を組み立て直します。
+
+use super::{ImportError, ImportOutcome, ImportWarning};
+use crate::export::delimited::{
+    decode_cell, MONSTER_TEXT_FIELDS, PART_INDEX_COLUMN, PART_PREFIX, PART_TEXT_FIELDS, SPELL_GROUPS,
+    SPELL_TEXT_FIELDS,
+};
+use crate::{Monster, Spell};
+use serde_json::{Map, Value};
+use std::fs;
+use std::path::Path;
+
+/// CSV / TSV インポーター
+pub struct DelimitedImporter {
+    delimiter: u8,
+}
+
+/// 読み込んだ表（行番号は見出し行を1行目とした番号）
+struct Rows {
+    headers: Vec<String>,
+    rows: Vec<(usize, Vec<String>)>,
+}
+
+/// 組み立て途中のモンスター
+struct PendingMonster {
+    row: usize,
+    fields: Map<String, Value>,
+    parts: Vec<Value>,
+}
+
+impl DelimitedImporter {
+    /// カンマ区切り（CSV）
+    pub fn csv() -> Self {
+        Self { delimiter: b',' }
+    }
+
+    /// タブ区切り（TSV）
+    pub fn tsv() -> Self {
+        Self { delimiter: b'\t' }
+    }
+
+    /// 拡張子から区切り文字を決める（`.tsv` `.tab` はタブ、それ以外はカンマ）
+    pub fn for_path(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()).map(|e| e.to_lowercase()).as_deref() {
+            Some("tsv") | Some("tab") => Self::tsv(),
+            _ => Self::csv(),
+        }
+    }
+
+    /// ファイルからモンスターを読み込む
+    ///
+    /// # 引数
+    /// * `path` - CSV / TSV ファイルのパス
+    ///
+    /// # 戻り値
+    /// * `Ok(ImportOutcome<Monster>)` - 復元したモンスターと警告
+    ///
+    /// # エラー
+    /// 読み込み・表の解析に失敗した場合、またはモンスターとして読めない行がある場合
+    pub fn import_monsters_file(&self, path: &Path) -> Result<ImportOutcome<Monster>, ImportError> {
+        self.import_monsters(&fs::read_to_string(path)?)
+    }
+
+    /// ファイルからスペルを読み込む
+    pub fn import_spells_file(&self, path: &Path) -> Result<ImportOutcome<Spell>, ImportError> {
+        self.import_spells(&fs::read_to_string(path)?)
+    }
+
+    /// 表の文字列からモンスターを読み込む
+    ///
+    /// `部位番号` が 1（部位の無いモンスターは 0）の行から新しいモンスターを始め、
+    /// 続く行を部位として追加する。2部位目以降の行のモンスターの列は空欄でもよく、
+    /// 1部位目と異なる値が入っていれば警告を出して無視する。
+    pub fn import_monsters(&self, content: &str) -> Result<ImportOutcome<Monster>, ImportError> {
+        let table = self.read_rows(content)?;
+        let index_column = table
+            .headers
+            .iter()
+            .position(|h| h == PART_INDEX_COLUMN)
+            .ok_or_else(|| ImportError::InvalidRecord {
+                row: 1,
+                message: format!("header has no '{}' column", PART_INDEX_COLUMN),
+            })?;
+
+        let mut records = Vec::new();
+        let mut warnings = Vec::new();
+        let mut current: Option<PendingMonster> = None;
+
+        for (row, cells) in &table.rows {
+            let index: usize = cells[index_column].trim().parse().map_err(|_| ImportError::InvalidRecord {
+                row: *row,
+                message: format!("'{}' must be a number: '{}'", PART_INDEX_COLUMN, cells[index_column]),
+            })?;
+
+            let mut fields = Map::new();
+            let mut part = Map::new();
+            for (i, (header, cell)) in table.headers.iter().zip(cells).enumerate() {
+                if i == index_column {
+                    continue;
+                }
+                let Some(value) = decode_cell(cell) else { continue };
+                match header.strip_prefix(PART_PREFIX) {
+                    Some(key) => part.insert(key.to_string(), value),
+                    None => fields.insert(header.clone(), value),
+                };
+            }
+
+            match &mut current {
+                Some(monster) if index > 1 => {
+                    for (key, value) in &fields {
+                        if monster.fields.get(key) != Some(value) {
+                            warnings.push(ImportWarning::new(
+                                &record_name(&monster.fields, monster.row),
+                                key,
+                                format!("{}行目の値は1部位目と異なるため無視しました", row),
+                            ));
+                        }
+                    }
+                }
+                _ => {
+                    if index > 1 {
+                        warnings.push(ImportWarning::new(
+                            &record_name(&fields, *row),
+                            PART_INDEX_COLUMN,
+                            format!("{}行目が部位番号 {} から始まっています", row, index),
+                        ));
+                    }
+                    if let Some(done) = current.take() {
+                        records.push(finish_monster(done)?);
+                    }
+                    current = Some(PendingMonster { row: *row, fields, parts: Vec::new() });
+                }
+            }
+
+            if index > 0
+                && let Some(monster) = &mut current
+            {
+                fill_text_fields(&mut part, PART_TEXT_FIELDS);
+                monster.parts.push(Value::Object(part));
+            }
+        }
+
+        if let Some(done) = current {
+            records.push(finish_monster(done)?);
+        }
+
+        Ok(ImportOutcome { records, warnings })
+    }
+
+    /// 表の文字列からスペルを読み込む
+    ///
+    /// `Lv.kind` `Lv.value` などの列は `Lv` のオブジェクトに組み立て直す。
+    pub fn import_spells(&self, content: &str) -> Result<ImportOutcome<Spell>, ImportError> {
+        let table = self.read_rows(content)?;
+        let mut records = Vec::new();
+        let mut warnings = Vec::new();
+
+        for (row, cells) in &table.rows {
+            let mut fields = Map::new();
+            let mut groups: Vec<(&str, Map<String, Value>)> = SPELL_GROUPS.iter().map(|g| (*g, Map::new())).collect();
+
+            for (header, cell) in table.headers.iter().zip(cells) {
+                let Some(value) = decode_cell(cell) else { continue };
+                let group = header
+                    .split_once('.')
+                    .and_then(|(name, key)| groups.iter_mut().find(|(g, _)| *g == name).map(|(_, m)| (m, key)));
+                match group {
+                    Some((map, key)) => {
+                        map.insert(key.to_string(), value);
+                    }
+                    None => {
+                        fields.insert(header.clone(), value);
+                    }
+                }
+            }
+
+            let name = record_name(&fields, *row);
+            for (group, map) in groups {
+                if map.is_empty() {
+                    continue;
+                }
+                let value = build_group(group, map).map_err(|message| ImportError::InvalidRecord { row: *row, message })?;
+                if fields.insert(group.to_string(), value).is_some() {
+                    warnings.push(ImportWarning::new(
+                        &name,
+                        group,
+                        format!("'{}' と '{}.*' の両方に値があるため '{}.*' を使いました", group, group, group),
+                    ));
+                }
+            }
+
+            coerce_text_fields(&mut fields, SPELL_TEXT_FIELDS);
+            let spell = serde_json::from_value(Value::Object(fields))
+                .map_err(|e| ImportError::InvalidRecord { row: *row, message: e.to_string() })?;
+            records.push(spell);
+        }
+
+        Ok(ImportOutcome { records, warnings })
+    }
+
+    /// 見出し行とデータ行を読み込む（UTF-8 の BOM は取り除く）
+    fn read_rows(&self, content: &str) -> Result<Rows, ImportError> {
+        let content = content.strip_prefix('\u{feff}').unwrap_or(content);
+        let mut reader = csv::ReaderBuilder::new()
+            .delimiter(self.delimiter)
+            .flexible(true)
+            .from_reader(content.as_bytes());
+
+        let headers: Vec<String> = reader
+            .headers()
+            .map_err(|e| ImportError::Csv(e.to_string()))?
+            .iter()
+            .map(|h| h.trim().to_string())
+            .collect();
+        if headers.iter().all(|h| h.is_empty()) {
+            return Err(ImportError::Empty("header row is empty".to_string()));
+        }
+
+        let mut rows = Vec::new();
+        for (i, record) in reader.records().enumerate() {
+            let record = record.map_err(|e| ImportError::Csv(e.to_string()))?;
+            if record.iter().all(|cell| cell.is_empty()) {
+                continue;
+            }
+            let mut cells: Vec<String> = record.iter().map(str::to_string).collect();
+            cells.resize(headers.len(), String::new());
+            rows.push((i + 2, cells));
+        }
+
+        if rows.is_empty() {
+            return Err(ImportError::Empty("no data rows".to_string()));
+        }
+        Ok(Rows { headers, rows })
+    }
+}
+
+/// 警告に使うレコード名（名前が無ければ行番号）
+fn record_name(fields: &Map<String, Value>, row: usize) -> String {
+    match fields.get("name") {
+        Some(Value::String(name)) if !name.is_empty() => name.clone(),
+        _ => format!("{}行目", row),
+    }
+}
+
+/// 組み立てたモンスターを型に変換する
+fn finish_monster(mut monster: PendingMonster) -> Result<Monster, ImportError> {
+    fill_text_fields(&mut monster.fields, MONSTER_TEXT_FIELDS);
+    monster.fields.insert("part".to_string(), Value::Array(monster.parts));
+    serde_json::from_value(Value::Object(monster.fields))
+        .map_err(|e| ImportError::InvalidRecord { row: monster.row, message: e.to_string() })
+}
+
+/// 文字列項目の空欄を空文字列で補い、数値・真偽値として読めたセルを文字列に戻す
+fn fill_text_fields(fields: &mut Map<String, Value>, keys: &[&str]) {
+    for key in keys {
+        fields.entry(key.to_string()).or_insert_with(|| Value::String(String::new()));
+    }
+    coerce_text_fields(fields, keys);
+}
+
+/// 数値・真偽値として読めたセルを文字列に戻す（表計算ソフトで入力し直した値など）
+fn coerce_text_fields(fields: &mut Map<String, Value>, keys: &[&str]) {
+    for key in keys {
+        if let Some(value) = fields.get_mut(*key)
+            && matches!(value, Value::Number(_) | Value::Bool(_))
+        {
+            *value = Value::String(value.to_string());
+        }
+    }
+}
+
+/// `Lv` `MP` `対象` `時間` のオブジェクトを組み立てる
+fn build_group(group: &str, mut map: Map<String, Value>) -> Result<Value, String> {
+    let mut take = |key: &str| map.remove(key);
+
+    if group == "時間" {
+        let value = take("value").ok_or("'時間.value' is empty")?;
+        let mut obj = Map::new();
+        obj.insert("value".to_string(), value);
+        if let Some(unit) = take("unit") {
+            obj.insert("unit".to_string(), unit);
+        }
+        return Ok(Value::Object(obj));
+    }
+
+    let kind = match take("kind") {
+        Some(Value::String(kind)) => kind,
+        _ => return Err(format!("'{}.kind' is empty", group)),
+    };
+    let value = take("value").ok_or_else(|| format!("'{}.value' is empty", group))?;
+
+    let value = if group == "対象" && kind == "エリア" {
+        let mut area = Map::new();
+        area.insert("value".to_string(), value);
+        for key in ["半径(m)", "末尾"] {
+            if let Some(v) = take(key) {
+                area.insert(key.to_string(), v);
+            }
+        }
+        Value::Object(area)
+    } else {
+        value
+    };
+
+    let mut obj = Map::new();
+    obj.insert("kind".to_string(), Value::String(kind.clone()));
+    obj.insert(kind, value);
+    Ok(Value::Object(obj))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export::delimited::{monster_table, spell_table, Table};
+
+    fn to_csv(table: &Table, delimiter: u8) -> String {
+        let mut writer = csv::WriterBuilder::new().delimiter(delimiter).from_writer(Vec::new());
+        writer.write_record(&table.headers).unwrap();
+        for row in &table.rows {
+            writer.write_record(row).unwrap();
+        }
+        String::from_utf8(writer.into_inner().unwrap()).unwrap()
+    }
+
+    fn sample_monsters() -> Vec<Monster> {
+        let content = fs::read_to_string("../../data/sample/monsters_sample.json").unwrap();
+        serde_json::from_str(&content).unwrap()
+    }
+
+    fn multi_part_monster() -> Monster {
+        serde_json::from_value(serde_json::json!({
+            "Category": "植物", "Lv": 7, "Revision": 2.5, "data": "123", "illust": "",
+            "movein": -1, "movein_des": "", "moveon": 10, "moveon_des": "",
+            "name": "トレント",
+            "part": [
+                {"HP": 60, "MP": 20, "name": "幹", "コア": true, "命中力": 12, "回避力": 10, "打撃点": 8, "部位数": 1, "部位特殊能力": "", "防護点": 6},
+                {"HP": null, "MP": -1, "name": "根", "コア": null, "命中力": 11, "回避力": 9, "打撃点": 6, "部位数": 2, "部位特殊能力": "絡みつき\n\"引用\", カンマ", "防護点": 4}
+            ],
+            "備考": "", "先制値": 11, "共通特殊能力": "true", "弱点": "炎属性ダメージ+3", "弱点値": 14,
+            "生命抵抗力": 10, "知名度": 12, "精神抵抗力": 9,
+            "出典": {"book": "ML", "page": 100},
+            "メモ": null
+        }))
+        .unwrap()
+    }
+
+    fn assert_monsters_round_trip(monsters: &[Monster], repeat: bool) {
+        let csv = to_csv(&monster_table(monsters, repeat).unwrap(), b',');
+        let outcome = DelimitedImporter::csv().import_monsters(&csv).unwrap();
+
+        assert!(outcome.warnings.is_empty(), "{:?}", outcome.warnings);
+        assert_eq!(
+            serde_json::to_value(&outcome.records).unwrap(),
+            serde_json::to_value(monsters).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_monster_round_trip() {
+        let mut monsters = sample_monsters();
+        monsters.push(multi_part_monster());
+
+        assert_monsters_round_trip(&monsters, true);
+        assert_monsters_round_trip(&monsters, false);
+    }
+
+    #[test]
+    fn test_monster_import_accepts_reordered_columns_and_bom() {
+        let table = monster_table(&[multi_part_monster()], true).unwrap();
+        let mut order: Vec<usize> = (0..table.headers.len()).collect();
+        order.reverse();
+        let reordered = Table {
+            headers: order.iter().map(|&i| table.headers[i].clone()).collect(),
+            rows: table.rows.iter().map(|row| order.iter().map(|&i| row[i].clone()).collect()).collect(),
+        };
+        let csv = format!("\u{feff}{}", to_csv(&reordered, b','));
+        let outcome = DelimitedImporter::csv().import_monsters(&csv).unwrap();
+
+        assert_eq!(outcome.records[0].part.len(), 2);
+        assert_eq!(outcome.records[0].part[1].special_abilities, "絡みつき\n\"引用\", カンマ");
+    }
+
+    #[test]
+    fn test_monster_round_trip_tsv() {
+        let monsters = vec![multi_part_monster()];
+        let tsv = to_csv(&monster_table(&monsters, true).unwrap(), b'\t');
+        let outcome = DelimitedImporter::tsv().import_monsters(&tsv).unwrap();
+
+        assert_eq!(
+            serde_json::to_value(&outcome.records).unwrap(),
+            serde_json::to_value(&monsters).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_monster_import_warns_on_conflicting_part_rows() {
+        let mut table = monster_table(&[multi_part_monster()], true).unwrap();
+        let lv = table.headers.iter().position(|h| h == "Lv").unwrap();
+        table.rows[1][lv] = "9".to_string();
+
+        let outcome = DelimitedImporter::csv().import_monsters(&to_csv(&table, b',')).unwrap();
+
+        assert_eq!(outcome.records[0].level, 7);
+        assert_eq!(outcome.warnings.len(), 1);
+        assert_eq!(outcome.warnings[0].record, "トレント");
+        assert_eq!(outcome.warnings[0].field, "Lv");
+    }
+
+    #[test]
+    fn test_monster_import_coerces_text_cells() {
+        let mut table = monster_table(&[multi_part_monster()], false).unwrap();
+        let notes = table.headers.iter().position(|h| h == "備考").unwrap();
+        let data = table.headers.iter().position(|h| h == "data").unwrap();
+        table.rows[0][notes] = String::new();
+        table.rows[0][data] = "123".to_string();
+
+        let outcome = DelimitedImporter::csv().import_monsters(&to_csv(&table, b',')).unwrap();
+
+        assert_eq!(outcome.records[0].notes, "");
+        assert_eq!(outcome.records[0].data, "123");
+    }
+
+    #[test]
+    fn test_monster_import_requires_part_index() {
+        let result = DelimitedImporter::csv().import_monsters("name,Lv\nゴブリン,1\n");
+        assert!(matches!(result, Err(ImportError::InvalidRecord { row: 1, .. })));
+    }
+
+    #[test]
+    fn test_spell_round_trip() {
+        let spells: Vec<Spell> = serde_json::from_value(serde_json::json!([
+            {
+                "reference": [{"book": "CR", "value": 12}],
+                "Revision": 2.5,
+                "name": "ファイアボール", "school": "真語",
+                "Lv": {"kind": "value", "value": 5},
+                "MP": {"kind": "special", "special": "効果参照"},
+                "対象": {"kind": "エリア", "エリア": {"value": "1エリア", "半径(m)": 3, "末尾": "すべて"}},
+                "補助": false,
+                "時間": {"value": 3, "unit": "R"},
+                "射程": "2(30m)", "射程(m)": 30,
+                "効果": "1", "独自": [1, 2]
+            },
+            {
+                "name": "プロテクション", "school": "神聖", "god": "",
+                "Lv": {"kind": "rank", "rank": 2},
+                "MP": {"kind": "value+", "value+": 3},
+                "対象": {"kind": "個別", "個別": "1体"},
+                "時間": {"value": "一瞬"}
+            }
+        ]))
+        .unwrap();
+
+        let csv = to_csv(&spell_table(&spells).unwrap(), b',');
+        let outcome = DelimitedImporter::csv().import_spells(&csv).unwrap();
+
+        assert!(outcome.warnings.is_empty());
+        assert_eq!(
+            serde_json::to_value(&outcome.records).unwrap(),
+            serde_json::to_value(&spells).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_spell_import_requires_kind() {
+        let result = DelimitedImporter::csv().import_spells("name,school,Lv.value\nボルト,真語,1\n");
+        match result {
+            Err(ImportError::InvalidRecord { row, message }) => {
+                assert_eq!(row, 2);
+                assert!(message.contains("Lv.kind"));
+            }
+            other => panic!("unexpected result: {:?}", other.map(|o| o.records.len())),
+        }
+    }
+
+    #[test]
+    fn test_for_path() {
+        assert_eq!(DelimitedImporter::for_path(Path::new("a.TSV")).delimiter, b'\t');
+        assert_eq!(DelimitedImporter::for_path(Path::new("a.csv")).delimiter, b',');
+    }
+}
@@ -0,0 +1,63 @@
+use std::fmt;
+use thiserror::Error;
+
+pub mod delimited;
+pub mod sheets;
+pub mod udonarium;
+
+/// インポート時のエラー型
+#[derive(Error, Debug)]
+pub enum ImportError {
+    #[error("IO error: {0}")]
+    IoError(#[from] std::io::Error),
+
+    #[error("ZIP error: {0}")]
+    ZipError(String),
+
+    #[error("XML error in {file}: {message}")]
+    XmlError { file: String, message: String },
+
+    #[error("No importable data found: {0}")]
+    Empty(String),
+
+    #[error("CSV error: {0}")]
+    Csv(String),
+
+    #[error("Invalid record at row {row}: {message}")]
+    InvalidRecord { row: usize, message: String },
+
+    #[error("Google Sheets error: {0}")]
+    Sheets(String),
+}
+
+/// 復元できなかった（または推定で補った）項目
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportWarning {
+    /// 対象のレコード名（モンスター名など）
+    pub record: String,
+    /// 項目名
+    pub field: String,
+    /// 内容
+    pub message: String,
+}
+
+impl ImportWarning {
+    pub fn new(record: &str, field: &str, message: impl Into<String>) -> Self {
+        Self { record: record.to_string(), field: field.to_string(), message: message.into() }
+    }
+}
+
+impl fmt::Display for ImportWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}: {}", self.record, self.field, self.message)
+    }
+}
+
+/// インポート結果
+#[derive(Debug, Clone)]
+pub struct ImportOutcome<T> {
+    /// 復元したレコード
+    pub records: Vec<T>,
+    /// 復元できなかった項目
+    pub warnings: Vec<ImportWarning>,
+}
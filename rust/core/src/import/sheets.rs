@@ -0,0 +1,793 @@
+//! Google Sheets からの読み込み
+//!
+//! [`crate::export::sheets::DataTransformer`] がレイアウトに従って書き出した行
+//! （1部位あたり `rows_per_part` 行）からモンスターを組み立て直し、
+//! ローカルのデータとの差分を求めます。
+
+use super::{ImportError, ImportOutcome, ImportWarning};
+use crate::export::google_sheets;
+use crate::export::sheets::{column_index, DataTransformer, SheetField, SheetLayout};
+use crate::dataset::DataSet;
+use crate::io::IoError;
+use crate::Monster;
+use serde_json::{json, Map, Value};
+use std::fmt;
+use std::path::Path;
+
+/// 差分の1項目
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldChange {
+    /// 項目名（部位の項目は "部位2(翼).HP"）
+    pub field: String,
+    /// ローカルの値（None は項目・部位が無い）
+    pub old: Option<Value>,
+    /// シートの値（None は項目・部位が無い）
+    pub new: Option<Value>,
+}
+
+impl fmt::Display for FieldChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let show = |value: &Option<Value>| value.as_ref().map_or_else(|| "(なし)".to_string(), Value::to_string);
+        write!(f, "{}: {} → {}", self.field, show(&self.old), show(&self.new))
+    }
+}
+
+/// シートから読み込んだモンスターと、ローカルのデータとの差分
+#[derive(Debug, Clone)]
+pub struct MonsterChange {
+    /// シートの内容を反映したモンスター
+    pub monster: Monster,
+    /// 同じ名前のローカルのモンスターの位置（None はローカルに無いモンスター）
+    pub position: Option<usize>,
+    /// 変更された項目（ローカルに無いモンスターは空）
+    pub changes: Vec<FieldChange>,
+}
+
+impl MonsterChange {
+    /// ローカルに無いモンスターか
+    pub fn is_new(&self) -> bool {
+        self.position.is_none()
+    }
+
+    /// 取り込む内容があるか（新しいモンスター、または変更された項目がある）
+    pub fn has_changes(&self) -> bool {
+        self.is_new() || !self.changes.is_empty()
+    }
+
+    /// データセットに反映する
+    ///
+    /// 同じ名前のモンスターは所有ファイル内の同じ位置で置き換え、無ければ `file` の末尾に追加する。
+    /// 位置は反映時に名前で引き直すため、先に別のモンスターを追加して位置がずれていても正しく置き換わる。
+    ///
+    /// # エラー
+    /// - 追加先のファイルがデータセットに含まれない場合は `IoError::InvalidFormat`
+    pub fn apply(&self, monsters: &mut DataSet<Monster>, file: &Path) -> Result<(), IoError> {
+        match monsters.position(|m| m.name == self.monster.name) {
+            Some(position) => {
+                monsters.replace(position, self.monster.clone());
+                Ok(())
+            }
+            None => monsters.push(self.monster.clone(), file).map(|_| ()),
+        }
+    }
+}
+
+/// 組み立て途中のモンスター（連続する部位の行）
+struct PendingMonster<'a> {
+    name: String,
+    /// 最初の行の行番号
+    row: usize,
+    /// 最後の部位の行番号
+    last_row: usize,
+    /// (部位名, コアか, 部位の行)
+    parts: Vec<(String, bool, &'a [Vec<String>])>,
+}
+
+/// Google Sheets インポーター（GoogleSheetsExporter の逆変換）
+///
+/// 名前のセルが空の部位は読み飛ばし、名前（"★モンスター名\n(部位名)"）が同じモンスターを指す
+/// 連続した部位を1体にまとめる（同じ名前のモンスターが再び現れた場合は読み飛ばす）。
+/// レイアウトに無い項目と、2部位目以降に "-" で書き出されるモンスター単位の項目は、
+/// ローカルの同じ名前のモンスターの値を残す。
+pub struct SheetsImporter {
+    layout: SheetLayout,
+    api_base_url: Option<String>,
+}
+
+impl SheetsImporter {
+    /// レイアウトを指定して作成
+    pub fn new(layout: SheetLayout) -> Self {
+        Self { layout, api_base_url: None }
+    }
+
+    /// Sheets API のベース URL を指定（ローカルのモックサーバーでの確認用）
+    pub fn with_api_base_url(mut self, api_base_url: Option<String>) -> Self {
+        self.api_base_url = api_base_url;
+        self
+    }
+
+    /// スプレッドシートから読み込み、ローカルのモンスターと比較する
+    ///
+    /// # 引数
+    /// * `spreadsheet_id` - スプレッドシート ID
+    /// * `local` - 比較するローカルのモンスター
+    ///
+    /// # 戻り値
+    /// シートのモンスターごとの差分と、読み込めなかった項目の警告
+    ///
+    /// # エラー
+    /// 認証・API の呼び出しに失敗した場合、モンスターが1体も無い場合
+    pub fn import_spreadsheet(
+        &self,
+        spreadsheet_id: &str,
+        local: &[Monster],
+    ) -> Result<ImportOutcome<MonsterChange>, ImportError> {
+        let client = google_sheets::connect(self.api_base_url.as_deref()).map_err(|e| ImportError::Sheets(e.to_string()))?;
+
+        let rt = tokio::runtime::Runtime::new()
+            .map_err(|e| ImportError::Sheets(format!("Failed to create async runtime: {}", e)))?;
+        let rows = rt
+            .block_on(client.read_monster_rows(spreadsheet_id, &self.layout))
+            .map_err(|e| ImportError::Sheets(e.to_string()))?;
+
+        self.import_rows(&rows, local)
+    }
+
+    /// 読み込んだ行からモンスターを組み立て、ローカルのモンスターと比較する
+    ///
+    /// `rows` はレイアウトの開始行から順に1行ずつ、セルは A 列から並べたもの
+    /// （末尾の空の行・セルは省略してよい）。
+    ///
+    /// # エラー
+    /// レイアウトに name のセルが無い場合、モンスターが1体も無い場合、
+    /// 組み立てたモンスターが JSON の形式を満たさない場合
+    pub fn import_rows(&self, rows: &[Vec<String>], local: &[Monster]) -> Result<ImportOutcome<MonsterChange>, ImportError> {
+        let name_cell = self
+            .layout
+            .cells
+            .iter()
+            .find(|cell| cell.field == Some(SheetField::Name))
+            .ok_or_else(|| ImportError::Sheets("sheet layout has no 'name' cell".to_string()))?;
+        let name_column = column_index(&name_cell.column).unwrap_or(0);
+        let rows_per_part = self.layout.rows_per_part.max(1);
+
+        // 部位の行を名前でまとめる
+        let mut pending: Vec<PendingMonster> = Vec::new();
+        for (i, block) in rows.chunks(rows_per_part).enumerate() {
+            let row = self.layout.start_row + i * rows_per_part;
+            let display_name = cell(block, name_cell.row, name_column).trim();
+            if display_name.is_empty() {
+                continue;
+            }
+            let (name, part_name, core) = parse_display_name(display_name);
+
+            let continues = pending.last().is_some_and(|monster| {
+                monster.name == name
+                    && monster.last_row + rows_per_part == row
+                    && !part_name.is_empty()
+                    && monster.parts.iter().all(|(existing, _, _)| *existing != part_name)
+            });
+            match pending.last_mut() {
+                Some(monster) if continues => {
+                    monster.last_row = row;
+                    monster.parts.push((part_name, core, block));
+                }
+                _ => pending.push(PendingMonster { name, row, last_row: row, parts: vec![(part_name, core, block)] }),
+            }
+        }
+
+        if pending.is_empty() {
+            return Err(ImportError::Empty(format!(
+                "no monsters in {}!{}",
+                self.layout.sheet_name, self.layout.start_row
+            )));
+        }
+
+        let mut warnings = Vec::new();
+        let mut records: Vec<MonsterChange> = Vec::new();
+        for monster in &pending {
+            // 同じ名前のモンスターは最初の1体だけを取り込む
+            if records.iter().any(|r| r.monster.name == monster.name) {
+                warnings.push(ImportWarning::new(
+                    &monster.name,
+                    "name",
+                    format!("{}行目は同じ名前のモンスターが前の行にあるため読み飛ばしました", monster.row),
+                ));
+                continue;
+            }
+            records.push(self.build_monster(monster, local, &mut warnings)?);
+        }
+
+        Ok(ImportOutcome { records, warnings })
+    }
+
+    /// まとめた部位の行から Monster を組み立て、ローカルのモンスターと比較する
+    fn build_monster(
+        &self,
+        pending: &PendingMonster,
+        local: &[Monster],
+        warnings: &mut Vec<ImportWarning>,
+    ) -> Result<MonsterChange, ImportError> {
+        let name = pending.name.as_str();
+        let position = local.iter().position(|m| m.name == name);
+        let existing = position.map(|p| &local[p]);
+
+        let mut fields = match existing {
+            Some(monster) => to_object(monster),
+            None => {
+                let missing = self.missing_keys(&monster_skeleton());
+                if !missing.is_empty() {
+                    warnings.push(ImportWarning::new(
+                        name,
+                        "その他",
+                        format!("{} はシートに含まれないため既定値を設定しました", missing.join(", ")),
+                    ));
+                }
+                monster_skeleton()
+            }
+        };
+        let local_parts = take_parts(&mut fields);
+        fields.insert("name".to_string(), Value::String(name.to_string()));
+
+        let mut parts = Vec::new();
+        for (index, (part_name, core, block)) in pending.parts.iter().enumerate() {
+            // 同じ名前の部位、無ければ同じ順番の部位の値を元にする
+            let mut part = match local_parts
+                .iter()
+                .find(|p| p["name"] == part_name.as_str())
+                .or_else(|| local_parts.get(index))
+            {
+                Some(Value::Object(part)) => part.clone(),
+                _ => {
+                    if existing.is_some() {
+                        let missing = self.missing_keys(&part_skeleton());
+                        if !missing.is_empty() {
+                            warnings.push(ImportWarning::new(
+                                name,
+                                &part_label(index, part_name),
+                                format!("{} はシートに含まれないため既定値を設定しました", missing.join(", ")),
+                            ));
+                        }
+                    }
+                    part_skeleton()
+                }
+            };
+
+            part.insert("name".to_string(), Value::String(part_name.clone()));
+            if *core {
+                part.insert("コア".to_string(), Value::Bool(true));
+            } else if part.get("コア") == Some(&Value::Bool(true)) {
+                part.insert("コア".to_string(), Value::Bool(false));
+            }
+
+            for layout_cell in &self.layout.cells {
+                let Some(field) = layout_cell.field else { continue };
+                let Some(column) = column_index(&layout_cell.column) else { continue };
+                if field == SheetField::Name || (!is_part_field(field) && index > 0) {
+                    continue;
+                }
+                let text = cell(block, layout_cell.row, column);
+                let target = if is_part_field(field) { &mut part } else { &mut fields };
+                let label = if is_part_field(field) { part_label(index, part_name) } else { String::new() };
+                read_field(field, text, target, existing, &mut |key, message| {
+                    let key = if label.is_empty() { key.to_string() } else { format!("{}.{}", label, key) };
+                    warnings.push(ImportWarning::new(name, &key, message));
+                });
+            }
+            parts.push(Value::Object(part));
+        }
+        fields.insert("part".to_string(), Value::Array(parts));
+
+        let monster: Monster = serde_json::from_value(Value::Object(fields))
+            .map_err(|e| ImportError::InvalidRecord { row: pending.row, message: e.to_string() })?;
+        let changes = existing.map(|old| diff_monsters(old, &monster)).unwrap_or_default();
+
+        Ok(MonsterChange { monster, position, changes })
+    }
+
+    /// レイアウトに含まれない項目（`skeleton` のキーのうち）
+    fn missing_keys(&self, skeleton: &Map<String, Value>) -> Vec<String> {
+        let covered: Vec<&str> = self
+            .layout
+            .cells
+            .iter()
+            .filter_map(|cell| cell.field)
+            .flat_map(json_keys)
+            .copied()
+            .collect();
+        skeleton
+            .keys()
+            .filter(|key| !matches!(key.as_str(), "name" | "part" | "コア") && !covered.contains(&key.as_str()))
+            .cloned()
+            .collect()
+    }
+}
+
+/// 2つのモンスターの差分（部位は順番で対応させる）
+///
+/// # 戻り値
+/// 値の異なる項目。部位の数が異なる場合、増えた・減った部位は部位全体を1項目とする
+pub fn diff_monsters(old: &Monster, new: &Monster) -> Vec<FieldChange> {
+    let mut old = to_object(old);
+    let mut new = to_object(new);
+    let old_parts = take_parts(&mut old);
+    let new_parts = take_parts(&mut new);
+
+    let mut changes = diff_objects("", &old, &new);
+    for index in 0..old_parts.len().max(new_parts.len()) {
+        let (old_part, new_part) = (old_parts.get(index), new_parts.get(index));
+        let part_name = new_part.or(old_part).and_then(|p| p["name"].as_str()).unwrap_or("");
+        let label = part_label(index, part_name);
+        match (old_part, new_part) {
+            (Some(Value::Object(old_part)), Some(Value::Object(new_part))) => {
+                changes.extend(diff_objects(&format!("{}.", label), old_part, new_part));
+            }
+            (old_part, new_part) if old_part != new_part => changes.push(FieldChange {
+                field: label,
+                old: old_part.cloned(),
+                new: new_part.cloned(),
+            }),
+            _ => {}
+        }
+    }
+    changes
+}
+
+/// オブジェクトの項目ごとの差分（キーの前に `prefix` を付ける）
+fn diff_objects(prefix: &str, old: &Map<String, Value>, new: &Map<String, Value>) -> Vec<FieldChange> {
+    let mut keys: Vec<&String> = old.keys().chain(new.keys().filter(|key| !old.contains_key(*key))).collect();
+    keys.sort();
+    keys.into_iter()
+        .filter(|key| old.get(*key) != new.get(*key))
+        .map(|key| FieldChange {
+            field: format!("{}{}", prefix, key),
+            old: old.get(key).cloned(),
+            new: new.get(key).cloned(),
+        })
+        .collect()
+}
+
+/// セルの値を読み取って `target` に設定する（読めない値は `warn` に渡して元の値を残す）
+fn read_field(
+    field: SheetField,
+    text: &str,
+    target: &mut Map<String, Value>,
+    existing: Option<&Monster>,
+    warn: &mut dyn FnMut(&str, String),
+) {
+    let trimmed = text.trim();
+    let mut set = |key: &str, value: Value| {
+        target.insert(key.to_string(), value);
+    };
+
+    match field {
+        SheetField::Name => {}
+        // 空欄・"-" は値なし
+        SheetField::Hp | SheetField::HitRate | SheetField::Dodge | SheetField::Damage => {
+            let key = json_keys(field)[0];
+            if trimmed.is_empty() || trimmed == "-" {
+                set(key, Value::Null);
+            } else {
+                match trimmed.parse::<i32>() {
+                    Ok(n) => set(key, json!(n)),
+                    Err(_) => warn(key, format!("数値ではないため無視しました: '{}'", text)),
+                }
+            }
+        }
+        // 空欄・"-" は MP なし（-1）
+        SheetField::Mp if trimmed.is_empty() || trimmed == "-" => set("MP", json!(-1)),
+        SheetField::Mp
+        | SheetField::Level
+        | SheetField::Armor
+        | SheetField::PartCount
+        | SheetField::Initiative
+        | SheetField::LifeResistance
+        | SheetField::MentalResistance => {
+            let key = json_keys(field)[0];
+            match trimmed.parse::<i32>() {
+                Ok(n) => set(key, json!(n)),
+                Err(_) if trimmed.is_empty() => warn(key, "空欄のため無視しました".to_string()),
+                Err(_) => warn(key, format!("数値ではないため無視しました: '{}'", text)),
+            }
+        }
+        SheetField::Category
+        | SheetField::Data
+        | SheetField::SpecialAbilities
+        | SheetField::CommonAbilities
+        | SheetField::Notes => set(json_keys(field)[0], Value::String(text.to_string())),
+        SheetField::Moveon | SheetField::Movein => {
+            let keys = json_keys(field);
+            match parse_movement(trimmed) {
+                Some((value, description)) => {
+                    set(keys[0], json!(value));
+                    set(keys[1], Value::String(description));
+                }
+                None => warn(keys[0], format!("移動速度として読めないため無視しました: '{}'", text)),
+            }
+        }
+        SheetField::FameWeaknessValue => {
+            let parsed = trimmed
+                .split_once('/')
+                .and_then(|(fame, value)| Some((fame.trim().parse::<i32>().ok()?, value.trim().parse::<i32>().ok()?)));
+            match parsed {
+                Some((fame, value)) => {
+                    set("知名度", json!(fame));
+                    set("弱点値", json!(value));
+                }
+                None => warn("知名度/弱点値", format!("\"知名度/弱点値\" の形式ではないため無視しました: '{}'", text)),
+            }
+        }
+        SheetField::Weakness => {
+            let local = existing.map(|m| m.weakness.as_str()).unwrap_or("");
+            if trimmed.is_empty() || trimmed == "-" {
+                set("弱点", Value::String(String::new()));
+            } else if DataTransformer::transform_weakness(local) == trimmed {
+                // 略記した値が変わっていなければ元の表記を残す
+                set("弱点", Value::String(local.to_string()));
+            } else {
+                let expanded = expand_weakness(trimmed);
+                warn("弱点", format!("略記 '{}' を '{}' に戻しました。表記を確認してください", trimmed, expanded));
+                set("弱点", Value::String(expanded));
+            }
+        }
+    }
+}
+
+/// 弱点の略記を戻す（[`DataTransformer::transform_weakness`] の逆）
+///
+/// "炎ダメ+3" → "炎属性ダメージ+3"、"魔法ダメ+2" → "魔法ダメージ+2"（魔法・物理には "属性" を付けない）
+fn expand_weakness(text: &str) -> String {
+    text.replace("属性ダメージ", "ダメ")
+        .replace("ダメージ", "ダメ")
+        .replace("ダメ", "属性ダメージ")
+        .replace("魔法属性", "魔法")
+        .replace("物理属性", "物理")
+        .replace("純E", "純エネルギー")
+}
+
+/// 移動速度のセル（"-"、"12"、"12\n(四足)"）を (値, 説明) に変換
+fn parse_movement(text: &str) -> Option<(i32, String)> {
+    if text.is_empty() || text == "-" {
+        return Some((-1, String::new()));
+    }
+    let (value, description) = match text.split_once('(') {
+        Some((value, rest)) => (value, rest.trim().trim_end_matches(')').trim()),
+        None => (text, ""),
+    };
+    Some((value.trim().parse().ok()?, description.to_string()))
+}
+
+/// 表示名（"★モンスター名\n(部位名)"）を (モンスター名, 部位名, コアか) に分ける
+///
+/// 手で編集されたセルも読めるよう、改行の前後の空白と部位名の括弧は省略可能とする。
+fn parse_display_name(display_name: &str) -> (String, String, bool) {
+    let (core, name) = match display_name.strip_prefix('★') {
+        Some(rest) => (true, rest.trim()),
+        None => (false, display_name.trim()),
+    };
+    match name.split_once('\n') {
+        Some((monster, part)) => {
+            let part = part.trim();
+            let part = part.strip_prefix('(').and_then(|p| p.strip_suffix(')')).unwrap_or(part);
+            (monster.trim().to_string(), part.trim().to_string(), core)
+        }
+        None => (name.to_string(), String::new(), core),
+    }
+}
+
+/// 部位の `row` 行目の `column` 列のセル（無ければ空文字列）
+fn cell(block: &[Vec<String>], row: usize, column: usize) -> &str {
+    block.get(row).and_then(|cells| cells.get(column)).map_or("", String::as_str)
+}
+
+/// 部位ごとの値か（それ以外はモンスター単位の値）
+fn is_part_field(field: SheetField) -> bool {
+    matches!(
+        field,
+        SheetField::Hp
+            | SheetField::Mp
+            | SheetField::Armor
+            | SheetField::HitRate
+            | SheetField::Dodge
+            | SheetField::Damage
+            | SheetField::PartCount
+            | SheetField::SpecialAbilities
+    )
+}
+
+/// セルに対応する JSON のキー
+fn json_keys(field: SheetField) -> &'static [&'static str] {
+    match field {
+        SheetField::Name => &["name"],
+        SheetField::Level => &["Lv"],
+        SheetField::Category => &["Category"],
+        SheetField::Data => &["data"],
+        SheetField::Hp => &["HP"],
+        SheetField::Mp => &["MP"],
+        SheetField::Armor => &["防護点"],
+        SheetField::HitRate => &["命中力"],
+        SheetField::Dodge => &["回避力"],
+        SheetField::Damage => &["打撃点"],
+        SheetField::PartCount => &["部位数"],
+        SheetField::SpecialAbilities => &["部位特殊能力"],
+        SheetField::CommonAbilities => &["共通特殊能力"],
+        SheetField::Moveon => &["moveon", "moveon_des"],
+        SheetField::Movein => &["movein", "movein_des"],
+        SheetField::Initiative => &["先制値"],
+        SheetField::LifeResistance => &["生命抵抗力"],
+        SheetField::MentalResistance => &["精神抵抗力"],
+        SheetField::FameWeaknessValue => &["知名度", "弱点値"],
+        SheetField::Weakness => &["弱点"],
+        SheetField::Notes => &["備考"],
+    }
+}
+
+/// 差分・警告に使う部位の名前（"部位2(翼)"、名前の無い部位は "部位1"）
+fn part_label(index: usize, name: &str) -> String {
+    if name.is_empty() {
+        format!("部位{}", index + 1)
+    } else {
+        format!("部位{}({})", index + 1, name)
+    }
+}
+
+fn to_object(monster: &Monster) -> Map<String, Value> {
+    match serde_json::to_value(monster) {
+        Ok(Value::Object(fields)) => fields,
+        _ => Map::new(),
+    }
+}
+
+fn take_parts(fields: &mut Map<String, Value>) -> Vec<Value> {
+    match fields.remove("part") {
+        Some(Value::Array(parts)) => parts,
+        _ => Vec::new(),
+    }
+}
+
+/// ローカルに無いモンスターの既定値
+fn monster_skeleton() -> Map<String, Value> {
+    let skeleton = json!({
+        "Category": "", "Lv": 0, "Revision": 2.5, "data": "", "illust": "",
+        "movein": -1, "movein_des": "", "moveon": -1, "moveon_des": "", "name": "", "part": [],
+        "備考": "", "先制値": 0, "共通特殊能力": "", "弱点": "", "弱点値": 0,
+        "生命抵抗力": 0, "知名度": 0, "精神抵抗力": 0
+    });
+    match skeleton {
+        Value::Object(fields) => fields,
+        _ => unreachable!(),
+    }
+}
+
+/// ローカルに無い部位の既定値
+fn part_skeleton() -> Map<String, Value> {
+    let skeleton = json!({
+        "HP": null, "MP": -1, "name": "", "コア": null, "命中力": null, "回避力": null,
+        "打撃点": null, "部位数": 1, "部位特殊能力": "", "防護点": 0
+    });
+    match skeleton {
+        Value::Object(fields) => fields,
+        _ => unreachable!(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn griffon() -> Monster {
+        serde_json::from_value(json!({
+            "Category": "幻獣", "Lv": 8, "Revision": 2.5, "data": "TEST003", "illust": "griffon",
+            "movein": 30, "movein_des": "飛行", "moveon": 20, "moveon_des": "四足", "name": "グリフォン",
+            "part": [
+                {"HP": 50, "MP": 20, "name": "胴体", "コア": true, "命中力": 14, "回避力": 13, "打撃点": 8, "部位数": 1, "部位特殊能力": "", "防護点": 6},
+                {"HP": 40, "MP": -1, "name": "翼", "命中力": 13, "回避力": 14, "打撃点": 6, "部位数": 2, "部位特殊能力": "飛翔", "防護点": 4}
+            ],
+            "備考": "", "先制値": 16, "共通特殊能力": "暗視", "弱点": "雷属性ダメージ+3", "弱点値": 18,
+            "生命抵抗力": 14, "知名度": 12, "精神抵抗力": 13, "出典": "ML"
+        }))
+        .unwrap()
+    }
+
+    fn goblin() -> Monster {
+        serde_json::from_value(json!({
+            "Category": "蛮族", "Lv": 1, "Revision": 2.5, "data": "", "illust": "",
+            "movein": -1, "movein_des": "", "moveon": 10, "moveon_des": "", "name": "ゴブリン",
+            "part": [{"HP": 16, "MP": 12, "name": "", "コア": null, "命中力": 3, "回避力": 3, "打撃点": 2, "部位数": 1, "部位特殊能力": "", "防護点": 2}],
+            "備考": "", "先制値": 11, "共通特殊能力": "", "弱点": "", "弱点値": 11,
+            "生命抵抗力": 3, "知名度": 5, "精神抵抗力": 3
+        }))
+        .unwrap()
+    }
+
+    /// 書き出した行を API が返す形（文字列、末尾の空セルなし）にする
+    fn exported_rows(monsters: &[Monster], layout: &SheetLayout) -> Vec<Vec<String>> {
+        let mut row = layout.start_row;
+        let mut rows = Vec::new();
+        for monster in monsters {
+            for output in DataTransformer::transform_monster_with_layout(monster, row, layout) {
+                let mut cells: Vec<String> = output.values.into_iter().map(Option::unwrap_or_default).collect();
+                while cells.last().is_some_and(String::is_empty) {
+                    cells.pop();
+                }
+                rows.push(cells);
+            }
+            row += monster.part.len() * layout.rows_per_part;
+        }
+        rows
+    }
+
+    #[test]
+    fn test_round_trip_without_changes() {
+        let local = vec![goblin(), griffon()];
+        let layout = SheetLayout::default();
+        let rows = exported_rows(&local, &layout);
+
+        let outcome = SheetsImporter::new(layout).import_rows(&rows, &local).unwrap();
+
+        assert_eq!(outcome.records.len(), 2);
+        assert!(outcome.warnings.is_empty(), "{:?}", outcome.warnings);
+        assert_eq!(outcome.records[0].position, Some(0));
+        assert_eq!(outcome.records[1].position, Some(1));
+        assert!(outcome.records.iter().all(|r| !r.has_changes()), "{:?}", outcome.records[1].changes);
+
+        let griffon = &outcome.records[1].monster;
+        assert_eq!(griffon.weakness, "雷属性ダメージ+3");
+        assert_eq!(griffon.illust, "griffon");
+        assert_eq!(griffon.extra["出典"], "ML");
+        assert_eq!(griffon.part[1].damage, Some(6));
+    }
+
+    #[test]
+    fn test_apply_after_push_replaces_by_name() {
+        let dir = tempfile::tempdir().unwrap();
+        let first = dir.path().join("a.json");
+        let second = dir.path().join("b.json");
+        crate::io::save_json_array_file(&first, &[goblin()]).unwrap();
+        crate::io::save_json_array_file(&second, &[griffon()]).unwrap();
+        let mut monsters = DataSet::load_monsters(&[&first, &second]).unwrap();
+
+        let mut ogre = goblin();
+        ogre.name = "オーガ".to_string();
+        let mut edited = griffon();
+        edited.level = 9;
+        // 新規のモンスターが既存のモンスターより先に並ぶ（位置は追加前に求めたもの）
+        let changes = [
+            MonsterChange { monster: ogre, position: None, changes: Vec::new() },
+            MonsterChange { monster: edited, position: Some(1), changes: Vec::new() },
+        ];
+        for change in &changes {
+            change.apply(&mut monsters, &first).unwrap();
+        }
+        monsters.save().unwrap();
+
+        let names = |path: &Path| -> Vec<String> {
+            crate::io::load_json_array(path).unwrap().into_iter().map(|m| m.name).collect()
+        };
+        assert_eq!(names(&first), vec!["ゴブリン", "オーガ"]);
+        assert_eq!(names(&second), vec!["グリフォン"]);
+        assert_eq!(crate::io::load_json_array(&second).unwrap()[0].level, 9);
+    }
+
+    #[test]
+    fn test_edited_cells_are_diffed() {
+        let local = vec![griffon()];
+        let layout = SheetLayout::default();
+        let mut rows = exported_rows(&local, &layout);
+        rows[0][11] = "55".to_string(); // 胴体 HP
+        rows[0][19] = "17".to_string(); // 先制値
+        rows[0][27] = "25\n(四足)".to_string(); // moveon
+        rows[1][48] = "炎ダメ+2".to_string(); // 弱点
+        rows[2][15] = "8".to_string(); // 翼 MP
+        rows[2][19] = "99".to_string(); // 2部位目の先制値は無視する
+
+        let outcome = SheetsImporter::new(layout).import_rows(&rows, &local).unwrap();
+        let change = &outcome.records[0];
+        let fields: Vec<String> = change.changes.iter().map(|c| c.field.clone()).collect();
+
+        assert_eq!(fields, vec!["moveon", "先制値", "弱点", "部位1(胴体).HP", "部位2(翼).MP"]);
+        assert_eq!(change.monster.initiative, 17);
+        assert_eq!(change.monster.weakness, "炎属性ダメージ+2");
+        assert_eq!(change.changes[3].to_string(), "部位1(胴体).HP: 50 → 55");
+        assert_eq!(outcome.warnings.len(), 1);
+        assert_eq!(outcome.warnings[0].field, "弱点");
+    }
+
+    #[test]
+    fn test_new_monster_and_removed_part() {
+        let local = vec![griffon()];
+        let layout = SheetLayout::default();
+        let mut rows = exported_rows(&[griffon(), goblin()], &layout);
+        rows.truncate(2); // グリフォンの翼を削除
+        rows.push(Vec::new()); // 空の部位は読み飛ばす
+        rows.push(Vec::new());
+        rows.extend(exported_rows(&[goblin()], &layout));
+
+        let outcome = SheetsImporter::new(layout).import_rows(&rows, &local).unwrap();
+
+        assert_eq!(outcome.records.len(), 2);
+        let griffon = &outcome.records[0];
+        assert_eq!(griffon.monster.part.len(), 1);
+        assert_eq!(griffon.changes.len(), 1);
+        assert_eq!(griffon.changes[0].field, "部位2(翼)");
+        assert_eq!(griffon.changes[0].new, None);
+
+        let goblin = &outcome.records[1];
+        assert!(goblin.is_new());
+        assert!(goblin.has_changes());
+        assert_eq!(goblin.monster.part[0].hp, Some(16));
+        assert_eq!(goblin.monster.part[0].damage, None);
+        assert_eq!(goblin.monster.fame, 5);
+        assert_eq!(goblin.monster.moveon, 10);
+        assert!(outcome.warnings.iter().any(|w| w.record == "ゴブリン" && w.message.contains("Lv")));
+    }
+
+    #[test]
+    fn test_same_name_monsters_are_skipped() {
+        let layout = SheetLayout::default();
+        let rows = exported_rows(&[goblin(), goblin()], &layout);
+
+        let outcome = SheetsImporter::new(layout).import_rows(&rows, &[goblin()]).unwrap();
+
+        assert_eq!(outcome.records.len(), 1);
+        assert_eq!(outcome.records[0].monster.part.len(), 1);
+        assert_eq!(outcome.warnings.len(), 1);
+        assert!(outcome.warnings[0].message.starts_with("5行目"));
+    }
+
+    #[test]
+    fn test_invalid_cells_keep_local_values() {
+        let local = vec![goblin()];
+        let layout = SheetLayout::default();
+        let mut rows = exported_rows(&local, &layout);
+        rows[0][11] = "たくさん".to_string(); // HP
+        rows[0][17] = String::new(); // 防護点
+        rows[0][48] = "5".to_string(); // 知名度/弱点値
+
+        let outcome = SheetsImporter::new(layout).import_rows(&rows, &local).unwrap();
+
+        assert!(!outcome.records[0].has_changes());
+        let fields: Vec<&str> = outcome.warnings.iter().map(|w| w.field.as_str()).collect();
+        assert_eq!(fields, vec!["部位1.HP", "部位1.防護点", "知名度/弱点値"]);
+    }
+
+    #[test]
+    fn test_custom_layout_and_errors() {
+        let layout: SheetLayout = toml::from_str(
+            "sheet_name = \"s\"\nstart_row = 2\nrows_per_part = 1\ncells = [{ column = \"B\", field = \"name\" }, { column = \"C\", field = \"HP\" }]",
+        )
+        .unwrap();
+        let rows = vec![vec![String::new(), "★ゴブリン".to_string(), "20".to_string()]];
+
+        let outcome = SheetsImporter::new(layout.clone()).import_rows(&rows, &[goblin()]).unwrap();
+        let fields: Vec<&str> = outcome.records[0].changes.iter().map(|c| c.field.as_str()).collect();
+        assert_eq!(fields, vec!["部位1.HP", "部位1.コア"]);
+        assert_eq!(outcome.records[0].monster.part[0].core, Some(true));
+        assert_eq!(outcome.records[0].monster.level, 1);
+
+        let err = SheetsImporter::new(layout.clone()).import_rows(&[Vec::new()], &[]).unwrap_err();
+        assert!(matches!(err, ImportError::Empty(_)));
+
+        let no_name = SheetLayout { cells: layout.cells[1..].to_vec(), ..layout };
+        let err = SheetsImporter::new(no_name).import_rows(&rows, &[]).unwrap_err();
+        assert!(err.to_string().contains("no 'name' cell"));
+    }
+
+    #[test]
+    fn test_parse_helpers() {
+        assert_eq!(parse_display_name("★グリフォン\n(胴体)"), ("グリフォン".to_string(), "胴体".to_string(), true));
+        assert_eq!(parse_display_name("ゴブリン"), ("ゴブリン".to_string(), String::new(), false));
+        assert_eq!(parse_display_name("ゴブリン_1"), ("ゴブリン_1".to_string(), String::new(), false));
+        // 手で編集されたセル
+        assert_eq!(parse_display_name("★グリフォン \n(胴体)"), ("グリフォン".to_string(), "胴体".to_string(), true));
+        assert_eq!(parse_display_name("グリフォン\n胴体"), ("グリフォン".to_string(), "胴体".to_string(), false));
+        assert_eq!(parse_display_name("グリフォン\n ( 翼 ) "), ("グリフォン".to_string(), "翼".to_string(), false));
+        assert_eq!(parse_movement("-"), Some((-1, String::new())));
+        assert_eq!(parse_movement("20\n(四足)"), Some((20, "四足".to_string())));
+        assert_eq!(parse_movement("20"), Some((20, String::new())));
+        assert_eq!(parse_movement("速い"), None);
+        assert_eq!(expand_weakness("純Eダメ+2"), "純エネルギー属性ダメージ+2");
+        assert_eq!(expand_weakness("炎属性ダメージ+3"), "炎属性ダメージ+3");
+        assert_eq!(expand_weakness("魔法ダメ+2"), "魔法ダメージ+2");
+    }
+}
@@ -0,0 +1,507 @@
+use super::{ImportError, ImportOutcome, ImportWarning};
+use crate::export::udonarium::data_transformer::DataTransformer;
+use crate::export::udonarium::part_namer::PartNamer;
+use crate::{Monster, Part};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+/// Udonarium のキャラクターデータに含まれないため既定値を設定する項目
+const MISSING_FIELDS: &str = "Revision, data, illust, movein, movein_des, moveon, moveon_des, 備考, 部位数";
+
+/// Udonarium インポーター（UdonariumExporter の逆変換）
+///
+/// `<character>` ごとの XML を部位として読み込み、表示名（PartNamer の命名規則）で
+/// 同じモンスターの部位をまとめて Monster を復元する。
+pub struct UdonariumImporter;
+
+impl UdonariumImporter {
+    /// Udonarium の ZIP ファイルから読み込む
+    ///
+    /// # 引数
+    /// * `path` - ZIP ファイルのパス（XML 以外のファイルは無視する）
+    ///
+    /// # 戻り値
+    /// 復元したモンスターと、復元できなかった項目の一覧
+    ///
+    /// # エラー
+    /// ZIP・XML を読み込めない場合、キャラクターデータが1つもない場合
+    pub fn import_zip<P: AsRef<Path>>(path: P) -> Result<ImportOutcome<Monster>, ImportError> {
+        let file = File::open(path)?;
+        let mut archive = zip::ZipArchive::new(file).map_err(|e| ImportError::ZipError(e.to_string()))?;
+
+        let mut documents = Vec::new();
+        for i in 0..archive.len() {
+            let mut entry = archive.by_index(i).map_err(|e| ImportError::ZipError(e.to_string()))?;
+            if !entry.is_file() || !entry.name().to_lowercase().ends_with(".xml") {
+                continue;
+            }
+            let mut content = String::new();
+            entry.read_to_string(&mut content)?;
+            documents.push((entry.name().to_string(), content));
+        }
+
+        Self::import_documents(&documents)
+    }
+
+    /// XML 文書（ファイル名, 内容）の一覧から読み込む
+    ///
+    /// 1つの文書に複数の `<character>` が含まれていてもよい（ルームデータなど）。
+    ///
+    /// # エラー
+    /// XML が整形式でない場合、キャラクターデータが1つもない場合
+    pub fn import_documents(documents: &[(String, String)]) -> Result<ImportOutcome<Monster>, ImportError> {
+        let mut characters = Vec::new();
+        for (file, xml) in documents {
+            characters.extend(parse_characters(file, xml)?);
+        }
+        if characters.is_empty() {
+            return Err(ImportError::Empty("no <character> element".to_string()));
+        }
+
+        // 表示名からモンスター名・部位名を取り出し、出現順にまとめる
+        let mut groups: Vec<(String, Vec<(String, CharacterData)>)> = Vec::new();
+        for character in characters {
+            let display_name = match character.value("name") {
+                Some(name) if !name.trim().is_empty() => name.to_string(),
+                _ => file_stem(&character.source),
+            };
+            let (monster_name, part_name) = PartNamer::parse_display_name(&display_name, character.is_core());
+            match groups.iter_mut().find(|(name, _)| *name == monster_name) {
+                Some((_, parts)) => parts.push((part_name, character)),
+                None => groups.push((monster_name, vec![(part_name, character)])),
+            }
+        }
+
+        let mut warnings = Vec::new();
+        let records = groups
+            .into_iter()
+            .map(|(name, parts)| {
+                let mut recovery = Recovery { record: name, warnings: Vec::new() };
+                let monster = recovery.monster(&parts);
+                warnings.append(&mut recovery.warnings);
+                monster
+            })
+            .collect();
+
+        Ok(ImportOutcome { records, warnings })
+    }
+}
+
+/// 1キャラクター（1部位）分のデータ
+#[derive(Debug, Default)]
+struct CharacterData {
+    /// 読み込み元のファイル名
+    source: String,
+    /// `<data name="...">` の値（子要素を持たない要素のみ）
+    values: HashMap<String, String>,
+    /// 子要素を持つ `<data name="...">`（セクション）
+    sections: HashSet<String>,
+}
+
+impl CharacterData {
+    fn value(&self, name: &str) -> Option<&str> {
+        self.values.get(name).map(String::as_str)
+    }
+
+    /// コア部位のみが持つ戦闘準備セクションの有無で判定
+    fn is_core(&self) -> bool {
+        self.sections.contains("戦闘準備") || self.values.contains_key("魔物知識・先制判定")
+    }
+}
+
+/// 復元処理（警告を集めながら値を取り出す）
+struct Recovery {
+    record: String,
+    warnings: Vec<ImportWarning>,
+}
+
+impl Recovery {
+    fn warn(&mut self, field: &str, message: impl Into<String>) {
+        self.warnings.push(ImportWarning::new(&self.record, field, message));
+    }
+
+    /// 整数値を取り出す（ない場合・数値でない場合は警告して None）
+    fn int(&mut self, character: &CharacterData, name: &str, field: &str) -> Option<i32> {
+        match character.value(name).map(str::trim) {
+            None => {
+                self.warn(field, "値がありません");
+                None
+            }
+            Some(text) => match text.parse::<i32>() {
+                Ok(v) => Some(v),
+                Err(_) => {
+                    self.warn(field, format!("数値ではありません: {:?}", text));
+                    None
+                }
+            },
+        }
+    }
+
+    /// adjust_value（-7）された値を元に戻す
+    fn adjusted(&mut self, character: &CharacterData, name: &str, field: &str) -> Option<i32> {
+        let value = self.int(character, name, field)?;
+        if value == 0 {
+            self.warn(field, "0 のため元の値を特定できません（7 以下、7 として復元）");
+        }
+        Some(DataTransformer::restore_value(value))
+    }
+
+    fn monster(&mut self, parts: &[(String, CharacterData)]) -> Monster {
+        let core_index = parts.iter().position(|(_, c)| c.is_core());
+        let main = &parts[core_index.unwrap_or(0)].1;
+        if core_index.is_none() {
+            self.warn("コア", "コア部位がないため Category, Lv, 知名度, 弱点値, 先制値, 弱点 を復元できません");
+        }
+
+        // 生態: "{Category} Lv.{Lv}"
+        let (category, level) = match main.value("生態").and_then(|v| v.rsplit_once(" Lv.")) {
+            Some((category, level)) => {
+                let level = level.trim().parse::<i32>().ok().filter(|lv| *lv >= 1);
+                if level.is_none() {
+                    self.warn("Lv", "レベルを読み取れません（1 として復元）");
+                }
+                (category.to_string(), level.unwrap_or(1))
+            }
+            None => {
+                if core_index.is_some() {
+                    self.warn("Category", "生態の値がないため Category, Lv を復元できません");
+                }
+                (String::new(), 1)
+            }
+        };
+
+        // 魔物知識・先制判定: "{知名度}/{弱点値}\n{先制値}"
+        let (fame, weakness_value, initiative) = match main.value("魔物知識・先制判定") {
+            Some(text) => {
+                let (knowledge, initiative) = text.split_once('\n').unwrap_or((text, ""));
+                let (fame, weakness_value) = knowledge.split_once('/').unwrap_or((knowledge, ""));
+                let mut number = |field: &str, s: &str| {
+                    s.trim().parse::<i32>().unwrap_or_else(|_| {
+                        self.warn(field, format!("数値ではありません: {:?}", s.trim()));
+                        0
+                    })
+                };
+                (number("知名度", fame), number("弱点値", weakness_value), number("先制値", initiative))
+            }
+            None => {
+                if core_index.is_some() {
+                    self.warn("先制値", "魔物知識・先制判定の値がないため 知名度, 弱点値, 先制値 を復元できません");
+                }
+                (0, 0, 0)
+            }
+        };
+
+        let weakness = match main.value("弱点") {
+            Some(text) => {
+                let restored = DataTransformer::restore_weakness(text);
+                if DataTransformer::transform_weakness(&restored) != text {
+                    self.warn("弱点", format!("表記を復元できません（{:?} のまま読み込み）", text));
+                    text.to_string()
+                } else {
+                    restored
+                }
+            }
+            None => String::new(),
+        };
+
+        let common_abilities = main.value("特殊能力1").unwrap_or_default().to_string();
+        if parts.iter().any(|(_, c)| c.value("特殊能力1").is_some_and(|v| v != common_abilities)) {
+            self.warn("共通特殊能力", "部位ごとに異なるため、コア部位の値を使用しました");
+        }
+
+        let life_resistance = self.adjusted(main, "生命抵抗力", "生命抵抗力").unwrap_or(0);
+        let mental_resistance = self.adjusted(main, "精神抵抗力", "精神抵抗力").unwrap_or(0);
+
+        let part = parts.iter().map(|(name, character)| self.part(name, character)).collect();
+
+        self.warn("その他", format!("{} は Udonarium データに含まれないため既定値を設定しました", MISSING_FIELDS));
+
+        Monster {
+            category,
+            level,
+            revision: 2.5,
+            data: String::new(),
+            illust: String::new(),
+            movein: -1,
+            movein_description: String::new(),
+            moveon: -1,
+            moveon_description: String::new(),
+            name: self.record.clone(),
+            part,
+            notes: String::new(),
+            initiative,
+            common_abilities,
+            weakness,
+            weakness_value,
+            life_resistance,
+            fame,
+            mental_resistance,
+            extra: HashMap::new(),
+        }
+    }
+
+    fn part(&mut self, name: &str, character: &CharacterData) -> Part {
+        let field = |f: &str| if name.is_empty() { f.to_string() } else { format!("{}.{}", name, f) };
+
+        let mp = match self.int(character, "MP", &field("MP")) {
+            Some(0) => {
+                self.warn(&field("MP"), "0 のため MP なし（-1）として復元しました");
+                -1
+            }
+            Some(mp) => mp,
+            None => -1,
+        };
+
+        Part {
+            hp: self.int(character, "HP", &field("HP")),
+            mp,
+            name: name.to_string(),
+            core: Some(character.is_core()),
+            hit_rate: self.adjusted(character, "命中力", &field("命中力")),
+            dodge: self.adjusted(character, "回避力", &field("回避力")),
+            damage: self.int(character, "打撃点", &field("打撃点")),
+            part_count: 1,
+            special_abilities: character.value("特殊能力2").unwrap_or_default().to_string(),
+            armor: self.int(character, "防護点", &field("防護点")).unwrap_or(0),
+        }
+    }
+}
+
+/// XML 文書から `<character>` 要素を読み込む
+fn parse_characters(file: &str, xml: &str) -> Result<Vec<CharacterData>, ImportError> {
+    let error = |message: String| ImportError::XmlError { file: file.to_string(), message };
+
+    let mut reader = Reader::from_str(xml);
+    let mut characters = Vec::new();
+    let mut current: Option<CharacterData> = None;
+    // 開いている data 要素の（name 属性, 子要素の有無）
+    let mut stack: Vec<(Option<String>, bool)> = Vec::new();
+    let mut text = String::new();
+
+    loop {
+        match reader.read_event().map_err(|e| error(e.to_string()))? {
+            Event::Start(e) => match e.name().as_ref() {
+                b"character" => {
+                    current = Some(CharacterData { source: file.to_string(), ..Default::default() });
+                    stack.clear();
+                }
+                b"data" if current.is_some() => {
+                    if let Some(parent) = stack.last_mut() {
+                        parent.1 = true;
+                    }
+                    stack.push((name_attribute(&e).map_err(error)?, false));
+                    text.clear();
+                }
+                _ => {}
+            },
+            Event::Empty(e) => {
+                if e.name().as_ref() == b"data"
+                    && let Some(character) = current.as_mut()
+                {
+                    if let Some(parent) = stack.last_mut() {
+                        parent.1 = true;
+                    }
+                    if let Some(name) = name_attribute(&e).map_err(error)? {
+                        character.values.entry(name).or_default();
+                    }
+                }
+            }
+            Event::Text(t) => text.push_str(&t.unescape().map_err(|e| error(e.to_string()))?),
+            Event::CData(c) => text.push_str(&String::from_utf8_lossy(&c.into_inner())),
+            Event::End(e) => match e.name().as_ref() {
+                b"data" => {
+                    if let (Some((name, has_children)), Some(character)) = (stack.pop(), current.as_mut())
+                        && let Some(name) = name
+                    {
+                        if has_children {
+                            character.sections.insert(name);
+                        } else {
+                            character.values.entry(name).or_insert_with(|| text.clone());
+                        }
+                    }
+                    text.clear();
+                }
+                b"character" => characters.extend(current.take()),
+                _ => {}
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    Ok(characters)
+}
+
+/// name 属性の値
+fn name_attribute(element: &BytesStart) -> Result<Option<String>, String> {
+    match element.try_get_attribute("name").map_err(|e| e.to_string())? {
+        Some(attr) => Ok(Some(attr.unescape_value().map_err(|e| e.to_string())?.into_owned())),
+        None => Ok(None),
+    }
+}
+
+/// ZIP 内のファイル名から拡張子とディレクトリを除いた名前
+fn file_stem(path: &str) -> String {
+    Path::new(path).file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export::udonarium::UdonariumExporter;
+    use crate::export::{DataExporter, ExportConfig, ExportFormat};
+    use crate::validate::{self, DataKind};
+    use tempfile::TempDir;
+
+    fn part(name: &str, core: bool, hp: i32, mp: i32) -> Part {
+        Part {
+            hp: Some(hp),
+            mp,
+            name: name.to_string(),
+            core: Some(core),
+            hit_rate: Some(14),
+            dodge: Some(13),
+            damage: Some(5),
+            part_count: 1,
+            special_abilities: if core { "▽再生=5点".to_string() } else { String::new() },
+            armor: 4,
+        }
+    }
+
+    fn monster(name: &str, parts: Vec<Part>) -> Monster {
+        Monster {
+            category: "植物".to_string(),
+            level: 7,
+            revision: 2.5,
+            data: "TEST".to_string(),
+            illust: "".to_string(),
+            movein: -1,
+            movein_description: "".to_string(),
+            moveon: 10,
+            moveon_description: "".to_string(),
+            name: name.to_string(),
+            part: parts,
+            notes: "".to_string(),
+            initiative: 12,
+            common_abilities: "●弱点看破 & <毒>".to_string(),
+            weakness: "炎属性ダメージ+3".to_string(),
+            weakness_value: 16,
+            life_resistance: 15,
+            fame: 13,
+            mental_resistance: 14,
+            extra: HashMap::new(),
+        }
+    }
+
+    /// エクスポートした ZIP を読み戻す
+    fn round_trip(monsters: &[Monster]) -> ImportOutcome<Monster> {
+        let dir = TempDir::new().unwrap();
+        let zip_path = dir.path().join("export.zip");
+        let config = ExportConfig {
+            destination: zip_path.to_string_lossy().to_string(),
+            format: ExportFormat::Udonarium,
+        };
+        UdonariumExporter::default().export(monsters, &config).unwrap();
+        UdonariumImporter::import_zip(&zip_path).unwrap()
+    }
+
+    #[test]
+    fn test_round_trip_multi_part_monster() {
+        let original = monster(
+            "トレント",
+            vec![part("幹", true, 60, 20), part("根", false, 30, -1), part("根", false, 30, -1)],
+        );
+        let outcome = round_trip(std::slice::from_ref(&original));
+
+        assert_eq!(outcome.records.len(), 1);
+        let restored = &outcome.records[0];
+        assert_eq!(restored.name, "トレント");
+        assert_eq!(restored.category, "植物");
+        assert_eq!(restored.level, 7);
+        assert_eq!(restored.fame, 13);
+        assert_eq!(restored.weakness_value, 16);
+        assert_eq!(restored.initiative, 12);
+        assert_eq!(restored.weakness, "炎属性ダメージ+3");
+        assert_eq!(restored.common_abilities, original.common_abilities);
+        assert_eq!(restored.life_resistance, 15);
+        assert_eq!(restored.mental_resistance, 14);
+
+        let names: Vec<&str> = restored.part.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["幹", "根", "根"]);
+        for (restored, original) in restored.part.iter().zip(&original.part) {
+            assert_eq!(restored.hp, original.hp);
+            assert_eq!(restored.mp, original.mp);
+            assert_eq!(restored.core, original.core);
+            assert_eq!(restored.hit_rate, original.hit_rate);
+            assert_eq!(restored.dodge, original.dodge);
+            assert_eq!(restored.damage, original.damage);
+            assert_eq!(restored.armor, original.armor);
+            assert_eq!(restored.special_abilities, original.special_abilities);
+        }
+
+        // 根の MP 0 は MP なしとして復元し、警告する
+        assert!(outcome.warnings.iter().any(|w| w.field == "根.MP"));
+        assert!(outcome.warnings.iter().any(|w| w.field == "その他"));
+    }
+
+    #[test]
+    fn test_round_trip_groups_monsters_and_unnamed_parts() {
+        let goblin = monster("ゴブリン", vec![part("", true, 16, 8)]);
+        let swarm = monster("スウォーム", vec![part("", true, 20, -1), part("", false, 10, -1)]);
+        let outcome = round_trip(&[goblin, swarm]);
+
+        let names: Vec<&str> = outcome.records.iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(names, vec!["ゴブリン", "スウォーム"]);
+        assert_eq!(outcome.records[1].part.len(), 2);
+        assert_eq!(outcome.records[1].part[1].core, Some(false));
+    }
+
+    #[test]
+    fn test_imported_monsters_pass_schema_validation() {
+        let outcome = round_trip(&[monster("トレント", vec![part("幹", true, 60, 20), part("根", false, 30, 5)])]);
+        for record in &outcome.records {
+            let value = serde_json::to_value(record).unwrap();
+            assert!(validate::validate_record(DataKind::Monster, &value).is_empty());
+        }
+    }
+
+    #[test]
+    fn test_hand_built_character_reports_missing_fields() {
+        let xml = r#"<?xml version="1.0" encoding="utf-8"?>
+<character>
+  <data name="character">
+    <data name="common"><data name="name">謎の魔物</data></data>
+    <data name="detail">
+      <data name="リソース"><data type="numberResource" currentValue="5" name="HP">30</data></data>
+      <data name="ステータス・バフ・デバフ"><data name="命中力" type="number">0</data></data>
+    </data>
+  </data>
+</character>"#;
+        let outcome = UdonariumImporter::import_documents(&[("謎.xml".to_string(), xml.to_string())]).unwrap();
+
+        let restored = &outcome.records[0];
+        assert_eq!(restored.name, "謎の魔物");
+        assert_eq!(restored.part[0].hp, Some(30));
+        assert_eq!(restored.part[0].hit_rate, Some(7));
+        assert_eq!(restored.part[0].dodge, None);
+        let fields: Vec<&str> = outcome.warnings.iter().map(|w| w.field.as_str()).collect();
+        assert!(fields.contains(&"コア"));
+        assert!(fields.contains(&"命中力"));
+        assert!(fields.contains(&"回避力"));
+        assert!(fields.contains(&"MP"));
+    }
+
+    #[test]
+    fn test_malformed_xml_is_an_error() {
+        let documents = [("broken.xml".to_string(), "<character><data name=\"a\"></character>".to_string())];
+        assert!(matches!(
+            UdonariumImporter::import_documents(&documents),
+            Err(ImportError::XmlError { .. })
+        ));
+        assert!(matches!(UdonariumImporter::import_documents(&[]), Err(ImportError::Empty(_))));
+    }
+}
@@ -1,6 +1,7 @@
 use crate::{Monster, Spell};
 use std::fs;
-use std::path::Path;
+use std::io::Write;
+use std::path::{Path, PathBuf};
 
 /// I/O操作時のエラー型
 #[derive(thiserror::Error, Debug)]
@@ -82,8 +83,7 @@ pub fn save_json_array_stdout(monsters: &[Monster]) -> Result<(), IoError> {
 /// * `Err(IoError)` - 保存に失敗した場合
 pub fn save_json_array_file<P: AsRef<Path>>(path: P, monsters: &[Monster]) -> Result<(), IoError> {
     let json = serde_json::to_string_pretty(monsters)?;
-    fs::write(path, json)?;
-    Ok(())
+    write_atomic(path, json.as_bytes())
 }
 
 /// 複数の JSON ファイルから Monster 配列をロードして統合
@@ -181,8 +181,7 @@ pub fn save_spells_json_array_stdout(spells: &[Spell]) -> Result<(), IoError> {
 /// * `Err(IoError)` - 保存に失敗した場合
 pub fn save_spells_json_array_file<P: AsRef<Path>>(path: P, spells: &[Spell]) -> Result<(), IoError> {
     let json = serde_json::to_string_pretty(spells)?;
-    fs::write(path, json)?;
-    Ok(())
+    write_atomic(path, json.as_bytes())
 }
 
 /// 複数の JSON ファイルから Spell 配列をロードして統合
@@ -209,6 +208,43 @@ pub fn load_multiple_spells_json_arrays<P: AsRef<Path>>(paths: &[P]) -> Result<V
     Ok(all_spells)
 }
 
+/// ファイルをアトミックに書き込む
+///
+/// 同じディレクトリの一時ファイルに書き込んで同期した後、リネームで置き換えます。
+/// 書き込み途中で中断されても元のファイルは壊れません。
+///
+/// # 引数
+/// * `path` - 保存先ファイルのパス
+/// * `contents` - 書き込む内容
+///
+/// # エラー
+/// - 一時ファイルの作成・書き込み・リネームに失敗した場合は `IoError::FileRead`
+pub fn write_atomic<P: AsRef<Path>>(path: P, contents: &[u8]) -> Result<(), IoError> {
+    let path = path.as_ref();
+    let temp_path = temp_path_for(path);
+
+    let result = (|| {
+        let mut file = fs::File::create(&temp_path)?;
+        file.write_all(contents)?;
+        file.sync_all()?;
+        fs::rename(&temp_path, path)
+    })();
+
+    if result.is_err() {
+        let _ = fs::remove_file(&temp_path);
+    }
+    Ok(result?)
+}
+
+/// 保存先と同じディレクトリに置く一時ファイルのパス（例: `.monsters.json.1234.tmp`）
+fn temp_path_for(path: &Path) -> PathBuf {
+    let file_name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    path.with_file_name(format!(".{}.{}.tmp", file_name, std::process::id()))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -431,6 +467,26 @@ mod tests {
         assert!(result.is_ok(), "空配列の出力は成功するべき");
     }
 
+    #[test]
+    fn test_write_atomic_replaces_file() {
+        let dir = tempfile::TempDir::new().expect("Failed to create temp dir");
+        let path = dir.path().join("monsters.json");
+        fs::write(&path, "[]").unwrap();
+
+        write_atomic(&path, b"[1]").expect("書き込みは成功するべき");
+        assert_eq!(fs::read_to_string(&path).unwrap(), "[1]");
+
+        // 一時ファイルが残っていないこと
+        let entries: Vec<_> = fs::read_dir(dir.path()).unwrap().collect();
+        assert_eq!(entries.len(), 1, "一時ファイルは残らないべき");
+    }
+
+    #[test]
+    fn test_write_atomic_missing_directory() {
+        let result = write_atomic("/nonexistent/dir/monsters.json", b"[]");
+        assert!(matches!(result, Err(IoError::FileRead(_))));
+    }
+
     #[test]
     fn test_load_multiple_json_arrays() {
         // 複数の一時ファイルを作成
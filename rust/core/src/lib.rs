@@ -1,11 +1,21 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+pub mod backup;
+pub mod combat;
 pub mod config;
+pub mod dataset;
+pub mod dice;
+pub mod encounter;
 pub mod export;
+pub mod import;
 pub mod io;
 pub mod query;
+pub mod spell;
 pub mod stats;
+pub mod validate;
+
+pub use spell::Spell;
 
 /// モンスター部位データ
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -96,26 +106,9 @@ pub struct Monster {
     pub extra: HashMap<String, serde_json::Value>,
 }
 
-/// スペル（魔法）データ
-/// スキーマ: schema/spell_array.json
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Spell {
-    /// スペル名
-    pub name: String,
-
-    /// 系統（MagicCat_1 など）
-    pub school: String,
-
-    // 複雑なフィールド: Lv（値と種別）, MP（値と種別）, 対象（個別 or エリア）
-    // すべてを汎用 Value として受け入れる
-    #[serde(flatten)]
-    pub extra: HashMap<String, serde_json::Value>,
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
-    use serde_json;
 
     #[test]
     fn test_monster_serde_roundtrip() {
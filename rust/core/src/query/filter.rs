@@ -0,0 +1,1170 @@
+//! フィルタ式（クエリ式言語）
+//!
+//! `Lv >= 5 and Lv <= 8 and 弱点値 <= 15 and part.部位特殊能力 ~ "飛行"` のような
+//! 式を解析し、モンスターなどのレコードに対して評価します。
+//!
+//! # 文法
+//! - 比較: `==` `!=` `<` `<=` `>` `>=` `~`（部分一致） `!~`（部分不一致）
+//! - 論理: `and` / `&&`、`or` / `||`、`not` / `!`、括弧 `( )`
+//! - 範囲: `Lv in 5..8`（両端を含む、`5..` や `..8` も可）
+//! - 列挙: `Category in [蛮族, 動物]`
+//! - 部位: `part.HP >= 50`（いずれかの部位が一致）、
+//!   `part.any(HP >= 50 and コア == true)`、`part.all(防護点 >= 5)`
+//! - フィールドのみを書いた場合は真偽判定（`part.コア`）
+//! - 括弧などを含むフィールド名はバッククォートで囲む（`` `射程(m)` ``）
+//! - 右辺の文字列はクォート省略可（`Category == 蛮族`）
+//!
+//! # スペルのフィールド
+//! - `Lv`: レベル値（`value` / `value+`）、`rank`: ランク値、`Lv.kind`: 種別
+//! - `MP`: 消費MP（`value` / `value+` は数値、`special` は文字列）、`MP.kind`: 種別
+//! - `対象`: 個別対象またはエリア名、`対象.kind`: `個別` / `エリア`、`対象.半径`、`対象.末尾`
+//! - `時間`: 値（数値または `一瞬` など）、`時間.unit`: 単位
+//! - `補助` / `準備` / `犯罪`: 未指定の場合は false
+//! - `reference.book` など配列要素は、いずれかの要素が一致すれば真
+
+use crate::spell::{MpCost, Reference, Scalar, Target};
+use crate::{Monster, Part, Spell};
+use std::str::FromStr;
+use thiserror::Error;
+
+/// フィルタ式の解析エラー
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum FilterError {
+    #[error("フィルタ式が空です")]
+    Empty,
+
+    #[error("フィルタ式の解析エラー（{position}文字目）: {message}")]
+    Syntax { position: usize, message: String },
+}
+
+/// フィールドの値
+#[derive(Debug, Clone, PartialEq)]
+pub enum FieldValue {
+    Null,
+    Bool(bool),
+    Number(f64),
+    Text(String),
+}
+
+impl FieldValue {
+    /// JSON値をパスに沿って辿り、フィールド値に変換
+    pub fn from_json(value: &serde_json::Value, path: &[String]) -> FieldValue {
+        let mut current = value;
+        for key in path {
+            match current.get(key.as_str()) {
+                Some(v) => current = v,
+                None => return FieldValue::Null,
+            }
+        }
+        match current {
+            serde_json::Value::Null => FieldValue::Null,
+            serde_json::Value::Bool(b) => FieldValue::Bool(*b),
+            serde_json::Value::Number(n) => n.as_f64().map_or(FieldValue::Null, FieldValue::Number),
+            serde_json::Value::String(s) => FieldValue::Text(s.clone()),
+            other => FieldValue::Text(other.to_string()),
+        }
+    }
+
+    /// 数値として解釈（文字列の場合は数値変換を試みる）
+    fn as_number(&self) -> Option<f64> {
+        match self {
+            FieldValue::Number(n) => Some(*n),
+            FieldValue::Text(s) => s.trim().parse().ok(),
+            _ => None,
+        }
+    }
+
+    /// 部分一致判定用の文字列表現
+    fn as_text(&self) -> Option<String> {
+        match self {
+            FieldValue::Null => None,
+            FieldValue::Bool(b) => Some(b.to_string()),
+            FieldValue::Number(n) => Some(n.to_string()),
+            FieldValue::Text(s) => Some(s.clone()),
+        }
+    }
+
+    /// 真偽判定（フィールドのみを書いた場合に使用）
+    fn is_truthy(&self) -> bool {
+        match self {
+            FieldValue::Null => false,
+            FieldValue::Bool(b) => *b,
+            FieldValue::Number(n) => *n != 0.0,
+            FieldValue::Text(s) => !s.is_empty(),
+        }
+    }
+}
+
+impl From<i32> for FieldValue {
+    fn from(v: i32) -> Self {
+        FieldValue::Number(v as f64)
+    }
+}
+
+impl From<Option<i32>> for FieldValue {
+    fn from(v: Option<i32>) -> Self {
+        v.map_or(FieldValue::Null, FieldValue::from)
+    }
+}
+
+impl From<&str> for FieldValue {
+    fn from(v: &str) -> Self {
+        FieldValue::Text(v.to_string())
+    }
+}
+
+/// フィルタ式で評価できるレコード
+pub trait Queryable {
+    /// フィールドパス（"Lv"、"対象.kind" など）に対応する値を返す
+    fn field(&self, path: &[String]) -> FieldValue;
+
+    /// 子レコードの集合（モンスターの "part" など）を返す
+    fn children(&self, _name: &str) -> Option<Vec<&dyn Queryable>> {
+        None
+    }
+}
+
+impl Queryable for Monster {
+    fn field(&self, path: &[String]) -> FieldValue {
+        let Some((key, rest)) = path.split_first() else {
+            return FieldValue::Null;
+        };
+        if !rest.is_empty() {
+            return self
+                .extra
+                .get(key)
+                .map_or(FieldValue::Null, |v| FieldValue::from_json(v, rest));
+        }
+        match key.as_str() {
+            "Category" => self.category.as_str().into(),
+            "Lv" => self.level.into(),
+            "Revision" => FieldValue::Number(self.revision as f64),
+            "data" => self.data.as_str().into(),
+            "illust" => self.illust.as_str().into(),
+            "movein" => self.movein.into(),
+            "movein_des" => self.movein_description.as_str().into(),
+            "moveon" => self.moveon.into(),
+            "moveon_des" => self.moveon_description.as_str().into(),
+            "name" => self.name.as_str().into(),
+            "備考" => self.notes.as_str().into(),
+            "先制値" => self.initiative.into(),
+            "共通特殊能力" => self.common_abilities.as_str().into(),
+            "弱点" => self.weakness.as_str().into(),
+            "弱点値" => self.weakness_value.into(),
+            "生命抵抗力" => self.life_resistance.into(),
+            "知名度" => self.fame.into(),
+            "精神抵抗力" => self.mental_resistance.into(),
+            "部位数" => FieldValue::Number(self.part.iter().map(|p| p.part_count).sum::<i32>() as f64),
+            other => self
+                .extra
+                .get(other)
+                .map_or(FieldValue::Null, |v| FieldValue::from_json(v, &[])),
+        }
+    }
+
+    fn children(&self, name: &str) -> Option<Vec<&dyn Queryable>> {
+        match name {
+            "part" => Some(self.part.iter().map(|p| p as &dyn Queryable).collect()),
+            _ => None,
+        }
+    }
+}
+
+impl Queryable for Part {
+    fn field(&self, path: &[String]) -> FieldValue {
+        if path.len() != 1 {
+            return FieldValue::Null;
+        }
+        match path[0].as_str() {
+            "HP" => self.hp.into(),
+            "MP" => self.mp.into(),
+            "name" => self.name.as_str().into(),
+            "コア" => self.core.map_or(FieldValue::Null, FieldValue::Bool),
+            "命中力" => self.hit_rate.into(),
+            "回避力" => self.dodge.into(),
+            "打撃点" => self.damage.into(),
+            "部位数" => self.part_count.into(),
+            "部位特殊能力" => self.special_abilities.as_str().into(),
+            "防護点" => self.armor.into(),
+            _ => FieldValue::Null,
+        }
+    }
+}
+
+impl Queryable for serde_json::Value {
+    fn field(&self, path: &[String]) -> FieldValue {
+        FieldValue::from_json(self, path)
+    }
+
+    fn children(&self, name: &str) -> Option<Vec<&dyn Queryable>> {
+        match self.get(name)? {
+            serde_json::Value::Array(items) => {
+                Some(items.iter().map(|v| v as &dyn Queryable).collect())
+            }
+            _ => None,
+        }
+    }
+}
+
+impl Queryable for Spell {
+    fn field(&self, path: &[String]) -> FieldValue {
+        let Some((key, rest)) = path.split_first() else {
+            return FieldValue::Null;
+        };
+        let scalar = |v: &Scalar| match v {
+            Scalar::Int(i) => FieldValue::Number(*i as f64),
+            Scalar::Text(s) => FieldValue::Text(s.clone()),
+        };
+        let sub = rest.first().map(String::as_str);
+        match (key.as_str(), sub) {
+            ("name", None) => self.name.as_str().into(),
+            ("school", None) => self.school.as_str().into(),
+            // Lv: value / value+ はレベル値、rank はランク値として扱う
+            ("Lv", None) => self.level_value().into(),
+            ("Lv", Some("kind")) => self.level.map_or(FieldValue::Null, |lv| lv.kind().into()),
+            ("rank", None) => self.rank().into(),
+            ("MP", None) => match &self.mp {
+                Some(MpCost::Special(s)) => s.as_str().into(),
+                Some(mp) => mp.amount().into(),
+                None => FieldValue::Null,
+            },
+            ("MP", Some("kind")) => self.mp.as_ref().map_or(FieldValue::Null, |mp| mp.kind().into()),
+            // 対象: 個別対象の文字列、またはエリア名
+            ("対象", _) => match (&self.target, sub) {
+                (Some(target), Some("kind")) => target.kind().into(),
+                (Some(Target::Individual(s)), None) => s.as_str().into(),
+                (Some(Target::Area { value, .. }), None | Some("value")) => value.as_str().into(),
+                (Some(Target::Area { radius, .. }), Some("半径")) => radius.as_ref().map_or(FieldValue::Null, scalar),
+                (Some(Target::Area { suffix, .. }), Some("末尾")) => suffix.as_ref().map_or(FieldValue::Null, scalar),
+                _ => self.json_field(path),
+            },
+            ("時間", None) => self.duration.as_ref().map_or(FieldValue::Null, |d| scalar(&d.value)),
+            ("時間", Some("unit")) => self
+                .duration
+                .as_ref()
+                .and_then(|d| d.unit.as_deref())
+                .map_or(FieldValue::Null, FieldValue::from),
+            ("補助", None) => FieldValue::Bool(self.is_support()),
+            ("準備", None) => FieldValue::Bool(self.requires_preparation()),
+            ("犯罪", None) => FieldValue::Bool(self.is_crime()),
+            _ => self.json_field(path),
+        }
+    }
+
+    fn children(&self, name: &str) -> Option<Vec<&dyn Queryable>> {
+        match name {
+            "reference" => Some(
+                self.references
+                    .iter()
+                    .flatten()
+                    .map(|r| r as &dyn Queryable)
+                    .collect(),
+            ),
+            _ => None,
+        }
+    }
+}
+
+impl Spell {
+    /// JSON 表現からフィールドを取得（型付きアクセサで扱わないフィールド用）
+    fn json_field(&self, path: &[String]) -> FieldValue {
+        let Some((key, rest)) = path.split_first() else {
+            return FieldValue::Null;
+        };
+        self.get(key).map_or(FieldValue::Null, |v| FieldValue::from_json(&v, rest))
+    }
+}
+
+impl Queryable for Reference {
+    fn field(&self, path: &[String]) -> FieldValue {
+        match path {
+            [key] if key == "book" => self.book.as_deref().map_or(FieldValue::Null, FieldValue::from),
+            [key] if key == "value" || key == "page" => {
+                self.page.map_or(FieldValue::Null, |p| FieldValue::Number(p as f64))
+            }
+            _ => FieldValue::Null,
+        }
+    }
+}
+
+/// 比較演算子
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompareOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Contains,
+    NotContains,
+}
+
+/// フィルタ式の構文木
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    /// フィールドと値の比較
+    Compare {
+        path: Vec<String>,
+        op: CompareOp,
+        value: FieldValue,
+    },
+    /// 範囲判定（両端を含む）
+    Range {
+        path: Vec<String>,
+        min: Option<f64>,
+        max: Option<f64>,
+    },
+    /// 列挙判定
+    OneOf {
+        path: Vec<String>,
+        values: Vec<FieldValue>,
+    },
+    /// フィールドの真偽判定
+    Truthy { path: Vec<String> },
+    /// 子レコードに対する量化（`part.any(...)` / `part.all(...)`）
+    Quantified {
+        collection: String,
+        all: bool,
+        expr: Box<Expr>,
+    },
+}
+
+impl Expr {
+    /// レコードに対して式を評価
+    pub fn eval(&self, record: &dyn Queryable) -> bool {
+        match self {
+            Expr::And(a, b) => a.eval(record) && b.eval(record),
+            Expr::Or(a, b) => a.eval(record) || b.eval(record),
+            Expr::Not(e) => !e.eval(record),
+            Expr::Compare { path, op, value } => {
+                eval_path(record, path, &|v| compare(v, *op, value))
+            }
+            Expr::Range { path, min, max } => eval_path(record, path, &|v| {
+                v.as_number().is_some_and(|n| {
+                    min.is_none_or(|lo| n >= lo) && max.is_none_or(|hi| n <= hi)
+                })
+            }),
+            Expr::OneOf { path, values } => eval_path(record, path, &|v| {
+                values.iter().any(|candidate| compare(v, CompareOp::Eq, candidate))
+            }),
+            Expr::Truthy { path } => eval_path(record, path, &|v| v.is_truthy()),
+            Expr::Quantified { collection, all, expr } => match record.children(collection) {
+                Some(children) if *all => !children.is_empty() && children.iter().all(|c| expr.eval(*c)),
+                Some(children) => children.iter().any(|c| expr.eval(*c)),
+                None => false,
+            },
+        }
+    }
+}
+
+/// パスを解決して述語を適用
+/// 先頭が子レコード集合の名前の場合は、いずれかの子レコードが一致すれば真
+fn eval_path(record: &dyn Queryable, path: &[String], pred: &dyn Fn(&FieldValue) -> bool) -> bool {
+    if let Some((head, rest)) = path.split_first()
+        && !rest.is_empty()
+        && let Some(children) = record.children(head)
+    {
+        return children.iter().any(|c| eval_path(*c, rest, pred));
+    }
+    pred(&record.field(path))
+}
+
+/// 2つの値を比較
+fn compare(left: &FieldValue, op: CompareOp, right: &FieldValue) -> bool {
+    match op {
+        CompareOp::Contains | CompareOp::NotContains => {
+            let contains = match (left.as_text(), right.as_text()) {
+                (Some(l), Some(r)) => l.contains(&r),
+                _ => false,
+            };
+            (op == CompareOp::Contains) == contains
+        }
+        CompareOp::Eq | CompareOp::Ne => {
+            let equal = match (left, right) {
+                (FieldValue::Null, FieldValue::Null) => true,
+                (FieldValue::Null, _) | (_, FieldValue::Null) => false,
+                (FieldValue::Bool(a), FieldValue::Bool(b)) => a == b,
+                (FieldValue::Text(a), FieldValue::Text(b)) => a == b,
+                _ => match (left.as_number(), right.as_number()) {
+                    (Some(a), Some(b)) => a == b,
+                    _ => false,
+                },
+            };
+            (op == CompareOp::Eq) == equal
+        }
+        _ => {
+            let ordering = match (left, right) {
+                (FieldValue::Text(a), FieldValue::Text(b))
+                    if a.parse::<f64>().is_err() || b.parse::<f64>().is_err() =>
+                {
+                    Some(a.cmp(b))
+                }
+                _ => match (left.as_number(), right.as_number()) {
+                    (Some(a), Some(b)) => a.partial_cmp(&b),
+                    _ => None,
+                },
+            };
+            let Some(ordering) = ordering else {
+                return false;
+            };
+            match op {
+                CompareOp::Lt => ordering.is_lt(),
+                CompareOp::Le => ordering.is_le(),
+                CompareOp::Gt => ordering.is_gt(),
+                _ => ordering.is_ge(),
+            }
+        }
+    }
+}
+
+/// 解析済みフィルタ式
+#[derive(Debug, Clone, PartialEq)]
+pub struct Filter {
+    source: String,
+    expr: Expr,
+}
+
+impl Filter {
+    /// フィルタ式を解析
+    pub fn parse(source: &str) -> Result<Self, FilterError> {
+        let tokens = tokenize(source)?;
+        if tokens.is_empty() {
+            return Err(FilterError::Empty);
+        }
+        let mut parser = Parser { tokens, pos: 0, end: source.chars().count() };
+        let expr = parser.parse_or()?;
+        if let Some(token) = parser.peek() {
+            return Err(FilterError::Syntax {
+                position: token.position,
+                message: format!("予期しないトークン '{}'", token.kind),
+            });
+        }
+        Ok(Filter { source: source.to_string(), expr })
+    }
+
+    /// レコードがフィルタ式に一致するか判定
+    pub fn matches(&self, record: &dyn Queryable) -> bool {
+        self.expr.eval(record)
+    }
+
+    /// 元のフィルタ式文字列
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+
+    /// 構文木
+    pub fn expr(&self) -> &Expr {
+        &self.expr
+    }
+}
+
+impl FromStr for Filter {
+    type Err = FilterError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Filter::parse(s)
+    }
+}
+
+// ============================================================================
+// 字句解析
+// ============================================================================
+
+#[derive(Debug, Clone, PartialEq)]
+enum TokenKind {
+    Ident(String),
+    Text(String),
+    Number(f64),
+    Op(CompareOp),
+    And,
+    Or,
+    Not,
+    In,
+    True,
+    False,
+    Null,
+    LParen,
+    RParen,
+    LBracket,
+    RBracket,
+    Comma,
+    DotDot,
+}
+
+impl std::fmt::Display for TokenKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TokenKind::Ident(s) => write!(f, "{}", s),
+            TokenKind::Text(s) => write!(f, "\"{}\"", s),
+            TokenKind::Number(n) => write!(f, "{}", n),
+            TokenKind::Op(op) => write!(f, "{}", match op {
+                CompareOp::Eq => "==",
+                CompareOp::Ne => "!=",
+                CompareOp::Lt => "<",
+                CompareOp::Le => "<=",
+                CompareOp::Gt => ">",
+                CompareOp::Ge => ">=",
+                CompareOp::Contains => "~",
+                CompareOp::NotContains => "!~",
+            }),
+            TokenKind::And => write!(f, "and"),
+            TokenKind::Or => write!(f, "or"),
+            TokenKind::Not => write!(f, "not"),
+            TokenKind::In => write!(f, "in"),
+            TokenKind::True => write!(f, "true"),
+            TokenKind::False => write!(f, "false"),
+            TokenKind::Null => write!(f, "null"),
+            TokenKind::LParen => write!(f, "("),
+            TokenKind::RParen => write!(f, ")"),
+            TokenKind::LBracket => write!(f, "["),
+            TokenKind::RBracket => write!(f, "]"),
+            TokenKind::Comma => write!(f, ","),
+            TokenKind::DotDot => write!(f, ".."),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Token {
+    kind: TokenKind,
+    /// 1始まりの文字位置
+    position: usize,
+}
+
+/// 識別子に使用できない文字
+fn is_delimiter(c: char) -> bool {
+    c.is_whitespace() || "()[],\"'`=!<>~&|".contains(c)
+}
+
+/// 位置 `start` から数値リテラルを読み取り、終端位置を返す
+/// 「1体」「2エリア」のように数字に続けて文字がある場合は数値とみなさない
+fn scan_number(chars: &[char], start: usize) -> Option<usize> {
+    let mut j = start;
+    if chars.get(j) == Some(&'-') {
+        j += 1;
+    }
+    let digits_start = j;
+    while j < chars.len() {
+        let ch = chars[j];
+        let is_fraction = ch == '.' && chars.get(j + 1).is_some_and(|n| n.is_ascii_digit());
+        if ch.is_ascii_digit() || is_fraction {
+            j += 1;
+        } else {
+            break;
+        }
+    }
+    if j == digits_start {
+        return None;
+    }
+    match chars.get(j) {
+        Some(&ch) if !is_delimiter(ch) && ch != '.' => None,
+        _ => Some(j),
+    }
+}
+
+fn tokenize(source: &str) -> Result<Vec<Token>, FilterError> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let position = i + 1;
+        let next = chars.get(i + 1).copied();
+        let number_end = scan_number(&chars, i);
+
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+
+        let (kind, len) = match (c, next) {
+            ('(', _) => (TokenKind::LParen, 1),
+            (')', _) => (TokenKind::RParen, 1),
+            ('[', _) => (TokenKind::LBracket, 1),
+            (']', _) => (TokenKind::RBracket, 1),
+            (',', _) => (TokenKind::Comma, 1),
+            ('.', Some('.')) => (TokenKind::DotDot, 2),
+            ('=', Some('=')) => (TokenKind::Op(CompareOp::Eq), 2),
+            ('=', _) => (TokenKind::Op(CompareOp::Eq), 1),
+            ('!', Some('=')) => (TokenKind::Op(CompareOp::Ne), 2),
+            ('!', Some('~')) => (TokenKind::Op(CompareOp::NotContains), 2),
+            ('!', _) => (TokenKind::Not, 1),
+            ('<', Some('=')) => (TokenKind::Op(CompareOp::Le), 2),
+            ('<', _) => (TokenKind::Op(CompareOp::Lt), 1),
+            ('>', Some('=')) => (TokenKind::Op(CompareOp::Ge), 2),
+            ('>', _) => (TokenKind::Op(CompareOp::Gt), 1),
+            ('~', _) => (TokenKind::Op(CompareOp::Contains), 1),
+            ('&', Some('&')) => (TokenKind::And, 2),
+            ('|', Some('|')) => (TokenKind::Or, 2),
+            ('"' | '\'' | '`', _) => {
+                // クォート文字列（バッククォートは識別子）
+                let mut value = String::new();
+                let mut j = i + 1;
+                loop {
+                    match chars.get(j) {
+                        None => {
+                            return Err(FilterError::Syntax {
+                                position,
+                                message: "文字列が閉じられていません".to_string(),
+                            });
+                        }
+                        Some('\\') if c != '`' && j + 1 < chars.len() => {
+                            value.push(chars[j + 1]);
+                            j += 2;
+                        }
+                        Some(&ch) if ch == c => break,
+                        Some(&ch) => {
+                            value.push(ch);
+                            j += 1;
+                        }
+                    }
+                }
+                let kind = if c == '`' { TokenKind::Ident(value) } else { TokenKind::Text(value) };
+                (kind, j + 1 - i)
+            }
+            _ if number_end.is_some() => {
+                let j = number_end.unwrap_or(i);
+                let text: String = chars[i..j].iter().collect();
+                let number = text.parse::<f64>().map_err(|_| FilterError::Syntax {
+                    position,
+                    message: format!("数値として解釈できません: {}", text),
+                })?;
+                (TokenKind::Number(number), j - i)
+            }
+            _ if !is_delimiter(c) => {
+                let mut j = i;
+                while j < chars.len() && !is_delimiter(chars[j]) {
+                    if chars[j] == '.' && chars.get(j + 1) == Some(&'.') {
+                        break;
+                    }
+                    j += 1;
+                }
+                let word: String = chars[i..j].iter().collect();
+                let kind = match word.to_lowercase().as_str() {
+                    "and" => TokenKind::And,
+                    "or" => TokenKind::Or,
+                    "not" => TokenKind::Not,
+                    "in" => TokenKind::In,
+                    "true" => TokenKind::True,
+                    "false" => TokenKind::False,
+                    "null" => TokenKind::Null,
+                    _ => TokenKind::Ident(word),
+                };
+                (kind, j - i)
+            }
+            _ => {
+                return Err(FilterError::Syntax {
+                    position,
+                    message: format!("使用できない文字 '{}'", c),
+                });
+            }
+        };
+
+        tokens.push(Token { kind, position });
+        i += len;
+    }
+
+    Ok(tokens)
+}
+
+// ============================================================================
+// 構文解析（再帰下降）
+// ============================================================================
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    /// 入力末尾の文字位置（エラー表示用）
+    end: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn eat(&mut self, kind: &TokenKind) -> bool {
+        if self.peek().is_some_and(|t| &t.kind == kind) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn error<T>(&self, message: &str) -> Result<T, FilterError> {
+        let position = self.peek().map_or(self.end + 1, |t| t.position);
+        Err(FilterError::Syntax { position, message: message.to_string() })
+    }
+
+    fn expect(&mut self, kind: &TokenKind) -> Result<(), FilterError> {
+        if self.eat(kind) {
+            Ok(())
+        } else {
+            self.error(&format!("'{}' が必要です", kind))
+        }
+    }
+
+    fn parse_or(&mut self) -> Result<Expr, FilterError> {
+        let mut left = self.parse_and()?;
+        while self.eat(&TokenKind::Or) {
+            let right = self.parse_and()?;
+            left = Expr::Or(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_and(&mut self) -> Result<Expr, FilterError> {
+        let mut left = self.parse_not()?;
+        while self.eat(&TokenKind::And) {
+            let right = self.parse_not()?;
+            left = Expr::And(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_not(&mut self) -> Result<Expr, FilterError> {
+        if self.eat(&TokenKind::Not) {
+            let inner = self.parse_not()?;
+            return Ok(Expr::Not(Box::new(inner)));
+        }
+        self.parse_primary()
+    }
+
+    fn parse_primary(&mut self) -> Result<Expr, FilterError> {
+        if self.eat(&TokenKind::LParen) {
+            let expr = self.parse_or()?;
+            self.expect(&TokenKind::RParen)?;
+            return Ok(expr);
+        }
+
+        let path = match self.peek().map(|t| &t.kind) {
+            Some(TokenKind::Ident(name)) => name.clone(),
+            _ => return self.error("フィールド名が必要です"),
+        };
+        self.pos += 1;
+        let mut segments: Vec<String> = path.split('.').map(str::to_string).collect();
+        if segments.iter().any(|s| s.is_empty()) {
+            return self.error("フィールド名が不正です");
+        }
+
+        // 量化子: part.any(...) / part.all(...)
+        if segments.len() == 2
+            && matches!(segments[1].as_str(), "any" | "all")
+            && self.peek().is_some_and(|t| t.kind == TokenKind::LParen)
+        {
+            self.pos += 1;
+            let inner = self.parse_or()?;
+            self.expect(&TokenKind::RParen)?;
+            let all = segments[1] == "all";
+            return Ok(Expr::Quantified {
+                collection: segments.swap_remove(0),
+                all,
+                expr: Box::new(inner),
+            });
+        }
+
+        match self.peek().map(|t| t.kind.clone()) {
+            Some(TokenKind::Op(op)) => {
+                self.pos += 1;
+                let value = self.parse_value()?;
+                Ok(Expr::Compare { path: segments, op, value })
+            }
+            Some(TokenKind::In) => {
+                self.pos += 1;
+                self.parse_in(segments)
+            }
+            Some(TokenKind::Not) if self.tokens.get(self.pos + 1).is_some_and(|t| t.kind == TokenKind::In) => {
+                self.pos += 2;
+                let inner = self.parse_in(segments)?;
+                Ok(Expr::Not(Box::new(inner)))
+            }
+            _ => Ok(Expr::Truthy { path: segments }),
+        }
+    }
+
+    /// `in` の右辺（範囲または列挙）
+    fn parse_in(&mut self, path: Vec<String>) -> Result<Expr, FilterError> {
+        if self.eat(&TokenKind::LBracket) {
+            let mut values = Vec::new();
+            if !self.eat(&TokenKind::RBracket) {
+                loop {
+                    values.push(self.parse_value()?);
+                    if self.eat(&TokenKind::RBracket) {
+                        break;
+                    }
+                    self.expect(&TokenKind::Comma)?;
+                }
+            }
+            return Ok(Expr::OneOf { path, values });
+        }
+
+        let min = self.parse_optional_number();
+        self.expect(&TokenKind::DotDot)?;
+        let max = self.parse_optional_number();
+        if min.is_none() && max.is_none() {
+            return self.error("範囲の上限または下限が必要です");
+        }
+        Ok(Expr::Range { path, min, max })
+    }
+
+    fn parse_optional_number(&mut self) -> Option<f64> {
+        match self.peek().map(|t| &t.kind) {
+            Some(TokenKind::Number(n)) => {
+                let n = *n;
+                self.pos += 1;
+                Some(n)
+            }
+            _ => None,
+        }
+    }
+
+    /// 比較の右辺となる値（クォートなしの単語は文字列として扱う）
+    fn parse_value(&mut self) -> Result<FieldValue, FilterError> {
+        let value = match self.peek().map(|t| &t.kind) {
+            Some(TokenKind::Number(n)) => FieldValue::Number(*n),
+            Some(TokenKind::Text(s)) | Some(TokenKind::Ident(s)) => FieldValue::Text(s.clone()),
+            Some(TokenKind::True) => FieldValue::Bool(true),
+            Some(TokenKind::False) => FieldValue::Bool(false),
+            Some(TokenKind::Null) => FieldValue::Null,
+            _ => return self.error("比較する値が必要です"),
+        };
+        self.next();
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_monster() -> Monster {
+        let json_data = r#"
+        {
+            "Category": "蛮族",
+            "Lv": 6,
+            "Revision": 2.5,
+            "data": "TEST001",
+            "illust": "",
+            "movein": 22,
+            "movein_des": "飛行",
+            "moveon": 22,
+            "moveon_des": "",
+            "name": "複合型人型敵",
+            "part": [
+                {
+                    "HP": 48,
+                    "MP": 75,
+                    "name": "頭部",
+                    "コア": true,
+                    "命中力": 15,
+                    "回避力": 15,
+                    "打撃点": 6,
+                    "部位数": 1,
+                    "部位特殊能力": "魔法適性",
+                    "防護点": 5
+                },
+                {
+                    "HP": 43,
+                    "MP": -1,
+                    "name": "翼",
+                    "コア": false,
+                    "命中力": 16,
+                    "回避力": 14,
+                    "打撃点": 8,
+                    "部位数": 2,
+                    "部位特殊能力": "飛行",
+                    "防護点": 7
+                }
+            ],
+            "備考": "",
+            "先制値": 15,
+            "共通特殊能力": "強靭な皮膚",
+            "弱点": "炎属性ダメージ+3",
+            "弱点値": 14,
+            "生命抵抗力": 17,
+            "知名度": 15,
+            "精神抵抗力": 17,
+            "出典": { "book": "BookRef_Y", "page": 120 }
+        }"#;
+        serde_json::from_str(json_data).unwrap()
+    }
+
+    fn matches(expr: &str) -> bool {
+        Filter::parse(expr).unwrap().matches(&sample_monster())
+    }
+
+    #[test]
+    fn test_simple_comparisons() {
+        assert!(matches("Lv == 6"));
+        assert!(matches("Lv >= 5"));
+        assert!(!matches("Lv > 6"));
+        assert!(matches("Lv != 7"));
+        assert!(matches("弱点値 <= 15"));
+        assert!(matches("Revision == 2.5"));
+    }
+
+    #[test]
+    fn test_string_comparisons() {
+        assert!(matches("Category == \"蛮族\""));
+        assert!(matches("Category == 蛮族"));
+        assert!(matches("Category == '蛮族'"));
+        assert!(!matches("Category == 動物"));
+        assert!(matches("name ~ 人型"));
+        assert!(matches("弱点 !~ 雷"));
+    }
+
+    #[test]
+    fn test_boolean_operators() {
+        assert!(matches("Lv >= 5 and Lv <= 8"));
+        assert!(matches("Lv >= 5 && Lv <= 8"));
+        assert!(matches("Lv == 1 or Category == 蛮族"));
+        assert!(matches("Lv == 1 || Category == 蛮族"));
+        assert!(matches("not Lv == 1"));
+        assert!(matches("!(Lv == 1)"));
+        assert!(!matches("not (Lv >= 5 and Lv <= 8)"));
+    }
+
+    #[test]
+    fn test_operator_precedence() {
+        // and は or より優先される
+        assert!(matches("Lv == 1 and Lv == 2 or Lv == 6"));
+        assert!(!matches("Lv == 1 and (Lv == 2 or Lv == 6)"));
+    }
+
+    #[test]
+    fn test_range() {
+        assert!(matches("Lv in 5..8"));
+        assert!(matches("Lv in 6..6"));
+        assert!(!matches("Lv in 7..10"));
+        assert!(matches("Lv in 5.."));
+        assert!(matches("Lv in ..6"));
+        assert!(matches("Lv not in 1..3"));
+    }
+
+    #[test]
+    fn test_one_of() {
+        assert!(matches("Category in [蛮族, 動物]"));
+        assert!(matches("Lv in [5, 6, 7]"));
+        assert!(!matches("Category in [\"動物\", \"魔法生物\"]"));
+        assert!(!matches("Category in []"));
+    }
+
+    #[test]
+    fn test_part_predicates_any_semantics() {
+        assert!(matches("part.部位特殊能力 ~ \"飛行\""));
+        assert!(matches("part.HP >= 45"));
+        assert!(!matches("part.HP >= 50"));
+        assert!(matches("part.コア"));
+        assert!(matches("part.name == 翼"));
+    }
+
+    #[test]
+    fn test_part_quantifiers() {
+        assert!(matches("part.any(コア == true and HP >= 45)"));
+        assert!(!matches("part.any(コア == false and HP >= 45)"));
+        assert!(matches("part.all(防護点 >= 5)"));
+        assert!(!matches("part.all(防護点 >= 6)"));
+        assert!(matches("part.any(部位特殊能力 ~ 飛行) and Lv in 5..8"));
+    }
+
+    #[test]
+    fn test_total_part_count() {
+        assert!(matches("部位数 == 3"));
+    }
+
+    #[test]
+    fn test_extra_fields() {
+        assert!(matches("出典.book == BookRef_Y"));
+        assert!(matches("出典.page > 100"));
+        assert!(!matches("出典.chapter == 1"));
+        assert!(matches("存在しない == null"));
+        assert!(matches("出典.book != null"));
+    }
+
+    #[test]
+    fn test_unknown_field_matches_nothing() {
+        assert!(!matches("存在しない >= 1"));
+        assert!(!matches("存在しない ~ a"));
+    }
+
+    #[test]
+    fn test_request_example() {
+        assert!(matches(
+            "Lv >= 5 and Lv <= 8 and 弱点値 <= 15 and part.部位特殊能力 ~ \"飛行\""
+        ));
+    }
+
+    #[test]
+    fn test_backtick_field_name() {
+        let filter = Filter::parse("`射程(m)` <= 30").unwrap();
+        match filter.expr() {
+            Expr::Compare { path, .. } => assert_eq!(path, &vec!["射程(m)".to_string()]),
+            other => panic!("unexpected expr: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_negative_numbers() {
+        assert!(matches("part.MP == -1"));
+        assert!(matches("movein > -1"));
+    }
+
+    #[test]
+    fn test_parse_error_empty() {
+        assert_eq!(Filter::parse("   "), Err(FilterError::Empty));
+    }
+
+    #[test]
+    fn test_parse_error_positions() {
+        match Filter::parse("Lv >= ") {
+            Err(FilterError::Syntax { position, .. }) => assert_eq!(position, 7),
+            other => panic!("unexpected result: {:?}", other),
+        }
+        match Filter::parse("Lv >= 5 and (Lv <= 8") {
+            Err(FilterError::Syntax { message, .. }) => assert!(message.contains(")")),
+            other => panic!("unexpected result: {:?}", other),
+        }
+        match Filter::parse("Lv >= 5 Lv") {
+            Err(FilterError::Syntax { position, .. }) => assert_eq!(position, 9),
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_error_unterminated_string() {
+        assert!(Filter::parse("name == \"abc").is_err());
+    }
+
+    #[test]
+    fn test_parse_error_empty_range() {
+        assert!(Filter::parse("Lv in ..").is_err());
+    }
+
+    fn sample_spells() -> Vec<Spell> {
+        let json_data = r#"[
+            {
+                "reference": [{"book": "BookRef_Y", "value": 122}],
+                "school": "MagicCat_2",
+                "schoolVariant": "水・氷",
+                "Lv": {"kind": "value", "value": 13},
+                "name": "エリアスペル",
+                "MP": {"kind": "value", "value": 5},
+                "対象": {"kind": "エリア", "エリア": {"value": "1エリア", "半径(m)": 5, "末尾": "すべて"}},
+                "準備": true,
+                "時間": {"value": 2, "unit": "R"},
+                "射程": "術者",
+                "射程(m)": 30,
+                "形状": "起点指定",
+                "抵抗": "消滅",
+                "効果": "効果"
+            },
+            {
+                "reference": [{"book": "BookRef_X", "value": 104}],
+                "school": "MagicCat_1",
+                "Lv": {"kind": "value+", "value+": 4},
+                "name": "個別スペル",
+                "MP": {"kind": "special", "special": "効果参照"},
+                "対象": {"kind": "個別", "個別": "1体"},
+                "補助": true,
+                "時間": {"value": "一瞬"},
+                "射程": "接触",
+                "射程(m)": 10,
+                "抵抗": "必中",
+                "属性": "炎",
+                "効果": "効果"
+            },
+            {
+                "school": "MagicCat_3",
+                "Lv": {"kind": "rank", "rank": 2},
+                "name": "ランクスペル",
+                "MP": {"kind": "value+", "value+": 3},
+                "対象": {"kind": "個別", "個別": "術者"},
+                "効果": "効果"
+            }
+        ]"#;
+        serde_json::from_str(json_data).unwrap()
+    }
+
+    fn spell_names(expr: &str) -> Vec<String> {
+        let filter = Filter::parse(expr).unwrap();
+        sample_spells()
+            .iter()
+            .filter(|s| filter.matches(*s))
+            .map(|s| s.name.clone())
+            .collect()
+    }
+
+    #[test]
+    fn test_spell_level_and_rank() {
+        assert_eq!(spell_names("Lv >= 10"), vec!["エリアスペル"]);
+        assert_eq!(spell_names("Lv == 4"), vec!["個別スペル"]);
+        assert_eq!(spell_names("Lv.kind == \"value+\""), vec!["個別スペル"]);
+        assert_eq!(spell_names("rank == 2"), vec!["ランクスペル"]);
+        assert_eq!(spell_names("Lv == null"), vec!["ランクスペル"]);
+    }
+
+    #[test]
+    fn test_spell_mp_kinds() {
+        assert_eq!(spell_names("MP <= 5"), vec!["エリアスペル", "ランクスペル"]);
+        assert_eq!(spell_names("MP.kind == special"), vec!["個別スペル"]);
+        assert_eq!(spell_names("MP == 効果参照"), vec!["個別スペル"]);
+    }
+
+    #[test]
+    fn test_spell_target() {
+        assert_eq!(spell_names("対象.kind == エリア"), vec!["エリアスペル"]);
+        assert_eq!(spell_names("対象 == 1体"), vec!["個別スペル"]);
+        assert_eq!(spell_names("対象 == 1エリア and 対象.半径 >= 5"), vec!["エリアスペル"]);
+        assert_eq!(spell_names("対象.末尾 == すべて"), vec!["エリアスペル"]);
+    }
+
+    #[test]
+    fn test_spell_request_example() {
+        assert_eq!(
+            spell_names("MP <= 5 and 抵抗 == \"消滅\" and 対象.kind == \"エリア\""),
+            vec!["エリアスペル"]
+        );
+    }
+
+    #[test]
+    fn test_spell_flags_default_false() {
+        assert_eq!(spell_names("補助"), vec!["個別スペル"]);
+        assert_eq!(spell_names("準備 == false"), vec!["個別スペル", "ランクスペル"]);
+        assert!(spell_names("犯罪").is_empty());
+    }
+
+    #[test]
+    fn test_spell_other_fields() {
+        assert_eq!(spell_names("時間 == 一瞬"), vec!["個別スペル"]);
+        assert_eq!(spell_names("時間 >= 2 and 時間.unit == R"), vec!["エリアスペル"]);
+        assert_eq!(spell_names("射程 == 接触"), vec!["個別スペル"]);
+        assert_eq!(spell_names("`射程(m)` > 20"), vec!["エリアスペル"]);
+        assert_eq!(spell_names("形状 == 起点指定"), vec!["エリアスペル"]);
+        assert_eq!(spell_names("属性 in [炎, 雷]"), vec!["個別スペル"]);
+        assert_eq!(spell_names("schoolVariant == \"水・氷\""), vec!["エリアスペル"]);
+    }
+
+    #[test]
+    fn test_spell_reference_array() {
+        assert_eq!(spell_names("reference.book == BookRef_X"), vec!["個別スペル"]);
+        assert_eq!(spell_names("reference.value > 110"), vec!["エリアスペル"]);
+    }
+
+    #[test]
+    fn test_from_str_and_source() {
+        let filter: Filter = "Lv >= 5".parse().unwrap();
+        assert_eq!(filter.as_str(), "Lv >= 5");
+    }
+}
@@ -0,0 +1,178 @@
+//! 名前のあいまい検索
+//!
+//! ひらがな・カタカナ、全角・半角、長音記号・中黒・空白の違いを無視して名前を比較し、
+//! 一致しない場合は編集距離で近い名前を候補として返します。
+
+use unicode_normalization::UnicodeNormalization;
+
+/// 比較用に名前を正規化する
+///
+/// NFKC で全角英数字・半角カタカナを揃え、ひらがなをカタカナに、英字を小文字にして、
+/// 長音記号（ー）・中黒（・）・空白を取り除く。
+///
+/// # 例
+/// "ごぶりん"、"ｺﾞﾌﾞﾘﾝ"、"ゴブリーン" はいずれも "ゴブリン" になる
+pub fn normalize(text: &str) -> String {
+    text.nfkc()
+        .filter(|c| !is_ignored(*c))
+        .map(to_katakana)
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// 正規化した上で `name` が `pattern` を含むか
+pub fn contains(name: &str, pattern: &str) -> bool {
+    normalize(name).contains(&normalize(pattern))
+}
+
+/// 正規化した上で同じ名前か
+pub fn same_name(a: &str, b: &str) -> bool {
+    normalize(a) == normalize(b)
+}
+
+/// 文字単位の編集距離（挿入・削除・置換をそれぞれ1とする Levenshtein 距離）
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+
+    previous[b.len()]
+}
+
+/// `pattern` と、`text` のいずれかの部分文字列との編集距離の最小値
+///
+/// 部分一致の検索語の打ち間違いを測る（`text` が `pattern` を含めば 0）。
+pub fn partial_distance(pattern: &str, text: &str) -> usize {
+    let text: Vec<char> = text.chars().collect();
+    // text の前後は読み飛ばしてよいので、1行目は 0、最後の行の最小値を取る
+    let mut previous = vec![0; text.len() + 1];
+
+    for (i, cp) in pattern.chars().enumerate() {
+        let mut current = vec![i + 1; text.len() + 1];
+        for (j, ct) in text.iter().enumerate() {
+            let substitution = previous[j] + usize::from(cp != *ct);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+
+    previous.into_iter().min().unwrap_or(0)
+}
+
+/// `pattern` に近い名前を近い順に返す（「もしかして」の候補）
+///
+/// 正規化した名前との部分一致の編集距離が、検索語の文字数の 1/3 以下のものを候補とし、
+/// 部分一致の距離、名前全体の距離の順に並べる（同じ距離は `candidates` の順）。
+/// 同じ名前は1つにまとめる。
+///
+/// # 引数
+/// * `candidates` - 候補の名前
+/// * `pattern` - 検索語
+/// * `limit` - 返す候補の最大数
+pub fn suggest<'a, I>(candidates: I, pattern: &str, limit: usize) -> Vec<&'a str>
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    let pattern = normalize(pattern);
+    if pattern.is_empty() {
+        return Vec::new();
+    }
+    let max_distance = pattern.chars().count() / 3;
+
+    let mut ranked: Vec<(usize, usize, &str)> = Vec::new();
+    for name in candidates {
+        if ranked.iter().any(|(_, _, existing)| *existing == name) {
+            continue;
+        }
+        let normalized = normalize(name);
+        let partial = partial_distance(&pattern, &normalized);
+        if partial <= max_distance {
+            ranked.push((partial, edit_distance(&pattern, &normalized), name));
+        }
+    }
+
+    ranked.sort_by_key(|(partial, full, _)| (*partial, *full));
+    ranked.into_iter().take(limit).map(|(_, _, name)| name).collect()
+}
+
+/// 比較で無視する文字（長音記号、中黒、空白）
+fn is_ignored(c: char) -> bool {
+    matches!(c, 'ー' | '‐' | '―' | '−' | '・' | '·') || c.is_whitespace()
+}
+
+/// ひらがなをカタカナに変換（それ以外はそのまま）
+fn to_katakana(c: char) -> char {
+    match c {
+        'ぁ'..='ゖ' | 'ゝ' | 'ゞ' => char::from_u32(c as u32 + 0x60).unwrap_or(c),
+        _ => c,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_kana_and_width() {
+        assert_eq!(normalize("ごぶりん"), "ゴブリン");
+        assert_eq!(normalize("ｺﾞﾌﾞﾘﾝ"), "ゴブリン");
+        assert_eq!(normalize("ﾎﾟｲｽﾞﾝ"), "ポイズン");
+        assert_eq!(normalize("ＡＢＣ１２３"), "abc123");
+        assert_eq!(normalize("ヴぁんぱいあ"), "ヴァンパイア");
+    }
+
+    #[test]
+    fn test_normalize_folds_long_vowel_and_dot() {
+        assert_eq!(normalize("エネルギー・ボルト"), normalize("えねるぎぼると"));
+        assert_eq!(normalize("ｴﾈﾙｷﾞｰ･ﾎﾞﾙﾄ"), "エネルギボルト");
+        assert_eq!(normalize("ゴブリーン"), normalize("ゴブリン"));
+        assert_eq!(normalize("ファイア ボール"), "ファイアボル");
+    }
+
+    #[test]
+    fn test_contains_and_same_name() {
+        assert!(contains("ゴブリンシャーマン", "ごぶりん"));
+        assert!(contains("ゴブリン", "ｺﾞﾌﾞﾘﾝ"));
+        assert!(contains("エネルギー・ボルト", "エネルギーボルト"));
+        assert!(!contains("ゴブリン", "コボルド"));
+        assert!(same_name("フォース・イクスプロージョン", "ふぉーすいくすぷろーじょん"));
+        assert!(!same_name("ゴブリン", "ゴブリンシャーマン"));
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("", ""), 0);
+        assert_eq!(edit_distance("ゴブリン", "ゴブリン"), 0);
+        assert_eq!(edit_distance("ゴブルン", "ゴブリン"), 1);
+        assert_eq!(edit_distance("ゴブリ", "ゴブリン"), 1);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("", "abc"), 3);
+    }
+
+    #[test]
+    fn test_partial_distance() {
+        assert_eq!(partial_distance("ブリ", "ゴブリン"), 0);
+        assert_eq!(partial_distance("ゴブルン", "ホブゴブリン"), 1);
+        assert_eq!(partial_distance("abc", "xyz"), 3);
+        assert_eq!(partial_distance("", "abc"), 0);
+    }
+
+    #[test]
+    fn test_suggest_ranks_by_distance() {
+        let names = ["ゴブリン", "ホブゴブリン", "コボルド", "ゴブリン", "ボガード", "ゴブリンシャーマン"];
+
+        assert_eq!(suggest(names, "ごぶるん", 5), vec!["ゴブリン", "ホブゴブリン", "ゴブリンシャーマン"]);
+        assert_eq!(suggest(names, "ゴブルン", 1), vec!["ゴブリン"]);
+        assert_eq!(suggest(names, "こぼるど", 5), vec!["コボルド"]);
+        assert!(suggest(names, "ドラゴン", 5).is_empty());
+        assert!(suggest(names, "", 5).is_empty());
+    }
+}
@@ -1,15 +1,22 @@
 use crate::{Monster, Spell};
 
+pub mod filter;
+pub mod fuzzy;
+
+pub use filter::{FieldValue, Filter, FilterError, Queryable};
+
 /// 名前でモンスターを検索（部分マッチ）
+///
+/// ひらがな・カタカナ、全角・半角、長音記号・中黒の違いは無視する（[`fuzzy::normalize`]）
 pub fn find_by_name<'a>(monsters: &'a [Monster], name: &str) -> Vec<&'a Monster> {
     monsters
         .iter()
-        .filter(|m| m.name.contains(name))
+        .filter(|m| fuzzy::contains(&m.name, name))
         .collect()
 }
 
 /// レベルでモンスターを検索（完全一致）
-pub fn find_by_level<'a>(monsters: &'a [Monster], level: i32) -> Vec<&'a Monster> {
+pub fn find_by_level(monsters: &[Monster], level: i32) -> Vec<&Monster> {
     monsters
         .iter()
         .filter(|m| m.level == level)
@@ -35,22 +42,22 @@ pub fn find_multi<'a>(
         .iter()
         .filter(|m| {
             // 名前フィルタ
-            if let Some(n) = name {
-                if !m.name.contains(n) {
-                    return false;
-                }
+            if let Some(n) = name
+                && !fuzzy::contains(&m.name, n)
+            {
+                return false;
             }
             // レベルフィルタ
-            if let Some(l) = level {
-                if m.level != l {
-                    return false;
-                }
+            if let Some(l) = level
+                && m.level != l
+            {
+                return false;
             }
             // カテゴリフィルタ
-            if let Some(c) = category {
-                if m.category != c {
-                    return false;
-                }
+            if let Some(c) = category
+                && m.category != c
+            {
+                return false;
             }
             true
         })
@@ -64,15 +71,41 @@ pub fn find_by_exact_name<'a>(monsters: &'a [Monster], name: &str) -> Option<&'a
         .find(|m| m.name == name)
 }
 
+/// 名前が近いモンスター名の候補（近い順に最大 `limit` 件、[`fuzzy::suggest`]）
+pub fn suggest_monster_names<'a>(monsters: &'a [Monster], name: &str, limit: usize) -> Vec<&'a str> {
+    fuzzy::suggest(monsters.iter().map(|m| m.name.as_str()), name, limit)
+}
+
+/// フィルタ式でモンスターを検索
+pub fn find_by_filter<'a>(monsters: &'a [Monster], filter: &Filter) -> Vec<&'a Monster> {
+    monsters
+        .iter()
+        .filter(|m| filter.matches(*m))
+        .collect()
+}
+
 // ============================================================================
 // Spell Query Functions
 // ============================================================================
 
-/// スペルを名前で検索（部分マッチ）
+/// スペルを名前で検索（部分マッチ、表記の違いは [`find_by_name`] と同じく無視する）
 pub fn spell_find_by_name<'a>(spells: &'a [Spell], name: &str) -> Vec<&'a Spell> {
     spells
         .iter()
-        .filter(|s| s.name.contains(name))
+        .filter(|s| fuzzy::contains(&s.name, name))
+        .collect()
+}
+
+/// 名前が近いスペル名の候補（近い順に最大 `limit` 件）
+pub fn spell_suggest_names<'a>(spells: &'a [Spell], name: &str, limit: usize) -> Vec<&'a str> {
+    fuzzy::suggest(spells.iter().map(|s| s.name.as_str()), name, limit)
+}
+
+/// フィルタ式でスペルを検索
+pub fn spell_find_by_filter<'a>(spells: &'a [Spell], filter: &Filter) -> Vec<&'a Spell> {
+    spells
+        .iter()
+        .filter(|s| filter.matches(*s))
         .collect()
 }
 
@@ -86,7 +119,7 @@ pub fn spell_find_by_school<'a>(spells: &'a [Spell], school: &str) -> Vec<&'a Sp
 
 /// スペルをレベルで検索（完全一致）
 /// Lv.kind: "value" または "value+" のスペルを検索
-pub fn spell_find_by_level<'a>(spells: &'a [Spell], level: i32) -> Vec<&'a Spell> {
+pub fn spell_find_by_level(spells: &[Spell], level: i32) -> Vec<&Spell> {
     spells
         .iter()
         .filter(|s| extract_spell_level(s) == level)
@@ -95,7 +128,7 @@ pub fn spell_find_by_level<'a>(spells: &'a [Spell], level: i32) -> Vec<&'a Spell
 
 /// スペルをランクで検索（完全一致）
 /// Lv.kind: "rank" のスペルを検索
-pub fn spell_find_by_rank<'a>(spells: &'a [Spell], rank: i32) -> Vec<&'a Spell> {
+pub fn spell_find_by_rank(spells: &[Spell], rank: i32) -> Vec<&Spell> {
     spells
         .iter()
         .filter(|s| has_rank_field(s) && extract_spell_rank(s) == rank)
@@ -133,51 +166,29 @@ pub fn spell_find_by_god<'a>(spells: &'a [Spell], god: &str) -> Vec<&'a Spell> {
 /// Spell オブジェクトから Lv を抽出
 /// Lv.kind: "value" または "value+" の場合のみ値を返す
 fn extract_spell_level(spell: &Spell) -> i32 {
-    if let Some(lv_obj) = spell.extra.get("Lv") {
-        if let Some(obj) = lv_obj.as_object() {
-            if let Some(value) = obj.get("value") {
-                return value.as_i64().unwrap_or(0) as i32;
-            }
-            if let Some(value) = obj.get("value+") {
-                return value.as_i64().unwrap_or(0) as i32;
-            }
-        }
-    }
-    0
+    spell.level_value().unwrap_or(0)
 }
 
 /// Spell オブジェクトから rank を抽出
 /// Lv.kind: "rank" の場合のみ値を返す
 fn extract_spell_rank(spell: &Spell) -> i32 {
-    if let Some(lv_obj) = spell.extra.get("Lv") {
-        if let Some(obj) = lv_obj.as_object() {
-            if let Some(rank) = obj.get("rank") {
-                return rank.as_i64().unwrap_or(0) as i32;
-            }
-        }
-    }
-    0
+    spell.rank().unwrap_or(0)
 }
 
 /// Spell オブジェクトが rank フィールドを持つかチェック
 /// Lv.kind: "rank" の場合のみ true を返す
 fn has_rank_field(spell: &Spell) -> bool {
-    if let Some(lv_obj) = spell.extra.get("Lv") {
-        if let Some(obj) = lv_obj.as_object() {
-            return obj.contains_key("rank");
-        }
-    }
-    false
+    spell.rank().is_some()
 }
 
 /// Spell オブジェクトから schoolVariant を抽出
 fn extract_school_variant(spell: &Spell) -> Option<&str> {
-    spell.extra.get("schoolVariant")?.as_str()
+    spell.school_variant.as_deref()
 }
 
 /// Spell オブジェクトから god を抽出
 fn extract_god(spell: &Spell) -> Option<&str> {
-    spell.extra.get("god")?.as_str()
+    spell.god.as_deref()
 }
 
 /// スペルを複合検索（名前、系統、レベル/ランク、schoolVariant、godの条件を組み合わせ）
@@ -195,16 +206,16 @@ pub fn spell_find_multi<'a>(
         .iter()
         .filter(|s| {
             // 名前フィルタ
-            if let Some(n) = name {
-                if !s.name.contains(n) {
-                    return false;
-                }
+            if let Some(n) = name
+                && !fuzzy::contains(&s.name, n)
+            {
+                return false;
             }
             // 系統フィルタ
-            if let Some(sch) = school {
-                if s.school != sch {
-                    return false;
-                }
+            if let Some(sch) = school
+                && s.school != sch
+            {
+                return false;
             }
             // レベルフィルタ（level優先）
             if let Some(l) = level {
@@ -218,16 +229,16 @@ pub fn spell_find_multi<'a>(
                 }
             }
             // schoolVariant フィルタ
-            if let Some(sv) = school_variant {
-                if extract_school_variant(s) != Some(sv) {
-                    return false;
-                }
+            if let Some(sv) = school_variant
+                && extract_school_variant(s) != Some(sv)
+            {
+                return false;
             }
             // god フィルタ
-            if let Some(g) = god {
-                if extract_god(s) != Some(g) {
-                    return false;
-                }
+            if let Some(g) = god
+                && extract_god(s) != Some(g)
+            {
+                return false;
             }
             true
         })
@@ -378,6 +389,24 @@ mod tests {
         assert_eq!(results.len(), 0);
     }
 
+    #[test]
+    fn test_find_by_name_ignores_kana_and_width() {
+        let mut monsters = sample_monsters();
+        monsters[1].name = "ゴブリン・シャーマン".to_string();
+
+        assert_eq!(find_by_name(&monsters, "ごぶりん").len(), 1);
+        assert_eq!(find_by_name(&monsters, "ｺﾞﾌﾞﾘﾝｼｬｰﾏﾝ").len(), 1);
+        assert_eq!(find_multi(&monsters, Some("ゴブリンシャマン"), Some(3), None).len(), 1);
+    }
+
+    #[test]
+    fn test_suggest_monster_names() {
+        let monsters = sample_monsters();
+
+        assert_eq!(suggest_monster_names(&monsters, "小型人形敵", 3), vec!["小型人型敵"]);
+        assert!(suggest_monster_names(&monsters, "存在しないモンスター", 3).is_empty());
+    }
+
     #[test]
     fn test_find_by_level_single_match() {
         let monsters = sample_monsters();
@@ -596,6 +625,13 @@ mod tests {
          assert_eq!(results.len(), 0);
      }
 
+     #[test]
+     fn test_spell_find_by_name_ignores_width_and_case() {
+         let spells = sample_spells();
+         assert_eq!(spell_find_by_name(&spells, "ｍａｇｉｃ＿４７４３８").len(), 1);
+         assert_eq!(spell_suggest_names(&spells, "Magic_47483", 3)[0], "Magic_47438");
+     }
+
      #[test]
      fn test_spell_find_by_school_single_match() {
          let spells = sample_spells();
@@ -0,0 +1,719 @@
+//! スペル（魔法）データの型定義
+//!
+//! `rust/core/schema/spell_array.json` に対応する型付きモデルです。
+//! 既知のキーは型付きフィールドに、未知のキーや型に合わない値は `extra` に保持し、
+//! 読み込んだ JSON をそのまま書き戻せる（ロスレス往復）ようにしています。
+
+use serde::de::{self, DeserializeOwned, Deserializer};
+use serde::ser::{SerializeMap, Serializer};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+
+/// 整数または文字列の値（射程・半径・エリア末尾・時間など）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Scalar {
+    Int(i64),
+    Text(String),
+}
+
+impl Scalar {
+    /// 整数として取得（文字列の場合は数値変換を試みる）
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            Scalar::Int(i) => Some(*i),
+            Scalar::Text(s) => s.trim().parse().ok(),
+        }
+    }
+}
+
+impl fmt::Display for Scalar {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Scalar::Int(i) => write!(f, "{}", i),
+            Scalar::Text(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+/// 出典
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct Reference {
+    /// 書籍名
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub book: Option<String>,
+
+    /// ページ番号
+    #[serde(rename = "value", default, skip_serializing_if = "Option::is_none")]
+    pub page: Option<i64>,
+}
+
+/// 習得レベル（Lv）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpellLevel {
+    /// `{"kind": "value", "value": n}`
+    Value(i32),
+    /// `{"kind": "value+", "value+": n}`（n 以上）
+    ValuePlus(i32),
+    /// `{"kind": "rank", "rank": n}`
+    Rank(i32),
+}
+
+impl SpellLevel {
+    /// 種別名（kind）
+    pub fn kind(&self) -> &'static str {
+        match self {
+            SpellLevel::Value(_) => "value",
+            SpellLevel::ValuePlus(_) => "value+",
+            SpellLevel::Rank(_) => "rank",
+        }
+    }
+
+    /// レベル値（value / value+ の場合のみ）
+    pub fn level(&self) -> Option<i32> {
+        match self {
+            SpellLevel::Value(v) | SpellLevel::ValuePlus(v) => Some(*v),
+            SpellLevel::Rank(_) => None,
+        }
+    }
+
+    /// ランク値（rank の場合のみ）
+    pub fn rank(&self) -> Option<i32> {
+        match self {
+            SpellLevel::Rank(r) => Some(*r),
+            _ => None,
+        }
+    }
+}
+
+/// 消費MP
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MpCost {
+    /// `{"kind": "value", "value": n}`
+    Value(i32),
+    /// `{"kind": "value+", "value+": n}`（n 以上）
+    ValuePlus(i32),
+    /// `{"kind": "special", "special": "効果参照"}`
+    Special(String),
+}
+
+impl MpCost {
+    /// 種別名（kind）
+    pub fn kind(&self) -> &'static str {
+        match self {
+            MpCost::Value(_) => "value",
+            MpCost::ValuePlus(_) => "value+",
+            MpCost::Special(_) => "special",
+        }
+    }
+
+    /// 数値コスト（value / value+ の場合のみ）
+    pub fn amount(&self) -> Option<i32> {
+        match self {
+            MpCost::Value(v) | MpCost::ValuePlus(v) => Some(*v),
+            MpCost::Special(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for MpCost {
+    /// チャットパレット向けの表記（`5`、`3～`、`効果参照`）
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MpCost::Value(v) => write!(f, "{}", v),
+            MpCost::ValuePlus(v) => write!(f, "{}～", v),
+            MpCost::Special(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+/// 対象
+#[derive(Debug, Clone, PartialEq)]
+pub enum Target {
+    /// `{"kind": "個別", "個別": "1体"}`
+    Individual(String),
+    /// `{"kind": "エリア", "エリア": {"value": "1エリア", "半径(m)": 5, "末尾": "すべて"}}`
+    Area {
+        value: String,
+        radius: Option<Scalar>,
+        suffix: Option<Scalar>,
+    },
+}
+
+impl Target {
+    /// 種別名（kind）
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Target::Individual(_) => "個別",
+            Target::Area { .. } => "エリア",
+        }
+    }
+}
+
+/// 効果時間
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Duration {
+    /// 値（`一瞬` などの文字列、または数値）
+    pub value: Scalar,
+
+    /// 単位（R、分、時間、日、年）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unit: Option<String>,
+}
+
+impl fmt::Display for Duration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.value {
+            Scalar::Text(s) => write!(f, "{}", s),
+            Scalar::Int(i) => write!(f, "{}{}", i, self.unit.as_deref().unwrap_or("")),
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Lv / MP / 対象 の serde 実装（kind によるタグ付き表現）
+// ----------------------------------------------------------------------------
+
+/// kind ごとの値キーを読む（kind が無い場合は存在するキーから推定する）
+fn read_tagged<'a>(
+    obj: &'a serde_json::Map<String, serde_json::Value>,
+    kinds: &[&'static str],
+) -> Option<(&'static str, &'a serde_json::Value)> {
+    match obj.get("kind").and_then(|k| k.as_str()) {
+        Some(kind) => {
+            let kind = *kinds.iter().find(|k| **k == kind)?;
+            // value+ は旧データの "value" キーも受け付ける
+            let value = obj
+                .get(kind)
+                .or_else(|| (kind == "value+").then(|| obj.get("value")).flatten())?;
+            Some((kind, value))
+        }
+        None => kinds.iter().find_map(|k| obj.get(*k).map(|v| (*k, v))),
+    }
+}
+
+fn tagged_object(kind: &str, value: serde_json::Value) -> serde_json::Value {
+    let mut obj = serde_json::Map::new();
+    obj.insert("kind".to_string(), kind.into());
+    obj.insert(kind.to_string(), value);
+    serde_json::Value::Object(obj)
+}
+
+fn as_i32(value: &serde_json::Value) -> Option<i32> {
+    value.as_i64().and_then(|v| i32::try_from(v).ok())
+}
+
+impl Serialize for SpellLevel {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let value = match self {
+            SpellLevel::Value(v) | SpellLevel::ValuePlus(v) | SpellLevel::Rank(v) => *v,
+        };
+        tagged_object(self.kind(), value.into()).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for SpellLevel {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let obj = serde_json::Map::deserialize(deserializer)?;
+        let (kind, value) = read_tagged(&obj, &["value", "value+", "rank"])
+            .ok_or_else(|| de::Error::custom("Lv は value / value+ / rank のいずれかで指定してください"))?;
+        let value = as_i32(value).ok_or_else(|| de::Error::custom("Lv の値は整数で指定してください"))?;
+        Ok(match kind {
+            "value" => SpellLevel::Value(value),
+            "value+" => SpellLevel::ValuePlus(value),
+            _ => SpellLevel::Rank(value),
+        })
+    }
+}
+
+impl Serialize for MpCost {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let value = match self {
+            MpCost::Value(v) | MpCost::ValuePlus(v) => (*v).into(),
+            MpCost::Special(s) => s.as_str().into(),
+        };
+        tagged_object(self.kind(), value).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for MpCost {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let obj = serde_json::Map::deserialize(deserializer)?;
+        let (kind, value) = read_tagged(&obj, &["value", "value+", "special"])
+            .ok_or_else(|| de::Error::custom("MP は value / value+ / special のいずれかで指定してください"))?;
+        match kind {
+            "special" => value
+                .as_str()
+                .map(|s| MpCost::Special(s.to_string()))
+                .ok_or_else(|| de::Error::custom("MP の special は文字列で指定してください")),
+            _ => {
+                let v = as_i32(value).ok_or_else(|| de::Error::custom("MP の値は整数で指定してください"))?;
+                Ok(if kind == "value" { MpCost::Value(v) } else { MpCost::ValuePlus(v) })
+            }
+        }
+    }
+}
+
+/// エリア対象の内部表現（`対象.エリア`）
+#[derive(Serialize, Deserialize)]
+struct AreaRepr {
+    value: String,
+    #[serde(rename = "半径(m)", default, skip_serializing_if = "Option::is_none")]
+    radius: Option<Scalar>,
+    #[serde(rename = "末尾", default, skip_serializing_if = "Option::is_none")]
+    suffix: Option<Scalar>,
+}
+
+impl Serialize for Target {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let value = match self {
+            Target::Individual(s) => s.as_str().into(),
+            Target::Area { value, radius, suffix } => serde_json::to_value(AreaRepr {
+                value: value.clone(),
+                radius: radius.clone(),
+                suffix: suffix.clone(),
+            })
+            .map_err(serde::ser::Error::custom)?,
+        };
+        tagged_object(self.kind(), value).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Target {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let obj = serde_json::Map::deserialize(deserializer)?;
+        let (kind, value) = read_tagged(&obj, &["個別", "エリア"])
+            .ok_or_else(|| de::Error::custom("対象は個別かエリアかを指定してください"))?;
+        match kind {
+            "個別" => value
+                .as_str()
+                .map(|s| Target::Individual(s.to_string()))
+                .ok_or_else(|| de::Error::custom("個別対象は文字列で指定してください")),
+            _ => {
+                let area: AreaRepr = serde_json::from_value(value.clone()).map_err(de::Error::custom)?;
+                Ok(Target::Area {
+                    value: area.value,
+                    radius: area.radius,
+                    suffix: area.suffix,
+                })
+            }
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Spell
+// ----------------------------------------------------------------------------
+
+/// スペル（魔法）データ
+/// スキーマ: rust/core/schema/spell_array.json
+#[derive(Debug, Clone, Default)]
+pub struct Spell {
+    /// スペル名
+    pub name: String,
+
+    /// 系統（MagicCat_1 など）
+    pub school: String,
+
+    /// 系統の細分類（schoolVariant）
+    pub school_variant: Option<String>,
+
+    /// 神格（god）
+    pub god: Option<String>,
+
+    /// 出典（reference）
+    pub references: Option<Vec<Reference>>,
+
+    /// Revision
+    pub revision: Option<f64>,
+
+    /// 習得レベル（Lv）
+    pub level: Option<SpellLevel>,
+
+    /// 消費MP（MP）
+    pub mp: Option<MpCost>,
+
+    /// 対象
+    pub target: Option<Target>,
+
+    /// 時間
+    pub duration: Option<Duration>,
+
+    /// 射程（"接触"、"術者" など）
+    pub range: Option<Scalar>,
+
+    /// 射程(m)
+    pub range_m: Option<Scalar>,
+
+    /// 形状
+    pub shape: Option<String>,
+
+    /// 抵抗
+    pub resistance: Option<String>,
+
+    /// 属性
+    pub element: Option<String>,
+
+    /// 効果
+    pub effect: Option<String>,
+
+    /// マギスフィア
+    pub magisphere: Option<String>,
+
+    /// オプション
+    pub option: Option<String>,
+
+    /// 補助
+    pub support: Option<bool>,
+
+    /// 準備
+    pub preparation: Option<bool>,
+
+    /// 犯罪
+    pub crime: Option<bool>,
+
+    /// 未知のフィールド、および型に合わない値（null を含む）
+    pub extra: HashMap<String, serde_json::Value>,
+
+    /// 正規化で表記が変わる既知フィールドの元の値（書き戻し用）
+    original: HashMap<String, serde_json::Value>,
+}
+
+/// 型付きフィールドの JSON キー
+const FIELD_KEYS: [&str; 21] = [
+    "reference", "Revision", "name", "school", "schoolVariant", "god", "Lv", "MP", "対象", "補助", "準備", "犯罪",
+    "時間", "射程", "射程(m)", "形状", "抵抗", "属性", "効果", "マギスフィア", "オプション",
+];
+
+impl Spell {
+    /// 名前と系統を指定してスペルを作成
+    pub fn new(name: impl Into<String>, school: impl Into<String>) -> Self {
+        Spell {
+            name: name.into(),
+            school: school.into(),
+            ..Default::default()
+        }
+    }
+
+    /// レベル値（Lv.kind が value / value+ の場合のみ）
+    pub fn level_value(&self) -> Option<i32> {
+        self.level.as_ref().and_then(SpellLevel::level)
+    }
+
+    /// ランク値（Lv.kind が rank の場合のみ）
+    pub fn rank(&self) -> Option<i32> {
+        self.level.as_ref().and_then(SpellLevel::rank)
+    }
+
+    /// 補助魔法か（未指定の場合は false）
+    pub fn is_support(&self) -> bool {
+        self.support.unwrap_or(false)
+    }
+
+    /// 準備が必要か（未指定の場合は false）
+    pub fn requires_preparation(&self) -> bool {
+        self.preparation.unwrap_or(false)
+    }
+
+    /// 犯罪魔法か（未指定の場合は false）
+    pub fn is_crime(&self) -> bool {
+        self.crime.unwrap_or(false)
+    }
+
+    /// JSON キー名でフィールド値を取得（未知のキーは extra から取得）
+    pub fn get(&self, key: &str) -> Option<serde_json::Value> {
+        self.field_value(key).or_else(|| self.extra.get(key).cloned())
+    }
+
+    /// JSON オブジェクトに変換
+    ///
+    /// serde_json の Map はキーの辞書順に並ぶため、出力のキー順は元のファイルと一致しない。
+    fn to_json_map(&self) -> serde_json::Map<String, serde_json::Value> {
+        let mut map: serde_json::Map<String, serde_json::Value> = FIELD_KEYS
+            .iter()
+            .filter_map(|key| Some((key.to_string(), self.field_value(key)?)))
+            .collect();
+        for (key, value) in &self.extra {
+            if !map.contains_key(key) {
+                map.insert(key.clone(), value.clone());
+            }
+        }
+        map
+    }
+
+    /// 型付きフィールドの値を JSON キー名で取得（未設定・未知のキーは None）
+    fn field_value(&self, key: &str) -> Option<serde_json::Value> {
+        match key {
+            "reference" => self.json_value(key, &self.references),
+            "Revision" => self.json_value(key, &self.revision),
+            "name" => Some(self.name.as_str().into()),
+            "school" => Some(self.school.as_str().into()),
+            "schoolVariant" => self.json_value(key, &self.school_variant),
+            "god" => self.json_value(key, &self.god),
+            "Lv" => self.json_value(key, &self.level),
+            "MP" => self.json_value(key, &self.mp),
+            "対象" => self.json_value(key, &self.target),
+            "補助" => self.json_value(key, &self.support),
+            "準備" => self.json_value(key, &self.preparation),
+            "犯罪" => self.json_value(key, &self.crime),
+            "時間" => self.json_value(key, &self.duration),
+            "射程" => self.json_value(key, &self.range),
+            "射程(m)" => self.json_value(key, &self.range_m),
+            "形状" => self.json_value(key, &self.shape),
+            "抵抗" => self.json_value(key, &self.resistance),
+            "属性" => self.json_value(key, &self.element),
+            "効果" => self.json_value(key, &self.effect),
+            "マギスフィア" => self.json_value(key, &self.magisphere),
+            "オプション" => self.json_value(key, &self.option),
+            _ => None,
+        }
+    }
+
+    /// 型付きフィールドを JSON 値に変換
+    /// 読み込み時から値が変わっていなければ元の表記をそのまま使う
+    fn json_value<T>(&self, key: &str, field: &Option<T>) -> Option<serde_json::Value>
+    where
+        T: Serialize + DeserializeOwned + PartialEq,
+    {
+        let value = field.as_ref()?;
+        match self.original.get(key) {
+            Some(raw) if serde_json::from_value::<T>(raw.clone()).ok().as_ref() == Some(value) => Some(raw.clone()),
+            _ => serde_json::to_value(value).ok(),
+        }
+    }
+
+    /// 既知フィールドを取り出して型付きの値に変換
+    /// 変換できない値（null を含む）は extra に残す
+    fn take<T>(&mut self, map: &mut serde_json::Map<String, serde_json::Value>, key: &str) -> Option<T>
+    where
+        T: Serialize + DeserializeOwned,
+    {
+        let raw = map.remove(key)?;
+        match serde_json::from_value::<T>(raw.clone()) {
+            Ok(value) => {
+                if serde_json::to_value(&value).ok().as_ref() != Some(&raw) {
+                    self.original.insert(key.to_string(), raw);
+                }
+                Some(value)
+            }
+            Err(_) => {
+                self.extra.insert(key.to_string(), raw);
+                None
+            }
+        }
+    }
+}
+
+impl Serialize for Spell {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let map = self.to_json_map();
+        let mut out = serializer.serialize_map(Some(map.len()))?;
+        for (key, value) in &map {
+            out.serialize_entry(key, value)?;
+        }
+        out.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for Spell {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut map = serde_json::Map::deserialize(deserializer)?;
+
+        let name = match map.remove("name") {
+            Some(serde_json::Value::String(s)) => s,
+            Some(_) => return Err(de::Error::custom("name は文字列で指定してください")),
+            None => return Err(de::Error::missing_field("name")),
+        };
+        let school = match map.remove("school") {
+            Some(serde_json::Value::String(s)) => s,
+            Some(_) => return Err(de::Error::custom("school は文字列で指定してください")),
+            None => return Err(de::Error::missing_field("school")),
+        };
+
+        let mut spell = Spell::new(name, school);
+        spell.references = spell.take(&mut map, "reference");
+        spell.revision = spell.take(&mut map, "Revision");
+        spell.school_variant = spell.take(&mut map, "schoolVariant");
+        spell.god = spell.take(&mut map, "god");
+        spell.level = spell.take(&mut map, "Lv");
+        spell.mp = spell.take(&mut map, "MP");
+        spell.target = spell.take(&mut map, "対象");
+        spell.support = spell.take(&mut map, "補助");
+        spell.preparation = spell.take(&mut map, "準備");
+        spell.crime = spell.take(&mut map, "犯罪");
+        spell.duration = spell.take(&mut map, "時間");
+        spell.range = spell.take(&mut map, "射程");
+        spell.range_m = spell.take(&mut map, "射程(m)");
+        spell.shape = spell.take(&mut map, "形状");
+        spell.resistance = spell.take(&mut map, "抵抗");
+        spell.element = spell.take(&mut map, "属性");
+        spell.effect = spell.take(&mut map, "効果");
+        spell.magisphere = spell.take(&mut map, "マギスフィア");
+        spell.option = spell.take(&mut map, "オプション");
+        spell.extra.extend(map);
+        Ok(spell)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn roundtrip(json: serde_json::Value) -> (Spell, serde_json::Value) {
+        let spell: Spell = serde_json::from_value(json).expect("Failed to deserialize spell");
+        let back = serde_json::to_value(&spell).expect("Failed to serialize spell");
+        (spell, back)
+    }
+
+    #[test]
+    fn test_typed_fields() {
+        let (spell, _) = roundtrip(serde_json::json!({
+            "reference": [{"book": "BookRef_Y", "value": 122}],
+            "school": "MagicCat_2",
+            "schoolVariant": "水・氷",
+            "Lv": {"kind": "value", "value": 13},
+            "name": "Magic_33778",
+            "MP": {"kind": "value+", "value+": 15},
+            "対象": {"kind": "エリア", "エリア": {"value": "1エリア", "半径(m)": 35, "末尾": "すべて"}},
+            "準備": true,
+            "時間": {"value": 2, "unit": "R"},
+            "射程": "術者",
+            "射程(m)": 30,
+            "形状": "起点指定",
+            "抵抗": "必中",
+            "効果": "EffectDescription_75723"
+        }));
+
+        assert_eq!(spell.level, Some(SpellLevel::Value(13)));
+        assert_eq!(spell.level_value(), Some(13));
+        assert_eq!(spell.rank(), None);
+        assert_eq!(spell.mp, Some(MpCost::ValuePlus(15)));
+        assert_eq!(
+            spell.target,
+            Some(Target::Area {
+                value: "1エリア".to_string(),
+                radius: Some(Scalar::Int(35)),
+                suffix: Some(Scalar::Text("すべて".to_string())),
+            })
+        );
+        assert_eq!(spell.duration.as_ref().map(|d| d.to_string()), Some("2R".to_string()));
+        assert_eq!(spell.range_m, Some(Scalar::Int(30)));
+        assert_eq!(spell.school_variant.as_deref(), Some("水・氷"));
+        assert_eq!(spell.references.as_ref().unwrap()[0].page, Some(122));
+        assert!(spell.requires_preparation());
+        assert!(!spell.is_support());
+        assert!(!spell.is_crime());
+        assert!(spell.extra.is_empty());
+    }
+
+    #[test]
+    fn test_roundtrip_is_lossless() {
+        let json = serde_json::json!({
+            "school": "MagicCat_3",
+            "Lv": {"kind": "rank", "rank": 2},
+            "name": "Magic_22924",
+            "MP": {"kind": "special", "special": "効果参照"},
+            "対象": {"kind": "個別", "個別": "接触点"},
+            "時間": {"value": "一瞬", "unit": null},
+            "射程": "接触",
+            "属性": null,
+            "補助": false,
+            "効果": "EffectDescription_89106",
+            "独自フィールド": {"nested": [1, 2, 3]}
+        });
+        let (spell, back) = roundtrip(json.clone());
+        assert_eq!(back, json);
+        assert_eq!(spell.rank(), Some(2));
+        assert_eq!(spell.extra.get("独自フィールド"), Some(&serde_json::json!({"nested": [1, 2, 3]})));
+        assert_eq!(spell.extra.get("属性"), Some(&serde_json::Value::Null));
+    }
+
+    #[test]
+    fn test_sample_file_roundtrip() {
+        let file_path = "../../data/sample/spells_sample.json";
+        if !std::path::Path::new(file_path).exists() {
+            println!("spells_sample.json not found at {}, skipping test", file_path);
+            return;
+        }
+        let content = std::fs::read_to_string(file_path).expect("Failed to read spells_sample.json");
+        let raw: serde_json::Value = serde_json::from_str(&content).unwrap();
+        let spells: Vec<Spell> = serde_json::from_value(raw.clone()).unwrap();
+        assert_eq!(serde_json::to_value(&spells).unwrap(), raw);
+        assert!(spells.iter().all(|s| s.level.is_some() && s.mp.is_some() && s.target.is_some()));
+    }
+
+    #[test]
+    fn test_invalid_values_are_kept_in_extra() {
+        let json = serde_json::json!({
+            "name": "テスト",
+            "school": "魔法",
+            "対象": {"kind": "不正"},
+            "Lv": {"kind": "value", "value": "高い"}
+        });
+        let (spell, back) = roundtrip(json.clone());
+        assert_eq!(spell.target, None);
+        assert_eq!(spell.level, None);
+        assert!(spell.extra.contains_key("対象"));
+        assert_eq!(back, json);
+    }
+
+    #[test]
+    fn test_lenient_kind_inference_keeps_original() {
+        let json = serde_json::json!({
+            "name": "テスト",
+            "school": "魔法",
+            "MP": {"value+": 3}
+        });
+        let (spell, back) = roundtrip(json.clone());
+        assert_eq!(spell.mp, Some(MpCost::ValuePlus(3)));
+        assert_eq!(back, json);
+    }
+
+    #[test]
+    fn test_modified_field_is_written_in_canonical_form() {
+        let mut spell: Spell = serde_json::from_value(serde_json::json!({
+            "name": "テスト",
+            "school": "魔法",
+            "MP": {"value": 3}
+        }))
+        .unwrap();
+        spell.mp = Some(MpCost::Value(4));
+        spell.support = Some(true);
+        let back = serde_json::to_value(&spell).unwrap();
+        assert_eq!(back["MP"], serde_json::json!({"kind": "value", "value": 4}));
+        assert_eq!(back["補助"], serde_json::json!(true));
+    }
+
+    #[test]
+    fn test_get_by_json_key() {
+        let spell: Spell = serde_json::from_value(serde_json::json!({
+            "name": "テスト",
+            "school": "魔法",
+            "MP": {"value+": 3},
+            "対象": {"kind": "不正"},
+            "独自フィールド": 1
+        }))
+        .unwrap();
+        assert_eq!(spell.get("name"), Some(serde_json::json!("テスト")));
+        assert_eq!(spell.get("MP"), Some(serde_json::json!({"value+": 3})));
+        assert_eq!(spell.get("対象"), Some(serde_json::json!({"kind": "不正"})));
+        assert_eq!(spell.get("独自フィールド"), Some(serde_json::json!(1)));
+        assert_eq!(spell.get("効果"), None);
+    }
+
+    #[test]
+    fn test_missing_name_is_error() {
+        let result: Result<Spell, _> = serde_json::from_value(serde_json::json!({"school": "魔法"}));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_display_helpers() {
+        assert_eq!(MpCost::Value(5).to_string(), "5");
+        assert_eq!(MpCost::ValuePlus(3).to_string(), "3～");
+        assert_eq!(MpCost::Special("効果参照".to_string()).to_string(), "効果参照");
+        let duration = Duration { value: Scalar::Text("一瞬".to_string()), unit: None };
+        assert_eq!(duration.to_string(), "一瞬");
+    }
+}
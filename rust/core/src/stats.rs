@@ -1,5 +1,6 @@
 // stats.rs - データセット統計情報の計算
 
+use crate::spell::SpellLevel;
 use crate::{Monster, Spell};
 #[cfg(test)]
 use crate::Part;
@@ -98,7 +99,7 @@ impl MonsterStats {
             .collect();
         
         // 件数で降順ソート、Top 5を取得
-        distribution.sort_by(|a, b| b.1.cmp(&a.1));
+        distribution.sort_by_key(|d| std::cmp::Reverse(d.1));
         distribution.truncate(5);
         
         distribution
@@ -218,19 +219,7 @@ impl SpellStats {
         
         for (label, min, max) in ranges {
             let count = spells.iter()
-                .filter(|s| {
-                    if let Some(lv_obj) = s.extra.get("Lv") {
-                        if let Some(kind) = lv_obj.get("kind").and_then(|k| k.as_str()) {
-                            if kind == "value" || kind == "value+" {
-                                if let Some(value) = lv_obj.get("value").and_then(|v| v.as_i64()) {
-                                    let level = value as i32;
-                                    return level >= min && level <= max;
-                                }
-                            }
-                        }
-                    }
-                    false
-                })
+                .filter(|s| s.level_value().is_some_and(|level| level >= min && level <= max))
                 .count();
             let percentage = if total > 0.0 { (count as f64 / total) * 100.0 } else { 0.0 };
             distribution.push((label.to_string(), count, percentage));
@@ -256,7 +245,7 @@ impl SpellStats {
             .collect();
         
         // 件数で降順ソート、Top 5を取得
-        distribution.sort_by(|a, b| b.1.cmp(&a.1));
+        distribution.sort_by_key(|d| std::cmp::Reverse(d.1));
         distribution.truncate(5);
         
         distribution
@@ -268,14 +257,10 @@ impl SpellStats {
         let mut rank_type = 0;
         
         for spell in spells {
-            if let Some(lv_obj) = spell.extra.get("Lv") {
-                if let Some(kind) = lv_obj.get("kind").and_then(|k| k.as_str()) {
-                    match kind {
-                        "value" | "value+" => level_type += 1,
-                        "rank" => rank_type += 1,
-                        _ => {}
-                    }
-                }
+            match spell.level {
+                Some(SpellLevel::Value(_) | SpellLevel::ValuePlus(_)) => level_type += 1,
+                Some(SpellLevel::Rank(_)) => rank_type += 1,
+                None => {}
             }
         }
         
@@ -437,8 +422,6 @@ mod tests {
     }
     
     fn create_test_spell(level: i32, school: &str, kind: &str) -> Spell {
-        let mut extra = HashMap::new();
-        
         let mut lv_obj = serde_json::Map::new();
         lv_obj.insert("kind".to_string(), serde_json::Value::String(kind.to_string()));
         if kind == "value" || kind == "value+" {
@@ -446,13 +429,13 @@ mod tests {
         } else if kind == "rank" {
             lv_obj.insert("rank".to_string(), serde_json::Value::Number(level.into()));
         }
-        extra.insert("Lv".to_string(), serde_json::Value::Object(lv_obj));
         
-        Spell {
-            name: format!("テストスペル{}", level),
-            school: school.to_string(),
-            extra,
-        }
+        serde_json::from_value(serde_json::json!({
+            "name": format!("テストスペル{}", level),
+            "school": school,
+            "Lv": lv_obj,
+        }))
+        .expect("Failed to build test spell")
     }
     
     #[test]
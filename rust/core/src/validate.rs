@@ -0,0 +1,612 @@
+//! JSON Schema によるデータ検証
+//!
+//! クレート直下の `schema/` にあるスキーマ（モンスター・スペル）を埋め込み、データファイルの
+//! すべての違反をファイル名・インデックス・JSON Pointer 付きで報告します。
+//!
+//! 対応しているキーワードはリポジトリのスキーマで使用している draft-07 のサブセットです:
+//! `type` `enum` `const` `required` `properties` `additionalProperties` `items`
+//! `minItems` `maxItems` `minLength` `maxLength` `minimum` `maximum`
+//! `allOf` `anyOf` `oneOf` `not` `if` / `then` / `else` `$ref`（`#/...` のみ）
+
+use serde_json::Value;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use crate::io::IoError;
+
+const MONSTER_SCHEMA: &str = include_str!("../schema/monster_array.json");
+const SPELL_SCHEMA: &str = include_str!("../schema/spell_array.json");
+
+/// 検証対象のデータ種別
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DataKind {
+    Monster,
+    Spell,
+}
+
+impl DataKind {
+    /// 埋め込みスキーマを取得
+    pub fn schema(&self) -> Schema {
+        let source = match self {
+            DataKind::Monster => MONSTER_SCHEMA,
+            DataKind::Spell => SPELL_SCHEMA,
+        };
+        // 埋め込みスキーマはテストで妥当性を確認している
+        Schema::new(serde_json::from_str(source).expect("embedded schema must be valid JSON"))
+    }
+}
+
+/// 検証結果の1件分
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    /// ファイルパス（メモリ上のデータを検証した場合は None）
+    pub file: Option<String>,
+
+    /// ルート配列内のインデックス（ファイル全体に関する違反の場合は None）
+    pub index: Option<usize>,
+
+    /// 違反箇所の JSON Pointer（例: "/3/part/0/HP"）
+    pub pointer: String,
+
+    /// 違反内容
+    pub message: String,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(file) = &self.file {
+            write!(f, "{}: ", file)?;
+        }
+        if let Some(index) = self.index {
+            write!(f, "[{}] ", index)?;
+        }
+        let pointer = if self.pointer.is_empty() { "/" } else { &self.pointer };
+        write!(f, "{}: {}", pointer, self.message)
+    }
+}
+
+/// 検証に使用するスキーマ
+#[derive(Debug, Clone)]
+pub struct Schema {
+    root: Value,
+}
+
+impl Schema {
+    /// JSON 値からスキーマを作成
+    pub fn new(root: Value) -> Self {
+        Schema { root }
+    }
+
+    /// インスタンスを検証し、すべての違反を返す
+    pub fn validate(&self, instance: &Value) -> Vec<Diagnostic> {
+        let mut out = Vec::new();
+        self.check(&self.root, instance, "", &mut out);
+        for diagnostic in &mut out {
+            diagnostic.index = root_index(&diagnostic.pointer);
+        }
+        out
+    }
+
+    /// インスタンスが妥当か判定
+    pub fn is_valid(&self, instance: &Value) -> bool {
+        let mut out = Vec::new();
+        self.check(&self.root, instance, "", &mut out);
+        out.is_empty()
+    }
+
+    fn check(&self, schema: &Value, instance: &Value, pointer: &str, out: &mut Vec<Diagnostic>) {
+        let Some(schema) = schema.as_object() else {
+            // true / false スキーマ
+            if schema == &Value::Bool(false) {
+                push(out, pointer, "この位置には値を指定できません".to_string());
+            }
+            return;
+        };
+
+        if let Some(reference) = schema.get("$ref").and_then(|r| r.as_str()) {
+            match self.resolve(reference) {
+                Some(target) => self.check(target, instance, pointer, out),
+                None => push(out, pointer, format!("スキーマの参照 '{}' を解決できません", reference)),
+            }
+            // draft-07 では $ref と並ぶキーワードは無視される
+            return;
+        }
+
+        if let Some(expected) = schema.get("type")
+            && !type_matches(expected, instance)
+        {
+            push(
+                out,
+                pointer,
+                format!("型が不正です（期待: {}、実際: {}）", describe_type(expected), type_name(instance)),
+            );
+            // 型が違う場合、以降の検証は無意味なので打ち切る
+            return;
+        }
+
+        if let Some(candidates) = schema.get("enum").and_then(|e| e.as_array())
+            && !candidates.iter().any(|c| json_equal(c, instance))
+        {
+            let list: Vec<String> = candidates.iter().map(|c| c.to_string()).collect();
+            push(out, pointer, format!("値 {} は許可されていません（候補: {}）", instance, list.join(", ")));
+        }
+
+        if let Some(expected) = schema.get("const")
+            && !json_equal(expected, instance)
+        {
+            push(out, pointer, format!("値は {} である必要があります（実際: {}）", expected, instance));
+        }
+
+        match instance {
+            Value::Object(obj) => self.check_object(schema, obj, pointer, out),
+            Value::Array(items) => self.check_array(schema, items, pointer, out),
+            Value::String(s) => {
+                let len = s.chars().count() as u64;
+                if let Some(min) = schema.get("minLength").and_then(|m| m.as_u64())
+                    && len < min
+                {
+                    push(out, pointer, format!("文字列が短すぎます（{}文字以上）", min));
+                }
+                if let Some(max) = schema.get("maxLength").and_then(|m| m.as_u64())
+                    && len > max
+                {
+                    push(out, pointer, format!("文字列が長すぎます（{}文字以下）", max));
+                }
+            }
+            Value::Number(n) => {
+                let value = n.as_f64().unwrap_or(0.0);
+                if let Some(min) = schema.get("minimum").and_then(|m| m.as_f64())
+                    && value < min
+                {
+                    push(out, pointer, format!("値 {} は最小値 {} 未満です", n, min));
+                }
+                if let Some(max) = schema.get("maximum").and_then(|m| m.as_f64())
+                    && value > max
+                {
+                    push(out, pointer, format!("値 {} は最大値 {} を超えています", n, max));
+                }
+            }
+            _ => {}
+        }
+
+        if let Some(all) = schema.get("allOf").and_then(|a| a.as_array()) {
+            for sub in all {
+                self.check(sub, instance, pointer, out);
+            }
+        }
+
+        if let Some(any) = schema.get("anyOf").and_then(|a| a.as_array())
+            && !any.iter().any(|sub| self.matches(sub, instance))
+        {
+            push(out, pointer, "anyOf のいずれの条件にも一致しません".to_string());
+        }
+
+        if let Some(one) = schema.get("oneOf").and_then(|a| a.as_array()) {
+            let count = one.iter().filter(|sub| self.matches(sub, instance)).count();
+            if count != 1 {
+                push(out, pointer, format!("oneOf の条件にちょうど1つ一致する必要があります（一致数: {}）", count));
+            }
+        }
+
+        if let Some(not) = schema.get("not")
+            && self.matches(not, instance)
+        {
+            push(out, pointer, "not の条件に一致してはいけません".to_string());
+        }
+
+        if let Some(condition) = schema.get("if") {
+            let branch = if self.matches(condition, instance) { "then" } else { "else" };
+            if let Some(sub) = schema.get(branch) {
+                self.check(sub, instance, pointer, out);
+            }
+        }
+    }
+
+    fn check_object(
+        &self,
+        schema: &serde_json::Map<String, Value>,
+        obj: &serde_json::Map<String, Value>,
+        pointer: &str,
+        out: &mut Vec<Diagnostic>,
+    ) {
+        if let Some(required) = schema.get("required").and_then(|r| r.as_array()) {
+            for key in required.iter().filter_map(|k| k.as_str()) {
+                if !obj.contains_key(key) {
+                    push(out, pointer, format!("必須フィールド '{}' がありません", key));
+                }
+            }
+        }
+
+        let properties = schema.get("properties").and_then(|p| p.as_object());
+        for (key, value) in obj {
+            let child = format!("{}/{}", pointer, escape_pointer(key));
+            match properties.and_then(|p| p.get(key)) {
+                Some(sub) => self.check(sub, value, &child, out),
+                None => match schema.get("additionalProperties") {
+                    Some(Value::Bool(false)) => {
+                        push(out, &child, format!("未定義のフィールド '{}' は使用できません", key));
+                    }
+                    Some(sub @ Value::Object(_)) => self.check(sub, value, &child, out),
+                    _ => {}
+                },
+            }
+        }
+    }
+
+    fn check_array(
+        &self,
+        schema: &serde_json::Map<String, Value>,
+        items: &[Value],
+        pointer: &str,
+        out: &mut Vec<Diagnostic>,
+    ) {
+        let len = items.len() as u64;
+        if let Some(min) = schema.get("minItems").and_then(|m| m.as_u64())
+            && len < min
+        {
+            push(out, pointer, format!("要素数が少なすぎます（{}個以上）", min));
+        }
+        if let Some(max) = schema.get("maxItems").and_then(|m| m.as_u64())
+            && len > max
+        {
+            push(out, pointer, format!("要素数が多すぎます（{}個以下）", max));
+        }
+        if let Some(sub) = schema.get("items") {
+            for (i, item) in items.iter().enumerate() {
+                self.check(sub, item, &format!("{}/{}", pointer, i), out);
+            }
+        }
+    }
+
+    fn matches(&self, schema: &Value, instance: &Value) -> bool {
+        let mut out = Vec::new();
+        self.check(schema, instance, "", &mut out);
+        out.is_empty()
+    }
+
+    /// `#/definitions/...` 形式の参照を解決
+    fn resolve(&self, reference: &str) -> Option<&Value> {
+        let pointer = reference.strip_prefix('#')?;
+        if pointer.is_empty() {
+            return Some(&self.root);
+        }
+        // JSON Pointer 内の非ASCII文字はパーセントエンコードされている場合がある
+        let decoded = urlencoding::decode(pointer).ok()?;
+        self.root.pointer(&decoded)
+    }
+}
+
+fn push(out: &mut Vec<Diagnostic>, pointer: &str, message: String) {
+    out.push(Diagnostic {
+        file: None,
+        index: None,
+        pointer: pointer.to_string(),
+        message,
+    });
+}
+
+/// JSON Pointer の先頭要素をルート配列のインデックスとして取得
+fn root_index(pointer: &str) -> Option<usize> {
+    pointer.strip_prefix('/')?.split('/').next()?.parse().ok()
+}
+
+fn escape_pointer(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(n) if n.is_i64() || n.is_u64() => "integer",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn type_matches(expected: &Value, value: &Value) -> bool {
+    let single = |name: &str| match name {
+        "integer" => match value {
+            Value::Number(n) => n.is_i64() || n.is_u64() || n.as_f64().is_some_and(|f| f.fract() == 0.0),
+            _ => false,
+        },
+        "number" => value.is_number(),
+        other => type_name(value) == other,
+    };
+    match expected {
+        Value::String(name) => single(name),
+        Value::Array(names) => names.iter().filter_map(|n| n.as_str()).any(single),
+        _ => true,
+    }
+}
+
+fn describe_type(expected: &Value) -> String {
+    match expected {
+        Value::Array(names) => names.iter().filter_map(|n| n.as_str()).collect::<Vec<_>>().join(" | "),
+        Value::String(name) => name.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// 数値は 2 と 2.0 を等しいとみなして比較
+fn json_equal(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        (Value::Number(x), Value::Number(y)) => x.as_f64() == y.as_f64(),
+        _ => a == b,
+    }
+}
+
+/// JSON 値を検証
+///
+/// # 引数
+/// * `kind` - データ種別（モンスター or スペル）
+/// * `value` - ルート配列を含む JSON 値
+///
+/// # 戻り値
+/// すべての違反（違反が無い場合は空）
+pub fn validate_value(kind: DataKind, value: &Value) -> Vec<Diagnostic> {
+    kind.schema().validate(value)
+}
+
+/// 単一レコード（配列に入れる前のオブジェクト）を検証
+///
+/// # 引数
+/// * `kind` - データ種別（モンスター or スペル）
+/// * `record` - 1件分の JSON オブジェクト
+///
+/// # 戻り値
+/// すべての違反（JSON Pointer はレコードを起点とし、`index` は None）
+pub fn validate_record(kind: DataKind, record: &Value) -> Vec<Diagnostic> {
+    let mut diagnostics = validate_value(kind, &Value::Array(vec![record.clone()]));
+    for diagnostic in &mut diagnostics {
+        diagnostic.index = None;
+        if let Some(rest) = diagnostic.pointer.strip_prefix("/0") {
+            diagnostic.pointer = rest.to_string();
+        }
+    }
+    diagnostics
+}
+
+/// JSON ファイルを検証
+///
+/// # 引数
+/// * `kind` - データ種別（モンスター or スペル）
+/// * `path` - 検証するファイルのパス
+///
+/// # 戻り値
+/// * `Ok(Vec<Diagnostic>)` - すべての違反（JSON 構文エラーも違反として含む）
+/// * `Err(IoError)` - ファイルが読み込めない場合
+///
+/// # エラー
+/// - ファイルが読み込めない場合は `IoError::FileRead`
+pub fn validate_file<P: AsRef<Path>>(kind: DataKind, path: P) -> Result<Vec<Diagnostic>, IoError> {
+    let path = path.as_ref();
+    let content = fs::read_to_string(path)?;
+    let file = Some(path.display().to_string());
+
+    let value: Value = match serde_json::from_str(&content) {
+        Ok(v) => v,
+        Err(e) => {
+            return Ok(vec![Diagnostic {
+                file,
+                index: None,
+                pointer: String::new(),
+                message: format!("JSON解析エラー: {}", e),
+            }]);
+        }
+    };
+
+    let mut diagnostics = validate_value(kind, &value);
+    for diagnostic in &mut diagnostics {
+        diagnostic.file = file.clone();
+    }
+    Ok(diagnostics)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    fn valid_monster() -> Value {
+        json!({
+            "Category": "蛮族",
+            "Lv": 6,
+            "Revision": 2.5,
+            "data": "TEST001",
+            "illust": "",
+            "movein": 22,
+            "movein_des": "飛行",
+            "moveon": -1,
+            "moveon_des": "",
+            "name": "テストモンスター",
+            "part": [
+                {
+                    "HP": 48,
+                    "MP": -1,
+                    "name": "",
+                    "コア": true,
+                    "命中力": 15,
+                    "回避力": 15,
+                    "打撃点": 6,
+                    "部位数": 1,
+                    "部位特殊能力": "",
+                    "防護点": 5
+                }
+            ],
+            "備考": "",
+            "先制値": 14,
+            "共通特殊能力": "",
+            "弱点": "",
+            "弱点値": 17,
+            "生命抵抗力": 16,
+            "知名度": 14,
+            "精神抵抗力": 16
+        })
+    }
+
+    fn valid_spell() -> Value {
+        json!({
+            "name": "テストスペル",
+            "school": "MagicCat_1",
+            "Lv": {"kind": "value", "value": 3},
+            "MP": {"kind": "value", "value": 5},
+            "対象": {"kind": "個別", "個別": "1体"},
+            "時間": {"value": "一瞬"},
+            "抵抗": "消滅",
+            "効果": "テスト"
+        })
+    }
+
+    #[test]
+    fn test_embedded_schemas_parse() {
+        DataKind::Monster.schema();
+        DataKind::Spell.schema();
+    }
+
+    #[test]
+    fn test_valid_data_has_no_diagnostics() {
+        assert!(validate_value(DataKind::Monster, &json!([valid_monster()])).is_empty());
+        assert!(validate_value(DataKind::Spell, &json!([valid_spell()])).is_empty());
+    }
+
+    #[test]
+    fn test_sample_files_are_valid() {
+        for (kind, path) in [
+            (DataKind::Monster, "../../data/sample/monsters_sample.json"),
+            (DataKind::Spell, "../../data/sample/spells_sample.json"),
+        ] {
+            if !Path::new(path).exists() {
+                println!("{} not found, skipping", path);
+                continue;
+            }
+            let diagnostics = validate_file(kind, path).unwrap();
+            assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+        }
+    }
+
+    #[test]
+    fn test_root_must_be_array() {
+        let diagnostics = validate_value(DataKind::Monster, &valid_monster());
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].pointer, "");
+        assert_eq!(diagnostics[0].index, None);
+        assert!(diagnostics[0].message.contains("array"));
+    }
+
+    #[test]
+    fn test_reports_every_violation() {
+        let mut broken = valid_monster();
+        broken["Lv"] = json!("六");
+        broken["part"][0]["HP"] = json!("48");
+        broken.as_object_mut().unwrap().remove("弱点値");
+
+        let diagnostics = validate_value(DataKind::Monster, &json!([valid_monster(), broken]));
+        let pointers: Vec<&str> = diagnostics.iter().map(|d| d.pointer.as_str()).collect();
+
+        assert_eq!(diagnostics.len(), 3, "{:?}", diagnostics);
+        assert!(pointers.contains(&"/1"));
+        assert!(pointers.contains(&"/1/Lv"));
+        assert!(pointers.contains(&"/1/part/0/HP"));
+        assert!(diagnostics.iter().all(|d| d.index == Some(1)));
+        assert!(diagnostics.iter().any(|d| d.message.contains("弱点値")));
+    }
+
+    #[test]
+    fn test_minimum_and_min_items() {
+        let mut broken = valid_monster();
+        broken["movein"] = json!(-5);
+        broken["part"] = json!([]);
+        let diagnostics = validate_value(DataKind::Monster, &json!([broken]));
+        assert!(diagnostics.iter().any(|d| d.pointer == "/0/movein" && d.message.contains("最小値")));
+        assert!(diagnostics.iter().any(|d| d.pointer == "/0/part" && d.message.contains("要素数")));
+    }
+
+    #[test]
+    fn test_spell_conditional_kind() {
+        let mut broken = valid_spell();
+        broken["Lv"] = json!({"kind": "rank", "value": 3});
+        broken["MP"] = json!({"kind": "value", "value": 500});
+        let diagnostics = validate_value(DataKind::Spell, &json!([broken]));
+
+        assert!(diagnostics.iter().any(|d| d.pointer == "/0/Lv" && d.message.contains("rank")));
+        assert!(diagnostics.iter().any(|d| d.pointer == "/0/MP/value" && d.message.contains("最大値")));
+    }
+
+    #[test]
+    fn test_spell_enum_violation() {
+        let mut broken = valid_spell();
+        broken["抵抗"] = json!("不明");
+        broken["対象"] = json!({"kind": "個別", "個別": "2体"});
+        let diagnostics = validate_value(DataKind::Spell, &json!([broken]));
+        assert_eq!(diagnostics.len(), 2, "{:?}", diagnostics);
+        assert!(diagnostics.iter().any(|d| d.pointer == "/0/抵抗" && d.message.contains("許可されていません")));
+        assert!(diagnostics.iter().any(|d| d.pointer == "/0/対象/個別"));
+    }
+
+    #[test]
+    fn test_validate_record() {
+        assert!(validate_record(DataKind::Spell, &valid_spell()).is_empty());
+
+        let mut broken = valid_spell();
+        broken["抵抗"] = json!("不明");
+        broken.as_object_mut().unwrap().remove("name");
+        let diagnostics = validate_record(DataKind::Spell, &broken);
+        let pointers: Vec<&str> = diagnostics.iter().map(|d| d.pointer.as_str()).collect();
+        assert_eq!(pointers.len(), 2, "{:?}", diagnostics);
+        assert!(pointers.contains(&"/抵抗"));
+        assert!(pointers.contains(&""));
+        assert!(diagnostics.iter().all(|d| d.index.is_none()));
+    }
+
+    #[test]
+    fn test_nullable_fields() {
+        let mut spell = valid_spell();
+        spell["属性"] = Value::Null;
+        spell["schoolVariant"] = Value::Null;
+        assert!(validate_value(DataKind::Spell, &json!([spell])).is_empty());
+    }
+
+    #[test]
+    fn test_additional_properties_and_pointer_escape() {
+        let schema = Schema::new(json!({
+            "type": "object",
+            "properties": {"a/b": {"type": "integer"}},
+            "additionalProperties": false
+        }));
+        let diagnostics = schema.validate(&json!({"a/b": "x", "c": 1}));
+        assert_eq!(diagnostics.len(), 2);
+        assert!(diagnostics.iter().any(|d| d.pointer == "/a~1b"));
+        assert!(diagnostics.iter().any(|d| d.pointer == "/c"));
+    }
+
+    #[test]
+    fn test_validate_file_reports_file_and_syntax_errors() {
+        let mut file = NamedTempFile::new().unwrap();
+        write!(file, "[{{\"name\": }}]").unwrap();
+        let diagnostics = validate_file(DataKind::Spell, file.path()).unwrap();
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0].message.contains("JSON解析エラー"));
+        assert!(diagnostics[0].file.is_some());
+
+        let mut file = NamedTempFile::new().unwrap();
+        write!(file, "[{{\"name\": \"x\"}}]").unwrap();
+        let diagnostics = validate_file(DataKind::Spell, file.path()).unwrap();
+        let rendered = diagnostics[0].to_string();
+        assert!(rendered.starts_with(&file.path().display().to_string()));
+        assert!(rendered.contains("[0] /0: "));
+    }
+
+    #[test]
+    fn test_validate_file_missing() {
+        assert!(matches!(
+            validate_file(DataKind::Monster, "/nonexistent/monsters.json"),
+            Err(IoError::FileRead(_))
+        ));
+    }
+}